@@ -0,0 +1,42 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Build metadata for `assemblyai-cli version`: commit, build time, target, and enabled features.
+fn main() {
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    // SOURCE_DATE_EPOCH keeps reproducible builds reproducible.
+    let built_at = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+        });
+    let mut features: Vec<String> = std::env::vars()
+        .filter_map(|(name, _)| name.strip_prefix("CARGO_FEATURE_").map(|feature| feature.to_lowercase().replace('_', "-")))
+        .collect();
+    features.sort();
+
+    println!("cargo:rustc-env=ASSEMBLYAI_CLI_COMMIT={commit}");
+    println!("cargo:rustc-env=ASSEMBLYAI_CLI_BUILT_AT={built_at}");
+    println!("cargo:rustc-env=ASSEMBLYAI_CLI_TARGET={}", std::env::var("TARGET").unwrap_or_default());
+    println!("cargo:rustc-env=ASSEMBLYAI_CLI_FEATURES={}", features.join(","));
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    // Any rerun-if line turns off cargo's default of rerunning whenever a package file changes, so outside a git
+    // checkout the sources are watched instead, to keep the build time fresh.
+    let git_paths: Vec<&str> =
+        [".git/HEAD", ".git/refs", ".git/packed-refs"].into_iter().filter(|path| Path::new(path).exists()).collect();
+    let watched = if git_paths.is_empty() { vec!["src", "Cargo.toml"] } else { git_paths };
+    for path in watched {
+        println!("cargo:rerun-if-changed={path}");
+    }
+}
@@ -0,0 +1,82 @@
+# English messages of assemblyai-cli; every other catalog defines the same ids.
+# Error messages and --help text are not localized yet.
+
+## Log prefixes
+
+log-warning = warning: { $message }
+log-error = error: { $message }
+
+## Transcription
+
+starting-transcription = starting transcription
+resuming-transcript = resuming transcript { $id }
+uploading = uploading: { $path }
+reusing-upload = reusing the upload of { $path } from the last 24 hours
+preprocessing-audio = preprocessing audio: { $filters }
+ffprobe-missing = ffprobe not found on PATH; skipping media validation
+keeping-temp-file = keeping temporary file { $path }
+detected-language = detected language: { $language }
+detected-language-confidence = detected language: { $language } (confidence { $confidence })
+cancelling = cancelling...
+bench-run = bench run { $run }/{ $runs }: { $model }
+
+## Outputs
+
+wrote-transcript = wrote transcript to { $path }
+embedded-transcript = embedded transcript into { $path }
+no-chapters = no chapters returned; skipped writing { $path }
+wrote-chapters = wrote { $count } chapters to { $path }
+wrote-report = wrote report to { $path }
+built-search-index = indexed { $count } transcripts ({ $sentences } sentences) in { $path }
+merged-subtitles = merged { $count } files ({ $cues } cues) into { $path }
+wrote-table = wrote { $rows } { $table } to { $path }
+wrote-transcript-database = wrote transcript { $id } to { $path }
+generating-meeting-notes = generating meeting minutes via LeMUR
+generating-show-notes = generating show notes via LeMUR
+
+## Translation
+
+translating-segments = translating { $count } segments to { $language }
+translating-captions = translating { $count } captions to { $language }
+untranslated-lines = { $count } line(s) were not translated; kept original text
+wrote-translated-subtitles = wrote translated subtitles to { $path }
+
+## Batch runs and history
+
+serving-metrics = serving metrics at http://{ $addr }/metrics
+resuming-batch = resuming an interrupted batch: { $done } of { $total } inputs were already started
+transcribing-input = transcribing { $input }
+batch-summary = transcribed { $done } of { $total } inputs into { $dir }
+batch-languages = detected languages: { $languages }
+batch-progress-failed = failed to record batch progress: { $error }
+batch-progress-save-failed = failed to record batch progress in { $path }: { $error }
+history-save-failed = failed to record history in { $path }: { $error }
+cache-write-failed = could not cache the transcript: { $error }
+upload-index-failed = could not record the upload for reuse: { $error }
+rerunning = re-running #{ $id }: assemblyai-cli { $args }
+
+## Transcript cleanup
+
+deleting-transcript = deleting { $current }/{ $total }: { $id }
+delete-failed = could not delete { $id }: { $error }
+delete-aborted = nothing deleted
+
+## Config
+
+no-config-file = no config file at { $path }
+restricted-config = restricted { $path } to its owner
+config-up-to-date = { $path } is up to date (configVersion { $version })
+moved-legacy-config = moved legacy config to { $path }
+wrote-migrated-config = wrote { $path } at configVersion { $version } (backup: { $backup })
+config-outdated = { $path } uses config schema version { $version }; run `assemblyai-cli config migrate` to upgrade it to version { $current }
+config-too-new = { $path } has config schema version { $version }, newer than this assemblyai-cli supports ({ $current }); some keys may be ignored
+config-readable-by-others = { $path } contains apiKey but is accessible to other users (mode { $mode }); run `assemblyai-cli config fix-permissions`
+init-aborted = init aborted; existing apiKey preserved (use --yes to overwrite)
+api-key-rejected = AssemblyAI rejected this API key
+storing-rejected-key = storing the rejected key anyway
+api-key-unverified = could not verify the API key: { $error }
+wrote-config = wrote config to { $path }
+
+## Self-update
+
+downloading = downloading { $asset }
@@ -0,0 +1,95 @@
+# Русские сообщения assemblyai-cli; идентификаторы те же, что в locales/en/cli.ftl.
+
+## Log prefixes
+
+log-warning = предупреждение: { $message }
+log-error = ошибка: { $message }
+
+## Transcription
+
+starting-transcription = запуск транскрибации
+resuming-transcript = продолжение транскрипта { $id }
+uploading = загрузка: { $path }
+reusing-upload = повторно используется загрузка { $path } за последние 24 часа
+preprocessing-audio = предобработка аудио: { $filters }
+ffprobe-missing = ffprobe не найден в PATH; проверка медиафайла пропущена
+keeping-temp-file = временный файл сохранён: { $path }
+detected-language = определён язык: { $language }
+detected-language-confidence = определён язык: { $language } (уверенность { $confidence })
+cancelling = отмена...
+bench-run = замер { $run }/{ $runs }: { $model }
+
+## Outputs
+
+wrote-transcript = транскрипт записан в { $path }
+embedded-transcript = транскрипт встроен в { $path }
+no-chapters = главы не получены; { $path } не записан
+wrote-chapters = { $count ->
+    [one] { $count } глава записана в { $path }
+    [few] { $count } главы записаны в { $path }
+   *[other] { $count } глав записано в { $path }
+}
+wrote-report = отчёт записан в { $path }
+built-search-index = проиндексировано транскриптов: { $count } (предложений: { $sentences }) в { $path }
+merged-subtitles = объединено файлов: { $count } (реплик: { $cues }) в { $path }
+wrote-table = таблица { $table } ({ $rows } стр.) записана в { $path }
+wrote-transcript-database = транскрипт { $id } записан в { $path }
+generating-meeting-notes = составление протокола встречи через LeMUR
+generating-show-notes = составление описания выпуска через LeMUR
+
+## Translation
+
+translating-segments = { $count ->
+    [one] перевод { $count } сегмента на { $language }
+   *[other] перевод { $count } сегментов на { $language }
+}
+translating-captions = { $count ->
+    [one] перевод { $count } субтитра на { $language }
+   *[other] перевод { $count } субтитров на { $language }
+}
+untranslated-lines = { $count ->
+    [one] { $count } строка не переведена; оставлен исходный текст
+    [few] { $count } строки не переведены; оставлен исходный текст
+   *[other] { $count } строк не переведено; оставлен исходный текст
+}
+wrote-translated-subtitles = переведённые субтитры записаны в { $path }
+
+## Batch runs and history
+
+serving-metrics = метрики доступны по адресу http://{ $addr }/metrics
+resuming-batch = продолжение прерванного пакета: начато { $done } из { $total } входных файлов
+transcribing-input = транскрибация { $input }
+batch-summary = транскрибировано { $done } из { $total } входных файлов в { $dir }
+batch-languages = обнаруженные языки: { $languages }
+batch-progress-failed = не удалось сохранить ход пакета: { $error }
+batch-progress-save-failed = не удалось сохранить ход пакета в { $path }: { $error }
+history-save-failed = не удалось записать историю в { $path }: { $error }
+cache-write-failed = не удалось сохранить транскрипт в кэш: { $error }
+upload-index-failed = не удалось запомнить загрузку для повторного использования: { $error }
+rerunning = повторный запуск #{ $id }: assemblyai-cli { $args }
+
+## Transcript cleanup
+
+deleting-transcript = удаление { $current }/{ $total }: { $id }
+delete-failed = не удалось удалить { $id }: { $error }
+delete-aborted = ничего не удалено
+
+## Config
+
+no-config-file = файл конфигурации { $path } не найден
+restricted-config = доступ к { $path } оставлен только владельцу
+config-up-to-date = { $path } уже актуален (configVersion { $version })
+moved-legacy-config = устаревший файл конфигурации перенесён в { $path }
+wrote-migrated-config = { $path } записан с configVersion { $version } (резервная копия: { $backup })
+config-outdated = { $path } использует схему конфигурации версии { $version }; выполните `assemblyai-cli config migrate`, чтобы обновить её до версии { $current }
+config-too-new = у { $path } схема конфигурации версии { $version }, новее поддерживаемой этой версией assemblyai-cli ({ $current }); некоторые ключи могут быть проигнорированы
+config-readable-by-others = { $path } содержит apiKey, но доступен другим пользователям (права { $mode }); выполните `assemblyai-cli config fix-permissions`
+init-aborted = init прерван; существующий apiKey сохранён (используйте --yes, чтобы перезаписать)
+api-key-rejected = AssemblyAI отклонил этот API-ключ
+storing-rejected-key = отклонённый ключ всё равно сохраняется
+api-key-unverified = не удалось проверить API-ключ: { $error }
+wrote-config = конфигурация записана в { $path }
+
+## Self-update
+
+downloading = скачивание { $asset }
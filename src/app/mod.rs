@@ -1,18 +1,30 @@
-use crate::domain::{Input, TranscribeOptions};
+use crate::domain::{AudioProfile, Input, TranscribeOptions};
 
 #[derive(Debug, Clone)]
 pub enum TranscribePlan {
     Url { url: url::Url },
     LocalAudio { path: std::path::PathBuf },
     LocalVideoExtract { path: std::path::PathBuf },
+    LocalVideo { path: std::path::PathBuf },
+    /// Audio (or a video's audio track) run through an ffmpeg filter chain before upload.
+    LocalPreprocess { path: std::path::PathBuf, profile: AudioProfile },
 }
 
 pub fn build_plan(options: &TranscribeOptions) -> Result<TranscribePlan, crate::domain::DomainError> {
+    if let Some(url) = options.audio_url() {
+        return Ok(TranscribePlan::Url { url: url.clone() });
+    }
     match options.input() {
         Input::Url(url) => Ok(TranscribePlan::Url { url: url.clone() }),
         Input::LocalPath(path) => match crate::domain::classify_local_media(path) {
+            crate::domain::MediaKind::Audio | crate::domain::MediaKind::Video
+                if let Some(profile) = options.audio_profile() =>
+            {
+                Ok(TranscribePlan::LocalPreprocess { path: path.clone(), profile })
+            }
             crate::domain::MediaKind::Audio => Ok(TranscribePlan::LocalAudio { path: path.clone() }),
-            crate::domain::MediaKind::Video => Ok(TranscribePlan::LocalVideoExtract { path: path.clone() }),
+            crate::domain::MediaKind::Video if options.extract_audio() => Ok(TranscribePlan::LocalVideoExtract { path: path.clone() }),
+            crate::domain::MediaKind::Video => Ok(TranscribePlan::LocalVideo { path: path.clone() }),
             crate::domain::MediaKind::Unknown => Err(crate::domain::DomainError::UnsupportedExtension {
                 path: path.clone(),
             }),
@@ -0,0 +1,59 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::DomainError;
+
+/// Human-friendly names for transcript ids, stored as `aliases.json`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Aliases(BTreeMap<String, String>);
+
+impl Aliases {
+    /// The transcript id for an alias; anything else is returned unchanged as an id.
+    pub fn resolve<'a>(&'a self, name_or_id: &'a str) -> &'a str {
+        self.0.get(name_or_id).map(String::as_str).unwrap_or(name_or_id)
+    }
+
+    /// Points `name` at `transcript_id`, returning the id it replaced.
+    pub fn set(&mut self, name: &str, transcript_id: &str) -> Result<Option<String>, DomainError> {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')) {
+            return Err(DomainError::InvalidAliasName { name: name.to_string() });
+        }
+        Ok(self.0.insert(name.to_string(), transcript_id.to_string()))
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        self.0.remove(name)
+    }
+}
+
+/// One `NAME  TRANSCRIPT_ID` line per alias, sorted by name.
+pub fn format_aliases(aliases: &Aliases) -> String {
+    let width = aliases.0.keys().map(|name| name.chars().count()).max().unwrap_or(0);
+    aliases
+        .0
+        .iter()
+        .map(|(name, id)| format!("{name:<width$}  {id}\n"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_aliases_and_passes_ids_through() {
+        let mut aliases = Aliases::default();
+        assert_eq!(aliases.set("weekly-standup", "tr_abc123").unwrap(), None);
+        assert_eq!(aliases.set("q3.review", "tr_def456").unwrap(), None);
+        assert_eq!(aliases.set("weekly-standup", "tr_new").unwrap().as_deref(), Some("tr_abc123"));
+
+        assert_eq!(aliases.resolve("weekly-standup"), "tr_new");
+        assert_eq!(aliases.resolve("tr_other"), "tr_other");
+        assert_eq!(format_aliases(&aliases), "q3.review       tr_def456\nweekly-standup  tr_new\n");
+
+        assert!(matches!(aliases.set("two words", "tr_x"), Err(DomainError::InvalidAliasName { .. })));
+        assert!(matches!(aliases.set("", "tr_x"), Err(DomainError::InvalidAliasName { .. })));
+    }
+}
@@ -0,0 +1,62 @@
+//! Tab-separated flashcard rows for Anki's text import, one sentence per note.
+
+use crate::domain::subtitles::Caption;
+use crate::domain::transcript::TimestampFormat;
+
+/// Anki file headers, then `SENTENCE<TAB>START-END[<TAB>TRANSLATION]` rows; `translations` pairs with `sentences`
+/// by index.
+pub fn format_anki_tsv(sentences: &[Caption], translations: Option<&[Caption]>, timestamps: TimestampFormat) -> String {
+    let mut out = String::from("#separator:tab\n#html:false\n");
+    out.push_str(if translations.is_some() { "#columns:Sentence\tTime\tTranslation\n" } else { "#columns:Sentence\tTime\n" });
+    for (idx, sentence) in sentences.iter().enumerate() {
+        out.push_str(&field(&sentence.text));
+        out.push('\t');
+        out.push_str(&timestamps.format(sentence.start_ms));
+        out.push('-');
+        out.push_str(&timestamps.format(sentence.end_ms));
+        if let Some(translations) = translations {
+            out.push('\t');
+            out.push_str(&field(translations.get(idx).map_or("", |caption| caption.text.as_str())));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Tabs and line breaks would start a new field or note.
+fn field(text: &str) -> String {
+    text.split(['\t', '\r', '\n']).filter(|part| !part.is_empty()).collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caption(start_ms: u64, end_ms: u64, text: &str) -> Caption {
+        Caption {
+            start_ms,
+            end_ms,
+            speaker: None,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn writes_one_note_per_sentence_with_optional_translation() {
+        let sentences = [caption(0, 1500, "Hola, ¿qué tal?"), caption(61_000, 63_250, "Muy\tbien.")];
+        assert_eq!(
+            format_anki_tsv(&sentences, None, TimestampFormat::HhMmSs),
+            "#separator:tab\n#html:false\n#columns:Sentence\tTime\n\
+             Hola, ¿qué tal?\t00:00:00-00:00:01\n\
+             Muy bien.\t00:01:01-00:01:03\n"
+        );
+
+        let translations = [caption(0, 1500, "Hi, how are you?"), caption(61_000, 63_250, "Very\nwell.")];
+        assert_eq!(
+            format_anki_tsv(&sentences, Some(&translations), TimestampFormat::Seconds),
+            "#separator:tab\n#html:false\n#columns:Sentence\tTime\tTranslation\n\
+             Hola, ¿qué tal?\t0.000-1.500\tHi, how are you?\n\
+             Muy bien.\t61.000-63.250\tVery well.\n"
+        );
+    }
+}
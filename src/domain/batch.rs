@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{input_stem, parse_input, Input};
+
+/// An input that failed in a batch run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Failure {
+    #[serde(with = "crate::domain::os_string")]
+    pub input: OsString,
+    /// The error message, as printed after `error:`.
+    pub error: String,
+    /// The exit code a single run of the input would have ended with.
+    pub exit_code: u8,
+}
+
+/// The `--failures-file` document, read back by `--retry-file`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailureList {
+    pub failures: Vec<Failure>,
+}
+
+impl FailureList {
+    pub fn parse(contents: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(contents)
+    }
+
+    pub fn inputs(&self) -> impl Iterator<Item = &OsStr> {
+        self.failures.iter().map(|failure| failure.input.as_os_str())
+    }
+}
+
+/// The `<name>.meta.json` sidecar written next to a batch output whose language was detected, and next to any output
+/// whose speakers were named with `--identify-speakers`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sidecar {
+    #[serde(with = "crate::domain::os_string")]
+    pub input: OsString,
+    pub transcript_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language_confidence: Option<f64>,
+    /// SHA-256 of the uploaded bytes, when the input was uploaded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_sha256: Option<String>,
+    /// Names of diarized speakers by label, reused by the next `--identify-speakers` run.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub speakers: BTreeMap<String, String>,
+}
+
+/// The speaker names recorded in a sidecar; none when it has no (readable) `speakers`.
+pub fn sidecar_speakers(json: &str) -> BTreeMap<String, String> {
+    serde_json::from_str::<serde_json::Value>(json)
+        .ok()
+        .and_then(|sidecar| serde_json::from_value(sidecar.get("speakers")?.clone()).ok())
+        .unwrap_or_default()
+}
+
+pub fn sidecar_path(output_base: &Path) -> PathBuf {
+    let mut path = output_base.as_os_str().to_os_string();
+    path.push(".meta.json");
+    PathBuf::from(path)
+}
+
+/// An input as `--deterministic` outputs record it: an absolute path relative to `cwd` when it is inside it, otherwise
+/// its file name. Relative paths and URLs are kept as given.
+pub fn portable_input(value: &OsStr, cwd: &Path) -> OsString {
+    let Ok(Input::LocalPath(path)) = parse_input(value) else {
+        return value.to_os_string();
+    };
+    if !path.is_absolute() {
+        return value.to_os_string();
+    }
+    match path.strip_prefix(cwd) {
+        Ok(relative) if !relative.as_os_str().is_empty() => relative.as_os_str().to_os_string(),
+        _ => path.file_name().map_or_else(|| value.to_os_string(), OsStr::to_os_string),
+    }
+}
+
+/// `message` with paths under `cwd` written relative to it, as `--deterministic` outputs record error messages.
+pub fn portable_message(message: &str, cwd: &Path) -> String {
+    let prefix = cwd.join("");
+    match prefix.to_str() {
+        Some(prefix) if prefix.len() > 1 => message.replace(prefix, ""),
+        _ => message.to_string(),
+    }
+}
+
+/// Detected languages with their input counts, most common first, e.g. `en 3, ru 2`.
+pub fn language_counts<'a>(codes: impl IntoIterator<Item = &'a str>) -> String {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for code in codes {
+        match counts.iter_mut().find(|(seen, _)| *seen == code) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((code, 1)),
+        }
+    }
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    counts
+        .iter()
+        .map(|(code, count)| format!("{code} {count}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Output path of each batch input in `dir`, without an extension: the input's file stem, with `-2`, `-3`, ...
+/// appended when two inputs share one.
+pub fn output_bases(dir: &Path, inputs: &[OsString]) -> Vec<PathBuf> {
+    let mut taken = std::collections::HashSet::new();
+    inputs
+        .iter()
+        .map(|input| {
+            let stem = input_stem(input).unwrap_or_else(|| OsString::from("transcript"));
+            let name = (1..)
+                .map(|n| {
+                    let mut name = stem.clone();
+                    if n > 1 {
+                        name.push(format!("-{n}"));
+                    }
+                    name
+                })
+                .find(|name| taken.insert(name.clone()))
+                .expect("an unused name");
+            dir.join(name)
+        })
+        .collect()
+}
+
+/// Whether a local input contains `*`, `?`, or `[` and should be expanded as a wildcard pattern.
+///
+/// URLs are never patterns, as `?` starts their query string; neither are non-Unicode inputs or Windows
+/// `\\?\` verbatim paths, which by definition take every character literally.
+pub fn is_glob_pattern(input: &OsStr) -> bool {
+    input.to_str().is_some_and(|input| {
+        !input.starts_with("http://")
+            && !input.starts_with("https://")
+            && !input.starts_with(r"\\?\")
+            && input.contains(['*', '?', '['])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_outputs_after_inputs_without_collisions() {
+        let inputs = [
+            "calls/monday.mp3",
+            "archive/monday.wav",
+            "https://cdn.example/media/standup.m4a?sig=1",
+            "https://cdn.example/",
+        ]
+        .map(OsString::from);
+        let bases = output_bases(Path::new("out"), &inputs);
+        assert_eq!(
+            bases,
+            ["out/monday", "out/monday-2", "out/standup", "out/transcript"].map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn recognizes_glob_patterns_but_not_urls() {
+        let is_pattern = |input: &str| is_glob_pattern(OsStr::new(input));
+        assert!(is_pattern(r"recordings\*.m4a"));
+        assert!(is_pattern("take-?.wav"));
+        assert!(is_pattern("day[12].mp3"));
+        assert!(!is_pattern("interview.mp3"));
+        assert!(!is_pattern("https://cdn.example/a.mp3?sig=1"));
+        assert!(!is_pattern(r"\\?\C:\very\long\path\talk.mp3"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn portable_inputs_drop_the_working_directory() {
+        let cwd = Path::new("/work/project");
+        let portable = |input: &str| portable_input(OsStr::new(input), cwd);
+        assert_eq!(portable("/work/project/calls/a.mp3"), "calls/a.mp3");
+        assert_eq!(portable("/tmp/b.mp3"), "b.mp3");
+        assert_eq!(portable("calls/a.mp3"), "calls/a.mp3");
+        assert_eq!(portable("https://cdn.example/a.mp3"), "https://cdn.example/a.mp3");
+        assert_eq!(
+            portable_message("failed to read \"/work/project/calls/a.mp3\": not found", cwd),
+            "failed to read \"calls/a.mp3\": not found"
+        );
+    }
+
+    #[test]
+    fn counts_detected_languages_and_names_sidecars() {
+        assert_eq!(language_counts(["ru", "en", "en", "de", "en", "ru"]), "en 3, ru 2, de 1");
+        assert_eq!(language_counts([]), "");
+        assert_eq!(sidecar_path(Path::new("out/monday")), PathBuf::from("out/monday.meta.json"));
+    }
+
+    #[test]
+    fn failure_list_round_trips() {
+        let list = FailureList {
+            failures: vec![Failure {
+                input: "a.mp3".into(),
+                error: "api returned HTTP 500".to_string(),
+                exit_code: 5,
+            }],
+        };
+        let json = serde_json::to_string(&list).unwrap();
+        assert_eq!(json, r#"{"failures":[{"input":"a.mp3","error":"api returned HTTP 500","exitCode":5}]}"#);
+        assert_eq!(FailureList::parse(&json).unwrap().inputs().collect::<Vec<_>>(), vec!["a.mp3"]);
+    }
+}
@@ -0,0 +1,145 @@
+//! Latency measurements of `bench`: where the time of each end-to-end transcription went.
+
+use serde::Serialize;
+
+/// One transcription of the benchmarked input with one speech model.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchRun {
+    /// 1-based run number.
+    pub run: usize,
+    pub model: String,
+    pub transcript_id: String,
+    /// Uploading the local file (and extracting its audio first, for video); 0 for URLs.
+    pub upload_ms: u64,
+    /// From submission until a poll first saw the transcript processing.
+    pub queue_ms: u64,
+    /// From then until a poll saw it completed.
+    pub processing_ms: u64,
+    /// Wall time of the whole run, upload included.
+    pub total_ms: u64,
+}
+
+/// Mean times of a model over its runs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchSummary {
+    pub model: String,
+    pub runs: usize,
+    pub upload_ms: u64,
+    pub queue_ms: u64,
+    pub processing_ms: u64,
+    pub total_ms: u64,
+}
+
+/// The `bench --json` document.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchReport {
+    pub runs: Vec<BenchRun>,
+    pub summary: Vec<BenchSummary>,
+}
+
+impl BenchReport {
+    /// `runs` with the mean of each model, in the order the models first ran.
+    pub fn new(runs: Vec<BenchRun>) -> Self {
+        let mut models: Vec<&str> = Vec::new();
+        for run in &runs {
+            if !models.contains(&run.model.as_str()) {
+                models.push(&run.model);
+            }
+        }
+        let summary = models
+            .into_iter()
+            .map(|model| {
+                let runs: Vec<&BenchRun> = runs.iter().filter(|run| run.model == model).collect();
+                let mean = |ms: fn(&BenchRun) -> u64| runs.iter().map(|run| ms(run)).sum::<u64>() / runs.len() as u64;
+                BenchSummary {
+                    model: model.to_string(),
+                    runs: runs.len(),
+                    upload_ms: mean(|run| run.upload_ms),
+                    queue_ms: mean(|run| run.queue_ms),
+                    processing_ms: mean(|run| run.processing_ms),
+                    total_ms: mean(|run| run.total_ms),
+                }
+            })
+            .collect();
+        Self { runs, summary }
+    }
+}
+
+/// One row per run, then a `mean` row per model; times in seconds.
+pub fn format_bench_table(report: &BenchReport) -> String {
+    let model_width = report
+        .runs
+        .iter()
+        .map(|run| run.model.len())
+        .max()
+        .unwrap_or(0)
+        .max("MODEL".len());
+    let row = |run: &str, model: &str, times: [u64; 4]| {
+        let [upload, queue, processing, total] = times.map(seconds);
+        format!("{run:<4}  {model:<model_width$}  {upload:>8}  {queue:>8}  {processing:>10}  {total:>8}\n")
+    };
+    let mut out = format!(
+        "{:<4}  {:<model_width$}  {:>8}  {:>8}  {:>10}  {:>8}\n",
+        "RUN", "MODEL", "UPLOAD", "QUEUE", "PROCESSING", "TOTAL"
+    );
+    for run in &report.runs {
+        out.push_str(&row(
+            &run.run.to_string(),
+            &run.model,
+            [run.upload_ms, run.queue_ms, run.processing_ms, run.total_ms],
+        ));
+    }
+    for summary in &report.summary {
+        out.push_str(&row(
+            "mean",
+            &summary.model,
+            [summary.upload_ms, summary.queue_ms, summary.processing_ms, summary.total_ms],
+        ));
+    }
+    out
+}
+
+fn seconds(ms: u64) -> String {
+    format!("{}.{:02}s", ms / 1000, ms % 1000 / 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(run: usize, model: &str, upload_ms: u64, queue_ms: u64, processing_ms: u64) -> BenchRun {
+        BenchRun {
+            run,
+            model: model.to_string(),
+            transcript_id: format!("tr_{run}_{model}"),
+            upload_ms,
+            queue_ms,
+            processing_ms,
+            total_ms: upload_ms + queue_ms + processing_ms,
+        }
+    }
+
+    #[test]
+    fn tabulates_runs_and_model_means() {
+        let report = BenchReport::new(vec![
+            run(1, "best", 1200, 3000, 9000),
+            run(1, "nano", 1100, 1000, 4000),
+            run(2, "best", 1000, 2000, 8000),
+            run(2, "nano", 900, 1500, 3500),
+        ]);
+        assert_eq!(report.summary[0].total_ms, 12_100);
+        assert_eq!(report.summary[1].queue_ms, 1250);
+        assert_eq!(
+            format_bench_table(&report),
+            "RUN   MODEL    UPLOAD     QUEUE  PROCESSING     TOTAL\n\
+             1     best      1.20s     3.00s       9.00s    13.20s\n\
+             1     nano      1.10s     1.00s       4.00s     6.10s\n\
+             2     best      1.00s     2.00s       8.00s    11.00s\n\
+             2     nano      0.90s     1.50s       3.50s     5.90s\n\
+             mean  best      1.10s     2.50s       8.50s    12.10s\n\
+             mean  nano      1.00s     1.25s       3.75s     6.00s\n"
+        );
+    }
+}
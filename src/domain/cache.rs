@@ -0,0 +1,96 @@
+//! The local cache of completed transcripts, one `<transcript id>.json` file each, kept as the API returned it.
+
+use std::time::Duration;
+
+use crate::domain::format_size;
+use crate::domain::history::format_unix_time;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntry {
+    pub transcript_id: String,
+    pub bytes: u64,
+    /// Unix seconds of the last read or write.
+    pub last_used: u64,
+}
+
+/// Whether `id` can name a cache file; anything that could reach outside the cache directory is not cached.
+pub fn is_cacheable_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Entries to evict so the rest fit in `max_bytes`, least recently used first.
+pub fn evictions(entries: &[CacheEntry], max_bytes: u64) -> Vec<&CacheEntry> {
+    let mut by_age: Vec<&CacheEntry> = entries.iter().collect();
+    by_age.sort_by_key(|entry| (entry.last_used, entry.transcript_id.clone()));
+    let mut total: u64 = entries.iter().map(|entry| entry.bytes).sum();
+    by_age
+        .into_iter()
+        .take_while(|entry| {
+            let over = total > max_bytes;
+            total -= entry.bytes;
+            over
+        })
+        .collect()
+}
+
+/// Entries last used more than `older_than` before `now` (unix seconds).
+pub fn unused_since(entries: &[CacheEntry], now: u64, older_than: Duration) -> Vec<&CacheEntry> {
+    let cutoff = now.saturating_sub(older_than.as_secs());
+    entries.iter().filter(|entry| entry.last_used < cutoff).collect()
+}
+
+/// One `ID  SIZE  LAST USED` line per entry, most recently used first.
+pub fn format_cache_list(entries: &[CacheEntry]) -> String {
+    let mut entries: Vec<&CacheEntry> = entries.iter().collect();
+    entries.sort_by(|a, b| b.last_used.cmp(&a.last_used).then(a.transcript_id.cmp(&b.transcript_id)));
+    let width = entries.iter().map(|entry| entry.transcript_id.len()).max().unwrap_or(0);
+    entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "{:<width$}  {:>6}  {}\n",
+                entry.transcript_id,
+                format_size(entry.bytes),
+                format_unix_time(entry.last_used)
+            )
+        })
+        .collect()
+}
+
+/// `N transcripts, SIZE`.
+pub fn format_cache_size(entries: &[&CacheEntry]) -> String {
+    let bytes = entries.iter().map(|entry| entry.bytes).sum();
+    let noun = if entries.len() == 1 { "transcript" } else { "transcripts" };
+    format!("{} {noun}, {}", entries.len(), format_size(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, bytes: u64, last_used: u64) -> CacheEntry {
+        CacheEntry {
+            transcript_id: id.to_string(),
+            bytes,
+            last_used,
+        }
+    }
+
+    #[test]
+    fn evicts_least_recently_used_and_expires_old_entries() {
+        let entries = vec![entry("tr_new", 400, 3_000), entry("tr_old", 300, 1_000), entry("tr_mid", 500, 2_000)];
+        let ids = |selected: Vec<&CacheEntry>| selected.iter().map(|e| e.transcript_id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(evictions(&entries, 800)), ["tr_old", "tr_mid"]);
+        assert_eq!(ids(evictions(&entries, 900)), ["tr_old"]);
+        assert_eq!(ids(evictions(&entries, 1_200)), Vec::<String>::new());
+        assert_eq!(ids(unused_since(&entries, 2_500, Duration::from_secs(1_000))), ["tr_old"]);
+
+        assert_eq!(
+            format_cache_list(&entries[..2]),
+            "tr_new    400B  1970-01-01 00:50\ntr_old    300B  1970-01-01 00:16\n"
+        );
+        assert_eq!(format_cache_size(&entries.iter().collect::<Vec<_>>()), "3 transcripts, 1.2K");
+        assert!(is_cacheable_id("5551722-f677-48a6-9287-39c0aafd9ac1"));
+        assert!(!is_cacheable_id("../config"));
+    }
+}
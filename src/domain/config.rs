@@ -2,17 +2,40 @@ use std::path::PathBuf;
 
 use serde::Deserialize;
 
-use crate::domain::{CustomSpelling, SpeechModel, TranscriptFormat};
+use crate::domain::encoding::{Compression, LineEndings, TextEncoding};
+use crate::domain::postprocess::{PostReplace, RedactStyle};
+use crate::domain::transcript::TimestampFormat;
+use crate::domain::polling::PollStrategy;
+use crate::domain::schema::{validate_config, SchemaViolation};
+use crate::domain::secret::ApiKey;
+use crate::domain::{AudioProfile, CustomSpelling, LimitAction, SpeechModel, TranscriptFormat};
+
+/// Current `configVersion`; files without one are version 1.
+pub const CONFIG_VERSION: u64 = 2;
 
 #[derive(Debug, Clone, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ConfigFile {
     #[serde(default)]
-    pub api_key: Option<String>,
+    pub config_version: Option<u64>,
+
+    /// Schema version of the file when it was older than [`CONFIG_VERSION`] and had keys upgraded in memory.
+    #[serde(skip)]
+    pub migrated_from: Option<u64>,
+
+    /// Keys in the file that no field reads, most likely typos.
+    #[serde(skip)]
+    pub unknown_keys: Vec<UnknownKey>,
+
+    #[serde(default)]
+    pub api_key: Option<ApiKey>,
 
     #[serde(default)]
     pub base_url: Option<String>,
 
+    #[serde(default)]
+    pub extra_headers: Option<std::collections::BTreeMap<String, String>>,
+
     #[serde(default)]
     pub format: Option<TranscriptFormat>,
 
@@ -28,6 +51,9 @@ pub struct ConfigFile {
     #[serde(default)]
     pub language: Option<String>,
 
+    #[serde(default)]
+    pub expect_language: Option<Vec<String>>,
+
     #[serde(default)]
     pub punctuate: Option<bool>,
 
@@ -43,26 +69,452 @@ pub struct ConfigFile {
     #[serde(default)]
     pub speaker_labels: Option<bool>,
 
+    #[serde(default)]
+    pub speaker_style: Option<String>,
+
+    #[serde(default)]
+    pub speaker_prefix: Option<bool>,
+
+    #[serde(default)]
+    pub merge_short_utterances: Option<String>,
+
+    #[serde(default)]
+    pub paragraph_gap: Option<String>,
+
+    #[serde(default)]
+    pub mark_uncertain: Option<f64>,
+
     #[serde(default)]
     pub multichannel: Option<bool>,
 
+    #[serde(default)]
+    pub channel_names: Option<std::collections::BTreeMap<String, String>>,
+
+    #[serde(default)]
+    pub split_by_channel: Option<bool>,
+
+    #[serde(default)]
+    pub sentiment_analysis: Option<bool>,
+
+    #[serde(default)]
+    pub auto_chapters: Option<bool>,
+
+    #[serde(default)]
+    pub auto_highlights: Option<bool>,
+
+    #[serde(default)]
+    pub entity_detection: Option<bool>,
+
+    #[serde(default)]
+    pub frame_rate: Option<u32>,
+
+    #[serde(default)]
+    pub timecode_start: Option<String>,
+
+    #[serde(default)]
+    pub embed_metadata: Option<bool>,
+
+    #[serde(default)]
+    pub write_chapters: Option<bool>,
+
+    #[serde(default)]
+    pub translate_to: Option<String>,
+
+    #[serde(default)]
+    pub lemur_model: Option<String>,
+
     #[serde(default)]
     pub speech_threshold: Option<f64>,
 
     #[serde(default)]
     pub chars_per_caption: Option<u32>,
 
+    #[serde(default)]
+    pub max_cps: Option<f64>,
+
+    #[serde(default)]
+    pub subtitle_source: Option<crate::domain::SubtitleSource>,
+
+    #[serde(default)]
+    pub srt_start_index: Option<u32>,
+
+    #[serde(default)]
+    pub srt_renumber: Option<bool>,
+
+    #[serde(default)]
+    pub split_output_every: Option<String>,
+
+    #[serde(default)]
+    pub split_output_by: Option<SplitOutputBy>,
+
+    #[serde(default)]
+    pub check: Option<bool>,
+
+    #[serde(default)]
+    pub lint: Option<LintConfig>,
+
     #[serde(default)]
     pub word_boost: Option<Vec<String>>,
 
+    #[serde(default)]
+    pub keyterms_prompt: Option<Vec<String>>,
+
     #[serde(default)]
     pub custom_spelling: Option<Vec<CustomSpelling>>,
 
+    #[serde(default)]
+    pub post_replace: Option<Vec<PostReplace>>,
+
+    #[serde(default)]
+    pub redact_terms_file: Option<PathBuf>,
+
+    #[serde(default)]
+    pub redact_style: Option<RedactStyle>,
+
+    #[serde(default)]
+    pub normalize: Option<Vec<String>>,
+
+    #[serde(default)]
+    pub reports: Option<Vec<String>>,
+
+    #[serde(default)]
+    pub report_output: Option<PathBuf>,
+
+    #[serde(default)]
+    pub timestamp_format: Option<TimestampFormat>,
+
+    #[serde(default)]
+    pub encoding: Option<TextEncoding>,
+
+    #[serde(default)]
+    pub line_endings: Option<LineEndings>,
+
+    #[serde(default)]
+    pub compress: Option<Compression>,
+
+    #[serde(default)]
+    pub tee: Option<crate::domain::TeeFormat>,
+
+    #[serde(default)]
+    pub preview: Option<usize>,
+
+    #[serde(default)]
+    pub poll_strategy: Option<PollStrategy>,
+
     #[serde(default)]
     pub poll_interval_seconds: Option<u64>,
 
     #[serde(default)]
     pub timeout_seconds: Option<u64>,
+
+    #[serde(default)]
+    pub extract_audio: Option<bool>,
+
+    #[serde(default)]
+    pub audio_profile: Option<AudioProfile>,
+
+    #[serde(default)]
+    pub skip_validation: Option<bool>,
+
+    #[serde(default)]
+    pub temp_dir: Option<PathBuf>,
+
+    #[serde(default)]
+    pub max_upload_bytes: Option<u64>,
+
+    #[serde(default)]
+    pub max_duration_minutes: Option<f64>,
+
+    #[serde(default)]
+    pub on_limit: Option<LimitAction>,
+
+    #[serde(default)]
+    pub fail_on_empty: Option<bool>,
+
+    #[serde(default)]
+    pub min_words: Option<usize>,
+
+    #[serde(default)]
+    pub min_avg_confidence: Option<f64>,
+
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+
+    #[serde(default)]
+    pub max_cache_bytes: Option<u64>,
+
+    /// Days a transcript is kept on the API before `delete` without `--before` removes it.
+    #[serde(default)]
+    pub retention_days: Option<u32>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigFileError {
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("unknown preset {name:?} (available: {available})")]
+    UnknownPreset { name: String, available: String },
+
+    #[error("preset {name:?} must be a JSON object")]
+    InvalidPreset { name: String },
+
+    #[error("byExtension rule {extension:?} must be a JSON object")]
+    InvalidExtensionRule { extension: String },
+
+    #[error("{}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+    Schema(Vec<SchemaViolation>),
+}
+
+/// Parses a config file, overlaying `byExtension.EXT` (for the input's extension) and then
+/// `presets.NAME` onto the top-level keys.
+pub fn parse_config_file(contents: &str, preset: Option<&str>, extension: Option<&str>) -> Result<ConfigFile, ConfigFileError> {
+    let mut root: serde_json::Value = serde_json::from_str(contents)?;
+    let version = config_version(&root);
+    let upgraded = !migrate(&mut root).is_empty();
+    let violations = validate_config(&root);
+    if !violations.is_empty() {
+        return Err(ConfigFileError::Schema(violations));
+    }
+    let unknown = unknown_keys(&root);
+
+    if let Some(extension) = extension {
+        let overrides = root.get("byExtension").and_then(|rules| {
+            rules.as_object()?.iter().find_map(|(key, value)| {
+                key.trim_start_matches('.')
+                    .eq_ignore_ascii_case(extension)
+                    .then(|| value.clone())
+            })
+        });
+        if let Some(overrides) = overrides {
+            let serde_json::Value::Object(overrides) = overrides else {
+                return Err(ConfigFileError::InvalidExtensionRule {
+                    extension: extension.to_string(),
+                });
+            };
+            overlay(&mut root, overrides);
+        }
+    }
+
+    if let Some(name) = preset {
+        let overrides = root
+            .get("presets")
+            .and_then(|p| p.get(name))
+            .cloned()
+            .ok_or_else(|| ConfigFileError::UnknownPreset {
+                name: name.to_string(),
+                available: preset_names(&root),
+            })?;
+        let serde_json::Value::Object(overrides) = overrides else {
+            return Err(ConfigFileError::InvalidPreset { name: name.to_string() });
+        };
+        overlay(&mut root, overrides);
+    }
+
+    let mut config: ConfigFile = serde_json::from_value(root)?;
+    config.migrated_from = upgraded.then_some(version);
+    config.unknown_keys = unknown;
+    Ok(config)
+}
+
+pub fn config_version(root: &serde_json::Value) -> u64 {
+    root.get("configVersion").and_then(|v| v.as_u64()).unwrap_or(1)
+}
+
+/// Upgrades a config document to [`CONFIG_VERSION`] in place and describes each changed key.
+///
+/// Steps run in order from the file's version, so a key renamed in a later release is added here as a new step
+/// rather than dropped from [`ConfigFile`].
+pub fn migrate(root: &mut serde_json::Value) -> Vec<String> {
+    let mut changes = Vec::new();
+    let version = config_version(root);
+    let Some(object) = root.as_object_mut() else {
+        return changes;
+    };
+    if version >= CONFIG_VERSION {
+        return changes;
+    }
+
+    if version < 2 {
+        // v2: the `txt` spelling of `format` is written as `text`.
+        for_each_section(object, &mut |scope, section| {
+            if section.get("format").and_then(|v| v.as_str()) == Some("txt") {
+                section.insert("format".to_string(), serde_json::Value::String("text".to_string()));
+                changes.push(format!("{scope}format: \"txt\" -> \"text\""));
+            }
+        });
+    }
+
+    object.insert("configVersion".to_string(), serde_json::Value::from(CONFIG_VERSION));
+    changes
+}
+
+/// Calls `f` with the top level and every `presets.NAME` and `byExtension.EXT` object, and the key prefix naming it.
+fn for_each_section(
+    object: &mut serde_json::Map<String, serde_json::Value>,
+    f: &mut impl FnMut(&str, &mut serde_json::Map<String, serde_json::Value>),
+) {
+    f("", object);
+    for key in ["presets", "byExtension"] {
+        let Some(serde_json::Value::Object(entries)) = object.get_mut(key) else {
+            continue;
+        };
+        for (name, section) in entries.iter_mut() {
+            if let serde_json::Value::Object(section) = section {
+                f(&format!("{key}.{name}."), section);
+            }
+        }
+    }
+}
+
+fn overlay(root: &mut serde_json::Value, overrides: serde_json::Map<String, serde_json::Value>) {
+    let Some(object) = root.as_object_mut() else {
+        return;
+    };
+    for (key, value) in overrides {
+        if key != "presets" && key != "byExtension" {
+            object.insert(key, value);
+        }
+    }
+}
+
+fn preset_names(root: &serde_json::Value) -> String {
+    let names: Vec<&str> = root
+        .get("presets")
+        .and_then(|p| p.as_object())
+        .map(|p| p.keys().map(String::as_str).collect())
+        .unwrap_or_default();
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+/// A config key that [`ConfigFile`] doesn't read, and the known key it most likely misspells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownKey {
+    /// The key with its section prefix, e.g. `presets.podcast.speakerLables`.
+    pub path: String,
+    pub suggestion: Option<&'static str>,
+}
+
+impl std::fmt::Display for UnknownKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown config key `{}`", self.path)?;
+        if let Some(suggestion) = self.suggestion {
+            write!(f, " (did you mean `{suggestion}`?)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Every key of a config document, its presets, byExtension rules, and `lint` objects that no field reads.
+pub fn unknown_keys(root: &serde_json::Value) -> Vec<UnknownKey> {
+    let mut unknown = Vec::new();
+    let Some(object) = root.as_object() else {
+        return unknown;
+    };
+    let section_keys = struct_fields::<ConfigFile>();
+    let top_level_keys: Vec<&'static str> = section_keys.iter().copied().chain(["$schema", "presets", "byExtension"]).collect();
+    collect_unknown_keys("", object, &top_level_keys, &mut unknown);
+    for key in ["presets", "byExtension"] {
+        let Some(serde_json::Value::Object(entries)) = object.get(key) else {
+            continue;
+        };
+        for (name, section) in entries {
+            if let serde_json::Value::Object(section) = section {
+                collect_unknown_keys(&format!("{key}.{name}."), section, section_keys, &mut unknown);
+            }
+        }
+    }
+    unknown
+}
+
+fn collect_unknown_keys(
+    scope: &str,
+    object: &serde_json::Map<String, serde_json::Value>,
+    known: &[&'static str],
+    unknown: &mut Vec<UnknownKey>,
+) {
+    for (key, value) in object {
+        if !known.contains(&key.as_str()) {
+            unknown.push(UnknownKey {
+                path: format!("{scope}{key}"),
+                suggestion: closest_key(key, known),
+            });
+        } else if key == "lint"
+            && let serde_json::Value::Object(lint) = value
+        {
+            collect_unknown_keys(&format!("{scope}lint."), lint, struct_fields::<LintConfig>(), unknown);
+        }
+    }
+}
+
+/// The known key within two edits of `key`, ignoring case, `_`, and `-`.
+fn closest_key(key: &str, known: &[&'static str]) -> Option<&'static str> {
+    let key = key.replace(['_', '-'], "").to_ascii_lowercase();
+    known
+        .iter()
+        .map(|candidate| (strsim::damerau_levenshtein(&key, &candidate.to_ascii_lowercase()), *candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The JSON keys `T` reads, as `#[derive(Deserialize)]` lists them; keeps [`unknown_keys`] in step with the structs.
+fn struct_fields<T: for<'de> Deserialize<'de>>() -> &'static [&'static str] {
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+/// A deserializer that records the field list of the struct asked for and fails without reading anything.
+struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> serde::Deserializer<'de> for FieldNames<'_> {
+    type Error = serde::de::value::Error;
+
+    fn deserialize_any<V: serde::de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(serde::de::Error::custom("only struct field names are collected"))
+    }
+
+    fn deserialize_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        self.deserialize_any(visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option unit
+        unit_struct newtype_struct seq tuple tuple_struct map enum identifier ignored_any
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitOutputBy {
+    Chapter,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintConfig {
+    #[serde(default)]
+    pub max_cps: Option<f64>,
+
+    #[serde(default)]
+    pub max_line_length: Option<usize>,
+
+    #[serde(default)]
+    pub max_lines: Option<usize>,
+
+    #[serde(default)]
+    pub min_gap_ms: Option<u64>,
 }
 
 #[cfg(test)]
@@ -75,28 +527,83 @@ mod tests {
         {
           "apiKey": "abc",
           "baseUrl": "https://api.assemblyai.com",
+          "extraHeaders": {"X-Org-Id": "42"},
           "format": "vtt",
           "output": "out.vtt",
           "speechModel": "nano",
           "languageDetection": false,
           "language": "ru",
+          "expectLanguage": ["en", "de"],
           "punctuate": true,
           "formatText": true,
           "disfluencies": false,
           "filterProfanity": false,
           "speakerLabels": true,
           "multichannel": false,
+          "channelNames": {"1": "Agent", "2": "Customer"},
+          "splitByChannel": true,
+          "sentimentAnalysis": true,
+          "autoChapters": true,
+          "autoHighlights": true,
+          "extractAudio": false,
+          "audioProfile": "phone",
+          "skipValidation": true,
+          "maxUploadBytes": 1000000000,
+          "maxDurationMinutes": 240,
+          "onLimit": "warn",
+          "failOnEmpty": true,
+          "minWords": 20,
+          "minAvgConfidence": 0.6,
+          "concurrency": 8,
+          "entityDetection": true,
+          "frameRate": 25,
+          "timecodeStart": "01:00:00:00",
+          "embedMetadata": true,
+          "writeChapters": true,
+          "translateTo": "es",
+          "lemurModel": "anthropic/claude-sonnet-4-20250514",
           "speechThreshold": 0.2,
           "charsPerCaption": 256,
+          "maxCps": 17,
+          "srtStartIndex": 101,
+          "srtRenumber": true,
+          "splitOutputEvery": "30m",
+          "splitOutputBy": "chapter",
+          "check": true,
+          "lint": {"maxCps": 20, "maxLineLength": 37, "maxLines": 3, "minGapMs": 40},
           "wordBoost": ["one", "two"],
+          "keytermsPrompt": ["Kubernetes", "Jane Doe"],
           "customSpelling": [{"from":"a","to":"b"}],
+          "speakerStyle": "[{name}] ",
+          "speakerPrefix": false,
+          "mergeShortUtterances": "1500ms",
+          "paragraphGap": "2s",
+          "markUncertain": 0.5,
+          "redactTermsFile": "secrets.txt",
+          "redactStyle": "tag",
+          "normalize": ["quotes=straight", "decimal=de"],
+          "postReplace": [{"from":"acme (\\d+)","to":"ACME-$1","regex":true}],
+          "reports": ["keywords=a,b"],
+          "reportOutput": "report.txt",
+          "timestampFormat": "mm:ss",
+          "encoding": "utf-8-bom",
+          "lineEndings": "crlf",
+          "compress": "zstd",
+          "tee": "text",
+          "preview": 5,
+          "subtitleSource": "api",
+          "pollStrategy": "fixed",
           "pollIntervalSeconds": 2,
           "timeoutSeconds": 900
         }"#;
 
         let parsed: ConfigFile = serde_json::from_str(json).expect("parse config");
-        assert_eq!(parsed.api_key.as_deref(), Some("abc"));
+        assert_eq!(parsed.api_key.as_ref().map(ApiKey::expose), Some("abc"));
         assert_eq!(parsed.base_url.as_deref(), Some("https://api.assemblyai.com"));
+        assert_eq!(
+            parsed.extra_headers.as_ref().and_then(|headers| headers.get("X-Org-Id")).map(String::as_str),
+            Some("42")
+        );
         assert_eq!(parsed.format, Some(TranscriptFormat::Vtt));
         assert_eq!(
             parsed.output.as_ref().and_then(|p| p.to_str()),
@@ -105,18 +612,67 @@ mod tests {
         assert_eq!(parsed.speech_model, Some(SpeechModel::Nano));
         assert_eq!(parsed.language_detection, Some(false));
         assert_eq!(parsed.language.as_deref(), Some("ru"));
+        assert_eq!(
+            parsed.expect_language.as_deref(),
+            Some(&["en".to_string(), "de".to_string()][..])
+        );
         assert_eq!(parsed.punctuate, Some(true));
         assert_eq!(parsed.format_text, Some(true));
         assert_eq!(parsed.disfluencies, Some(false));
         assert_eq!(parsed.filter_profanity, Some(false));
         assert_eq!(parsed.speaker_labels, Some(true));
+        assert_eq!(parsed.speaker_style.as_deref(), Some("[{name}] "));
+        assert_eq!(parsed.speaker_prefix, Some(false));
+        assert_eq!(parsed.merge_short_utterances.as_deref(), Some("1500ms"));
+        assert_eq!(parsed.paragraph_gap.as_deref(), Some("2s"));
+        assert_eq!(parsed.mark_uncertain, Some(0.5));
         assert_eq!(parsed.multichannel, Some(false));
+        assert_eq!(
+            parsed.channel_names.as_ref().and_then(|names| names.get("2")).map(String::as_str),
+            Some("Customer")
+        );
+        assert_eq!(parsed.split_by_channel, Some(true));
+        assert_eq!(parsed.sentiment_analysis, Some(true));
+        assert_eq!(parsed.auto_chapters, Some(true));
+        assert_eq!(parsed.auto_highlights, Some(true));
+        assert_eq!(parsed.extract_audio, Some(false));
+        assert_eq!(parsed.audio_profile, Some(AudioProfile::Phone));
+        assert_eq!(parsed.skip_validation, Some(true));
+        assert_eq!(parsed.max_upload_bytes, Some(1_000_000_000));
+        assert_eq!(parsed.max_duration_minutes, Some(240.0));
+        assert_eq!(parsed.on_limit, Some(LimitAction::Warn));
+        assert_eq!(parsed.fail_on_empty, Some(true));
+        assert_eq!(parsed.min_words, Some(20));
+        assert_eq!(parsed.min_avg_confidence, Some(0.6));
+        assert_eq!(parsed.concurrency, Some(8));
+        assert_eq!(parsed.entity_detection, Some(true));
+        assert_eq!(parsed.frame_rate, Some(25));
+        assert_eq!(parsed.timecode_start.as_deref(), Some("01:00:00:00"));
+        assert_eq!(parsed.embed_metadata, Some(true));
+        assert_eq!(parsed.write_chapters, Some(true));
+        assert_eq!(parsed.translate_to.as_deref(), Some("es"));
+        assert_eq!(parsed.lemur_model.as_deref(), Some("anthropic/claude-sonnet-4-20250514"));
         assert_eq!(parsed.speech_threshold, Some(0.2));
         assert_eq!(parsed.chars_per_caption, Some(256));
+        assert_eq!(parsed.max_cps, Some(17.0));
+        assert_eq!(parsed.srt_start_index, Some(101));
+        assert_eq!(parsed.srt_renumber, Some(true));
+        assert_eq!(parsed.split_output_every.as_deref(), Some("30m"));
+        assert_eq!(parsed.split_output_by, Some(SplitOutputBy::Chapter));
+        assert_eq!(parsed.check, Some(true));
+        let lint = parsed.lint.expect("lint");
+        assert_eq!(lint.max_cps, Some(20.0));
+        assert_eq!(lint.max_line_length, Some(37));
+        assert_eq!(lint.max_lines, Some(3));
+        assert_eq!(lint.min_gap_ms, Some(40));
         assert_eq!(
             parsed.word_boost.as_deref(),
             Some(&["one".to_string(), "two".to_string()][..])
         );
+        assert_eq!(
+            parsed.keyterms_prompt.as_deref(),
+            Some(&["Kubernetes".to_string(), "Jane Doe".to_string()][..])
+        );
         assert_eq!(
             parsed.custom_spelling.as_deref(),
             Some(&[CustomSpelling {
@@ -124,7 +680,162 @@ mod tests {
                 to: "b".to_string()
             }][..])
         );
+        assert_eq!(
+            parsed.post_replace.as_deref(),
+            Some(&[PostReplace {
+                from: r"acme (\d+)".to_string(),
+                to: "ACME-$1".to_string(),
+                regex: true,
+                ignore_case: false,
+            }][..])
+        );
+        assert_eq!(
+            parsed.redact_terms_file.as_ref().and_then(|p| p.to_str()),
+            Some("secrets.txt")
+        );
+        assert_eq!(parsed.redact_style, Some(RedactStyle::Tag));
+        assert_eq!(
+            parsed.normalize.as_deref(),
+            Some(&["quotes=straight".to_string(), "decimal=de".to_string()][..])
+        );
+        assert_eq!(parsed.reports.as_deref(), Some(&["keywords=a,b".to_string()][..]));
+        assert_eq!(
+            parsed.report_output.as_ref().and_then(|p| p.to_str()),
+            Some("report.txt")
+        );
+        assert_eq!(parsed.timestamp_format, Some(TimestampFormat::MmSs));
+        assert_eq!(parsed.encoding, Some(TextEncoding::Utf8Bom));
+        assert_eq!(parsed.line_endings, Some(LineEndings::Crlf));
+        assert_eq!(parsed.compress, Some(Compression::Zstd));
+        assert_eq!(parsed.tee, Some(crate::domain::TeeFormat::Text));
+        assert_eq!(parsed.preview, Some(5));
+        assert_eq!(parsed.subtitle_source, Some(crate::domain::SubtitleSource::Api));
+        assert_eq!(parsed.poll_strategy, Some(PollStrategy::Fixed));
         assert_eq!(parsed.poll_interval_seconds, Some(2));
         assert_eq!(parsed.timeout_seconds, Some(900));
+        assert_eq!(unknown_keys(&serde_json::from_str(json).unwrap()), vec![]);
+    }
+
+    #[test]
+    fn reports_unknown_keys_with_suggestions() {
+        let config = parse_config_file(
+            r#"{"speakerLables":true,"speaker_labels":true,"lint":{"maxCPS":20},"presets":{"subs":{"fromat":"srt","zzz":1}}}"#,
+            None,
+            None,
+        )
+        .expect("unknown keys are not a parse error");
+        let found: Vec<(String, Option<&str>)> =
+            config.unknown_keys.iter().map(|key| (key.path.clone(), key.suggestion)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("lint.maxCPS".to_string(), Some("maxCps")),
+                ("speakerLables".to_string(), Some("speakerLabels")),
+                ("speaker_labels".to_string(), Some("speakerLabels")),
+                ("presets.subs.fromat".to_string(), Some("format")),
+                ("presets.subs.zzz".to_string(), None),
+            ]
+        );
+        assert_eq!(
+            config.unknown_keys[1].to_string(),
+            "unknown config key `speakerLables` (did you mean `speakerLabels`?)"
+        );
+    }
+
+    #[test]
+    fn schema_documents_every_key() {
+        let schema: serde_json::Value = serde_json::from_str(crate::domain::schema::CONFIG_SCHEMA).unwrap();
+        let keys = |properties: &serde_json::Value| {
+            let mut keys: Vec<String> = properties.as_object().unwrap().keys().cloned().collect();
+            keys.sort();
+            keys
+        };
+        let mut fields: Vec<String> = struct_fields::<ConfigFile>().iter().map(|key| key.to_string()).collect();
+        fields.sort();
+        assert_eq!(keys(&schema["$defs"]["settings"]["properties"]), fields);
+        let mut lint: Vec<String> = struct_fields::<LintConfig>().iter().map(|key| key.to_string()).collect();
+        lint.sort();
+        assert_eq!(keys(&schema["$defs"]["lint"]["properties"]), lint);
+
+        let err = parse_config_file(r#"{"$schema":"config.schema.json","presets":{"subs":{"srtRenumber":"yes"}}}"#, None, None)
+            .expect_err("schema violation");
+        assert_eq!(err.to_string(), "`presets.subs.srtRenumber` must be a boolean, not string");
+        assert!(parse_config_file(r#"{"$schema":"config.schema.json"}"#, None, None).unwrap().unknown_keys.is_empty());
+    }
+
+    #[test]
+    fn preset_overrides_top_level_keys() {
+        let json = r#"
+        {
+          "format": "srt",
+          "speakerLabels": false,
+          "language": "en",
+          "presets": {
+            "interview": { "speakerLabels": true, "format": "txt" }
+          }
+        }
+        "#;
+
+        let parsed = parse_config_file(json, Some("interview"), None).expect("preset applies");
+        assert_eq!(parsed.format, Some(TranscriptFormat::Text));
+        assert_eq!(parsed.speaker_labels, Some(true));
+        assert_eq!(parsed.language.as_deref(), Some("en"));
+
+        let base = parse_config_file(json, None, None).expect("config parses");
+        assert_eq!(base.format, Some(TranscriptFormat::Srt));
+
+        let err = parse_config_file(json, Some("podcast"), None).expect_err("unknown preset");
+        assert_eq!(err.to_string(), "unknown preset \"podcast\" (available: interview)");
+    }
+
+    #[test]
+    fn extension_rule_applies_before_preset() {
+        let json = r#"
+        {
+          "format": "text",
+          "byExtension": {
+            ".MP4": { "extractAudio": true, "format": "srt", "speakerLabels": true }
+          },
+          "presets": {
+            "quiet": { "speakerLabels": false }
+          }
+        }
+        "#;
+
+        let parsed = parse_config_file(json, None, Some("mp4")).expect("rule applies");
+        assert_eq!(parsed.format, Some(TranscriptFormat::Srt));
+        assert_eq!(parsed.extract_audio, Some(true));
+        assert_eq!(parsed.speaker_labels, Some(true));
+
+        let parsed = parse_config_file(json, Some("quiet"), Some("mp4")).expect("rule and preset apply");
+        assert_eq!(parsed.format, Some(TranscriptFormat::Srt));
+        assert_eq!(parsed.speaker_labels, Some(false));
+
+        let parsed = parse_config_file(json, None, Some("mp3")).expect("no rule");
+        assert_eq!(parsed.format, Some(TranscriptFormat::Text));
+    }
+
+    #[test]
+    fn migrates_version_1_configs() {
+        let mut root: serde_json::Value = serde_json::from_str(
+            r#"{"format":"txt","presets":{"notes":{"format":"txt"},"subs":{"format":"srt"}},"byExtension":{"mp4":{"format":"txt"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            migrate(&mut root),
+            [
+                "format: \"txt\" -> \"text\"",
+                "presets.notes.format: \"txt\" -> \"text\"",
+                "byExtension.mp4.format: \"txt\" -> \"text\"",
+            ]
+        );
+        assert_eq!(root["configVersion"], 2);
+        assert_eq!(root["presets"]["subs"]["format"], "srt");
+        assert!(migrate(&mut root).is_empty());
+
+        let parsed = parse_config_file(r#"{"format":"txt"}"#, None, None).unwrap();
+        assert_eq!(parsed.migrated_from, Some(1));
+        let parsed = parse_config_file(r#"{"format":"srt"}"#, None, None).unwrap();
+        assert_eq!(parsed.migrated_from, None);
     }
 }
@@ -0,0 +1,115 @@
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize)]
+pub enum TextEncoding {
+    #[default]
+    #[serde(rename = "utf-8", alias = "utf8")]
+    Utf8,
+    #[serde(rename = "utf-8-bom", alias = "utf8-bom")]
+    Utf8Bom,
+    #[serde(rename = "utf-16le", alias = "utf16le")]
+    Utf16Le,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEndings {
+    Lf,
+    Crlf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// `.gz` -> gzip, `.zst` -> zstd.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "gz" => Some(Compression::Gzip),
+            "zst" => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// Byte encoding of written outputs. Line endings are left as rendered unless set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OutputEncoding {
+    pub encoding: TextEncoding,
+    pub line_endings: Option<LineEndings>,
+    /// Set by `--compress`; otherwise files are compressed when their extension asks for it.
+    pub compression: Option<Compression>,
+}
+
+impl OutputEncoding {
+    pub fn compression_for(&self, path: &Path) -> Option<Compression> {
+        self.compression.or_else(|| Compression::from_path(path))
+    }
+
+    pub fn encode(&self, content: &str) -> Vec<u8> {
+        let content = match self.line_endings {
+            None => std::borrow::Cow::Borrowed(content),
+            Some(LineEndings::Lf) => std::borrow::Cow::Owned(content.replace("\r\n", "\n")),
+            Some(LineEndings::Crlf) => std::borrow::Cow::Owned(content.replace("\r\n", "\n").replace('\n', "\r\n")),
+        };
+
+        match self.encoding {
+            TextEncoding::Utf8 => content.into_owned().into_bytes(),
+            TextEncoding::Utf8Bom => {
+                let mut out = vec![0xEF, 0xBB, 0xBF];
+                out.extend_from_slice(content.trim_start_matches('\u{feff}').as_bytes());
+                out
+            }
+            TextEncoding::Utf16Le => {
+                let mut out = vec![0xFF, 0xFE];
+                for unit in content.trim_start_matches('\u{feff}').encode_utf16() {
+                    out.extend_from_slice(&unit.to_le_bytes());
+                }
+                out
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_with_bom_and_crlf() {
+        let encoding = OutputEncoding {
+            encoding: TextEncoding::Utf8Bom,
+            line_endings: Some(LineEndings::Crlf),
+            compression: None,
+        };
+        assert_eq!(encoding.encode("a\nb\r\n"), b"\xEF\xBB\xBFa\r\nb\r\n".to_vec());
+
+        let encoding = OutputEncoding {
+            encoding: TextEncoding::Utf16Le,
+            line_endings: Some(LineEndings::Lf),
+            compression: None,
+        };
+        assert_eq!(encoding.encode("\u{e9}\r\n"), vec![0xFF, 0xFE, 0xE9, 0x00, 0x0A, 0x00]);
+
+        assert_eq!(OutputEncoding::default().encode("x\r\n"), b"x\r\n".to_vec());
+    }
+
+    #[test]
+    fn infers_compression_from_extension_unless_set() {
+        let default = OutputEncoding::default();
+        assert_eq!(default.compression_for(Path::new("out.json.gz")), Some(Compression::Gzip));
+        assert_eq!(default.compression_for(Path::new("out.srt.ZST")), Some(Compression::Zstd));
+        assert_eq!(default.compression_for(Path::new("out.txt")), None);
+
+        let zstd = OutputEncoding {
+            compression: Some(Compression::Zstd),
+            ..Default::default()
+        };
+        assert_eq!(zstd.compression_for(Path::new("out.txt.gz")), Some(Compression::Zstd));
+    }
+}
@@ -0,0 +1,339 @@
+use crate::domain::reports::normalize_token;
+
+const ALIGNMENT_WIDTH: usize = 100;
+const ROW_LABEL_WIDTH: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditOp {
+    Match,
+    Substitution,
+    Deletion,
+    Insertion,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alignment<T> {
+    pub op: EditOp,
+    pub reference: Option<T>,
+    pub hypothesis: Option<T>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ErrorCounts {
+    pub matches: usize,
+    pub substitutions: usize,
+    pub deletions: usize,
+    pub insertions: usize,
+}
+
+impl ErrorCounts {
+    pub fn reference_len(&self) -> usize {
+        self.matches + self.substitutions + self.deletions
+    }
+
+    pub fn errors(&self) -> usize {
+        self.substitutions + self.deletions + self.insertions
+    }
+
+    /// Errors over reference length; an empty reference scores 0 only when the hypothesis is empty too.
+    pub fn rate(&self) -> f64 {
+        match self.reference_len() {
+            0 if self.errors() == 0 => 0.0,
+            0 => 1.0,
+            n => self.errors() as f64 / n as f64,
+        }
+    }
+
+    fn add(&mut self, op: EditOp) {
+        match op {
+            EditOp::Match => self.matches += 1,
+            EditOp::Substitution => self.substitutions += 1,
+            EditOp::Deletion => self.deletions += 1,
+            EditOp::Insertion => self.insertions += 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    pub words: Vec<Alignment<String>>,
+    pub wer: ErrorCounts,
+    pub cer: ErrorCounts,
+}
+
+/// Lowercased words with surrounding punctuation removed, so formatting differences don't count as errors.
+pub fn normalize_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(normalize_token)
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+pub fn evaluate(reference: &str, hypothesis: &str) -> Evaluation {
+    let reference = normalize_words(reference);
+    let hypothesis = normalize_words(hypothesis);
+
+    let words = align(&reference, &hypothesis);
+    let mut wer = ErrorCounts::default();
+    for pair in &words {
+        wer.add(pair.op);
+    }
+
+    let reference_chars: Vec<char> = reference.join(" ").chars().collect();
+    let hypothesis_chars: Vec<char> = hypothesis.join(" ").chars().collect();
+    let cer = edit_counts(&reference_chars, &hypothesis_chars);
+
+    Evaluation { words, wer, cer }
+}
+
+/// Minimum edit alignment (Levenshtein), preferring substitutions over deletions over insertions on ties.
+pub fn align<T: PartialEq + Clone>(reference: &[T], hypothesis: &[T]) -> Vec<Alignment<T>> {
+    let cols = hypothesis.len() + 1;
+    let mut ops = vec![EditOp::Match; (reference.len() + 1) * cols];
+    let mut prev: Vec<usize> = (0..cols).collect();
+    let mut cur = vec![0; cols];
+
+    for op in ops.iter_mut().take(cols).skip(1) {
+        *op = EditOp::Insertion;
+    }
+    for i in 1..=reference.len() {
+        cur[0] = i;
+        ops[i * cols] = EditOp::Deletion;
+        for j in 1..cols {
+            let (diag_op, diag_cost) = if reference[i - 1] == hypothesis[j - 1] {
+                (EditOp::Match, prev[j - 1])
+            } else {
+                (EditOp::Substitution, prev[j - 1] + 1)
+            };
+            let (op, cost) = [(EditOp::Deletion, prev[j] + 1), (EditOp::Insertion, cur[j - 1] + 1)]
+                .into_iter()
+                .fold((diag_op, diag_cost), |best, next| if next.1 < best.1 { next } else { best });
+            ops[i * cols + j] = op;
+            cur[j] = cost;
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+
+    let mut out = Vec::new();
+    let (mut i, mut j) = (reference.len(), hypothesis.len());
+    while i > 0 || j > 0 {
+        let op = ops[i * cols + j];
+        let (reference, hypothesis) = match op {
+            EditOp::Match | EditOp::Substitution => {
+                i -= 1;
+                j -= 1;
+                (Some(reference[i].clone()), Some(hypothesis[j].clone()))
+            }
+            EditOp::Deletion => {
+                i -= 1;
+                (Some(reference[i].clone()), None)
+            }
+            EditOp::Insertion => {
+                j -= 1;
+                (None, Some(hypothesis[j].clone()))
+            }
+        };
+        out.push(Alignment { op, reference, hypothesis });
+    }
+    out.reverse();
+    out
+}
+
+/// Same costs as [`align`] but keeps only two rows, for long character sequences.
+fn edit_counts<T: PartialEq>(reference: &[T], hypothesis: &[T]) -> ErrorCounts {
+    let cost = |c: &ErrorCounts| c.errors();
+    let mut prev: Vec<ErrorCounts> = (0..=hypothesis.len())
+        .map(|j| ErrorCounts {
+            insertions: j,
+            ..Default::default()
+        })
+        .collect();
+    let mut cur = prev.clone();
+
+    for i in 1..=reference.len() {
+        cur[0] = ErrorCounts {
+            deletions: i,
+            ..Default::default()
+        };
+        for j in 1..=hypothesis.len() {
+            let mut best = prev[j - 1];
+            best.add(if reference[i - 1] == hypothesis[j - 1] {
+                EditOp::Match
+            } else {
+                EditOp::Substitution
+            });
+            let mut deletion = prev[j];
+            deletion.add(EditOp::Deletion);
+            let mut insertion = cur[j - 1];
+            insertion.add(EditOp::Insertion);
+            for candidate in [deletion, insertion] {
+                if cost(&candidate) < cost(&best) {
+                    best = candidate;
+                }
+            }
+            cur[j] = best;
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+
+    prev[hypothesis.len()]
+}
+
+/// Summary lines followed by a REF/HYP/EVAL alignment; errors are uppercased and gaps shown as `***`.
+pub fn format_evaluation(evaluation: &Evaluation) -> String {
+    let mut out = String::new();
+    push_summary(&mut out, "WER", &evaluation.wer);
+    push_summary(&mut out, "CER", &evaluation.cer);
+    out.push_str(&format_alignment(&evaluation.words));
+    out
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelRun {
+    pub model: String,
+    pub text: String,
+    pub confidence: Option<f64>,
+}
+
+/// Summary table per model, then the word alignment of each model against the first one.
+/// WER is measured against `reference` when given, otherwise against the first model.
+pub fn format_model_comparison(runs: &[ModelRun], reference: Option<&str>) -> String {
+    let Some(baseline) = runs.first() else {
+        return String::new();
+    };
+
+    let wer_label = match reference {
+        Some(_) => "WER (reference)".to_string(),
+        None => format!("WER (vs {})", baseline.model),
+    };
+    let model_width = runs.iter().map(|r| r.model.len()).max().unwrap_or(0).max("MODEL".len());
+    let mut out = format!("{:<model_width$}  {:<10}  {:>6}  {wer_label}\n", "MODEL", "CONFIDENCE", "WORDS");
+    for run in runs {
+        let confidence = run.confidence.map(|c| format!("{c:.3}")).unwrap_or_else(|| "-".to_string());
+        let wer = evaluate(reference.unwrap_or(&baseline.text), &run.text).wer.rate() * 100.0;
+        out.push_str(&format!(
+            "{:<model_width$}  {confidence:<10}  {:>6}  {wer:.2}%\n",
+            run.model,
+            normalize_words(&run.text).len()
+        ));
+    }
+
+    for run in &runs[1..] {
+        out.push_str(&format!("\n== {} vs {} ==\n", baseline.model, run.model));
+        out.push_str(&format_alignment(&evaluate(&baseline.text, &run.text).words));
+    }
+    out
+}
+
+fn format_alignment(words: &[Alignment<String>]) -> String {
+    let mut out = String::new();
+    let mut blocks = Vec::new();
+    let mut rows = alignment_rows();
+    for pair in words {
+        let (reference, hypothesis, mark) = match pair.op {
+            EditOp::Match => (word(&pair.reference), word(&pair.hypothesis), ""),
+            EditOp::Substitution => (upper(&pair.reference), upper(&pair.hypothesis), "S"),
+            EditOp::Deletion => (upper(&pair.reference), "***".to_string(), "D"),
+            EditOp::Insertion => ("***".to_string(), upper(&pair.hypothesis), "I"),
+        };
+        let width = reference.chars().count().max(hypothesis.chars().count());
+        if rows[0].chars().count() + width + 1 > ALIGNMENT_WIDTH && rows[0].len() > ROW_LABEL_WIDTH {
+            blocks.push(std::mem::replace(&mut rows, alignment_rows()));
+        }
+        for (row, cell) in rows.iter_mut().zip([reference, hypothesis, mark.to_string()]) {
+            row.push(' ');
+            row.push_str(&format!("{cell:<width$}"));
+        }
+    }
+    if !words.is_empty() {
+        blocks.push(rows);
+    }
+
+    for block in blocks {
+        out.push('\n');
+        for row in block {
+            out.push_str(row.trim_end());
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn alignment_rows() -> [String; 3] {
+    ["REF:", "HYP:", "EVAL:"].map(|label| format!("{label:<ROW_LABEL_WIDTH$}"))
+}
+
+fn push_summary(out: &mut String, label: &str, counts: &ErrorCounts) {
+    out.push_str(&format!(
+        "{label}: {:.2}% (S={} D={} I={}, N={})\n",
+        counts.rate() * 100.0,
+        counts.substitutions,
+        counts.deletions,
+        counts.insertions,
+        counts.reference_len()
+    ));
+}
+
+fn word(value: &Option<String>) -> String {
+    value.as_deref().unwrap_or_default().to_string()
+}
+
+fn upper(value: &Option<String>) -> String {
+    value.as_deref().unwrap_or_default().to_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_substitutions_deletions_and_insertions() {
+        let evaluation = evaluate("The quick brown fox jumps.", "the quack brown fox really jumps");
+        assert_eq!(
+            evaluation.wer,
+            ErrorCounts {
+                matches: 4,
+                substitutions: 1,
+                deletions: 0,
+                insertions: 1,
+            }
+        );
+        assert!((evaluation.wer.rate() - 0.4).abs() < 1e-9);
+        assert_eq!(evaluation.cer.errors(), 8);
+    }
+
+    #[test]
+    fn empty_reference_rates() {
+        assert_eq!(evaluate("", "").wer.rate(), 0.0);
+        assert_eq!(evaluate("", "extra").wer.rate(), 1.0);
+    }
+
+    #[test]
+    fn formats_aligned_rows() {
+        let text = format_evaluation(&evaluate("a big cat sat", "a cat sat down"));
+        assert_eq!(
+            text,
+            "WER: 50.00% (S=0 D=1 I=1, N=4)\nCER: 61.54% (S=7 D=0 I=1, N=13)\n\nREF:  a BIG cat sat ***\nHYP:  a *** cat sat DOWN\nEVAL:   D           I\n"
+        );
+    }
+
+    #[test]
+    fn compares_models_against_the_first() {
+        let runs = vec![
+            ModelRun {
+                model: "best".to_string(),
+                text: "Hello world.".to_string(),
+                confidence: Some(0.95),
+            },
+            ModelRun {
+                model: "nano".to_string(),
+                text: "Hello word.".to_string(),
+                confidence: None,
+            },
+        ];
+        assert_eq!(
+            format_model_comparison(&runs, None),
+            "MODEL  CONFIDENCE   WORDS  WER (vs best)\nbest   0.950            2  0.00%\nnano   -                2  50.00%\n\n== best vs nano ==\n\nREF:  hello WORLD\nHYP:  hello WORD\nEVAL:       S\n"
+        );
+    }
+}
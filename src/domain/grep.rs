@@ -0,0 +1,157 @@
+//! `grep` and `index search`: searching transcripts, reported per sentence with its speaker and start time.
+
+use regex::{Regex, RegexBuilder};
+
+use crate::domain::transcript::{format_clock_time, Word};
+use crate::domain::DomainError;
+
+/// A sentence (or a speaker's part of one) containing a match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrepMatch {
+    /// Start of the first matching word; `None` for transcripts without word timings.
+    pub start_ms: Option<u64>,
+    pub speaker: Option<String>,
+    pub text: String,
+}
+
+pub fn build_pattern(pattern: &str, ignore_case: bool) -> Result<Regex, DomainError> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(ignore_case)
+        .build()
+        .map_err(|err| DomainError::InvalidGrepPattern {
+            pattern: pattern.to_string(),
+            message: err.to_string(),
+        })
+}
+
+/// `words` split into sentences, which also end where the speaker changes.
+pub fn sentences(words: &[Word]) -> Vec<&[Word]> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    for (idx, word) in words.iter().enumerate() {
+        let last = idx + 1 == words.len();
+        if last || ends_sentence(&word.text) || words[idx + 1].speaker != word.speaker {
+            sentences.push(&words[start..=idx]);
+            start = idx + 1;
+        }
+    }
+    sentences
+}
+
+/// The sentences of a transcript's `text`, for transcripts without words.
+pub fn text_sentences(text: &str) -> impl Iterator<Item = &str> {
+    text.split_inclusive(['.', '?', '!']).map(str::trim).filter(|sentence| !sentence.is_empty())
+}
+
+/// Matches of `pattern` in the [`sentences`] of `words`, so a match can span words but not speakers.
+pub fn grep_words(words: &[Word], pattern: &Regex) -> Vec<GrepMatch> {
+    let mut matches = Vec::new();
+    for sentence in sentences(words) {
+        let mut text = String::new();
+        let mut word_starts = Vec::with_capacity(sentence.len());
+        for word in sentence {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            word_starts.push(text.len());
+            text.push_str(&word.text);
+        }
+        if let Some(found) = pattern.find(&text) {
+            let matched_word = word_starts.partition_point(|&offset| offset <= found.start()) - 1;
+            matches.push(GrepMatch {
+                start_ms: Some(sentence[matched_word].start_ms),
+                speaker: sentence[0].speaker.clone(),
+                text,
+            });
+        }
+    }
+    matches
+}
+
+/// Matches of `pattern` in the [`text_sentences`] of a transcript's `text`.
+pub fn grep_text(text: &str, pattern: &Regex) -> Vec<GrepMatch> {
+    text_sentences(text)
+        .filter(|sentence| pattern.is_match(sentence))
+        .map(|sentence| GrepMatch {
+            start_ms: None,
+            speaker: None,
+            text: sentence.to_string(),
+        })
+        .collect()
+}
+
+/// `SOURCE:[hh:mm:ss] Speaker A: text`, leaving out what the transcript doesn't have.
+pub fn format_match(source: &str, found: &GrepMatch) -> String {
+    let mut line = format!("{source}:");
+    if let Some(start_ms) = found.start_ms {
+        line.push_str(&format!("[{}] ", format_clock_time(start_ms)));
+    }
+    if let Some(speaker) = &found.speaker {
+        line.push_str(&format!("Speaker {speaker}: "));
+    }
+    line.push_str(&found.text);
+    line
+}
+
+/// An `index search` query as SQLite FTS5 syntax. Words are quoted, so punctuation such as `don't` can't break the
+/// syntax: all must occur, in any order; `word*` matches a prefix; `OR`, `AND`, and `NOT` keep their meaning. A query
+/// with double quotes is already FTS5 syntax, e.g. a `"contract renewal"` phrase, and is kept as is.
+pub fn fts_query(query: &str) -> String {
+    if query.contains('"') {
+        return query.to_string();
+    }
+    query
+        .split_whitespace()
+        .map(|term| match term {
+            "OR" | "AND" | "NOT" => term.to_string(),
+            _ => match term.strip_suffix('*') {
+                Some(prefix) if !prefix.is_empty() => format!("\"{prefix}\"*"),
+                _ => format!("\"{term}\""),
+            },
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn ends_sentence(word: &str) -> bool {
+    word.trim_end_matches(['"', '\'', ')', '\u{201d}']).ends_with(['.', '?', '!'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(start_ms: u64, text: &str, speaker: &str) -> Word {
+        Word::new(start_ms, start_ms + 400, text.to_string(), None, Some(speaker.to_string())).expect("word")
+    }
+
+    #[test]
+    fn reports_matching_sentences_with_speaker_and_time() {
+        let words = [
+            word(0, "Let's", "A"),
+            word(500, "talk", "A"),
+            word(1_000, "pricing.", "A"),
+            word(2_000, "The", "A"),
+            word(2_500, "annual", "A"),
+            word(3_000, "plan", "B"),
+            word(3_500, "works.", "B"),
+        ];
+        let pattern = build_pattern(r"annual|plan\s+works", true).expect("pattern");
+        let found = grep_words(&words, &pattern);
+        assert_eq!(
+            found.iter().map(|m| format_match("tr_1", m)).collect::<Vec<_>>(),
+            ["tr_1:[00:00:02] Speaker A: The annual", "tr_1:[00:00:03] Speaker B: plan works."]
+        );
+
+        let found = grep_text("No words here. Only pricing? Yes!", &build_pattern("PRICING", true).expect("pattern"));
+        assert_eq!(format_match("call.json", &found[0]), "call.json:Only pricing?");
+        assert!(build_pattern("(", false).is_err());
+    }
+
+    #[test]
+    fn quotes_search_terms_for_fts() {
+        assert_eq!(fts_query("contract renewal"), "\"contract\" \"renewal\"");
+        assert_eq!(fts_query("don't renew* OR cancel"), "\"don't\" \"renew\"* OR \"cancel\"");
+        assert_eq!(fts_query("\"contract renewal\" NOT draft"), "\"contract renewal\" NOT draft");
+    }
+}
@@ -0,0 +1,211 @@
+use std::ffi::{OsStr, OsString};
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::DomainError;
+
+/// One recorded transcription run, stored as a line of `history.jsonl`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub id: u64,
+    /// Unix seconds when the run started.
+    pub started_at: u64,
+    pub duration_ms: u64,
+    #[serde(with = "crate::domain::os_string")]
+    pub input: OsString,
+    /// Command-line arguments after the program name; `history rerun` replays them.
+    #[serde(with = "crate::domain::os_string::vec")]
+    pub args: Vec<OsString>,
+    /// Hash of `args` without the input, so runs with the same options can be grouped.
+    pub options_hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcript_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// `ok`, or the error message.
+    pub result: String,
+}
+
+impl HistoryEntry {
+    /// Tags compare case-insensitively.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag.trim()))
+    }
+
+    /// Case-insensitive match on the input, transcript id, outputs, arguments, and result.
+    pub fn matches(&self, term: &str) -> bool {
+        let term = term.to_lowercase();
+        let paths = std::iter::once(&self.input).chain(&self.args).map(|field| field.to_string_lossy());
+        let texts = self
+            .transcript_id
+            .iter()
+            .chain(&self.outputs)
+            .chain(std::iter::once(&self.result))
+            .map(|field| std::borrow::Cow::from(field.as_str()));
+        paths.chain(texts).any(|field| field.to_lowercase().contains(&term))
+    }
+}
+
+/// Trims `--tag` values and drops duplicates; tags must be non-empty and free of whitespace and commas.
+pub fn parse_tags(values: &[String]) -> Result<Vec<String>, DomainError> {
+    let mut tags: Vec<String> = Vec::new();
+    for value in values {
+        let tag = value.trim();
+        if tag.is_empty() || tag.contains(|c: char| c.is_whitespace() || c == ',') {
+            return Err(DomainError::InvalidTag { value: value.clone() });
+        }
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    Ok(tags)
+}
+
+/// FNV-1a over the arguments (minus the input), as 16 hex digits.
+pub fn options_hash(args: &[OsString], input: &OsStr) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for arg in args.iter().filter(|arg| *arg != input) {
+        for byte in arg.as_encoded_bytes().iter().copied().chain(std::iter::once(0)) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    format!("{hash:016x}")
+}
+
+/// `YYYY-MM-DD HH:MM` (UTC) for unix seconds.
+pub fn format_unix_time(seconds: u64) -> String {
+    let (year, month, day) = civil_date(seconds / 86_400);
+    let minute_of_day = seconds % 86_400 / 60;
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+        minute_of_day / 60,
+        minute_of_day % 60
+    )
+}
+
+/// RFC 3339 UTC timestamp with milliseconds, e.g. `2025-03-01T09:30:05.250Z`, for unix milliseconds.
+pub fn format_rfc3339_millis(millis: u64) -> String {
+    let seconds = millis / 1000;
+    let (year, month, day) = civil_date(seconds / 86_400);
+    let second_of_day = seconds % 86_400;
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        second_of_day / 3600,
+        second_of_day % 3600 / 60,
+        second_of_day % 60,
+        millis % 1000
+    )
+}
+
+/// Civil date from days since 1970-01-01 (Howard Hinnant's algorithm).
+fn civil_date(days: u64) -> (i64, i64, i64) {
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// One line per run, newest first.
+pub fn format_history(entries: &[&HistoryEntry]) -> String {
+    let mut out = String::new();
+    for entry in entries.iter().rev() {
+        let mut line = format!(
+            "{:>4}  {}  {:>7.1}s  {:<8}  {}  {}",
+            entry.id,
+            format_unix_time(entry.started_at),
+            entry.duration_ms as f64 / 1000.0,
+            entry.options_hash.get(..8).unwrap_or(&entry.options_hash),
+            entry.transcript_id.as_deref().unwrap_or("-"),
+            entry.input.display(),
+        );
+        if !entry.outputs.is_empty() {
+            line.push_str(" -> ");
+            line.push_str(&entry.outputs.join(", "));
+        }
+        for tag in &entry.tags {
+            line.push_str(" #");
+            line.push_str(tag);
+        }
+        if entry.result != "ok" {
+            line.push_str("  [");
+            line.push_str(&entry.result);
+            line.push(']');
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u64, result: &str) -> HistoryEntry {
+        HistoryEntry {
+            id,
+            started_at: 1_760_605_440,
+            duration_ms: 12_340,
+            input: "call.mp3".into(),
+            args: ["transcribe", "call.mp3", "--format", "srt"].map(OsString::from).to_vec(),
+            options_hash: "0123456789abcdef".to_string(),
+            transcript_id: Some(format!("tr_{id}")),
+            outputs: vec!["call.srt".to_string()],
+            tags: Vec::new(),
+            result: result.to_string(),
+        }
+    }
+
+    #[test]
+    fn formats_unix_time_as_utc() {
+        assert_eq!(format_unix_time(0), "1970-01-01 00:00");
+        assert_eq!(format_unix_time(1_760_605_440), "2025-10-16 09:04");
+        assert_eq!(format_unix_time(951_782_400), "2000-02-29 00:00");
+        assert_eq!(format_rfc3339_millis(1_760_605_445_250), "2025-10-16T09:04:05.250Z");
+    }
+
+    #[test]
+    fn lists_newest_first_and_searches_fields() {
+        let mut ok = entry(1, "ok");
+        ok.tags = vec!["client-x".to_string()];
+        let failed = entry(2, "API error: bad audio");
+        assert_eq!(
+            format_history(&[&ok, &failed]),
+            "   2  2025-10-16 09:04     12.3s  01234567  tr_2  call.mp3 -> call.srt  [API error: bad audio]\n\
+                 \x20  1  2025-10-16 09:04     12.3s  01234567  tr_1  call.mp3 -> call.srt #client-x\n"
+        );
+        assert!(ok.has_tag("CLIENT-X"));
+        assert!(!failed.has_tag("client-x"));
+        assert!(ok.matches("TR_1"));
+        assert!(ok.matches("srt"));
+        assert!(!ok.matches("interview"));
+    }
+
+    #[test]
+    fn parses_tags() {
+        let tags = ["client-x", " q3 ", "Q3"].map(String::from);
+        assert_eq!(parse_tags(&tags).unwrap(), ["client-x", "q3"]);
+        assert!(matches!(parse_tags(&["a b".to_string()]), Err(DomainError::InvalidTag { .. })));
+        assert!(matches!(parse_tags(&[String::new()]), Err(DomainError::InvalidTag { .. })));
+    }
+
+    #[test]
+    fn options_hash_ignores_the_input() {
+        let args = |input: &str, format: &str| {
+            ["transcribe", input, "--format", format].map(OsString::from).to_vec()
+        };
+        let hash = |input: &str, format: &str| options_hash(&args(input, format), OsStr::new(input));
+        assert_eq!(hash("a.mp3", "srt"), hash("b.mp3", "srt"));
+        assert_ne!(hash("a.mp3", "srt"), hash("a.mp3", "vtt"));
+    }
+}
@@ -0,0 +1,136 @@
+use std::ffi::OsString;
+
+use serde::{Deserialize, Serialize};
+
+/// Batches still in progress, stored as `jobs.json` so an interrupted run can resume.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobStore {
+    pub batches: Vec<Batch>,
+}
+
+/// One batch run and the progress of each of its inputs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Batch {
+    /// [`batch_id`] of the command that started the batch.
+    pub id: String,
+    #[serde(with = "crate::domain::os_string::vec")]
+    pub args: Vec<OsString>,
+    pub jobs: Vec<Job>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Job {
+    #[serde(with = "crate::domain::os_string")]
+    pub input: OsString,
+    #[serde(flatten)]
+    pub state: JobState,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum JobState {
+    Pending,
+    /// The transcript was created; a resumed batch polls it instead of uploading again.
+    Submitted {
+        transcript_id: String,
+        /// SHA-256 of the uploaded bytes, when the input was uploaded.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content_sha256: Option<String>,
+    },
+    Done {
+        transcript_id: String,
+        /// The detected language, when the batch ran with language detection.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        language_code: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content_sha256: Option<String>,
+    },
+    Failed { error: String, exit_code: u8 },
+}
+
+impl JobStore {
+    pub fn batch(&self, id: &str) -> Option<&Batch> {
+        self.batches.iter().find(|batch| batch.id == id)
+    }
+
+    /// Records `batch`, replacing an earlier one with the same id.
+    pub fn start(&mut self, batch: Batch) {
+        self.finish(&batch.id);
+        self.batches.push(batch);
+    }
+
+    /// Sets the state of input `index` of batch `id`; false when there is no such job.
+    pub fn update(&mut self, id: &str, index: usize, state: JobState) -> bool {
+        let job = self
+            .batches
+            .iter_mut()
+            .find(|batch| batch.id == id)
+            .and_then(|batch| batch.jobs.get_mut(index));
+        match job {
+            Some(job) => {
+                job.state = state;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn finish(&mut self, id: &str) {
+        self.batches.retain(|batch| batch.id != id);
+    }
+
+    /// Ids of the transcripts created by inputs of unfinished batches.
+    pub fn transcript_ids(&self) -> impl Iterator<Item = &str> {
+        self.batches.iter().flat_map(|batch| &batch.jobs).filter_map(|job| match &job.state {
+            JobState::Submitted { transcript_id, .. } | JobState::Done { transcript_id, .. } => Some(transcript_id.as_str()),
+            JobState::Pending | JobState::Failed { .. } => None,
+        })
+    }
+}
+
+/// Identifies a batch by the command line and the directory it ran in, so running the same command again resumes it.
+pub fn batch_id(cwd: &str, args: &[OsString]) -> String {
+    let mut key = vec![OsString::from(cwd)];
+    key.extend_from_slice(args);
+    crate::domain::history::options_hash(&key, "".as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_job_states_per_batch() {
+        let mut store = JobStore::default();
+        let args = ["transcribe", "a.mp3"].map(OsString::from).to_vec();
+        let id = batch_id("/srv", &args);
+        assert_ne!(id, batch_id("/home", &args));
+        store.start(Batch {
+            id: id.clone(),
+            args,
+            jobs: vec![Job {
+                input: "a.mp3".into(),
+                state: JobState::Pending,
+            }],
+        });
+        let submitted = JobState::Submitted {
+            transcript_id: "tr_1".to_string(),
+            content_sha256: Some("ab12".to_string()),
+        };
+        assert!(store.update(&id, 0, submitted));
+        assert!(!store.update(&id, 1, JobState::Pending));
+
+        let json = serde_json::to_string(&store).unwrap();
+        assert!(
+            json.contains(r#""jobs":[{"input":"a.mp3","state":"submitted","transcriptId":"tr_1","contentSha256":"ab12"}]"#),
+            "{json}"
+        );
+        let parsed: JobStore = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, store);
+        assert_eq!(store.transcript_ids().collect::<Vec<_>>(), ["tr_1"]);
+
+        store.finish(&id);
+        assert!(store.batch(&id).is_none());
+    }
+}
@@ -0,0 +1,340 @@
+use crate::domain::{DomainError, SpeechModel};
+
+/// Transcription options whose availability depends on the language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    SpeakerLabels,
+    Punctuation,
+    FormatText,
+    Disfluencies,
+    FilterProfanity,
+    SentimentAnalysis,
+    AutoChapters,
+    AutoHighlights,
+    EntityDetection,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 9] = [
+        Feature::SpeakerLabels,
+        Feature::Punctuation,
+        Feature::FormatText,
+        Feature::Disfluencies,
+        Feature::FilterProfanity,
+        Feature::SentimentAnalysis,
+        Feature::AutoChapters,
+        Feature::AutoHighlights,
+        Feature::EntityDetection,
+    ];
+
+    /// CLI flag name, without the leading dashes.
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::SpeakerLabels => "speaker-labels",
+            Feature::Punctuation => "punctuate",
+            Feature::FormatText => "format-text",
+            Feature::Disfluencies => "disfluencies",
+            Feature::FilterProfanity => "filter-profanity",
+            Feature::SentimentAnalysis => "sentiment-analysis",
+            Feature::AutoChapters => "auto-chapters",
+            Feature::AutoHighlights => "auto-highlights",
+            Feature::EntityDetection => "entity-detection",
+        }
+    }
+}
+
+/// Support level of a language: English has every model and feature, high-accuracy languages run on
+/// best and nano with diarization and entity detection, the rest are nano-only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tier {
+    English,
+    HighAccuracy,
+    Nano,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LanguageInfo {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub tier: Tier,
+}
+
+impl LanguageInfo {
+    pub fn supports(&self, feature: Feature) -> bool {
+        match feature {
+            Feature::Punctuation | Feature::FormatText | Feature::FilterProfanity => true,
+            Feature::SpeakerLabels | Feature::EntityDetection => self.tier != Tier::Nano,
+            Feature::Disfluencies
+            | Feature::SentimentAnalysis
+            | Feature::AutoChapters
+            | Feature::AutoHighlights => self.tier == Tier::English,
+        }
+    }
+
+    pub fn supports_model(&self, model: SpeechModel) -> bool {
+        match model {
+            SpeechModel::Nano => true,
+            SpeechModel::Best => self.tier != Tier::Nano,
+            SpeechModel::Slam1 => self.tier == Tier::English,
+        }
+    }
+
+    pub fn models(&self) -> Vec<SpeechModel> {
+        [SpeechModel::Best, SpeechModel::Nano, SpeechModel::Slam1]
+            .into_iter()
+            .filter(|model| self.supports_model(*model))
+            .collect()
+    }
+
+    pub fn features(&self) -> Vec<Feature> {
+        Feature::ALL.into_iter().filter(|feature| self.supports(*feature)).collect()
+    }
+}
+
+const fn lang(code: &'static str, name: &'static str, tier: Tier) -> LanguageInfo {
+    LanguageInfo { code, name, tier }
+}
+
+/// Language codes accepted by the API (`language_code`).
+pub const LANGUAGES: &[LanguageInfo] = &[
+    lang("en", "English (global)", Tier::English),
+    lang("en_au", "English (Australian)", Tier::English),
+    lang("en_uk", "English (British)", Tier::English),
+    lang("en_us", "English (US)", Tier::English),
+    lang("es", "Spanish", Tier::HighAccuracy),
+    lang("fr", "French", Tier::HighAccuracy),
+    lang("de", "German", Tier::HighAccuracy),
+    lang("it", "Italian", Tier::HighAccuracy),
+    lang("pt", "Portuguese", Tier::HighAccuracy),
+    lang("nl", "Dutch", Tier::HighAccuracy),
+    lang("hi", "Hindi", Tier::HighAccuracy),
+    lang("ja", "Japanese", Tier::HighAccuracy),
+    lang("zh", "Chinese", Tier::HighAccuracy),
+    lang("fi", "Finnish", Tier::HighAccuracy),
+    lang("ko", "Korean", Tier::HighAccuracy),
+    lang("pl", "Polish", Tier::HighAccuracy),
+    lang("ru", "Russian", Tier::HighAccuracy),
+    lang("tr", "Turkish", Tier::HighAccuracy),
+    lang("uk", "Ukrainian", Tier::HighAccuracy),
+    lang("vi", "Vietnamese", Tier::HighAccuracy),
+    lang("af", "Afrikaans", Tier::Nano),
+    lang("am", "Amharic", Tier::Nano),
+    lang("ar", "Arabic", Tier::Nano),
+    lang("as", "Assamese", Tier::Nano),
+    lang("az", "Azerbaijani", Tier::Nano),
+    lang("ba", "Bashkir", Tier::Nano),
+    lang("be", "Belarusian", Tier::Nano),
+    lang("bg", "Bulgarian", Tier::Nano),
+    lang("bn", "Bengali", Tier::Nano),
+    lang("bo", "Tibetan", Tier::Nano),
+    lang("br", "Breton", Tier::Nano),
+    lang("bs", "Bosnian", Tier::Nano),
+    lang("ca", "Catalan", Tier::Nano),
+    lang("cs", "Czech", Tier::Nano),
+    lang("cy", "Welsh", Tier::Nano),
+    lang("da", "Danish", Tier::Nano),
+    lang("el", "Greek", Tier::Nano),
+    lang("et", "Estonian", Tier::Nano),
+    lang("eu", "Basque", Tier::Nano),
+    lang("fa", "Persian", Tier::Nano),
+    lang("fo", "Faroese", Tier::Nano),
+    lang("gl", "Galician", Tier::Nano),
+    lang("gu", "Gujarati", Tier::Nano),
+    lang("ha", "Hausa", Tier::Nano),
+    lang("haw", "Hawaiian", Tier::Nano),
+    lang("he", "Hebrew", Tier::Nano),
+    lang("hr", "Croatian", Tier::Nano),
+    lang("ht", "Haitian Creole", Tier::Nano),
+    lang("hu", "Hungarian", Tier::Nano),
+    lang("hy", "Armenian", Tier::Nano),
+    lang("id", "Indonesian", Tier::Nano),
+    lang("is", "Icelandic", Tier::Nano),
+    lang("jw", "Javanese", Tier::Nano),
+    lang("ka", "Georgian", Tier::Nano),
+    lang("kk", "Kazakh", Tier::Nano),
+    lang("km", "Khmer", Tier::Nano),
+    lang("kn", "Kannada", Tier::Nano),
+    lang("la", "Latin", Tier::Nano),
+    lang("lb", "Luxembourgish", Tier::Nano),
+    lang("ln", "Lingala", Tier::Nano),
+    lang("lo", "Lao", Tier::Nano),
+    lang("lt", "Lithuanian", Tier::Nano),
+    lang("lv", "Latvian", Tier::Nano),
+    lang("mg", "Malagasy", Tier::Nano),
+    lang("mi", "Maori", Tier::Nano),
+    lang("mk", "Macedonian", Tier::Nano),
+    lang("ml", "Malayalam", Tier::Nano),
+    lang("mn", "Mongolian", Tier::Nano),
+    lang("mr", "Marathi", Tier::Nano),
+    lang("ms", "Malay", Tier::Nano),
+    lang("mt", "Maltese", Tier::Nano),
+    lang("my", "Myanmar", Tier::Nano),
+    lang("ne", "Nepali", Tier::Nano),
+    lang("nn", "Norwegian Nynorsk", Tier::Nano),
+    lang("no", "Norwegian", Tier::Nano),
+    lang("oc", "Occitan", Tier::Nano),
+    lang("pa", "Panjabi", Tier::Nano),
+    lang("ps", "Pashto", Tier::Nano),
+    lang("ro", "Romanian", Tier::Nano),
+    lang("sa", "Sanskrit", Tier::Nano),
+    lang("sd", "Sindhi", Tier::Nano),
+    lang("si", "Sinhala", Tier::Nano),
+    lang("sk", "Slovak", Tier::Nano),
+    lang("sl", "Slovenian", Tier::Nano),
+    lang("sn", "Shona", Tier::Nano),
+    lang("so", "Somali", Tier::Nano),
+    lang("sq", "Albanian", Tier::Nano),
+    lang("sr", "Serbian", Tier::Nano),
+    lang("su", "Sundanese", Tier::Nano),
+    lang("sv", "Swedish", Tier::Nano),
+    lang("sw", "Swahili", Tier::Nano),
+    lang("ta", "Tamil", Tier::Nano),
+    lang("te", "Telugu", Tier::Nano),
+    lang("tg", "Tajik", Tier::Nano),
+    lang("th", "Thai", Tier::Nano),
+    lang("tk", "Turkmen", Tier::Nano),
+    lang("tl", "Tagalog", Tier::Nano),
+    lang("tt", "Tatar", Tier::Nano),
+    lang("ur", "Urdu", Tier::Nano),
+    lang("uz", "Uzbek", Tier::Nano),
+    lang("yi", "Yiddish", Tier::Nano),
+    lang("yo", "Yoruba", Tier::Nano),
+    lang("yue", "Cantonese", Tier::Nano),
+];
+
+/// The API's language when none is given and detection is off.
+pub const DEFAULT_LANGUAGE: &str = "en_us";
+
+/// Case-insensitive lookup; `-` is accepted for `_` (`en-US`).
+pub fn find_language(code: &str) -> Option<&'static LanguageInfo> {
+    let code = code.trim().replace('-', "_").to_ascii_lowercase();
+    LANGUAGES.iter().find(|l| l.code == code)
+}
+
+/// Whether a detected code is one of `expected`; a bare code (`en`) also accepts its regional
+/// variants (`en_us`).
+pub fn is_expected_language(detected: &str, expected: &[String]) -> bool {
+    let detected = detected.replace('-', "_").to_ascii_lowercase();
+    let base = detected.split('_').next().unwrap_or_default();
+    expected.iter().any(|code| *code == detected || *code == base)
+}
+
+/// Fails on language codes the API doesn't know, and on models/features the language doesn't support.
+pub fn check_language_support(
+    code: &str,
+    model: SpeechModel,
+    features: &[Feature],
+) -> Result<&'static LanguageInfo, DomainError> {
+    let info = find_language(code).ok_or_else(|| DomainError::UnknownLanguage {
+        code: code.to_string(),
+    })?;
+    if !info.supports_model(model) {
+        return Err(DomainError::LanguageUnsupportedByModel {
+            language: info.code,
+            model: model.name(),
+        });
+    }
+    if let Some(feature) = features.iter().find(|f| !info.supports(**f)) {
+        return Err(DomainError::FeatureUnsupportedForLanguage {
+            feature: feature.name(),
+            language: info.code,
+        });
+    }
+    Ok(info)
+}
+
+/// Languages supporting every one of `features`, in table order.
+pub fn languages_with(features: &[Feature]) -> Vec<&'static LanguageInfo> {
+    LANGUAGES
+        .iter()
+        .filter(|info| features.iter().all(|feature| info.supports(*feature)))
+        .collect()
+}
+
+/// One aligned row per language: code, name, speech models, supported features.
+pub fn format_languages(languages: &[&LanguageInfo]) -> String {
+    let code_width = languages.iter().map(|l| l.code.len()).max().unwrap_or(0).max("CODE".len());
+    let name_width = languages.iter().map(|l| l.name.len()).max().unwrap_or(0).max("NAME".len());
+    let join = |names: Vec<&str>| names.join(",");
+    let models_of = |l: &LanguageInfo| join(l.models().into_iter().map(SpeechModel::name).collect());
+    let models_width = languages.iter().map(|l| models_of(l).len()).max().unwrap_or(0).max("MODELS".len());
+
+    let mut out = format!("{:code_width$}  {:name_width$}  {:models_width$}  FEATURES\n", "CODE", "NAME", "MODELS");
+    for l in languages {
+        let features = join(l.features().into_iter().map(Feature::name).collect());
+        out.push_str(&format!(
+            "{:code_width$}  {:name_width$}  {:models_width$}  {features}\n",
+            l.code,
+            l.name,
+            models_of(l)
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_codes_case_and_separator_insensitively() {
+        assert_eq!(find_language("en-US").map(|l| l.code), Some("en_us"));
+        assert_eq!(find_language("RU").map(|l| l.name), Some("Russian"));
+        assert!(find_language("ru_ru").is_none());
+    }
+
+    #[test]
+    fn checks_model_and_feature_support() {
+        assert!(check_language_support("de", SpeechModel::Best, &[Feature::SpeakerLabels]).is_ok());
+        assert!(matches!(
+            check_language_support("de", SpeechModel::Best, &[Feature::AutoChapters]),
+            Err(DomainError::FeatureUnsupportedForLanguage {
+                feature: "auto-chapters",
+                language: "de"
+            })
+        ));
+        assert!(matches!(
+            check_language_support("sv", SpeechModel::Best, &[]),
+            Err(DomainError::LanguageUnsupportedByModel { language: "sv", .. })
+        ));
+        assert!(check_language_support("sv", SpeechModel::Nano, &[]).is_ok());
+        assert!(matches!(
+            check_language_support("fr", SpeechModel::Slam1, &[]),
+            Err(DomainError::LanguageUnsupportedByModel {
+                model: "slam-1",
+                ..
+            })
+        ));
+        assert!(matches!(
+            check_language_support("xx", SpeechModel::Nano, &[]),
+            Err(DomainError::UnknownLanguage { .. })
+        ));
+    }
+
+    #[test]
+    fn matches_detected_language_against_expected_set() {
+        let expected = vec!["en".to_string(), "de".to_string()];
+        assert!(is_expected_language("en_us", &expected));
+        assert!(is_expected_language("de", &expected));
+        assert!(!is_expected_language("es", &expected));
+        assert!(!is_expected_language("en", &["en_uk".to_string()]));
+    }
+
+    #[test]
+    fn lists_languages_with_a_feature() {
+        let english = languages_with(&[Feature::AutoChapters]);
+        assert_eq!(english.iter().map(|l| l.code).collect::<Vec<_>>(), ["en", "en_au", "en_uk", "en_us"]);
+
+        let diarized = languages_with(&[Feature::SpeakerLabels]);
+        assert!(diarized.iter().any(|l| l.code == "ru"));
+        assert!(!diarized.iter().any(|l| l.code == "sv"));
+
+        let table = format_languages(&[find_language("ru").unwrap(), find_language("sv").unwrap()]);
+        assert_eq!(
+            table,
+            "CODE  NAME     MODELS     FEATURES\n\
+             ru    Russian  best,nano  speaker-labels,punctuate,format-text,filter-profanity,entity-detection\n\
+             sv    Swedish  nano       punctuate,format-text,filter-profanity\n"
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use crate::domain::subtitles::Cue;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LintOptions {
+    pub max_cps: f64,
+    pub max_line_chars: usize,
+    pub max_lines: usize,
+    pub min_gap_ms: u64,
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        Self {
+            max_cps: 17.0,
+            max_line_chars: 42,
+            max_lines: 2,
+            min_gap_ms: 80,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub line: usize,
+    pub rule: &'static str,
+    pub message: String,
+}
+
+pub fn lint_cues(cues: &[Cue], options: &LintOptions) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    for (idx, cue) in cues.iter().enumerate() {
+        if cue.end_ms <= cue.start_ms {
+            violations.push(Violation {
+                line: cue.line,
+                rule: "timing",
+                message: "cue ends before it starts".to_string(),
+            });
+        }
+
+        if let Some(prev) = idx.checked_sub(1).map(|p| &cues[p]) {
+            if cue.start_ms < prev.end_ms {
+                violations.push(Violation {
+                    line: cue.line,
+                    rule: "overlap",
+                    message: format!("overlaps previous cue (line {}) by {}ms", prev.line, prev.end_ms - cue.start_ms),
+                });
+            } else {
+                let gap = cue.start_ms - prev.end_ms;
+                if gap > 0 && gap < options.min_gap_ms {
+                    violations.push(Violation {
+                        line: cue.line,
+                        rule: "gap",
+                        message: format!("gap of {gap}ms after previous cue is below {}ms", options.min_gap_ms),
+                    });
+                }
+            }
+        }
+
+        let chars: usize = cue.lines.iter().map(|l| visible_chars(l)).sum();
+        let duration_ms = cue.end_ms.saturating_sub(cue.start_ms);
+        if duration_ms > 0 {
+            let cps = chars as f64 * 1000.0 / duration_ms as f64;
+            if cps > options.max_cps {
+                violations.push(Violation {
+                    line: cue.line,
+                    rule: "reading-speed",
+                    message: format!("{cps:.1} characters per second exceeds {}", options.max_cps),
+                });
+            }
+        }
+
+        if cue.lines.len() > options.max_lines {
+            violations.push(Violation {
+                line: cue.line,
+                rule: "line-count",
+                message: format!("{} lines exceeds {}", cue.lines.len(), options.max_lines),
+            });
+        }
+
+        for (offset, text) in cue.lines.iter().enumerate() {
+            let len = visible_chars(text);
+            if len > options.max_line_chars {
+                violations.push(Violation {
+                    line: cue.line + 1 + offset,
+                    rule: "line-length",
+                    message: format!("{len} characters exceeds {}", options.max_line_chars),
+                });
+            }
+        }
+    }
+
+    violations
+}
+
+fn visible_chars(line: &str) -> usize {
+    let mut count = 0;
+    let mut in_tag = false;
+    for c in line.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => count += 1,
+            _ => {}
+        }
+    }
+    count
+}
+
+pub fn format_violations(source: &str, violations: &[Violation]) -> String {
+    let mut out = String::new();
+    for v in violations {
+        out.push_str(&format!("{source}:{}: {}: {}\n", v.line, v.rule, v.message));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::subtitles::parse_cues;
+
+    #[test]
+    fn reports_overlaps_gaps_speed_and_length() {
+        let srt = "\
+1
+00:00:00,000 --> 00:00:02,000
+Short line
+
+2
+00:00:01,500 --> 00:00:03,000
+<i>This line is far too long to be read comfortably on screen</i>
+
+3
+00:00:03,040 --> 00:00:05,000
+ok
+";
+        let cues = parse_cues(srt).expect("parse");
+        let violations = lint_cues(&cues, &LintOptions::default());
+        let rules: Vec<(usize, &str)> = violations.iter().map(|v| (v.line, v.rule)).collect();
+        assert_eq!(
+            rules,
+            vec![(6, "overlap"), (6, "reading-speed"), (7, "line-length"), (10, "gap")]
+        );
+    }
+
+    #[test]
+    fn clean_captions_have_no_violations() {
+        let srt = "1\n00:00:00,000 --> 00:00:02,000\nHello there\n\n2\n00:00:02,000 --> 00:00:04,000\nGeneral Kenobi\n";
+        let cues = parse_cues(srt).expect("parse");
+        assert!(lint_cues(&cues, &LintOptions::default()).is_empty());
+    }
+}
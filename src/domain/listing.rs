@@ -0,0 +1,158 @@
+//! Transcripts submitted to the account, as listed by the API newest first.
+
+use serde::Deserialize;
+
+use crate::domain::DomainError;
+use crate::domain::history::format_rfc3339_millis;
+
+/// One transcript of a listing page.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TranscriptSummary {
+    pub id: String,
+    pub status: String,
+    /// UTC creation time as the API reports it, e.g. `2025-03-01T09:30:05.250614`.
+    pub created: String,
+    #[serde(default)]
+    pub audio_url: String,
+}
+
+/// Which transcripts to list; `status` and `throttled_only` are applied by the API, the created range locally.
+#[derive(Debug, Clone, Default)]
+pub struct TranscriptFilter {
+    pub status: Option<String>,
+    /// Earliest creation time, inclusive, as accepted by [`parse_date_bound`].
+    pub created_after: Option<String>,
+    /// Latest creation time, exclusive.
+    pub created_before: Option<String>,
+    pub throttled_only: bool,
+}
+
+impl TranscriptFilter {
+    pub fn matches(&self, transcript: &TranscriptSummary) -> bool {
+        self.status.as_deref().is_none_or(|status| transcript.status == status)
+            && self.created_after.as_deref().is_none_or(|after| transcript.created.as_str() >= after)
+            && self.created_before.as_deref().is_none_or(|before| transcript.created.as_str() < before)
+    }
+
+    /// Whether `transcript` was created before the range, so no later (older) page can match either.
+    pub fn is_exhausted_by(&self, transcript: &TranscriptSummary) -> bool {
+        self.created_after.as_deref().is_some_and(|after| transcript.created.as_str() < after)
+    }
+}
+
+/// A `YYYY-MM-DD` date or `YYYY-MM-DDTHH:MM[:SS]` time (UTC), normalized so it compares as a string
+/// against the API's `created` timestamps.
+pub fn parse_date_bound(value: &str) -> Result<String, DomainError> {
+    let invalid = || DomainError::InvalidDate {
+        value: value.to_string(),
+    };
+    let normalized = value.trim().replacen(' ', "T", 1);
+    let (date, time) = match normalized.split_once('T') {
+        Some((date, time)) => (date, Some(time)),
+        None => (normalized.as_str(), None),
+    };
+
+    let fields: Vec<&str> = date.split('-').collect();
+    let [year, month, day] = fields.as_slice() else {
+        return Err(invalid());
+    };
+    let in_range = |field: &str, len: usize, min: u32, max: u32| {
+        field.len() == len && field.chars().all(|c| c.is_ascii_digit()) && field.parse::<u32>().is_ok_and(|n| (min..=max).contains(&n))
+    };
+    if !(in_range(year, 4, 0, 9999) && in_range(month, 2, 1, 12) && in_range(day, 2, 1, 31)) {
+        return Err(invalid());
+    }
+    if let Some(time) = time {
+        let fields: Vec<&str> = time.split(':').collect();
+        let valid = matches!(fields.len(), 2 | 3)
+            && in_range(fields[0], 2, 0, 23)
+            && fields[1..].iter().all(|field| in_range(field, 2, 0, 59));
+        if !valid {
+            return Err(invalid());
+        }
+    }
+    Ok(normalized)
+}
+
+/// The creation time before which transcripts are past a retention of `days` at `now` (unix seconds).
+pub fn retention_cutoff(now: u64, days: u32) -> String {
+    let cutoff = now.saturating_sub(u64::from(days) * 86_400);
+    format_rfc3339_millis(cutoff * 1000)[..19].to_string()
+}
+
+/// One `ID  STATUS  CREATED  AUDIO URL` line per transcript, in the given (newest first) order.
+pub fn format_transcript_list(transcripts: &[TranscriptSummary]) -> String {
+    let width = transcripts.iter().map(|t| t.id.len()).max().unwrap_or(0);
+    transcripts
+        .iter()
+        .map(|t| {
+            let created = t.created.get(..19).unwrap_or(&t.created).replacen('T', " ", 1);
+            format!("{:<width$}  {:<10}  {created}  {}\n", t.id, t.status, t.audio_url)
+        })
+        .collect()
+}
+
+/// `N transcripts`, followed by the count of each status, e.g. `3 transcripts (2 completed, 1 error)`.
+pub fn format_list_totals(transcripts: &[TranscriptSummary]) -> String {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for transcript in transcripts {
+        match counts.iter_mut().find(|(status, _)| *status == transcript.status) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((&transcript.status, 1)),
+        }
+    }
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let noun = if transcripts.len() == 1 { "transcript" } else { "transcripts" };
+    if counts.is_empty() {
+        return format!("0 {noun}");
+    }
+    let breakdown: Vec<String> = counts.iter().map(|(status, count)| format!("{count} {status}")).collect();
+    format!("{} {noun} ({})", transcripts.len(), breakdown.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(id: &str, status: &str, created: &str) -> TranscriptSummary {
+        TranscriptSummary {
+            id: id.to_string(),
+            status: status.to_string(),
+            created: created.to_string(),
+            audio_url: "https://cdn.example/a.mp3".to_string(),
+        }
+    }
+
+    #[test]
+    fn filters_by_created_range_and_counts_statuses() {
+        assert_eq!(parse_date_bound("2025-03-01").unwrap(), "2025-03-01");
+        assert_eq!(parse_date_bound("2025-03-01 09:30").unwrap(), "2025-03-01T09:30");
+        assert!(parse_date_bound("2025-13-01").is_err());
+        assert!(parse_date_bound("03/01/2025").is_err());
+        assert!(parse_date_bound("2025-03-01T25:00").is_err());
+
+        let march = TranscriptFilter {
+            created_after: Some(parse_date_bound("2025-03-01").unwrap()),
+            created_before: Some(parse_date_bound("2025-04-01").unwrap()),
+            ..TranscriptFilter::default()
+        };
+        let first = summary("a", "completed", "2025-03-01T00:00:01.5");
+        let april = summary("b", "completed", "2025-04-01T00:00:00");
+        let february = summary("c", "error", "2025-02-28T23:59:59.9");
+        assert!(march.matches(&first));
+        assert!(!march.matches(&april));
+        assert!(!march.matches(&february));
+        assert!(march.is_exhausted_by(&february));
+        assert!(!march.is_exhausted_by(&april));
+
+        assert_eq!(retention_cutoff(1_741_000_000, 30), "2025-02-01T11:06:40");
+
+        let listed = [first, april, february];
+        assert_eq!(format_list_totals(&listed), "3 transcripts (2 completed, 1 error)");
+        assert_eq!(format_list_totals(&[]), "0 transcripts");
+        assert_eq!(
+            format_transcript_list(&listed[..1]),
+            "a  completed   2025-03-01 00:00:01  https://cdn.example/a.mp3\n"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use crate::domain::reports::csv_field;
+use crate::domain::DomainError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Marker {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimecodeOptions {
+    pub frame_rate: u32,
+    pub start_frames: u64,
+}
+
+pub fn parse_timecode(value: &str, frame_rate: u32) -> Result<u64, DomainError> {
+    let invalid = || DomainError::InvalidTimecode {
+        value: value.to_string(),
+    };
+
+    let parts = value
+        .trim()
+        .split([':', ';'])
+        .map(|p| p.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(invalid)?;
+    let [hours, minutes, seconds, frames] = parts.as_slice() else {
+        return Err(invalid());
+    };
+    if *minutes >= 60 || *seconds >= 60 || *frames >= u64::from(frame_rate) {
+        return Err(invalid());
+    }
+
+    Ok(((hours * 60 + minutes) * 60 + seconds) * u64::from(frame_rate) + frames)
+}
+
+fn ms_to_frames(ms: u64, frame_rate: u32) -> u64 {
+    ms * u64::from(frame_rate) / 1000
+}
+
+fn format_timecode(frames: u64, frame_rate: u32) -> String {
+    let fps = u64::from(frame_rate);
+    let total_seconds = frames / fps;
+    let ff = frames % fps;
+    let hours = total_seconds / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+    let seconds = total_seconds % 60;
+    format!("{hours:02}:{minutes:02}:{seconds:02}:{ff:02}")
+}
+
+/// CMX3600 EDL with one single-frame event per marker, using DaVinci Resolve's marker comment syntax.
+pub fn format_edl(title: &str, markers: &[Marker], timecode: TimecodeOptions) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("TITLE: {title}\n"));
+    out.push_str("FCM: NON-DROP FRAME\n\n");
+
+    for (idx, marker) in markers.iter().enumerate() {
+        let start = timecode.start_frames + ms_to_frames(marker.start_ms, timecode.frame_rate);
+        let end = start + 1;
+        let duration = ms_to_frames(marker.end_ms.saturating_sub(marker.start_ms), timecode.frame_rate).max(1);
+        let tc_in = format_timecode(start, timecode.frame_rate);
+        let tc_out = format_timecode(end, timecode.frame_rate);
+        out.push_str(&format!(
+            "{:03}  001      V     C        {tc_in} {tc_out} {tc_in} {tc_out}  \n",
+            idx + 1
+        ));
+        out.push_str(&format!(
+            " |C:ResolveColorBlue |M:{} |D:{duration}\n\n",
+            single_line(&marker.name)
+        ));
+    }
+
+    out
+}
+
+pub fn format_premiere_markers_csv(markers: &[Marker], timecode: TimecodeOptions) -> String {
+    let mut out = String::new();
+    out.push_str("Marker Name,Description,In,Out,Duration,Marker Type\n");
+
+    for marker in markers {
+        let start = timecode.start_frames + ms_to_frames(marker.start_ms, timecode.frame_rate);
+        let end = timecode.start_frames + ms_to_frames(marker.end_ms, timecode.frame_rate);
+        let duration = end.saturating_sub(start);
+        out.push_str(&format!(
+            "{},{},{},{},{},Comment\n",
+            csv_field(&single_line(&marker.name)),
+            csv_field(&single_line(&marker.description)),
+            format_timecode(start, timecode.frame_rate),
+            format_timecode(end, timecode.frame_rate),
+            format_timecode(duration, timecode.frame_rate),
+        ));
+    }
+
+    out
+}
+
+fn single_line(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn markers() -> Vec<Marker> {
+        vec![Marker {
+            start_ms: 61_500,
+            end_ms: 125_000,
+            name: "Pricing, and\nplans".to_string(),
+            description: "We discuss \"pricing\"".to_string(),
+        }]
+    }
+
+    #[test]
+    fn parses_timecodes() {
+        assert_eq!(parse_timecode("01:00:00:00", 25).expect("tc"), 90_000);
+        assert_eq!(parse_timecode("00:00:01:05", 25).expect("tc"), 30);
+        assert!(parse_timecode("00:00:01:25", 25).is_err());
+        assert!(parse_timecode("00:01:00", 25).is_err());
+    }
+
+    #[test]
+    fn formats_resolve_edl_markers() {
+        let timecode = TimecodeOptions {
+            frame_rate: 25,
+            start_frames: 90_000,
+        };
+        let edl = format_edl("talk", &markers(), timecode);
+        assert!(edl.starts_with("TITLE: talk\nFCM: NON-DROP FRAME\n\n"));
+        assert!(edl.contains("001  001      V     C        01:01:01:12 01:01:01:13 01:01:01:12 01:01:01:13"));
+        assert!(edl.contains(" |C:ResolveColorBlue |M:Pricing, and plans |D:1587\n"));
+    }
+
+    #[test]
+    fn formats_premiere_csv_markers() {
+        let timecode = TimecodeOptions {
+            frame_rate: 30,
+            start_frames: 0,
+        };
+        let csv = format_premiere_markers_csv(&markers(), timecode);
+        assert_eq!(
+            csv,
+            "Marker Name,Description,In,Out,Duration,Marker Type\n\"Pricing, and plans\",\"We discuss \"\"pricing\"\"\",00:01:01:15,00:02:05:00,00:01:03:15,Comment\n"
+        );
+    }
+}
@@ -1,22 +1,263 @@
+use std::collections::BTreeMap;
+use std::ffi::{OsStr, OsString};
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+pub mod aliases;
+pub mod anki;
+pub mod batch;
+pub mod bench;
+pub mod cache;
 pub mod config;
+pub mod encoding;
+pub mod eval;
+pub mod grep;
+pub mod history;
+pub mod jobs;
+pub mod languages;
+pub mod lint;
+pub mod listing;
+pub mod markers;
+pub mod notes;
+pub mod os_string;
+pub mod otr;
+pub mod polling;
+pub mod postprocess;
+pub mod reports;
+pub mod schema;
+pub mod secret;
+pub mod speakers;
 pub mod subtitles;
+pub mod transcript;
+pub mod translation;
+pub mod update;
+pub mod uploads;
+pub mod version;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SpeechModel {
     Best,
     Nano,
+    #[serde(rename = "slam-1")]
+    Slam1,
 }
 
+impl SpeechModel {
+    /// API `speech_model` value.
+    pub fn name(self) -> &'static str {
+        match self {
+            SpeechModel::Best => "best",
+            SpeechModel::Nano => "nano",
+            SpeechModel::Slam1 => "slam-1",
+        }
+    }
+
+    /// slam-1 is prompted with `keyterms_prompt` instead.
+    pub fn supports_word_boost(self) -> bool {
+        !matches!(self, SpeechModel::Slam1)
+    }
+
+    pub fn supports_keyterms_prompt(self) -> bool {
+        matches!(self, SpeechModel::Slam1)
+    }
+}
+
+/// Preprocessing applied with ffmpeg before upload, for recordings that transcribe poorly as-is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioProfile {
+    /// Telephone audio: keeps the 200-3400 Hz voice band and evens out the level.
+    Phone,
+    /// Background noise or hum: removes rumble, denoises, and normalizes loudness.
+    Noisy,
+    /// Speech over music: narrows to the speech band and normalizes loudness.
+    Music,
+}
+
+impl AudioProfile {
+    /// ffmpeg `-af` filter chain.
+    pub fn filter_chain(self) -> &'static str {
+        match self {
+            AudioProfile::Phone => "highpass=f=200,lowpass=f=3400,loudnorm=I=-16:TP=-1.5:LRA=11",
+            AudioProfile::Noisy => "highpass=f=80,afftdn=nf=-25,loudnorm=I=-16:TP=-1.5:LRA=11",
+            AudioProfile::Music => "highpass=f=100,lowpass=f=8000,loudnorm=I=-16:TP=-1.5:LRA=11",
+        }
+    }
+}
+
+/// Rendering echoed to stdout while the transcript is written to `--output`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TeeFormat {
+    Text,
+}
+
+/// Where srt/vtt captions come from (`--subtitle-source`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleSource {
+    /// The API's SRT/VTT export.
+    Api,
+    /// Built from the transcript's words, or its utterances with speaker labels.
+    Local,
+}
+
+/// What happens when a local file exceeds an upload limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LimitAction {
+    #[default]
+    Abort,
+    Warn,
+}
+
+/// Size and duration ceilings checked before a local file is uploaded.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct UploadLimits {
+    pub max_bytes: Option<u64>,
+    pub max_duration_minutes: Option<f64>,
+    pub action: LimitAction,
+}
+
+impl UploadLimits {
+    pub fn size_violation(&self, bytes: u64) -> Option<String> {
+        let max = self.max_bytes?;
+        (bytes > max).then(|| format!("file is {bytes} bytes, over the {max}-byte upload limit"))
+    }
+
+    pub fn duration_violation(&self, seconds: f64) -> Option<String> {
+        let max = self.max_duration_minutes?;
+        let minutes = seconds / 60.0;
+        (minutes > max).then(|| format!("recording is {minutes:.1} minutes, over the {max}-minute limit"))
+    }
+}
+
+/// Minimum-content assertions checked on the completed transcript before anything is written.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ContentRequirements {
+    pub fail_on_empty: bool,
+    pub min_words: Option<usize>,
+    pub min_avg_confidence: Option<f64>,
+}
+
+impl ContentRequirements {
+    /// The first unmet requirement, given the word count and average word confidence.
+    pub fn violation(&self, words: usize, avg_confidence: Option<f64>) -> Option<String> {
+        if self.fail_on_empty && words == 0 {
+            return Some("transcript is empty".to_string());
+        }
+        if let Some(min) = self.min_words
+            && words < min
+        {
+            return Some(format!("transcript has {words} word(s), fewer than --min-words {min}"));
+        }
+        if let (Some(min), Some(avg)) = (self.min_avg_confidence, avg_confidence)
+            && avg < min
+        {
+            return Some(format!("average word confidence {avg:.2} is below --min-avg-confidence {min}"));
+        }
+        None
+    }
+}
+
+pub const MAX_KEYTERMS: usize = 1000;
+pub const MAX_KEYTERM_WORDS: usize = 6;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum TranscriptFormat {
+    #[serde(alias = "txt")]
     Text,
     Srt,
     Vtt,
+    #[serde(rename = "srt-dual")]
+    SrtDual,
+    Edl,
+    #[serde(rename = "premiere-markers-csv")]
+    PremiereMarkersCsv,
+    #[serde(rename = "anki-tsv")]
+    AnkiTsv,
+    Otr,
+    #[serde(rename = "meeting-notes")]
+    MeetingNotes,
+    #[serde(rename = "show-notes")]
+    ShowNotes,
+    Sqlite,
+    Parquet,
+    Json,
+}
+
+impl TranscriptFormat {
+    /// `--format` value.
+    pub fn name(self) -> &'static str {
+        match self {
+            TranscriptFormat::Text => "text",
+            TranscriptFormat::Srt => "srt",
+            TranscriptFormat::Vtt => "vtt",
+            TranscriptFormat::SrtDual => "srt-dual",
+            TranscriptFormat::Edl => "edl",
+            TranscriptFormat::PremiereMarkersCsv => "premiere-markers-csv",
+            TranscriptFormat::AnkiTsv => "anki-tsv",
+            TranscriptFormat::Otr => "otr",
+            TranscriptFormat::MeetingNotes => "meeting-notes",
+            TranscriptFormat::ShowNotes => "show-notes",
+            TranscriptFormat::Sqlite => "sqlite",
+            TranscriptFormat::Parquet => "parquet",
+            TranscriptFormat::Json => "json",
+        }
+    }
+
+    /// File extension of an output in this format, as batch runs name them.
+    pub fn extension(self) -> &'static str {
+        match self {
+            TranscriptFormat::Text => "txt",
+            TranscriptFormat::Srt | TranscriptFormat::SrtDual => "srt",
+            TranscriptFormat::Vtt => "vtt",
+            TranscriptFormat::Edl => "edl",
+            TranscriptFormat::PremiereMarkersCsv => "csv",
+            TranscriptFormat::AnkiTsv => "tsv",
+            TranscriptFormat::Otr => "otr",
+            TranscriptFormat::MeetingNotes | TranscriptFormat::ShowNotes => "md",
+            TranscriptFormat::Sqlite => "sqlite",
+            TranscriptFormat::Parquet => "parquet",
+            TranscriptFormat::Json => "json",
+        }
+    }
+
+    pub fn is_subtitle(self) -> bool {
+        matches!(self, TranscriptFormat::Srt | TranscriptFormat::Vtt | TranscriptFormat::SrtDual)
+    }
+
+    /// Formats written as tables to a file rather than rendered as text.
+    pub fn writes_tables(self) -> bool {
+        matches!(self, TranscriptFormat::Sqlite | TranscriptFormat::Parquet)
+    }
+}
+
+/// Top-level sections of the transcript JSON kept in `--format json` output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonSections {
+    /// `--json-include`: only these, plus `id`.
+    Include(Vec<String>),
+    /// `--json-exclude`: all but these.
+    Exclude(Vec<String>),
+}
+
+impl JsonSections {
+    pub fn apply(&self, transcript: &mut serde_json::Map<String, serde_json::Value>) {
+        match self {
+            JsonSections::Include(keep) => transcript.retain(|key, _| key == "id" || keep.contains(key)),
+            JsonSections::Exclude(drop) => transcript.retain(|key, _| !drop.contains(key)),
+        }
+    }
+}
+
+/// How `--output` is split into several sequential subtitle files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitOutput {
+    Every(Duration),
+    Chapter,
 }
 
 #[derive(Debug, Clone)]
@@ -38,6 +279,11 @@ pub enum Language {
     Fixed { code: String },
 }
 
+/// Whether `--language` or config `language` asks for language detection instead of a fixed code.
+pub fn is_auto_language(code: &str) -> bool {
+    code.trim().eq_ignore_ascii_case("auto")
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
 pub struct CustomSpelling {
     pub from: String,
@@ -47,27 +293,133 @@ pub struct CustomSpelling {
 #[derive(Debug, Clone)]
 pub struct TranscribeOptions {
     input: Input,
+    audio_url: Option<url::Url>,
+    concat: Vec<Input>,
     output: Output,
     format: TranscriptFormat,
     speech_model: SpeechModel,
     language: Language,
+    expect_language: Vec<String>,
     punctuate: bool,
     format_text: bool,
     disfluencies: bool,
     filter_profanity: bool,
     speaker_labels: bool,
+    speaker_style: subtitles::SpeakerStyle,
+    merge_short_utterances: Option<Duration>,
+    paragraph_gap: Option<Duration>,
+    mark_uncertain: Option<f64>,
     multichannel: bool,
+    channel_names: BTreeMap<String, String>,
+    identify_speakers: bool,
+    speaker_names: BTreeMap<String, String>,
+    split_by_channel: bool,
+    extract_audio: bool,
+    audio_profile: Option<AudioProfile>,
+    skip_validation: bool,
+    temp_dir: Option<PathBuf>,
+    keep_temp: bool,
+    upload_index: Option<PathBuf>,
+    verify_upload: bool,
+    upload_limits: UploadLimits,
+    content_requirements: ContentRequirements,
+    sentiment_analysis: bool,
+    auto_chapters: bool,
+    auto_highlights: bool,
+    entity_detection: bool,
     word_boost: Vec<String>,
+    keyterms_prompt: Vec<String>,
     custom_spelling: Vec<CustomSpelling>,
+    post_process: postprocess::PostProcessor,
     chars_per_caption: u32,
+    max_cps: Option<f64>,
+    subtitle_source: Option<SubtitleSource>,
+    srt_numbering: Option<u32>,
+    split_output: Option<SplitOutput>,
+    check: Option<lint::LintOptions>,
+    timecode: markers::TimecodeOptions,
+    embed_metadata: bool,
+    write_chapters: Option<PathBuf>,
+    translate_to: Option<String>,
+    translated_output: Option<PathBuf>,
+    lemur_model: Option<String>,
     speech_threshold: Option<f64>,
+    reports: Vec<reports::ReportKind>,
+    report_output: Option<PathBuf>,
+    timestamp_format: Option<transcript::TimestampFormat>,
+    output_encoding: encoding::OutputEncoding,
+    tee: Option<TeeFormat>,
+    preview: Option<usize>,
+    exec_formatter: Option<String>,
+    json_sections: Option<JsonSections>,
+    poll_strategy: polling::PollStrategy,
     poll_interval: Duration,
     timeout: Duration,
+    deterministic: bool,
 }
 
 impl TranscribeOptions {
     pub fn new(params: TranscribeOptionsParams) -> Result<Self, DomainError> {
         let input = parse_input(&params.input)?;
+        let audio_url = match params.audio_url.as_deref().map(|value| parse_input(value.as_ref())).transpose()? {
+            Some(Input::Url(url)) => Some(url),
+            Some(Input::LocalPath(_)) => {
+                return Err(DomainError::InvalidUrl {
+                    value: params.audio_url.unwrap_or_default(),
+                });
+            }
+            None => None,
+        };
+        let concat = params
+            .concat
+            .iter()
+            .map(|part| parse_input(part))
+            .collect::<Result<Vec<_>, _>>()?;
+        if !concat.is_empty() {
+            let conflict = if params.audio_url.is_some() {
+                Some("--from-upload-url")
+            } else if params.embed_metadata {
+                Some("--embed-metadata")
+            } else if params.write_chapters {
+                Some("--write-chapters")
+            } else if params.exec_formatter.is_some() {
+                Some("--format exec:")
+            } else if params.format == TranscriptFormat::MeetingNotes {
+                Some("--format meeting-notes")
+            } else if params.format == TranscriptFormat::ShowNotes {
+                Some("--format show-notes")
+            } else if params.format == TranscriptFormat::Json {
+                Some("--format json")
+            } else {
+                None
+            };
+            if let Some(other) = conflict {
+                return Err(DomainError::OptionsConflict {
+                    option: "--concat",
+                    other,
+                });
+            }
+        }
+        // The API's export can't show what these change in the captions.
+        if params.subtitle_source == Some(SubtitleSource::Api) {
+            let conflict = [
+                ("--max-cps", params.max_cps.is_some()),
+                ("--mark-uncertain", params.mark_uncertain.is_some()),
+                ("--merge-short-utterances", params.merge_short_utterances.is_some()),
+                ("--identify-speakers", params.identify_speakers),
+                ("--channel-names", !params.channel_names.is_empty()),
+                ("--split-by-channel", params.split_by_channel),
+                ("--concat", !concat.is_empty()),
+            ]
+            .into_iter()
+            .find(|(_, used)| *used);
+            if let Some((other, _)) = conflict {
+                return Err(DomainError::OptionsConflict {
+                    option: "--subtitle-source api",
+                    other,
+                });
+            }
+        }
 
         let output = match params.output {
             Some(path) => Output::FilePath(path),
@@ -76,21 +428,377 @@ impl TranscribeOptions {
 
         let language = match (params.language_detection, params.language) {
             (true, None) => Language::AutoDetect,
+            (true, Some(code)) if is_auto_language(&code) => Language::AutoDetect,
+            (false, Some(code)) if is_auto_language(&code) => {
+                return Err(DomainError::OptionRequiresOption {
+                    option: "--language auto",
+                    requires: "language detection",
+                });
+            }
             (true, Some(_)) => return Err(DomainError::LanguageProvidedWithDetection),
             (false, None) => Language::NoDetect,
-            (false, Some(code)) => Language::Fixed { code },
+            (false, Some(code)) => Language::Fixed {
+                code: languages::find_language(&code).map_or(code, |info| info.code.to_string()),
+            },
+        };
+
+        if !params.expect_language.is_empty() && !matches!(language, Language::AutoDetect) {
+            return Err(DomainError::OptionRequiresOption {
+                option: "--expect-language",
+                requires: "language detection",
+            });
+        }
+        let expect_language = params
+            .expect_language
+            .iter()
+            .map(|code| code.trim())
+            .filter(|code| !code.is_empty())
+            .map(|code| {
+                languages::find_language(code)
+                    .map(|info| info.code.to_string())
+                    .ok_or_else(|| DomainError::UnknownLanguage { code: code.to_string() })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let Some(value) = params.speech_threshold
+            && !(0.0..=1.0).contains(&value)
+        {
+            return Err(DomainError::InvalidSpeechThreshold { value });
+        }
+
+        for report in &params.reports {
+            if matches!(report, reports::ReportKind::SentimentTimeline { .. }) && !params.sentiment_analysis {
+                return Err(DomainError::ReportRequiresOption {
+                    report: "sentiment-timeline",
+                    option: "--sentiment-analysis",
+                });
+            }
+            if matches!(report, reports::ReportKind::Profanity) && !params.filter_profanity {
+                return Err(DomainError::ReportRequiresOption {
+                    report: "profanity",
+                    option: "--filter-profanity",
+                });
+            }
+            if matches!(report, reports::ReportKind::Interruptions { .. }) && !params.multichannel {
+                return Err(DomainError::ReportRequiresOption {
+                    report: "interruptions",
+                    option: "--multichannel",
+                });
+            }
+        }
+
+        if params.format.writes_tables() && matches!(output, Output::Stdout) {
+            return Err(DomainError::OptionRequiresOption {
+                option: if params.format == TranscriptFormat::Sqlite { "--format sqlite" } else { "--format parquet" },
+                requires: "--output",
+            });
+        }
+
+        for (option, used) in [("--tee", params.tee.is_some()), ("--preview", params.preview.is_some())] {
+            if used && matches!(output, Output::Stdout) {
+                return Err(DomainError::OptionRequiresOption {
+                    option,
+                    requires: "--output",
+                });
+            }
+        }
+        if params.tee.is_some() && params.preview.is_some() {
+            return Err(DomainError::OptionsConflict {
+                option: "--preview",
+                other: "--tee",
+            });
+        }
+        if params.preview == Some(0) {
+            return Err(DomainError::InvalidPreview);
+        }
+        let json_sections = match (params.json_include.is_empty(), params.json_exclude.is_empty()) {
+            (true, true) => None,
+            (false, false) => {
+                return Err(DomainError::OptionsConflict {
+                    option: "--json-include",
+                    other: "--json-exclude",
+                });
+            }
+            (false, true) => Some(JsonSections::Include(params.json_include.clone())),
+            (true, false) => Some(JsonSections::Exclude(params.json_exclude.clone())),
+        };
+        if json_sections.is_some() && (params.format != TranscriptFormat::Json || params.exec_formatter.is_some()) {
+            return Err(DomainError::OptionRequiresOption {
+                option: if params.json_include.is_empty() { "--json-exclude" } else { "--json-include" },
+                requires: "--format json",
+            });
+        }
+        // `--format json` and exec formatters get the API's document as returned, so they can't show what these change;
+        // passing redacted terms through in clear text would be a leak.
+        let raw_document = match (&params.exec_formatter, params.format) {
+            (Some(_), _) => Some("--format exec:"),
+            (None, TranscriptFormat::Json) => Some("--format json"),
+            (None, _) => None,
         };
+        if let Some(option) = raw_document {
+            let conflict = [
+                ("--identify-speakers", params.identify_speakers),
+                ("--mark-uncertain", params.mark_uncertain.is_some()),
+                ("--merge-short-utterances", params.merge_short_utterances.is_some()),
+                ("--redact-terms-file", !params.redact_terms.is_empty()),
+                ("config postReplace", !params.post_replace.is_empty()),
+                ("--normalize", !params.normalize.is_empty()),
+            ]
+            .into_iter()
+            .find(|(_, used)| *used);
+            if let Some((other, _)) = conflict {
+                return Err(DomainError::OptionsConflict { option, other });
+            }
+        }
+        if params.merge_short_utterances.is_some() && !params.speaker_labels {
+            return Err(DomainError::OptionRequiresOption {
+                option: "--merge-short-utterances",
+                requires: "--speaker-labels",
+            });
+        }
+
+        if let Some(command) = &params.exec_formatter {
+            if command.trim().is_empty() {
+                return Err(DomainError::EmptyExecFormatter);
+            }
+            for (option, used) in [
+                ("--split-by-channel", params.split_by_channel),
+                ("--split-output-every/--split-output-by", params.split_output.is_some()),
+            ] {
+                if used {
+                    return Err(DomainError::OptionsConflict {
+                        option,
+                        other: "--format exec:COMMAND",
+                    });
+                }
+            }
+        }
 
-        if let Some(value) = params.speech_threshold {
-            if !(0.0..=1.0).contains(&value) {
-                return Err(DomainError::InvalidSpeechThreshold { value });
+        if params.output_encoding.compression.is_some() {
+            if matches!(output, Output::Stdout) {
+                return Err(DomainError::OptionRequiresOption {
+                    option: "--compress",
+                    requires: "--output",
+                });
+            }
+            if params.format.writes_tables() {
+                return Err(DomainError::OptionsConflict {
+                    option: "--compress",
+                    other: "--format sqlite/parquet",
+                });
             }
         }
 
+        if params.check.is_some() && !params.format.is_subtitle() {
+            return Err(DomainError::CheckRequiresSubtitles);
+        }
+
         if params.chars_per_caption == 0 {
             return Err(DomainError::InvalidCharsPerCaption);
         }
 
+        if params.embed_metadata && !supports_embedded_metadata(&input) {
+            return Err(DomainError::EmbedMetadataUnsupported {
+                input: params.input.to_string_lossy().into_owned(),
+            });
+        }
+
+        let write_chapters = if params.write_chapters {
+            if !params.auto_chapters {
+                return Err(DomainError::OptionRequiresOption {
+                    option: "--write-chapters",
+                    requires: "--auto-chapters",
+                });
+            }
+            Some(chapters_copy_path(&input).ok_or_else(|| DomainError::WriteChaptersUnsupported {
+                input: params.input.to_string_lossy().into_owned(),
+            })?)
+        } else {
+            None
+        };
+
+        if params.audio_profile.is_some() && !matches!(input, Input::LocalPath(_)) {
+            return Err(DomainError::OptionRequiresOption {
+                option: "--audio-profile",
+                requires: "a local input file",
+            });
+        }
+
+        if let Some(value) = params.content_requirements.min_avg_confidence
+            && !(0.0..=1.0).contains(&value)
+        {
+            return Err(DomainError::InvalidMinAvgConfidence { value });
+        }
+        if let Some(value) = params.mark_uncertain
+            && !(0.0..=1.0).contains(&value)
+        {
+            return Err(DomainError::InvalidMarkUncertain { value });
+        }
+
+        if params.upload_limits.max_bytes == Some(0) {
+            return Err(DomainError::InvalidUploadLimit { option: "maxUploadBytes" });
+        }
+        if params.upload_limits.max_duration_minutes.is_some_and(|m| m.is_nan() || m <= 0.0) {
+            return Err(DomainError::InvalidUploadLimit { option: "maxDurationMinutes" });
+        }
+
+        let channel_names = params
+            .channel_names
+            .into_iter()
+            .map(|(channel, name)| (channel.trim().to_string(), name.trim().to_string()))
+            .collect::<BTreeMap<_, _>>();
+        if let Some((channel, name)) = channel_names.iter().find(|(c, n)| c.is_empty() || n.is_empty()) {
+            return Err(DomainError::InvalidChannelName {
+                value: format!("{channel}={name}"),
+            });
+        }
+        if params.identify_speakers && !params.speaker_labels {
+            return Err(DomainError::OptionRequiresOption {
+                option: "--identify-speakers",
+                requires: "--speaker-labels",
+            });
+        }
+        for (option, used) in [
+            ("--channel-names", !channel_names.is_empty()),
+            ("--split-by-channel", params.split_by_channel),
+        ] {
+            if used && !params.multichannel {
+                return Err(DomainError::OptionRequiresOption {
+                    option,
+                    requires: "--multichannel",
+                });
+            }
+        }
+        if params.split_by_channel {
+            if !matches!(params.format, TranscriptFormat::Text | TranscriptFormat::Srt | TranscriptFormat::Vtt) {
+                return Err(DomainError::SplitByChannelUnsupportedFormat);
+            }
+            if matches!(output, Output::Stdout) {
+                return Err(DomainError::OptionRequiresOption {
+                    option: "--split-by-channel",
+                    requires: "--output",
+                });
+            }
+            if params.split_output.is_some() {
+                return Err(DomainError::OptionsConflict {
+                    option: "--split-by-channel",
+                    other: "--split-output-every/--split-output-by",
+                });
+            }
+        }
+
+        if let Some(split) = params.split_output {
+            let option = match split {
+                SplitOutput::Every(_) => "--split-output-every",
+                SplitOutput::Chapter => "--split-output-by chapter",
+            };
+            if !matches!(params.format, TranscriptFormat::Srt | TranscriptFormat::Vtt) {
+                return Err(DomainError::SplitOutputRequiresSubtitles);
+            }
+            if matches!(output, Output::Stdout) {
+                return Err(DomainError::OptionRequiresOption {
+                    option,
+                    requires: "--output",
+                });
+            }
+            match split {
+                SplitOutput::Every(every) if every.is_zero() => return Err(DomainError::InvalidSplitInterval),
+                SplitOutput::Chapter if !params.auto_chapters => {
+                    return Err(DomainError::OptionRequiresOption {
+                        option,
+                        requires: "--auto-chapters",
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let translate_to = params
+            .translate_to
+            .map(|code| code.trim().to_string())
+            .filter(|code| !code.is_empty());
+        if params.format == TranscriptFormat::SrtDual && translate_to.is_none() {
+            return Err(DomainError::OptionRequiresOption {
+                option: "--format srt-dual",
+                requires: "--translate-to",
+            });
+        }
+        if params.translated_output.is_some() && translate_to.is_none() {
+            return Err(DomainError::OptionRequiresOption {
+                option: "--translated-output",
+                requires: "--translate-to",
+            });
+        }
+        if translate_to.is_some()
+            && !matches!(params.format, TranscriptFormat::SrtDual | TranscriptFormat::AnkiTsv)
+            && params.translated_output.is_none()
+        {
+            return Err(DomainError::TranslationWithoutTarget);
+        }
+
+        if params.frame_rate == 0 {
+            return Err(DomainError::InvalidFrameRate);
+        }
+
+        let timecode = markers::TimecodeOptions {
+            frame_rate: params.frame_rate,
+            start_frames: match params.timecode_start.as_deref() {
+                Some(value) => markers::parse_timecode(value, params.frame_rate)?,
+                None => 0,
+            },
+        };
+
+        if let Some(value) = params.max_cps
+            && !(value.is_finite() && value > 0.0)
+        {
+            return Err(DomainError::InvalidMaxCps { value });
+        }
+
+        if params.srt_start_index == Some(0) {
+            return Err(DomainError::InvalidSrtStartIndex);
+        }
+        let srt_numbering = match (params.srt_start_index, params.srt_renumber) {
+            (Some(start), _) => Some(start),
+            (None, true) => Some(1),
+            (None, false) => None,
+        };
+
+        let keyterms_prompt: Vec<String> = params
+            .keyterms_prompt
+            .iter()
+            .map(|term| term.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|term| !term.is_empty())
+            .collect();
+        if !keyterms_prompt.is_empty() && !params.speech_model.supports_keyterms_prompt() {
+            return Err(DomainError::UnsupportedBySpeechModel {
+                option: "--keyterms-prompt",
+                model: params.speech_model.name(),
+                hint: " (use --speech-model slam-1)",
+            });
+        }
+        if !params.word_boost.is_empty() && !params.speech_model.supports_word_boost() {
+            return Err(DomainError::UnsupportedBySpeechModel {
+                option: "--word-boost",
+                model: params.speech_model.name(),
+                hint: " (use --keyterms-prompt)",
+            });
+        }
+        if keyterms_prompt.len() > MAX_KEYTERMS {
+            return Err(DomainError::TooManyKeyterms {
+                count: keyterms_prompt.len(),
+                max: MAX_KEYTERMS,
+            });
+        }
+        if let Some(term) = keyterms_prompt
+            .iter()
+            .find(|term| term.split(' ').count() > MAX_KEYTERM_WORDS)
+        {
+            return Err(DomainError::KeytermTooLong {
+                term: term.clone(),
+                max: MAX_KEYTERM_WORDS,
+            });
+        }
+
         let custom_spelling = params
             .custom_spelling
             .into_iter()
@@ -105,31 +813,132 @@ impl TranscribeOptions {
             })
             .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(Self {
+        let post_process = postprocess::PostProcessor::new(
+            &params.post_replace,
+            &params.redact_terms,
+            params.redact_style,
+            &params.normalize,
+        )?;
+
+        let options = Self {
             input,
+            audio_url,
+            concat,
             output,
             format: params.format,
             speech_model: params.speech_model,
             language,
+            expect_language,
             punctuate: params.punctuate,
             format_text: params.format_text,
             disfluencies: params.disfluencies,
             filter_profanity: params.filter_profanity,
             speaker_labels: params.speaker_labels,
+            speaker_style: params.speaker_style,
+            merge_short_utterances: params.merge_short_utterances,
+            paragraph_gap: params.paragraph_gap,
+            mark_uncertain: params.mark_uncertain,
             multichannel: params.multichannel,
+            channel_names,
+            identify_speakers: params.identify_speakers,
+            speaker_names: BTreeMap::new(),
+            split_by_channel: params.split_by_channel,
+            extract_audio: params.extract_audio,
+            audio_profile: params.audio_profile,
+            skip_validation: params.skip_validation,
+            temp_dir: params.temp_dir,
+            upload_index: params.upload_index,
+            verify_upload: params.verify_upload,
+            keep_temp: params.keep_temp,
+            upload_limits: params.upload_limits,
+            content_requirements: params.content_requirements,
+            sentiment_analysis: params.sentiment_analysis,
+            auto_chapters: params.auto_chapters,
+            auto_highlights: params.auto_highlights,
+            entity_detection: params.entity_detection,
             word_boost: params.word_boost,
+            keyterms_prompt,
             custom_spelling,
+            post_process,
             chars_per_caption: params.chars_per_caption,
+            max_cps: params.max_cps,
+            subtitle_source: params.subtitle_source,
+            srt_numbering,
+            split_output: params.split_output,
+            check: params.check,
+            timecode,
+            embed_metadata: params.embed_metadata,
+            write_chapters,
+            translate_to,
+            translated_output: params.translated_output,
+            lemur_model: params.lemur_model,
             speech_threshold: params.speech_threshold,
+            reports: params.reports,
+            report_output: params.report_output,
+            timestamp_format: params.timestamp_format,
+            output_encoding: params.output_encoding,
+            tee: params.tee,
+            preview: params.preview,
+            exec_formatter: params.exec_formatter.clone(),
+            json_sections,
+            poll_strategy: params.poll_strategy,
             poll_interval: params.poll_interval,
             timeout: params.timeout,
-        })
+            deterministic: params.deterministic,
+        };
+        options.check_language_support()?;
+        Ok(options)
+    }
+
+    /// Fails fast when the fixed (or API default) language can't be transcribed by the speech model
+    /// or doesn't support a requested feature. Detected languages are only known after transcription.
+    pub fn check_language_support(&self) -> Result<(), DomainError> {
+        let code = match &self.language {
+            Language::AutoDetect => return Ok(()),
+            Language::NoDetect => languages::DEFAULT_LANGUAGE,
+            Language::Fixed { code } => code,
+        };
+        let requested = [
+            (languages::Feature::SpeakerLabels, self.speaker_labels),
+            (languages::Feature::Punctuation, self.punctuate),
+            (languages::Feature::FormatText, self.format_text),
+            (languages::Feature::Disfluencies, self.disfluencies),
+            (languages::Feature::FilterProfanity, self.filter_profanity),
+            (languages::Feature::SentimentAnalysis, self.sentiment_analysis),
+            (languages::Feature::AutoChapters, self.auto_chapters),
+            (languages::Feature::AutoHighlights, self.auto_highlights),
+            (languages::Feature::EntityDetection, self.entity_detection),
+        ];
+        let features: Vec<_> = requested
+            .into_iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(feature, _)| feature)
+            .collect();
+        languages::check_language_support(code, self.speech_model, &features).map(|_| ())
     }
 
     pub fn input(&self) -> &Input {
         &self.input
     }
 
+    /// The inputs after `input` of a `--concat` run, transcribed as parts of one recording; empty otherwise.
+    pub fn concat(&self) -> &[Input] {
+        &self.concat
+    }
+
+    /// These options for another part of a `--concat` run.
+    pub fn with_input(&self, input: Input) -> Self {
+        Self {
+            input,
+            ..self.clone()
+        }
+    }
+
+    /// The `--from-upload-url` to transcribe; `input` then only names the outputs.
+    pub fn audio_url(&self) -> Option<&url::Url> {
+        self.audio_url.as_ref()
+    }
+
     pub fn output(&self) -> &Output {
         &self.output
     }
@@ -142,10 +951,29 @@ impl TranscribeOptions {
         self.speech_model
     }
 
+    /// The same options for another model; model-specific prompting the model doesn't support is dropped.
+    pub fn with_speech_model(&self, speech_model: SpeechModel) -> Self {
+        Self {
+            speech_model,
+            word_boost: if speech_model.supports_word_boost() { self.word_boost.clone() } else { Vec::new() },
+            keyterms_prompt: if speech_model.supports_keyterms_prompt() {
+                self.keyterms_prompt.clone()
+            } else {
+                Vec::new()
+            },
+            ..self.clone()
+        }
+    }
+
     pub fn language(&self) -> &Language {
         &self.language
     }
 
+    /// Languages detection must land on; empty when any language is accepted.
+    pub fn expect_language(&self) -> &[String] {
+        &self.expect_language
+    }
+
     pub fn punctuate(&self) -> bool {
         self.punctuate
     }
@@ -166,26 +994,239 @@ impl TranscribeOptions {
         self.speaker_labels
     }
 
+    pub fn speaker_style(&self) -> &subtitles::SpeakerStyle {
+        &self.speaker_style
+    }
+
+    /// Single-word utterances shorter than this are folded into the surrounding speaker's turn.
+    pub fn merge_short_utterances(&self) -> Option<Duration> {
+        self.merge_short_utterances
+    }
+
+    /// Silence between utterances (or words, without speakers) longer than this starts a new paragraph in text output.
+    pub fn paragraph_gap(&self) -> Option<Duration> {
+        self.paragraph_gap
+    }
+
+    /// Words with a confidence below this are marked for correction in the rendered text.
+    pub fn mark_uncertain(&self) -> Option<f64> {
+        self.mark_uncertain
+    }
+
     pub fn multichannel(&self) -> bool {
         self.multichannel
     }
 
+    /// Name given to a multichannel channel (`1`, `2`, ...) with `--channel-names`.
+    pub fn channel_name(&self, channel: &str) -> Option<&str> {
+        self.channel_names.get(channel).map(String::as_str)
+    }
+
+    pub fn has_channel_names(&self) -> bool {
+        !self.channel_names.is_empty()
+    }
+
+    pub fn identify_speakers(&self) -> bool {
+        self.identify_speakers
+    }
+
+    /// Names of diarized speakers (`A`, `B`, ...) given in an earlier `--identify-speakers` run.
+    pub fn speaker_names(&self) -> &BTreeMap<String, String> {
+        &self.speaker_names
+    }
+
+    pub fn with_speaker_names(&self, speaker_names: BTreeMap<String, String>) -> Self {
+        Self {
+            speaker_names,
+            ..self.clone()
+        }
+    }
+
+    pub fn split_by_channel(&self) -> bool {
+        self.split_by_channel
+    }
+
+    /// Utterances are rendered with a speaker (or channel) prefix.
+    pub fn attributes_speakers(&self) -> bool {
+        self.speaker_labels || self.has_channel_names()
+    }
+
+    pub fn extract_audio(&self) -> bool {
+        self.extract_audio
+    }
+
+    pub fn audio_profile(&self) -> Option<AudioProfile> {
+        self.audio_profile
+    }
+
+    /// Local files are uploaded without the ffprobe check.
+    pub fn skip_validation(&self) -> bool {
+        self.skip_validation
+    }
+
+    /// Where ffmpeg intermediates go; the system temp directory when `None`.
+    pub fn temp_dir(&self) -> Option<&Path> {
+        self.temp_dir.as_deref()
+    }
+
+    /// Intermediates are left in place for debugging instead of being removed.
+    pub fn keep_temp(&self) -> bool {
+        self.keep_temp
+    }
+
+    /// The index of recent uploads that local files are looked up in before uploading, when reuse is on.
+    pub fn upload_index(&self) -> Option<&Path> {
+        self.upload_index.as_deref()
+    }
+
+    pub fn verify_upload(&self) -> bool {
+        self.verify_upload
+    }
+
+    pub fn upload_limits(&self) -> UploadLimits {
+        self.upload_limits
+    }
+
+    pub fn content_requirements(&self) -> ContentRequirements {
+        self.content_requirements
+    }
+
+    pub fn sentiment_analysis(&self) -> bool {
+        self.sentiment_analysis
+    }
+
+    pub fn auto_chapters(&self) -> bool {
+        self.auto_chapters
+    }
+
+    pub fn auto_highlights(&self) -> bool {
+        self.auto_highlights
+    }
+
+    pub fn entity_detection(&self) -> bool {
+        self.entity_detection
+    }
+
     pub fn word_boost(&self) -> &[String] {
         &self.word_boost
     }
 
+    pub fn keyterms_prompt(&self) -> &[String] {
+        &self.keyterms_prompt
+    }
+
     pub fn custom_spelling(&self) -> &[CustomSpelling] {
         &self.custom_spelling
     }
 
+    pub fn post_process(&self) -> &postprocess::PostProcessor {
+        &self.post_process
+    }
+
     pub fn chars_per_caption(&self) -> u32 {
         self.chars_per_caption
     }
 
+    pub fn max_cps(&self) -> Option<f64> {
+        self.max_cps
+    }
+
+    pub fn subtitle_source(&self) -> Option<SubtitleSource> {
+        self.subtitle_source
+    }
+
+    /// Whether srt/vtt captions are built locally: as `--subtitle-source` says, else only when local options shape
+    /// them (speaker prefixes, `--max-cps`, `--mark-uncertain`, `--concat`); otherwise the API's export is downloaded.
+    pub fn local_subtitles(&self) -> bool {
+        match self.subtitle_source {
+            Some(source) => source == SubtitleSource::Local,
+            None => {
+                self.attributes_speakers() || self.max_cps.is_some() || self.mark_uncertain.is_some() || !self.concat.is_empty()
+            }
+        }
+    }
+
+    /// First cue number when SRT output should be renumbered; `None` keeps the rendered numbering.
+    pub fn srt_numbering(&self) -> Option<u32> {
+        self.srt_numbering
+    }
+
+    pub fn split_output(&self) -> Option<SplitOutput> {
+        self.split_output
+    }
+
+    pub fn check(&self) -> Option<&lint::LintOptions> {
+        self.check.as_ref()
+    }
+
+    pub fn timecode(&self) -> markers::TimecodeOptions {
+        self.timecode
+    }
+
+    pub fn embed_metadata(&self) -> bool {
+        self.embed_metadata
+    }
+
+    pub fn write_chapters(&self) -> Option<&Path> {
+        self.write_chapters.as_deref()
+    }
+
+    pub fn translate_to(&self) -> Option<&str> {
+        self.translate_to.as_deref()
+    }
+
+    pub fn translated_output(&self) -> Option<&Path> {
+        self.translated_output.as_deref()
+    }
+
+    pub fn lemur_model(&self) -> Option<&str> {
+        self.lemur_model.as_deref()
+    }
+
     pub fn speech_threshold(&self) -> Option<f64> {
         self.speech_threshold
     }
 
+    pub fn reports(&self) -> &[reports::ReportKind] {
+        &self.reports
+    }
+
+    pub fn report_output(&self) -> Option<&Path> {
+        self.report_output.as_deref()
+    }
+
+    /// `None` lets each output use its usual format (clock time in text, milliseconds in CSV).
+    pub fn timestamp_format(&self) -> Option<transcript::TimestampFormat> {
+        self.timestamp_format
+    }
+
+    pub fn output_encoding(&self) -> encoding::OutputEncoding {
+        self.output_encoding
+    }
+
+    pub fn tee(&self) -> Option<TeeFormat> {
+        self.tee
+    }
+
+    /// Number of utterances (or sentences) echoed to stdout with `--preview`.
+    pub fn preview(&self) -> Option<usize> {
+        self.preview
+    }
+
+    /// Command of `--format exec:COMMAND`, which renders instead of the built-in formatter.
+    pub fn exec_formatter(&self) -> Option<&str> {
+        self.exec_formatter.as_deref()
+    }
+
+    /// Sections kept in `--format json` output; `None` keeps the whole transcript.
+    pub fn json_sections(&self) -> Option<&JsonSections> {
+        self.json_sections.as_ref()
+    }
+
+    pub fn poll_strategy(&self) -> polling::PollStrategy {
+        self.poll_strategy
+    }
+
     pub fn poll_interval(&self) -> Duration {
         self.poll_interval
     }
@@ -193,27 +1234,190 @@ impl TranscribeOptions {
     pub fn timeout(&self) -> Duration {
         self.timeout
     }
+
+    /// Whether outputs leave out the time of the run and absolute input paths, for snapshot tests.
+    pub fn deterministic(&self) -> bool {
+        self.deterministic
+    }
 }
 
 pub struct TranscribeOptionsParams {
-    pub input: String,
+    pub input: OsString,
+    /// Audio already reachable by the API, submitted as is instead of uploading `input`.
+    pub audio_url: Option<String>,
+    /// Inputs that continue `input` as one recording (`--concat`).
+    pub concat: Vec<OsString>,
     pub format: TranscriptFormat,
     pub output: Option<PathBuf>,
     pub speech_model: SpeechModel,
     pub language_detection: bool,
     pub language: Option<String>,
+    pub expect_language: Vec<String>,
     pub punctuate: bool,
     pub format_text: bool,
     pub disfluencies: bool,
     pub filter_profanity: bool,
     pub speaker_labels: bool,
+    pub speaker_style: subtitles::SpeakerStyle,
+    pub merge_short_utterances: Option<Duration>,
+    pub paragraph_gap: Option<Duration>,
+    pub mark_uncertain: Option<f64>,
     pub multichannel: bool,
+    pub channel_names: BTreeMap<String, String>,
+    /// Ask on the terminal for the name of each diarized speaker.
+    pub identify_speakers: bool,
+    pub split_by_channel: bool,
+    pub extract_audio: bool,
+    pub audio_profile: Option<AudioProfile>,
+    pub skip_validation: bool,
+    pub temp_dir: Option<PathBuf>,
+    pub keep_temp: bool,
+    /// Where uploads are remembered for reuse; `None` uploads every time.
+    pub upload_index: Option<PathBuf>,
+    /// Local files are hashed again after upload and must match the bytes that were sent.
+    pub verify_upload: bool,
+    pub upload_limits: UploadLimits,
+    pub content_requirements: ContentRequirements,
+    pub sentiment_analysis: bool,
+    pub auto_chapters: bool,
+    pub auto_highlights: bool,
+    pub entity_detection: bool,
     pub speech_threshold: Option<f64>,
     pub chars_per_caption: u32,
+    pub max_cps: Option<f64>,
+    pub subtitle_source: Option<SubtitleSource>,
+    pub srt_start_index: Option<u32>,
+    pub srt_renumber: bool,
+    pub split_output: Option<SplitOutput>,
+    pub check: Option<lint::LintOptions>,
+    pub frame_rate: u32,
+    pub timecode_start: Option<String>,
+    pub embed_metadata: bool,
+    pub write_chapters: bool,
+    pub translate_to: Option<String>,
+    pub translated_output: Option<PathBuf>,
+    pub lemur_model: Option<String>,
     pub word_boost: Vec<String>,
+    pub keyterms_prompt: Vec<String>,
     pub custom_spelling: Vec<CustomSpelling>,
+    pub post_replace: Vec<postprocess::PostReplace>,
+    pub redact_terms: Vec<String>,
+    pub redact_style: postprocess::RedactStyle,
+    pub normalize: Vec<postprocess::Normalization>,
+    pub reports: Vec<reports::ReportKind>,
+    pub report_output: Option<PathBuf>,
+    pub timestamp_format: Option<transcript::TimestampFormat>,
+    pub output_encoding: encoding::OutputEncoding,
+    pub tee: Option<TeeFormat>,
+    pub preview: Option<usize>,
+    pub exec_formatter: Option<String>,
+    /// `--json-include` sections; empty keeps every section.
+    pub json_include: Vec<String>,
+    /// `--json-exclude` sections.
+    pub json_exclude: Vec<String>,
+    pub poll_strategy: polling::PollStrategy,
     pub poll_interval: Duration,
     pub timeout: Duration,
+    pub deterministic: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct TranslateOptions {
+    transcript_id: String,
+    language: String,
+    format: TranscriptFormat,
+    output: Output,
+    lemur_model: Option<String>,
+    chars_per_caption: u32,
+    timestamp_format: transcript::TimestampFormat,
+    output_encoding: encoding::OutputEncoding,
+}
+
+impl TranslateOptions {
+    pub fn new(params: TranslateOptionsParams) -> Result<Self, DomainError> {
+        let transcript_id = params.transcript_id.trim().to_string();
+        if transcript_id.is_empty() {
+            return Err(DomainError::EmptyTranscriptId);
+        }
+
+        let language = params.language.trim().to_string();
+        if language.is_empty() {
+            return Err(DomainError::EmptyTranslationLanguage);
+        }
+
+        if !matches!(params.format, TranscriptFormat::Text | TranscriptFormat::Srt | TranscriptFormat::Vtt) {
+            return Err(DomainError::UnsupportedTranslateFormat);
+        }
+
+        if params.chars_per_caption == 0 {
+            return Err(DomainError::InvalidCharsPerCaption);
+        }
+
+        let output = match params.output {
+            Some(path) => Output::FilePath(path),
+            None => Output::Stdout,
+        };
+        if params.output_encoding.compression.is_some() && matches!(output, Output::Stdout) {
+            return Err(DomainError::OptionRequiresOption {
+                option: "--compress",
+                requires: "--output",
+            });
+        }
+
+        Ok(Self {
+            transcript_id,
+            language,
+            format: params.format,
+            output,
+            lemur_model: params.lemur_model,
+            chars_per_caption: params.chars_per_caption,
+            timestamp_format: params.timestamp_format,
+            output_encoding: params.output_encoding,
+        })
+    }
+
+    pub fn transcript_id(&self) -> &str {
+        &self.transcript_id
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    pub fn format(&self) -> TranscriptFormat {
+        self.format
+    }
+
+    pub fn output(&self) -> &Output {
+        &self.output
+    }
+
+    pub fn lemur_model(&self) -> Option<&str> {
+        self.lemur_model.as_deref()
+    }
+
+    pub fn chars_per_caption(&self) -> u32 {
+        self.chars_per_caption
+    }
+
+    pub fn timestamp_format(&self) -> transcript::TimestampFormat {
+        self.timestamp_format
+    }
+
+    pub fn output_encoding(&self) -> encoding::OutputEncoding {
+        self.output_encoding
+    }
+}
+
+pub struct TranslateOptionsParams {
+    pub transcript_id: String,
+    pub language: String,
+    pub format: TranscriptFormat,
+    pub output: Option<PathBuf>,
+    pub lemur_model: Option<String>,
+    pub chars_per_caption: u32,
+    pub timestamp_format: transcript::TimestampFormat,
+    pub output_encoding: encoding::OutputEncoding,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -230,6 +1434,73 @@ pub enum DomainError {
     #[error("chars-per-caption must be greater than 0")]
     InvalidCharsPerCaption,
 
+    #[error("--embed-metadata requires a local mp3, m4a, mp4, or mov file: {input}")]
+    EmbedMetadataUnsupported { input: String },
+
+    #[error("--write-chapters requires a local mp4, m4a, m4b, mov, or mkv file: {input}")]
+    WriteChaptersUnsupported { input: String },
+
+    #[error("{option} is not supported by speech model {model}{hint}")]
+    UnsupportedBySpeechModel {
+        option: &'static str,
+        model: &'static str,
+        hint: &'static str,
+    },
+
+    #[error("too many key terms ({count}); at most {max} are allowed")]
+    TooManyKeyterms { count: usize, max: usize },
+
+    #[error("key term {term:?} is longer than {max} words")]
+    KeytermTooLong { term: String, max: usize },
+
+    #[error("unknown language code {code:?}; run `assemblyai-cli languages` for the supported codes")]
+    UnknownLanguage { code: String },
+
+    #[error("language {language} is not supported by speech model {model}")]
+    LanguageUnsupportedByModel { language: &'static str, model: &'static str },
+
+    #[error("--{feature} is not supported for language {language}")]
+    FeatureUnsupportedForLanguage { feature: &'static str, language: &'static str },
+
+    #[error("compare-models needs at least two different speech models")]
+    CompareNeedsTwoModels,
+
+    #[error("invalid --concurrency 0; expected at least 1")]
+    InvalidConcurrency,
+
+    #[error("invalid --runs 0; expected at least 1")]
+    InvalidBenchRuns,
+
+    #[error("invalid token lifetime {value}s; expected {min}..={max} seconds")]
+    InvalidTokenExpiry { value: u32, min: u32, max: u32 },
+
+    #[error("transcript id must not be empty")]
+    EmptyTranscriptId,
+
+    #[error("--to must not be empty")]
+    EmptyTranslationLanguage,
+
+    #[error("translate supports only text, srt, or vtt output")]
+    UnsupportedTranslateFormat,
+
+    #[error("--translate-to requires --format srt-dual, --format anki-tsv, or --translated-output")]
+    TranslationWithoutTarget,
+
+    #[error("{option} requires {requires}")]
+    OptionRequiresOption { option: &'static str, requires: &'static str },
+
+    #[error("frame rate must be greater than 0")]
+    InvalidFrameRate,
+
+    #[error("invalid timecode {value:?}; expected HH:MM:SS:FF within the frame rate")]
+    InvalidTimecode { value: String },
+
+    #[error("invalid max-cps {value}; expected a positive number")]
+    InvalidMaxCps { value: f64 },
+
+    #[error("srt-start-index must be greater than 0")]
+    InvalidSrtStartIndex,
+
     #[error("--language is not allowed when language detection is enabled")]
     LanguageProvidedWithDetection,
 
@@ -238,6 +1509,95 @@ pub enum DomainError {
 
     #[error("invalid custom spelling entry at index {index}; 'from' and 'to' must be non-empty")]
     InvalidCustomSpellingEntry { index: usize },
+
+    #[error("invalid postReplace entry at index {index}: {message}")]
+    InvalidPostReplace { index: usize, message: String },
+
+    #[error("invalid pattern {pattern:?}: {message}")]
+    InvalidGrepPattern { pattern: String, message: String },
+
+    #[error("invalid speaker style {value:?}: {message}")]
+    InvalidSpeakerStyle { value: String, message: String },
+
+    #[error(
+        "invalid normalize rule {value:?}; expected quotes=straight|curly, whitespace, numbers, or decimal=LOCALE"
+    )]
+    InvalidNormalize { value: String },
+
+    #[error("invalid report {value:?}; expected keywords[=TERM,...], sentiment-timeline[=OPTIONS], silence[=THRESHOLD], interruptions[=MIN_OVERLAP], or profanity")]
+    InvalidReport { value: String },
+
+    #[error("report {report} requires {option}")]
+    ReportRequiresOption { report: &'static str, option: &'static str },
+
+    #[error("--check is only valid for srt/vtt output")]
+    CheckRequiresSubtitles,
+
+    #[error("--split-output-every/--split-output-by is only valid for srt/vtt output")]
+    SplitOutputRequiresSubtitles,
+
+    #[error("split-output-every must be greater than 0")]
+    InvalidSplitInterval,
+
+    #[error("--split-by-channel is only valid for text, srt, or vtt output")]
+    SplitByChannelUnsupportedFormat,
+
+    #[error("{option} cannot be combined with {other}")]
+    OptionsConflict { option: &'static str, other: &'static str },
+
+    #[error("{option} must be greater than 0")]
+    InvalidUploadLimit { option: &'static str },
+
+    #[error("invalid channel name {value:?}; expected CHANNEL=NAME")]
+    InvalidChannelName { value: String },
+
+    #[error("failed to parse subtitles {path:?} at line {line}: {message}")]
+    InvalidSubtitleFile { path: PathBuf, line: usize, message: String },
+
+    #[error("invalid duration {value:?}; expected e.g. 500ms, 30s, 5m, 1h, 30d")]
+    InvalidDuration { value: String },
+
+    #[error("invalid offset {value:?}; expected a time such as 1:02:00, 62:00.5, or 3720s")]
+    InvalidOffset { value: String },
+
+    #[error("--offsets needs one offset per file; got {offsets} for {inputs} files")]
+    OffsetCountMismatch { offsets: usize, inputs: usize },
+
+    #[error("invalid date {value:?}; expected YYYY-MM-DD or YYYY-MM-DDTHH:MM[:SS] (UTC)")]
+    InvalidDate { value: String },
+
+    #[error("delete needs --before DATE or config `retentionDays`")]
+    DeleteNeedsCutoff,
+
+    #[error("retentionDays must be greater than 0")]
+    InvalidRetentionDays,
+
+    #[error("invalid input pattern {pattern:?}: {message}; pass --no-glob to take it literally")]
+    InvalidGlob { pattern: String, message: String },
+
+    #[error("no files match {pattern:?}")]
+    NoGlobMatches { pattern: String },
+
+    #[error("invalid size {value:?}; expected e.g. 512K, 10M, 1G")]
+    InvalidSize { value: String },
+
+    #[error("invalid alias {name:?}; use letters, digits, '-', '_', or '.'")]
+    InvalidAliasName { name: String },
+
+    #[error("invalid --min-avg-confidence {value}; expected 0.0..=1.0")]
+    InvalidMinAvgConfidence { value: f64 },
+
+    #[error("invalid --mark-uncertain {value}; expected 0.0..=1.0")]
+    InvalidMarkUncertain { value: f64 },
+
+    #[error("--preview must be greater than 0")]
+    InvalidPreview,
+
+    #[error("--format exec: needs a command, e.g. exec:./my-formatter")]
+    EmptyExecFormatter,
+
+    #[error("invalid tag {value:?}; tags must be non-empty without spaces or commas")]
+    InvalidTag { value: String },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -253,16 +1613,122 @@ pub fn classify_local_media(path: &Path) -> MediaKind {
     };
 
     match ext.as_str() {
-        "mp3" | "wav" | "flac" | "m4a" | "ogg" => MediaKind::Audio,
+        "mp3" | "wav" | "flac" | "m4a" | "m4b" | "ogg" => MediaKind::Audio,
         "mp4" | "avi" | "mov" | "mkv" | "webm" => MediaKind::Video,
         _ => MediaKind::Unknown,
     }
 }
 
-fn parse_input(value: &str) -> Result<Input, DomainError> {
-    if value.starts_with("http://") || value.starts_with("https://") {
-        let url = url::Url::parse(value).map_err(|_| DomainError::InvalidUrl {
+fn supports_embedded_metadata(input: &Input) -> bool {
+    let Input::LocalPath(path) = input else {
+        return false;
+    };
+    let ext = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_ascii_lowercase())
+        .unwrap_or_default();
+    matches!(ext.as_str(), "mp3" | "m4a" | "mp4" | "mov")
+}
+
+/// `talk.mp4` -> `talk.chapters.mp4`, next to the input.
+fn chapters_copy_path(input: &Input) -> Option<PathBuf> {
+    let Input::LocalPath(path) = input else {
+        return None;
+    };
+    let ext = path.extension().and_then(|s| s.to_str())?;
+    if !matches!(ext.to_ascii_lowercase().as_str(), "mp4" | "m4a" | "m4b" | "mov" | "mkv") {
+        return None;
+    }
+    let stem = path.file_stem()?.to_string_lossy();
+    Some(path.with_file_name(format!("{stem}.chapters.{ext}")))
+}
+
+/// `out.srt` -> `out.part01.srt`; the number is padded to at least two digits, or to `total`'s width.
+pub fn split_part_path(path: &Path, index: usize, total: usize) -> PathBuf {
+    let width = total.to_string().len().max(2);
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let name = match path.extension() {
+        Some(ext) => format!("{stem}.part{index:0width$}.{}", ext.to_string_lossy()),
+        None => format!("{stem}.part{index:0width$}"),
+    };
+    path.with_file_name(name)
+}
+
+/// `out.txt` -> `out.Agent.txt`; characters unsafe in file names are replaced with `_`.
+pub fn channel_output_path(path: &Path, label: &str) -> PathBuf {
+    let label: String = label
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    table_output_path(path, &label)
+}
+
+/// `out.parquet` -> `out.words.parquet`.
+pub fn table_output_path(path: &Path, label: &str) -> PathBuf {
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let name = match path.extension() {
+        Some(ext) => format!("{stem}.{label}.{}", ext.to_string_lossy()),
+        None => format!("{stem}.{label}"),
+    };
+    path.with_file_name(name)
+}
+
+/// Parses `--channel-names` entries (`1=Agent`).
+pub fn parse_channel_name(value: &str) -> Result<(String, String), DomainError> {
+    match value.split_once('=') {
+        Some((channel, name)) if !channel.trim().is_empty() && !name.trim().is_empty() => {
+            Ok((channel.trim().to_string(), name.trim().to_string()))
+        }
+        _ => Err(DomainError::InvalidChannelName {
             value: value.to_string(),
+        }),
+    }
+}
+
+pub const REALTIME_TOKEN_MIN_EXPIRY: u32 = 60;
+pub const REALTIME_TOKEN_MAX_EXPIRY: u32 = 360_000;
+
+/// Realtime token lifetimes accepted by the API, in seconds.
+pub fn validate_token_expiry(seconds: u32) -> Result<u32, DomainError> {
+    if (REALTIME_TOKEN_MIN_EXPIRY..=REALTIME_TOKEN_MAX_EXPIRY).contains(&seconds) {
+        Ok(seconds)
+    } else {
+        Err(DomainError::InvalidTokenExpiry {
+            value: seconds,
+            min: REALTIME_TOKEN_MIN_EXPIRY,
+            max: REALTIME_TOKEN_MAX_EXPIRY,
+        })
+    }
+}
+
+/// Lowercased extension of a local path or of the last URL path segment.
+pub fn input_extension(value: &OsStr) -> Option<String> {
+    let path = match parse_input(value).ok()? {
+        Input::LocalPath(path) => path,
+        Input::Url(url) => PathBuf::from(url.path_segments()?.next_back()?),
+    };
+    path.extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_ascii_lowercase())
+}
+
+/// File name without its extension of a local path or of the last URL path segment.
+pub fn input_stem(value: &OsStr) -> Option<OsString> {
+    let path = match parse_input(value).ok()? {
+        Input::LocalPath(path) => path,
+        Input::Url(url) => PathBuf::from(url.path_segments()?.next_back()?),
+    };
+    path.file_stem().map(OsStr::to_os_string).filter(|s| !s.is_empty())
+}
+
+/// A URL, or else a local path kept as the platform string it was given as, whatever its encoding.
+fn parse_input(value: &OsStr) -> Result<Input, DomainError> {
+    if let Some(text) = value.to_str()
+        && (text.starts_with("http://") || text.starts_with("https://"))
+    {
+        let url = url::Url::parse(text).map_err(|_| DomainError::InvalidUrl {
+            value: text.to_string(),
         })?;
         return Ok(Input::Url(url));
     }
@@ -288,3 +1754,104 @@ pub fn parse_custom_spelling_kv(value: &str) -> Result<CustomSpelling, DomainErr
         to: to.to_string(),
     })
 }
+
+pub fn parse_duration_spec(value: &str) -> Result<Duration, DomainError> {
+    let trimmed = value.trim();
+    let invalid = || DomainError::InvalidDuration {
+        value: value.to_string(),
+    };
+
+    let split = trimmed
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    let number: f64 = number.parse().map_err(|_| invalid())?;
+    if !number.is_finite() || number < 0.0 {
+        return Err(invalid());
+    }
+
+    let millis = match unit.trim() {
+        "ms" => number,
+        "" | "s" => number * 1000.0,
+        "m" => number * 60_000.0,
+        "h" => number * 3_600_000.0,
+        "d" => number * 86_400_000.0,
+        _ => return Err(invalid()),
+    };
+
+    Ok(Duration::from_millis(millis.round() as u64))
+}
+
+/// A `merge --offsets` entry: a clock time (`1:02:00`, `62:00.5`) or a duration (`3720s`, `62m`; bare numbers are
+/// seconds).
+pub fn parse_offset_spec(value: &str) -> Result<u64, DomainError> {
+    let trimmed = value.trim();
+    if !trimmed.contains(':') {
+        return parse_duration_spec(trimmed)
+            .map(|duration| duration.as_millis() as u64)
+            .map_err(|_| DomainError::InvalidOffset {
+                value: value.to_string(),
+            });
+    }
+    let invalid = || DomainError::InvalidOffset {
+        value: value.to_string(),
+    };
+
+    let mut fields: Vec<&str> = trimmed.split(':').collect();
+    let seconds: f64 = fields.pop().and_then(|s| s.parse().ok()).ok_or_else(invalid)?;
+    let clock = fields
+        .iter()
+        .map(|field| field.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(invalid)?;
+    let (hours, minutes) = match clock.as_slice() {
+        [h, m] => (*h, *m),
+        [m] => (0, *m),
+        _ => return Err(invalid()),
+    };
+    if !(0.0..60.0).contains(&seconds) || (clock.len() == 2 && minutes >= 60) {
+        return Err(invalid());
+    }
+
+    Ok((hours * 60 + minutes) * 60_000 + (seconds * 1000.0).round() as u64)
+}
+
+/// A byte count such as `512K`, `10M`, or `1G`, in powers of 1024; a bare number is bytes.
+pub fn parse_size_spec(value: &str) -> Result<u64, DomainError> {
+    let trimmed = value.trim();
+    let invalid = || DomainError::InvalidSize {
+        value: value.to_string(),
+    };
+
+    let split = trimmed.find(|c: char| !c.is_ascii_digit()).unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    let number: u64 = number.parse().map_err(|_| invalid())?;
+    let shift = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 0,
+        "K" | "KB" | "KIB" => 10,
+        "M" | "MB" | "MIB" => 20,
+        "G" | "GB" | "GIB" => 30,
+        _ => return Err(invalid()),
+    };
+    number.checked_mul(1 << shift).filter(|bytes| *bytes > 0).ok_or_else(invalid)
+}
+
+/// `bytes` in the largest unit [`parse_size_spec`] reads, with one decimal, e.g. `1.5G`, `512K`, or `900B`.
+pub fn format_size(bytes: u64) -> String {
+    for (shift, unit) in [(30, "G"), (20, "M"), (10, "K")] {
+        if bytes >= 1 << shift {
+            let value = format!("{:.1}", bytes as f64 / (1u64 << shift) as f64);
+            return format!("{}{unit}", value.trim_end_matches(".0"));
+        }
+    }
+    format!("{bytes}B")
+}
+
+/// Upper bound on the mp3 that audio extraction writes: `duration_seconds` at 320 kbit/s, the highest MP3
+/// bitrate, or the input's own size when the duration is unknown.
+pub fn extracted_audio_bytes(input_bytes: u64, duration_seconds: Option<f64>) -> u64 {
+    match duration_seconds {
+        Some(seconds) => (seconds.max(0.0) * 40_000.0).ceil() as u64,
+        None => input_bytes,
+    }
+}
@@ -0,0 +1,239 @@
+use crate::domain::subtitles::DiarizedUtterance;
+use crate::domain::transcript::{format_clock_time, Chapter, Entity, Highlight, TimestampFormat};
+
+const MAX_KEY_TOPICS: usize = 10;
+
+pub const MEETING_MINUTES_PROMPT: &str = "You are writing minutes for the meeting in this transcript. \
+Respond in markdown with exactly these sections: `## Summary` (3-5 sentences), \
+`## Decisions` (one bullet per decision that was agreed on), and \
+`## Action items` (one `- [ ] OWNER: task` bullet per action item, with a due date when one was mentioned). \
+Refer to people as they are labelled in the transcript (e.g. `Speaker A`), adding a name in parentheses when it is stated. \
+Write `- None recorded.` under a section with nothing to report. Do not add other sections or commentary.";
+
+pub const SHOW_NOTES_PROMPT: &str = "You are writing show notes for the podcast episode in this transcript. \
+Respond in markdown with exactly two sections: `## Summary` (one paragraph of 3-5 sentences that makes a listener want to hear the episode) \
+and `## Key quotes` (3-5 bullets, each a short verbatim quote in double quotes followed by ` - ` and the speaker's name when it is stated). \
+Do not add other sections or commentary.";
+
+const KEY_QUOTES_HEADING: &str = "## Key quotes";
+const MAX_SHOW_TOPICS: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attendee {
+    pub speaker: String,
+    pub turns: usize,
+    pub talk_ms: u64,
+}
+
+/// Speakers in order of first appearance, with their turn count and total talk time.
+pub fn attendees(utterances: &[DiarizedUtterance]) -> Vec<Attendee> {
+    let mut out: Vec<Attendee> = Vec::new();
+    for utterance in utterances {
+        let talk_ms = utterance.end_ms.saturating_sub(utterance.start_ms);
+        match out.iter_mut().find(|a| a.speaker == utterance.speaker) {
+            Some(attendee) => {
+                attendee.turns += 1;
+                attendee.talk_ms += talk_ms;
+            }
+            None => out.push(Attendee {
+                speaker: utterance.speaker.clone(),
+                turns: 1,
+                talk_ms,
+            }),
+        }
+    }
+    out
+}
+
+pub fn format_meeting_notes(title: &str, attendees: &[Attendee], highlights: &[Highlight], minutes: &str) -> String {
+    let mut out = format!("# Meeting notes: {title}\n\n");
+
+    if !attendees.is_empty() {
+        out.push_str("## Attendees\n\n");
+        for attendee in attendees {
+            out.push_str(&format!(
+                "- Speaker {} ({} {}, {} speaking)\n",
+                attendee.speaker,
+                attendee.turns,
+                if attendee.turns == 1 { "turn" } else { "turns" },
+                format_clock_time(attendee.talk_ms)
+            ));
+        }
+        out.push('\n');
+    }
+
+    let mut topics: Vec<&Highlight> = highlights.iter().collect();
+    topics.sort_by(|a, b| b.rank.total_cmp(&a.rank));
+    if !topics.is_empty() {
+        out.push_str("## Key topics\n\n");
+        for topic in topics.into_iter().take(MAX_KEY_TOPICS) {
+            out.push_str(&format!("- {} ({}x)\n", topic.text, topic.count));
+        }
+        out.push('\n');
+    }
+
+    out.push_str(minutes.trim());
+    out.push('\n');
+    out
+}
+
+/// Person names from entity detection, most mentioned first.
+pub fn guest_names(entities: &[Entity]) -> Vec<String> {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for entity in entities.iter().filter(|e| e.entity_type == "person_name") {
+        let name = entity.text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if name.is_empty() {
+            continue;
+        }
+        match counts.iter_mut().find(|(existing, _)| existing.eq_ignore_ascii_case(&name)) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((name, 1)),
+        }
+    }
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    counts.into_iter().map(|(name, _)| name).collect()
+}
+
+/// `summary` is the LeMUR response; its key quotes section is moved after the chapter list.
+pub fn format_show_notes(
+    title: &str,
+    summary: &str,
+    chapters: &[Chapter],
+    highlights: &[Highlight],
+    guests: &[String],
+    timestamps: TimestampFormat,
+) -> String {
+    let (summary, quotes) = match summary.find(KEY_QUOTES_HEADING) {
+        Some(idx) => (&summary[..idx], &summary[idx..]),
+        None => (summary, ""),
+    };
+
+    let mut out = format!("# {title}\n\n");
+    out.push_str(summary.trim());
+    out.push_str("\n\n");
+
+    let mut topics: Vec<&Highlight> = highlights.iter().collect();
+    topics.sort_by(|a, b| b.rank.total_cmp(&a.rank));
+    if !topics.is_empty() {
+        let topics: Vec<&str> = topics.iter().take(MAX_SHOW_TOPICS).map(|t| t.text.as_str()).collect();
+        out.push_str(&format!("Topics: {}\n\n", topics.join(", ")));
+    }
+
+    if !chapters.is_empty() {
+        out.push_str("## Chapters\n\n");
+        for chapter in chapters {
+            out.push_str(&format!("- {} {}\n", timestamps.format(chapter.start_ms), chapter.headline.trim()));
+        }
+        out.push('\n');
+    }
+
+    if !quotes.trim().is_empty() {
+        out.push_str(quotes.trim());
+        out.push_str("\n\n");
+    }
+
+    if !guests.is_empty() {
+        out.push_str("## Guests\n\n");
+        for guest in guests {
+            out.push_str(&format!("- {guest}\n"));
+        }
+        out.push('\n');
+    }
+
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utterance(speaker: &str, start_ms: u64, end_ms: u64) -> DiarizedUtterance {
+        DiarizedUtterance::new(start_ms, end_ms, speaker.to_string(), "text".to_string()).expect("utterance")
+    }
+
+    #[test]
+    fn attendees_aggregate_turns_in_order_of_appearance() {
+        let got = attendees(&[utterance("B", 0, 1000), utterance("A", 1000, 4000), utterance("B", 4000, 6000)]);
+        assert_eq!(
+            got,
+            vec![
+                Attendee {
+                    speaker: "B".to_string(),
+                    turns: 2,
+                    talk_ms: 3000
+                },
+                Attendee {
+                    speaker: "A".to_string(),
+                    turns: 1,
+                    talk_ms: 3000
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn meeting_notes_rank_topics_and_append_minutes() {
+        let highlights = vec![
+            Highlight {
+                text: "budget".to_string(),
+                count: 2,
+                rank: 0.05,
+            },
+            Highlight {
+                text: "launch date".to_string(),
+                count: 4,
+                rank: 0.09,
+            },
+        ];
+        let notes = format_meeting_notes(
+            "standup",
+            &attendees(&[utterance("A", 0, 61_000)]),
+            &highlights,
+            "\n## Summary\n\nShort.\n",
+        );
+        assert_eq!(
+            notes,
+            "# Meeting notes: standup\n\n## Attendees\n\n- Speaker A (1 turn, 00:01:01 speaking)\n\n## Key topics\n\n- launch date (4x)\n- budget (2x)\n\n## Summary\n\nShort.\n"
+        );
+    }
+
+    #[test]
+    fn guest_names_dedupe_person_entities_by_mentions() {
+        let entity = |entity_type: &str, text: &str| Entity {
+            entity_type: entity_type.to_string(),
+            text: text.to_string(),
+        };
+        let got = guest_names(&[
+            entity("person_name", "Ada Lovelace"),
+            entity("location", "London"),
+            entity("person_name", "Charles Babbage"),
+            entity("person_name", "charles  babbage"),
+        ]);
+        assert_eq!(got, vec!["Charles Babbage".to_string(), "Ada Lovelace".to_string()]);
+    }
+
+    #[test]
+    fn show_notes_put_chapters_between_summary_and_quotes() {
+        let chapters = vec![Chapter {
+            start_ms: 65_000,
+            end_ms: 120_000,
+            headline: "Early computers".to_string(),
+            gist: String::new(),
+            summary: String::new(),
+        }];
+        let notes = format_show_notes(
+            "Episode 1",
+            "## Summary\n\nAbout engines.\n\n## Key quotes\n\n- \"It computes.\"\n",
+            &chapters,
+            &[],
+            &["Ada Lovelace".to_string()],
+            TimestampFormat::MmSs,
+        );
+        assert_eq!(
+            notes,
+            "# Episode 1\n\n## Summary\n\nAbout engines.\n\n## Chapters\n\n- 01:05 Early computers\n\n## Key quotes\n\n- \"It computes.\"\n\n## Guests\n\n- Ada Lovelace\n"
+        );
+    }
+}
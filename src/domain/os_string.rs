@@ -0,0 +1,126 @@
+//! Serde for `OsString` inputs and arguments in history, jobs, and failures files.
+//!
+//! Valid Unicode is stored as a plain JSON string, so existing files read unchanged. Anything else is stored as
+//! `{"unix": [bytes]}` or `{"windows": [UTF-16 units]}`, so a non-UTF-8 file name survives the round trip.
+
+use std::ffi::{OsStr, OsString};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Repr {
+    Text(String),
+    Raw(Raw),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Raw {
+    Unix(Vec<u8>),
+    Windows(Vec<u16>),
+}
+
+fn to_repr(value: &OsStr) -> Repr {
+    if let Some(text) = value.to_str() {
+        return Repr::Text(text.to_string());
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        Repr::Raw(Raw::Unix(value.as_bytes().to_vec()))
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStrExt;
+
+        Repr::Raw(Raw::Windows(value.encode_wide().collect()))
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        Repr::Text(value.to_string_lossy().into_owned())
+    }
+}
+
+/// A value recorded on another platform comes back lossily converted.
+fn from_repr(repr: Repr) -> OsString {
+    match repr {
+        Repr::Text(text) => OsString::from(text),
+        #[cfg(unix)]
+        Repr::Raw(Raw::Unix(bytes)) => {
+            use std::os::unix::ffi::OsStringExt;
+
+            OsString::from_vec(bytes)
+        }
+        #[cfg(windows)]
+        Repr::Raw(Raw::Windows(units)) => {
+            use std::os::windows::ffi::OsStringExt;
+
+            OsString::from_wide(&units)
+        }
+        #[allow(unreachable_patterns)]
+        Repr::Raw(Raw::Unix(bytes)) => OsString::from(String::from_utf8_lossy(&bytes).into_owned()),
+        #[allow(unreachable_patterns)]
+        Repr::Raw(Raw::Windows(units)) => OsString::from(String::from_utf16_lossy(&units)),
+    }
+}
+
+pub fn serialize<S: Serializer>(value: &OsStr, serializer: S) -> Result<S::Ok, S::Error> {
+    to_repr(value).serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<OsString, D::Error> {
+    Repr::deserialize(deserializer).map(from_repr)
+}
+
+/// The same encoding for each element of a `Vec<OsString>`.
+pub mod vec {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(values: &[OsString], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(values.iter().map(|value| to_repr(value)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<OsString>, D::Error> {
+        Ok(Vec::<Repr>::deserialize(deserializer)?.into_iter().map(from_repr).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        #[serde(with = "super")]
+        input: OsString,
+        #[serde(with = "super::vec")]
+        args: Vec<OsString>,
+    }
+
+    #[test]
+    fn unicode_stays_a_string_and_other_names_round_trip() {
+        let record = Record {
+            input: OsString::from("Клод 🎙.m4a"),
+            args: vec![OsString::from("transcribe"), OsString::from("Клод 🎙.m4a")],
+        };
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(json, r#"{"input":"Клод 🎙.m4a","args":["transcribe","Клод 🎙.m4a"]}"#);
+        assert_eq!(serde_json::from_str::<Record>(&json).unwrap(), record);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStringExt;
+
+            let latin1 = OsString::from_vec(b"caf\xe9.mp3".to_vec());
+            let record = Record {
+                input: latin1.clone(),
+                args: vec![OsString::from("transcribe"), latin1],
+            };
+            let json = serde_json::to_string(&record).unwrap();
+            assert!(json.starts_with(r#"{"input":{"unix":[99,97,102,233,46,109,112,51]}"#), "{json}");
+            assert_eq!(serde_json::from_str::<Record>(&json).unwrap(), record);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! oTranscribe `.otr` documents, so a transcript can be corrected further in that web editor.
+
+use crate::domain::subtitles::Caption;
+
+/// The `.otr` JSON for `media`: one paragraph per caption, each opening with a clickable timestamp.
+pub fn format_otr(media: &str, paragraphs: &[Caption]) -> String {
+    let text: String = paragraphs
+        .iter()
+        .map(|paragraph| {
+            format!(
+                "<p>{} {}</p>",
+                timestamp_span(paragraph.start_ms),
+                escape_html(&paragraph.text).replace('\n', "<br>")
+            )
+        })
+        .collect();
+    let document = serde_json::json!({
+        "text": text,
+        "media": media,
+        "media-source": "",
+        "media-time": 0.0,
+    });
+    format!("{document}\n")
+}
+
+/// oTranscribe's own timestamp markup: seconds in `data-timestamp`, `mm:ss` (or `h:mm:ss`) as the label.
+fn timestamp_span(ms: u64) -> String {
+    let seconds = ms / 1000;
+    let label = if seconds >= 3600 {
+        format!("{}:{:02}:{:02}", seconds / 3600, (seconds / 60) % 60, seconds % 60)
+    } else {
+        format!("{:02}:{:02}", seconds / 60, seconds % 60)
+    };
+    format!(
+        r#"<span class="timestamp" contenteditable="false" data-timestamp="{seconds}.{:03}">{label}</span>"#,
+        ms % 1000
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_timestamped_paragraphs() {
+        let paragraphs = [
+            Caption {
+                start_ms: 1500,
+                end_ms: 3000,
+                speaker: None,
+                text: "Speaker A: Fish & <chips>.".to_string(),
+            },
+            Caption {
+                start_ms: 3_723_040,
+                end_ms: 3_725_000,
+                speaker: None,
+                text: "Bye.".to_string(),
+            },
+        ];
+        let document: serde_json::Value = serde_json::from_str(&format_otr("call.mp3", &paragraphs)).unwrap();
+        assert_eq!(
+            document["text"],
+            "<p><span class=\"timestamp\" contenteditable=\"false\" data-timestamp=\"1.500\">00:01</span> \
+             Speaker A: Fish &amp; &lt;chips&gt;.</p>\
+             <p><span class=\"timestamp\" contenteditable=\"false\" data-timestamp=\"3723.040\">1:02:03</span> Bye.</p>"
+        );
+        assert_eq!(document["media"], "call.mp3");
+        assert_eq!(document["media-time"], 0.0);
+    }
+}
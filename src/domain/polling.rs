@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+/// Longest wait between two polls of the adaptive strategy.
+const MAX_ADAPTIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Share of the audio duration a transcription is expected to take.
+const EXPECTED_PROCESSING_RATIO: f64 = 0.25;
+
+/// How long `transcribe` waits between polls of a queued or processing transcript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PollStrategy {
+    /// Always the poll interval.
+    Fixed,
+    /// The poll interval early on, backing off to 30s on long jobs and tightening again near the expected completion.
+    #[default]
+    Adaptive,
+}
+
+impl PollStrategy {
+    /// The wait before the next poll, `elapsed` into the job.
+    ///
+    /// `base` is the configured poll interval and the shortest wait; `audio_seconds` is the duration once the API reports it.
+    pub fn next_interval(self, base: Duration, elapsed: Duration, audio_seconds: Option<f64>) -> Duration {
+        if self == PollStrategy::Fixed {
+            return base;
+        }
+        let backoff = |since: Duration| (since / 4).clamp(base, MAX_ADAPTIVE_INTERVAL.max(base));
+        let expected = audio_seconds
+            .filter(|seconds| seconds.is_finite() && *seconds > 0.0)
+            .map(|seconds| Duration::from_secs_f64(seconds * EXPECTED_PROCESSING_RATIO));
+        match expected {
+            Some(expected) if elapsed < expected => backoff(elapsed).min((expected - elapsed) / 2).max(base),
+            Some(expected) => backoff(elapsed - expected),
+            None => backoff(elapsed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adaptive_interval_backs_off_and_tightens_near_expected_completion() {
+        let base = Duration::from_secs(3);
+        let secs = Duration::from_secs;
+
+        assert_eq!(PollStrategy::Fixed.next_interval(base, secs(600), Some(3600.0)), base);
+
+        // No duration yet: back off with elapsed time, capped at 30s.
+        assert_eq!(PollStrategy::Adaptive.next_interval(base, secs(4), None), base);
+        assert_eq!(PollStrategy::Adaptive.next_interval(base, secs(40), None), secs(10));
+        assert_eq!(PollStrategy::Adaptive.next_interval(base, secs(600), None), secs(30));
+
+        // A 1h recording is expected after 15 minutes.
+        assert_eq!(PollStrategy::Adaptive.next_interval(base, secs(300), Some(3600.0)), secs(30));
+        assert_eq!(PollStrategy::Adaptive.next_interval(base, secs(880), Some(3600.0)), secs(10));
+        assert_eq!(PollStrategy::Adaptive.next_interval(base, secs(899), Some(3600.0)), base);
+        // Overdue: poll at the base interval again, then back off from the expected completion.
+        assert_eq!(PollStrategy::Adaptive.next_interval(base, secs(905), Some(3600.0)), base);
+        assert_eq!(PollStrategy::Adaptive.next_interval(base, secs(1000), Some(3600.0)), secs(25));
+
+        // A base interval above the cap is never shortened.
+        assert_eq!(PollStrategy::Adaptive.next_interval(secs(60), secs(600), None), secs(60));
+    }
+}
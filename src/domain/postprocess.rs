@@ -0,0 +1,396 @@
+use regex::{NoExpand, Regex, RegexBuilder};
+
+use crate::domain::reports::normalize_token;
+use crate::domain::DomainError;
+
+/// A `postReplace` config entry; `from` is literal text unless `regex` is set.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostReplace {
+    pub from: String,
+    pub to: String,
+
+    #[serde(default)]
+    pub regex: bool,
+
+    #[serde(default)]
+    pub ignore_case: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RedactStyle {
+    #[default]
+    Block,
+    Tag,
+}
+
+impl RedactStyle {
+    pub fn mask(self) -> &'static str {
+        match self {
+            RedactStyle::Block => "\u{2588}\u{2588}\u{2588}\u{2588}",
+            RedactStyle::Tag => "[REDACTED]",
+        }
+    }
+}
+
+/// Terms from a redaction file: one word or phrase per line; blank lines and `#` comments are skipped.
+pub fn parse_redact_terms(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(|line| line.trim_start_matches('\u{feff}').trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect()
+}
+
+/// Decimal-comma languages; other locales keep the API's `1,234.5` style.
+const DECIMAL_COMMA_LANGUAGES: &[&str] = &[
+    "bg", "ca", "cs", "da", "de", "el", "es", "et", "fi", "fr", "hr", "hu", "id", "it", "lt", "lv", "nb", "nl", "nn",
+    "no", "pl", "pt", "ro", "ru", "sk", "sl", "sr", "sv", "tr", "uk", "vi",
+];
+
+/// A `--normalize` rule, applied after replacements and redaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    StraightQuotes,
+    CurlyQuotes,
+    Whitespace,
+    Numbers,
+    DecimalComma,
+    DecimalPoint,
+}
+
+pub fn parse_normalize_spec(value: &str) -> Result<Normalization, DomainError> {
+    let invalid = || DomainError::InvalidNormalize {
+        value: value.to_string(),
+    };
+    let (name, arg) = match value.split_once('=') {
+        Some((name, arg)) => (name.trim(), Some(arg.trim())),
+        None => (value.trim(), None),
+    };
+
+    match (name, arg) {
+        ("quotes", Some("straight")) => Ok(Normalization::StraightQuotes),
+        ("quotes", Some("curly")) => Ok(Normalization::CurlyQuotes),
+        ("whitespace", None) => Ok(Normalization::Whitespace),
+        ("numbers", None) => Ok(Normalization::Numbers),
+        ("decimal", Some(locale)) => {
+            let language = locale.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase();
+            if language.is_empty() || !language.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(invalid());
+            }
+            if DECIMAL_COMMA_LANGUAGES.contains(&language.as_str()) {
+                Ok(Normalization::DecimalComma)
+            } else {
+                Ok(Normalization::DecimalPoint)
+            }
+        }
+        _ => Err(invalid()),
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Redaction {
+    pattern: Regex,
+    phrases: Vec<Vec<String>>,
+    mask: &'static str,
+}
+
+#[derive(Debug, Clone)]
+struct ReplaceRule {
+    pattern: Regex,
+    replacement: String,
+    expand: bool,
+}
+
+/// Local text rewrites applied to the API result before any output is rendered.
+#[derive(Debug, Clone, Default)]
+pub struct PostProcessor {
+    rules: Vec<ReplaceRule>,
+    redaction: Option<Redaction>,
+    normalize: Vec<Normalization>,
+}
+
+impl PostProcessor {
+    pub fn new(
+        post_replace: &[PostReplace],
+        redact_terms: &[String],
+        redact_style: RedactStyle,
+        normalize: &[Normalization],
+    ) -> Result<Self, DomainError> {
+        let mut rules = Vec::new();
+        for (index, entry) in post_replace.iter().enumerate() {
+            if entry.from.is_empty() {
+                return Err(DomainError::InvalidPostReplace {
+                    index,
+                    message: "'from' must be non-empty".to_string(),
+                });
+            }
+            let source = if entry.regex {
+                entry.from.clone()
+            } else {
+                regex::escape(&entry.from)
+            };
+            let pattern = RegexBuilder::new(&source)
+                .case_insensitive(entry.ignore_case)
+                .build()
+                .map_err(|err| DomainError::InvalidPostReplace {
+                    index,
+                    message: err.to_string(),
+                })?;
+            rules.push(ReplaceRule {
+                pattern,
+                replacement: entry.to.clone(),
+                expand: entry.regex,
+            });
+        }
+        Ok(Self {
+            rules,
+            redaction: build_redaction(redact_terms, redact_style),
+            normalize: normalize.to_vec(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty() && self.redaction.is_none() && self.normalize.is_empty()
+    }
+
+    /// Masks redacted phrases that span several words, one mask per word so word timings are kept.
+    /// Single words are handled by [`PostProcessor::apply`].
+    pub fn redact_word_sequence(&self, words: &mut [String]) {
+        let Some(redaction) = &self.redaction else {
+            return;
+        };
+        let tokens: Vec<String> = words.iter().map(|w| normalize_token(w)).collect();
+        for phrase in redaction.phrases.iter().filter(|p| p.len() > 1) {
+            for start in 0..tokens.len().saturating_sub(phrase.len() - 1) {
+                if tokens[start..start + phrase.len()] == phrase[..] {
+                    for word in &mut words[start..start + phrase.len()] {
+                        *word = redaction.mask.to_string();
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        let mut out = text.to_string();
+        for rule in &self.rules {
+            out = if rule.expand {
+                rule.pattern.replace_all(&out, rule.replacement.as_str()).into_owned()
+            } else {
+                rule.pattern.replace_all(&out, NoExpand(&rule.replacement)).into_owned()
+            };
+        }
+        if let Some(redaction) = &self.redaction {
+            out = redaction.pattern.replace_all(&out, NoExpand(redaction.mask)).into_owned();
+        }
+        for rule in &self.normalize {
+            out = normalize(&out, *rule);
+        }
+        out
+    }
+}
+
+fn normalize(text: &str, rule: Normalization) -> String {
+    match rule {
+        Normalization::StraightQuotes => text
+            .chars()
+            .map(|c| match c {
+                '\u{201c}' | '\u{201d}' | '\u{201e}' => '"',
+                '\u{2018}' | '\u{2019}' | '\u{201a}' => '\'',
+                other => other,
+            })
+            .collect(),
+        Normalization::CurlyQuotes => {
+            let mut out = String::with_capacity(text.len());
+            let mut prev: Option<char> = None;
+            for c in text.chars() {
+                let opening = prev.is_none_or(|p| p.is_whitespace() || "([{\u{2014}\u{2013}".contains(p));
+                out.push(match (c, opening) {
+                    ('"', true) => '\u{201c}',
+                    ('"', false) => '\u{201d}',
+                    ('\'', true) => '\u{2018}',
+                    ('\'', false) => '\u{2019}',
+                    (other, _) => other,
+                });
+                prev = Some(c);
+            }
+            out
+        }
+        Normalization::Whitespace => text
+            .split('\n')
+            .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Normalization::Numbers | Normalization::DecimalComma | Normalization::DecimalPoint => {
+            number_pattern().replace_all(text, |caps: &regex::Captures| rewrite_number(&caps[0], rule)).into_owned()
+        }
+    }
+}
+
+fn number_pattern() -> &'static Regex {
+    static PATTERN: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\d[\d,.]*\d").expect("number pattern compiles"))
+}
+
+/// Rewrites `1,234.5`-style numbers; anything else (versions, dates, times) is left alone.
+fn rewrite_number(token: &str, rule: Normalization) -> String {
+    let (integer, fraction) = match token.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (token, None),
+    };
+    let groups: Vec<&str> = integer.split(',').collect();
+    let grouped = groups.len() > 1
+        && (1..=3).contains(&groups[0].len())
+        && groups[1..].iter().all(|g| g.len() == 3);
+    let valid_fraction = fraction.is_none_or(|f| !f.is_empty() && f.chars().all(|c| c.is_ascii_digit()));
+    if !valid_fraction || (groups.len() > 1 && !grouped) {
+        return token.to_string();
+    }
+
+    match rule {
+        Normalization::Numbers => {
+            let mut out = groups.concat();
+            if let Some(fraction) = fraction {
+                out.push('.');
+                out.push_str(fraction);
+            }
+            out
+        }
+        Normalization::DecimalComma => {
+            let mut out = groups.join(".");
+            if let Some(fraction) = fraction {
+                out.push(',');
+                out.push_str(fraction);
+            }
+            out
+        }
+        _ => token.to_string(),
+    }
+}
+
+/// Case-insensitive, whole-word matching; whitespace inside a phrase matches any run of whitespace.
+/// Longer terms come first so a phrase wins over a term it starts with.
+fn build_redaction(terms: &[String], style: RedactStyle) -> Option<Redaction> {
+    let mut sorted: Vec<&String> = terms.iter().collect();
+    sorted.sort_by_key(|t| std::cmp::Reverse(t.len()));
+    let alternatives: Vec<String> = sorted
+        .into_iter()
+        .filter(|t| !t.trim().is_empty())
+        .map(|term| {
+            let body = term.split_whitespace().map(regex::escape).collect::<Vec<_>>().join(r"\s+");
+            let starts_word = term.trim_start().starts_with(|c: char| c.is_alphanumeric() || c == '_');
+            let ends_word = term.trim_end().ends_with(|c: char| c.is_alphanumeric() || c == '_');
+            format!(
+                "{}{body}{}",
+                if starts_word { r"\b" } else { "" },
+                if ends_word { r"\b" } else { "" }
+            )
+        })
+        .collect();
+    if alternatives.is_empty() {
+        return None;
+    }
+
+    let pattern = RegexBuilder::new(&alternatives.join("|"))
+        .case_insensitive(true)
+        .build()
+        .ok()?;
+    let phrases = terms
+        .iter()
+        .map(|term| term.split_whitespace().map(normalize_token).collect())
+        .collect();
+
+    Some(Redaction {
+        pattern,
+        phrases,
+        mask: style.mask(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(from: &str, to: &str, regex: bool, ignore_case: bool) -> PostReplace {
+        PostReplace {
+            from: from.to_string(),
+            to: to.to_string(),
+            regex,
+            ignore_case,
+        }
+    }
+
+    #[test]
+    fn applies_literal_and_regex_rules_in_order() {
+        let processor = PostProcessor::new(
+            &[
+                rule("acme", "ACME", false, true),
+                rule(r"ACME (\d+)", "ACME-$1", true, false),
+                rule("$1", "one dollar", false, false),
+            ],
+            &[],
+            RedactStyle::Block,
+            &[],
+        )
+        .expect("rules compile");
+        assert_eq!(processor.apply("Acme 42 costs $1"), "ACME-42 costs one dollar");
+    }
+
+    #[test]
+    fn rejects_invalid_regex() {
+        let err = PostProcessor::new(&[rule("(", "", true, false)], &[], RedactStyle::Block, &[]).expect_err("invalid regex");
+        assert!(matches!(err, DomainError::InvalidPostReplace { index: 0, .. }));
+    }
+
+    #[test]
+    fn redacts_whole_words_and_phrases() {
+        let terms = parse_redact_terms("# codenames\nBlue Falcon\n\nc++\nzeta\n");
+        assert_eq!(terms, vec!["Blue Falcon", "c++", "zeta"]);
+        let processor = PostProcessor::new(&[], &terms, RedactStyle::Tag, &[]).expect("redaction builds");
+
+        assert_eq!(
+            processor.apply("Project blue  falcon uses C++ and Zeta, not zetas."),
+            "Project [REDACTED] uses [REDACTED] and [REDACTED], not zetas."
+        );
+
+        let mut words: Vec<String> = ["the", "Blue", "falcon.", "flies"].iter().map(|w| w.to_string()).collect();
+        processor.redact_word_sequence(&mut words);
+        assert_eq!(words, vec!["the", "[REDACTED]", "[REDACTED]", "flies"]);
+    }
+
+    #[test]
+    fn parses_normalize_specs() {
+        assert_eq!(parse_normalize_spec("quotes=curly").unwrap(), Normalization::CurlyQuotes);
+        assert_eq!(parse_normalize_spec("decimal=de-DE").unwrap(), Normalization::DecimalComma);
+        assert_eq!(parse_normalize_spec("decimal=en_US").unwrap(), Normalization::DecimalPoint);
+        assert!(parse_normalize_spec("quotes").is_err());
+        assert!(parse_normalize_spec("decimal=").is_err());
+    }
+
+    #[test]
+    fn normalizes_quotes_whitespace_and_numbers() {
+        let processor = PostProcessor::new(
+            &[],
+            &[],
+            RedactStyle::Block,
+            &[Normalization::CurlyQuotes, Normalization::Whitespace, Normalization::DecimalComma],
+        )
+        .expect("processor builds");
+        assert_eq!(
+            processor.apply("  He said \"it's 1,234.5  now\" in v1.2.3\nok "),
+            "He said \u{201c}it\u{2019}s 1.234,5 now\u{201d} in v1.2.3\nok"
+        );
+
+        let processor = PostProcessor::new(
+            &[],
+            &[],
+            RedactStyle::Block,
+            &[Normalization::StraightQuotes, Normalization::Numbers],
+        )
+        .expect("processor builds");
+        assert_eq!(processor.apply("\u{201c}2,500,000\u{201d} at 10:30"), "\"2500000\" at 10:30");
+    }
+}
@@ -0,0 +1,694 @@
+use std::time::Duration;
+
+use crate::domain::transcript::{SentimentSegment, TimestampFormat, Word};
+use crate::domain::DomainError;
+
+const KEYWORD_TOP_N: usize = 10;
+const KEYWORD_CONTEXT_WORDS: usize = 5;
+const KEYWORD_TOP_N_CONTEXT_LINES: usize = 5;
+
+const SENTIMENT_DEFAULT_BUCKET: Duration = Duration::from_secs(60);
+
+const SILENCE_DEFAULT_THRESHOLD: Duration = Duration::from_secs(2);
+
+const INTERRUPTION_DEFAULT_MIN_OVERLAP: Duration = Duration::from_millis(500);
+
+const STOPWORDS: &[&str] = &[
+    "about", "above", "after", "again", "all", "also", "and", "any", "are", "because", "been", "before", "being",
+    "but", "can", "could", "did", "does", "doing", "don't", "down", "for", "from", "get", "got", "had", "has",
+    "have", "having", "her", "here", "hers", "him", "his", "how", "i'm", "into", "it's", "its", "just", "know",
+    "like", "more", "most", "not", "now", "off", "once", "one", "only", "other", "our", "out", "over", "really",
+    "she", "should", "some", "such", "than", "that", "that's", "the", "their", "them", "then", "there", "these",
+    "they", "this", "those", "through", "too", "under", "until", "very", "was", "way", "well", "were", "what",
+    "when", "where", "which", "while", "who", "why", "will", "with", "would", "yeah", "yes", "you", "you're",
+    "your",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReportKind {
+    Keywords { terms: Vec<String> },
+    SentimentTimeline {
+        bucket: Duration,
+        per_speaker: bool,
+        format: SeriesFormat,
+    },
+    Silence { threshold: Duration },
+    Interruptions { min_overlap: Duration },
+    Profanity,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesFormat {
+    Csv,
+    Json,
+}
+
+pub fn parse_report_spec(value: &str) -> Result<ReportKind, DomainError> {
+    let (name, arg) = match value.split_once('=') {
+        Some((name, arg)) => (name.trim(), Some(arg)),
+        None => (value.trim(), None),
+    };
+
+    match name {
+        "keywords" => {
+            let terms = arg
+                .map(|arg| {
+                    arg.split(',')
+                        .map(|t| t.trim().to_string())
+                        .filter(|t| !t.is_empty())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            if arg.is_some() && terms.is_empty() {
+                return Err(DomainError::InvalidReport {
+                    value: value.to_string(),
+                });
+            }
+            Ok(ReportKind::Keywords { terms })
+        }
+        "sentiment-timeline" => {
+            let mut bucket = SENTIMENT_DEFAULT_BUCKET;
+            let mut per_speaker = false;
+            let mut format = SeriesFormat::Csv;
+            for option in arg.unwrap_or_default().split(',').map(str::trim).filter(|o| !o.is_empty()) {
+                match option {
+                    "csv" => format = SeriesFormat::Csv,
+                    "json" => format = SeriesFormat::Json,
+                    "per-speaker" => per_speaker = true,
+                    other => {
+                        bucket = crate::domain::parse_duration_spec(other).map_err(|_| DomainError::InvalidReport {
+                            value: value.to_string(),
+                        })?;
+                        if bucket.is_zero() {
+                            return Err(DomainError::InvalidReport {
+                                value: value.to_string(),
+                            });
+                        }
+                    }
+                }
+            }
+            Ok(ReportKind::SentimentTimeline {
+                bucket,
+                per_speaker,
+                format,
+            })
+        }
+        "silence" => {
+            let threshold = match arg {
+                Some(arg) => crate::domain::parse_duration_spec(arg.trim()).map_err(|_| DomainError::InvalidReport {
+                    value: value.to_string(),
+                })?,
+                None => SILENCE_DEFAULT_THRESHOLD,
+            };
+            if threshold.is_zero() {
+                return Err(DomainError::InvalidReport {
+                    value: value.to_string(),
+                });
+            }
+            Ok(ReportKind::Silence { threshold })
+        }
+        "interruptions" => {
+            let min_overlap = match arg {
+                Some(arg) => crate::domain::parse_duration_spec(arg.trim()).map_err(|_| DomainError::InvalidReport {
+                    value: value.to_string(),
+                })?,
+                None => INTERRUPTION_DEFAULT_MIN_OVERLAP,
+            };
+            Ok(ReportKind::Interruptions { min_overlap })
+        }
+        "profanity" if arg.is_none() => Ok(ReportKind::Profanity),
+        _ => Err(DomainError::InvalidReport {
+            value: value.to_string(),
+        }),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SentimentBucket {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    pub positive: u32,
+    pub neutral: u32,
+    pub negative: u32,
+    pub score: f64,
+}
+
+pub fn sentiment_timeline(segments: &[SentimentSegment], bucket: Duration, per_speaker: bool) -> Vec<SentimentBucket> {
+    let bucket_ms = (bucket.as_millis() as u64).max(1);
+    let mut buckets: std::collections::BTreeMap<(u64, Option<String>), (u32, u32, u32)> =
+        std::collections::BTreeMap::new();
+
+    for segment in segments {
+        let index = segment.start_ms / bucket_ms;
+        let speaker = if per_speaker {
+            Some(segment.speaker.clone().unwrap_or_else(|| "Unknown".to_string()))
+        } else {
+            None
+        };
+        let entry = buckets.entry((index, speaker)).or_default();
+        match segment.sentiment {
+            crate::domain::transcript::Sentiment::Positive => entry.0 += 1,
+            crate::domain::transcript::Sentiment::Neutral => entry.1 += 1,
+            crate::domain::transcript::Sentiment::Negative => entry.2 += 1,
+        }
+    }
+
+    buckets
+        .into_iter()
+        .map(|((index, speaker), (positive, neutral, negative))| {
+            let total = f64::from(positive + neutral + negative);
+            let score = (f64::from(positive) - f64::from(negative)) / total;
+            SentimentBucket {
+                start_ms: index * bucket_ms,
+                end_ms: (index + 1) * bucket_ms,
+                speaker,
+                positive,
+                neutral,
+                negative,
+                score: (score * 1000.0).round() / 1000.0,
+            }
+        })
+        .collect()
+}
+
+pub fn format_sentiment_timeline(
+    segments: &[SentimentSegment],
+    bucket: Duration,
+    per_speaker: bool,
+    format: SeriesFormat,
+    timestamps: Option<TimestampFormat>,
+) -> String {
+    let series = sentiment_timeline(segments, bucket, per_speaker);
+    match format {
+        SeriesFormat::Json => {
+            let mut out = serde_json::to_string_pretty(&series).unwrap_or_else(|_| "[]".to_string());
+            out.push('\n');
+            out
+        }
+        SeriesFormat::Csv => {
+            let timestamps = timestamps.unwrap_or(TimestampFormat::Ms);
+            let mut out = String::from(if timestamps == TimestampFormat::Ms {
+                "start_ms,end_ms,"
+            } else {
+                "start,end,"
+            });
+            if per_speaker {
+                out.push_str("speaker,");
+            }
+            out.push_str("positive,neutral,negative,score\n");
+            for b in &series {
+                out.push_str(&format!("{},{},", timestamps.format(b.start_ms), timestamps.format(b.end_ms)));
+                if let Some(speaker) = &b.speaker {
+                    out.push_str(&csv_field(speaker));
+                    out.push(',');
+                }
+                out.push_str(&format!("{},{},{},{}\n", b.positive, b.neutral, b.negative, b.score));
+            }
+            out
+        }
+    }
+}
+
+/// A stretch without speech, in ms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SilenceGap {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// Gaps of at least `threshold` around the speech `spans` (start/end ms of words or utterances, in order): before
+/// the first, between them, and after the last when the audio's `duration_ms` is known. Overlapping spans, such as
+/// crosstalk, count as speech until the latest end.
+pub fn silence_gaps(spans: &[(u64, u64)], duration_ms: Option<u64>, threshold: Duration) -> Vec<SilenceGap> {
+    let threshold_ms = threshold.as_millis() as u64;
+    let mut gaps = Vec::new();
+    let mut speech_end = 0;
+    for &(start_ms, end_ms) in spans.iter().chain(duration_ms.map(|ms| (ms, ms)).iter()) {
+        if start_ms >= speech_end + threshold_ms {
+            gaps.push(SilenceGap {
+                start_ms: speech_end,
+                end_ms: start_ms,
+            });
+        }
+        speech_end = speech_end.max(end_ms);
+    }
+    gaps
+}
+
+/// Every silence of at least `threshold`, then their total as a share of the audio (or of the speech, when the
+/// audio's duration is unknown).
+pub fn format_silence_report(
+    spans: &[(u64, u64)],
+    duration_ms: Option<u64>,
+    threshold: Duration,
+    timestamps: TimestampFormat,
+) -> String {
+    let gaps = silence_gaps(spans, duration_ms, threshold);
+    let total_ms = duration_ms
+        .or_else(|| spans.iter().map(|&(_, end_ms)| end_ms).max())
+        .unwrap_or(0);
+    let silent_ms: u64 = gaps.iter().map(|gap| gap.end_ms - gap.start_ms).sum();
+
+    let mut out = format!("Silences of {} or more\n", seconds(threshold.as_millis() as u64));
+    for gap in &gaps {
+        out.push_str(&format!(
+            "  [{}] - [{}]  {}\n",
+            timestamps.format(gap.start_ms),
+            timestamps.format(gap.end_ms),
+            seconds(gap.end_ms - gap.start_ms)
+        ));
+    }
+    let percent = if total_ms == 0 { 0.0 } else { silent_ms as f64 * 100.0 / total_ms as f64 };
+    out.push_str(&format!(
+        "\nTotal silence  {} of {} ({percent:.1}%)\n",
+        seconds(silent_ms),
+        seconds(total_ms)
+    ));
+    out
+}
+
+/// An utterance of one multichannel channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelTurn {
+    pub channel: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// Speech of `interrupter` starting while `interrupted` is still talking.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interruption {
+    pub interrupter: String,
+    pub interrupted: String,
+    /// Where both talk.
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// Every turn that starts inside another channel's turn and overlaps it by at least `min_overlap`, in time order.
+pub fn interruptions(turns: &[ChannelTurn], min_overlap: Duration) -> Vec<Interruption> {
+    let min_overlap_ms = min_overlap.as_millis() as u64;
+    let mut turns: Vec<&ChannelTurn> = turns.iter().collect();
+    turns.sort_by_key(|turn| (turn.start_ms, turn.end_ms));
+
+    let mut found = Vec::new();
+    for (idx, turn) in turns.iter().enumerate() {
+        for earlier in &turns[..idx] {
+            if earlier.channel == turn.channel || earlier.end_ms <= turn.start_ms {
+                continue;
+            }
+            let end_ms = earlier.end_ms.min(turn.end_ms);
+            if end_ms - turn.start_ms >= min_overlap_ms {
+                found.push(Interruption {
+                    interrupter: turn.channel.clone(),
+                    interrupted: earlier.channel.clone(),
+                    start_ms: turn.start_ms,
+                    end_ms,
+                });
+            }
+        }
+    }
+    found
+}
+
+/// Each overlap of at least `min_overlap`, how often each channel interrupted each other one, and the total
+/// overlapping speech as a share of the audio (or of the speech, when the audio's duration is unknown).
+pub fn format_interruption_report(
+    turns: &[ChannelTurn],
+    duration_ms: Option<u64>,
+    min_overlap: Duration,
+    timestamps: TimestampFormat,
+) -> String {
+    let found = interruptions(turns, min_overlap);
+    let total_ms = duration_ms
+        .or_else(|| turns.iter().map(|turn| turn.end_ms).max())
+        .unwrap_or(0);
+
+    let mut out = format!("Overlapping speech of {} or more\n", seconds(min_overlap.as_millis() as u64));
+    for interruption in &found {
+        out.push_str(&format!(
+            "  [{}] - [{}]  {} interrupts {}  {}\n",
+            timestamps.format(interruption.start_ms),
+            timestamps.format(interruption.end_ms),
+            interruption.interrupter,
+            interruption.interrupted,
+            seconds(interruption.end_ms - interruption.start_ms)
+        ));
+    }
+
+    let mut counts: std::collections::BTreeMap<(&str, &str), usize> = std::collections::BTreeMap::new();
+    for interruption in &found {
+        *counts
+            .entry((interruption.interrupter.as_str(), interruption.interrupted.as_str()))
+            .or_default() += 1;
+    }
+    let mut counts: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|((interrupter, interrupted), count)| (format!("{interrupter} -> {interrupted}"), count))
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    out.push_str("\nInterruptions\n");
+    let width = counts.iter().map(|(pair, _)| pair.chars().count()).max().unwrap_or(0);
+    for (pair, count) in &counts {
+        out.push_str(&format!("  {pair:<width$}  {count}\n"));
+    }
+
+    let mut regions: Vec<(u64, u64)> = found.iter().map(|i| (i.start_ms, i.end_ms)).collect();
+    regions.sort_unstable();
+    let mut overlap_ms = 0;
+    let mut counted_until = 0;
+    for (start_ms, end_ms) in regions {
+        let start_ms = start_ms.max(counted_until);
+        if end_ms > start_ms {
+            overlap_ms += end_ms - start_ms;
+            counted_until = end_ms;
+        }
+    }
+    let percent = if total_ms == 0 { 0.0 } else { overlap_ms as f64 * 100.0 / total_ms as f64 };
+    out.push_str(&format!(
+        "\nTotal overlapping speech  {} of {} ({percent:.1}%)\n",
+        seconds(overlap_ms),
+        seconds(total_ms)
+    ));
+    out
+}
+
+/// A word masked by `filter_profanity`, which keeps its first letter and stars the rest (`s***`).
+pub fn is_masked_profanity(text: &str) -> bool {
+    let token = text.trim_matches(|c: char| !c.is_alphanumeric() && c != '*');
+    let mut chars = token.chars();
+    chars.next().is_some_and(char::is_alphanumeric) && token.len() > 1 && chars.all(|c| c == '*')
+}
+
+/// Each masked word with its start, end, and speaker, for bleeping those moments in an editor.
+pub fn format_profanity_report(words: &[Word], timestamps: TimestampFormat) -> String {
+    let flagged: Vec<&Word> = words.iter().filter(|word| is_masked_profanity(&word.text)).collect();
+    let mut out = String::from("Profanity\n");
+    for word in &flagged {
+        out.push_str(&format!("  [{}] - [{}]  ", timestamps.format(word.start_ms), timestamps.format(word.end_ms)));
+        if let Some(speaker) = &word.speaker {
+            out.push_str(&format!("Speaker {speaker}  "));
+        }
+        out.push_str(&word.text);
+        out.push('\n');
+    }
+    out.push_str(&format!("\nTotal  {}\n", flagged.len()));
+    out
+}
+
+fn seconds(ms: u64) -> String {
+    format!("{}.{}s", ms / 1000, ms % 1000 / 100)
+}
+
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn format_keyword_report(words: &[Word], terms: &[String], timestamps: TimestampFormat) -> String {
+    let normalized: Vec<String> = words.iter().map(|w| normalize_token(&w.text)).collect();
+
+    let (terms, max_lines): (Vec<Vec<String>>, Option<usize>) = if terms.is_empty() {
+        let top = top_terms(&normalized, KEYWORD_TOP_N)
+            .into_iter()
+            .map(|t| vec![t])
+            .collect();
+        (top, Some(KEYWORD_TOP_N_CONTEXT_LINES))
+    } else {
+        let supplied = terms
+            .iter()
+            .map(|t| t.split_whitespace().map(normalize_token).filter(|t| !t.is_empty()).collect::<Vec<_>>())
+            .filter(|t| !t.is_empty())
+            .collect();
+        (supplied, None)
+    };
+
+    let mut out = String::new();
+    out.push_str("Keywords\n");
+
+    let matches: Vec<(String, Vec<usize>)> = terms
+        .iter()
+        .map(|term| (term.join(" "), find_phrase(&normalized, term)))
+        .collect();
+
+    let width = matches.iter().map(|(t, _)| t.chars().count()).max().unwrap_or(0);
+    for (term, positions) in &matches {
+        out.push_str(&format!("  {term:<width$}  {}\n", positions.len()));
+    }
+
+    out.push_str("\nKeyword in context\n");
+    for ((term, positions), phrase) in matches.iter().zip(terms.iter()) {
+        if positions.is_empty() {
+            continue;
+        }
+        out.push_str("  ");
+        out.push_str(term);
+        out.push('\n');
+        let limit = max_lines.unwrap_or(positions.len());
+        for &pos in positions.iter().take(limit) {
+            out.push_str("    ");
+            out.push_str(&format_context_line(words, pos, phrase.len(), timestamps));
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+fn format_context_line(words: &[Word], pos: usize, len: usize, timestamps: TimestampFormat) -> String {
+    let start = pos.saturating_sub(KEYWORD_CONTEXT_WORDS);
+    let end = (pos + len + KEYWORD_CONTEXT_WORDS).min(words.len());
+
+    let mut parts: Vec<String> = Vec::new();
+    for (idx, word) in words.iter().enumerate().take(end).skip(start) {
+        if idx == pos {
+            parts.push(format!("[{}", word.text));
+        } else {
+            parts.push(word.text.clone());
+        }
+        if idx + 1 == pos + len
+            && let Some(last) = parts.last_mut()
+        {
+            last.push(']');
+        }
+    }
+
+    format!("[{}] {}", timestamps.format(words[pos].start_ms), parts.join(" "))
+}
+
+fn find_phrase(tokens: &[String], phrase: &[String]) -> Vec<usize> {
+    if phrase.is_empty() || tokens.len() < phrase.len() {
+        return Vec::new();
+    }
+
+    (0..=tokens.len() - phrase.len())
+        .filter(|&i| tokens[i..i + phrase.len()] == *phrase)
+        .collect()
+}
+
+fn top_terms(tokens: &[String], n: usize) -> Vec<String> {
+    let mut counts: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+    for token in tokens {
+        if token.chars().count() < 3 || token.chars().all(|c| c.is_numeric()) || STOPWORDS.contains(&token.as_str()) {
+            continue;
+        }
+        *counts.entry(token.as_str()).or_default() += 1;
+    }
+
+    let mut sorted: Vec<(&str, usize)> = counts.into_iter().collect();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    sorted.into_iter().take(n).map(|(t, _)| t.to_string()).collect()
+}
+
+pub fn normalize_token(value: &str) -> String {
+    value
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+        .replace('\u{2019}', "'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<Word> {
+        text.split_whitespace()
+            .enumerate()
+            .map(|(i, w)| {
+                let start = i as u64 * 1000;
+                Word::new(start, start + 500, w.to_string(), None, None).expect("word")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_report_specs() {
+        assert_eq!(parse_report_spec("keywords").expect("spec"), ReportKind::Keywords { terms: vec![] });
+        assert_eq!(
+            parse_report_spec("keywords=contract, renewal").expect("spec"),
+            ReportKind::Keywords {
+                terms: vec!["contract".to_string(), "renewal".to_string()]
+            }
+        );
+        assert!(parse_report_spec("keywords=").is_err());
+        assert!(parse_report_spec("unknown").is_err());
+    }
+
+    #[test]
+    fn parses_sentiment_timeline_options() {
+        assert_eq!(
+            parse_report_spec("sentiment-timeline").expect("spec"),
+            ReportKind::SentimentTimeline {
+                bucket: Duration::from_secs(60),
+                per_speaker: false,
+                format: SeriesFormat::Csv,
+            }
+        );
+        assert_eq!(
+            parse_report_spec("sentiment-timeline=30s,per-speaker,json").expect("spec"),
+            ReportKind::SentimentTimeline {
+                bucket: Duration::from_secs(30),
+                per_speaker: true,
+                format: SeriesFormat::Json,
+            }
+        );
+        assert!(parse_report_spec("sentiment-timeline=0s").is_err());
+        assert!(parse_report_spec("sentiment-timeline=weekly").is_err());
+    }
+
+    #[test]
+    fn buckets_sentiment_per_speaker() {
+        use crate::domain::transcript::Sentiment;
+
+        let seg = |start_ms: u64, sentiment, speaker: &str| SentimentSegment {
+            start_ms,
+            end_ms: start_ms + 1000,
+            sentiment,
+            speaker: Some(speaker.to_string()),
+        };
+        let segments = vec![
+            seg(1_000, Sentiment::Positive, "A"),
+            seg(5_000, Sentiment::Negative, "A"),
+            seg(8_000, Sentiment::Positive, "A"),
+            seg(65_000, Sentiment::Neutral, "B"),
+        ];
+
+        let csv = format_sentiment_timeline(&segments, Duration::from_secs(60), true, SeriesFormat::Csv, None);
+        assert_eq!(
+            csv,
+            "start_ms,end_ms,speaker,positive,neutral,negative,score\n0,60000,A,2,0,1,0.333\n60000,120000,B,0,1,0,0\n"
+        );
+
+        let csv = format_sentiment_timeline(
+            &segments,
+            Duration::from_secs(60),
+            false,
+            SeriesFormat::Csv,
+            Some(TimestampFormat::MmSs),
+        );
+        assert_eq!(csv, "start,end,positive,neutral,negative,score\n00:00,01:00,2,0,1,0.333\n01:00,02:00,0,1,0,0\n");
+
+        let overall = sentiment_timeline(&segments, Duration::from_secs(60), false);
+        assert_eq!(overall.len(), 2);
+        assert_eq!(overall[0].speaker, None);
+    }
+
+    #[test]
+    fn reports_silences_over_the_threshold() {
+        assert_eq!(
+            parse_report_spec("silence").expect("spec"),
+            ReportKind::Silence {
+                threshold: Duration::from_secs(2)
+            }
+        );
+        assert_eq!(
+            parse_report_spec("silence=500ms").expect("spec"),
+            ReportKind::Silence {
+                threshold: Duration::from_millis(500)
+            }
+        );
+        assert!(parse_report_spec("silence=0s").is_err());
+
+        let spans = [(3_000, 5_000), (5_500, 9_000), (6_000, 8_000), (12_000, 15_000)];
+        let report = format_silence_report(&spans, Some(20_000), Duration::from_secs(2), TimestampFormat::default());
+        assert_eq!(
+            report,
+            "Silences of 2.0s or more\n  [00:00:00] - [00:00:03]  3.0s\n  [00:00:09] - [00:00:12]  3.0s\n  \
+             [00:00:15] - [00:00:20]  5.0s\n\nTotal silence  11.0s of 20.0s (55.0%)\n"
+        );
+        assert_eq!(silence_gaps(&spans, None, Duration::from_secs(4)), vec![]);
+    }
+
+    #[test]
+    fn reports_who_interrupts_whom() {
+        assert_eq!(
+            parse_report_spec("interruptions=1s").expect("spec"),
+            ReportKind::Interruptions {
+                min_overlap: Duration::from_secs(1)
+            }
+        );
+        assert!(parse_report_spec("interruptions=often").is_err());
+
+        let turn = |channel: &str, start_ms, end_ms| ChannelTurn {
+            channel: channel.to_string(),
+            start_ms,
+            end_ms,
+        };
+        let turns = [
+            turn("Agent", 0, 5_000),
+            turn("Customer", 4_000, 9_000),
+            turn("Agent", 8_800, 10_000),
+            turn("Agent", 12_000, 20_000),
+            turn("Customer", 13_000, 15_000),
+        ];
+        let found = interruptions(&turns, Duration::from_millis(500));
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[1].interrupted, "Agent");
+
+        let report = format_interruption_report(&turns, Some(40_000), Duration::from_millis(500), TimestampFormat::default());
+        assert_eq!(
+            report,
+            "Overlapping speech of 0.5s or more\n  [00:00:04] - [00:00:05]  Customer interrupts Agent  1.0s\n  \
+             [00:00:13] - [00:00:15]  Customer interrupts Agent  2.0s\n\nInterruptions\n  Customer -> Agent  2\n\n\
+             Total overlapping speech  3.0s of 40.0s (7.5%)\n"
+        );
+    }
+
+    #[test]
+    fn lists_masked_profanity_with_speakers() {
+        assert_eq!(parse_report_spec("profanity").expect("spec"), ReportKind::Profanity);
+        assert!(parse_report_spec("profanity=all").is_err());
+        assert!(is_masked_profanity("s***,"));
+        assert!(!is_masked_profanity("***") && !is_masked_profanity("a") && !is_masked_profanity("5*3"));
+
+        let mut w = words("What the h*** is this s***?");
+        w[2].speaker = Some("A".to_string());
+        assert_eq!(
+            format_profanity_report(&w, TimestampFormat::Seconds),
+            "Profanity\n  [2.000] - [2.500]  Speaker A  h***\n  [5.000] - [5.500]  s***?\n\nTotal  2\n"
+        );
+    }
+
+    #[test]
+    fn counts_supplied_terms_and_phrases_with_context() {
+        let w = words("We renew the Contract. The contract renewal is due, contract renewal again.");
+        let report = format_keyword_report(&w, &["contract".to_string(), "contract renewal".to_string()], TimestampFormat::default());
+        assert!(report.contains("contract          3"), "{report}");
+        assert!(report.contains("contract renewal  2"), "{report}");
+        assert!(report.contains("[00:00:03] We renew the [Contract.] The contract renewal is due,"));
+    }
+
+    #[test]
+    fn top_terms_skip_stopwords() {
+        let w = words("the budget and the budget and the roadmap");
+        let report = format_keyword_report(&w, &[], TimestampFormat::default());
+        assert!(report.contains("budget   2"), "{report}");
+        assert!(report.contains("roadmap  1"), "{report}");
+        assert!(!report.contains("  the "), "{report}");
+    }
+}
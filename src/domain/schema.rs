@@ -0,0 +1,238 @@
+//! JSON Schemas for the config file and the CLI's JSON outputs, printed by `schema config|output`.
+//!
+//! [`validate`] checks the shape of a document against the keywords these schemas use: `$ref` to `#/$defs`, `allOf`,
+//! `anyOf`, `oneOf`, `type`, `enum`, `required`, `properties`, `additionalProperties` (as a schema), and `items`.
+//! Numeric ranges are left to the options' own checks, which name the flag; closed objects to
+//! [`crate::domain::config::unknown_keys`], which warns with a suggestion rather than failing.
+
+use std::sync::LazyLock;
+
+use serde_json::Value;
+
+pub const CONFIG_SCHEMA: &str = include_str!("../../schemas/config.schema.json");
+pub const OUTPUT_SCHEMA: &str = include_str!("../../schemas/output.schema.json");
+
+static CONFIG: LazyLock<Value> = LazyLock::new(|| serde_json::from_str(CONFIG_SCHEMA).expect("config schema is valid JSON"));
+
+/// A value that doesn't match its schema, at `path` (e.g. `presets.podcast.frameRate` or `postReplace[0].from`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "`{}` {}", self.path, self.message)
+        }
+    }
+}
+
+/// Every violation of the config schema in a config document, its presets, and byExtension rules.
+pub fn validate_config(root: &Value) -> Vec<SchemaViolation> {
+    validate(&CONFIG, root)
+}
+
+/// Every violation of `schema` in `value`; `schema` is the root that `$ref`s resolve against.
+pub fn validate(schema: &Value, value: &Value) -> Vec<SchemaViolation> {
+    let mut violations = Vec::new();
+    check(schema, schema, value, "", &mut violations);
+    violations
+}
+
+fn check(root: &Value, schema: &Value, value: &Value, path: &str, out: &mut Vec<SchemaViolation>) {
+    let violation = |out: &mut Vec<SchemaViolation>, message: String| {
+        out.push(SchemaViolation {
+            path: path.to_string(),
+            message,
+        })
+    };
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        match reference.strip_prefix("#/$defs/").and_then(|name| root.get("$defs")?.get(name)) {
+            Some(target) => check(root, target, value, path, out),
+            None => violation(out, format!("refers to unknown schema {reference:?}")),
+        }
+        return;
+    }
+
+    if let Some(expected) = schema.get("type").and_then(Value::as_str)
+        && !has_type(value, expected)
+    {
+        violation(out, format!("must be {} {expected}, not {}", article(expected), type_name(value)));
+        return;
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+        && !allowed.contains(value)
+    {
+        let names: Vec<String> = allowed.iter().map(Value::to_string).collect();
+        violation(out, format!("must be one of {}, not {value}", names.join(", ")));
+    }
+
+    for sub in schema.get("allOf").and_then(Value::as_array).into_iter().flatten() {
+        check(root, sub, value, path, out);
+    }
+    for (keyword, exactly_one) in [("anyOf", false), ("oneOf", true)] {
+        let Some(branches) = schema.get(keyword).and_then(Value::as_array) else {
+            continue;
+        };
+        let matching = branches.iter().filter(|branch| {
+            let mut errors = Vec::new();
+            check(root, branch, value, path, &mut errors);
+            errors.is_empty()
+        });
+        let count = matching.count();
+        if count == 0 || (exactly_one && count > 1) {
+            violation(out, "doesn't match any of its allowed forms".to_string());
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        for key in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+            if !object.contains_key(key) {
+                violation(out, format!("is missing `{key}`"));
+            }
+        }
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let additional = schema.get("additionalProperties").filter(|additional| additional.is_object());
+        for (key, item) in object {
+            let item_path = if path.is_empty() { key.clone() } else { format!("{path}.{key}") };
+            if let Some(sub) = properties.and_then(|properties| properties.get(key)) {
+                check(root, sub, item, &item_path, out);
+            } else if let Some(sub) = additional {
+                check(root, sub, item, &item_path, out);
+            }
+        }
+    }
+    if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+        for (idx, item) in array.iter().enumerate() {
+            check(root, items, item, &format!("{path}[{idx}]"), out);
+        }
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "integer" => value.is_u64() || value.is_i64(),
+        "number" => value.is_number(),
+        other => type_name(value) == other,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_u64() || n.is_i64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn article(type_name: &str) -> &'static str {
+    if type_name.starts_with(['a', 'e', 'i', 'o', 'u']) { "an" } else { "a" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::config::parse_config_file;
+
+    #[test]
+    fn config_schema_enums_parse() {
+        let settings = &CONFIG["$defs"]["settings"]["properties"];
+        for (key, property) in settings.as_object().unwrap() {
+            for allowed in property["enum"].as_array().into_iter().flatten() {
+                let json = serde_json::json!({ key: allowed }).to_string();
+                assert!(parse_config_file(&json, None, None).is_ok(), "{json}");
+            }
+        }
+    }
+
+    #[test]
+    fn reports_violations_with_their_path() {
+        let config = serde_json::json!({
+            "frameRate": "25",
+            "markUncertain": "high",
+            "format": "docx",
+            "postReplace": [{"from": "a"}],
+            "presets": {"podcast": {"wordBoost": ["a", 2]}},
+        });
+        let violations: Vec<String> = validate_config(&config).iter().map(ToString::to_string).collect();
+        assert_eq!(
+            violations,
+            vec![
+                "`format` must be one of \"text\", \"txt\", \"srt\", \"vtt\", \"srt-dual\", \"edl\", \"premiere-markers-csv\", \
+                 \"anki-tsv\", \"otr\", \"meeting-notes\", \"show-notes\", \"sqlite\", \"parquet\", \"json\", not \"docx\"",
+                "`frameRate` must be an integer, not string",
+                "`markUncertain` must be a number, not string",
+                "`postReplace[0]` is missing `to`",
+                "`presets.podcast.wordBoost[1]` must be a string, not integer",
+            ]
+        );
+
+        let output: Value = serde_json::from_str(OUTPUT_SCHEMA).unwrap();
+        assert_eq!(validate(&output, &serde_json::json!({"failures": "none"})).len(), 1);
+    }
+
+    #[test]
+    fn output_schema_matches_written_documents() {
+        use std::ffi::OsString;
+
+        use crate::domain::batch::{Failure, FailureList, Sidecar};
+        use crate::domain::bench::{BenchReport, BenchRun};
+        use crate::domain::uploads::UploadedFile;
+        use crate::domain::version::VersionInfo;
+
+        let output: Value = serde_json::from_str(OUTPUT_SCHEMA).unwrap();
+        let documents = [
+            serde_json::to_value(VersionInfo {
+                version: "0.1.4".to_string(),
+                commit: "abc1234".to_string(),
+                build_date: "2025-03-01T09:30:05.250Z".to_string(),
+                target: "x86_64-unknown-linux-gnu".to_string(),
+                features: vec![],
+                base_url: "https://api.assemblyai.com".to_string(),
+            }),
+            serde_json::to_value(FailureList {
+                failures: vec![Failure {
+                    input: OsString::from("a.mp3"),
+                    error: "upload failed".to_string(),
+                    exit_code: 5,
+                }],
+            }),
+            serde_json::to_value(Sidecar {
+                input: OsString::from("b.mp3"),
+                transcript_id: "tr_1".to_string(),
+                language_code: Some("en".to_string()),
+                language_confidence: Some(0.97),
+                content_sha256: Some("ab12".to_string()),
+                speakers: [("A".to_string(), "Alice".to_string())].into(),
+            }),
+            serde_json::to_value(BenchReport::new(vec![BenchRun {
+                run: 1,
+                model: "best".to_string(),
+                transcript_id: "tr_2".to_string(),
+                upload_ms: 1200,
+                queue_ms: 3000,
+                processing_ms: 9000,
+                total_ms: 13_200,
+            }])),
+            serde_json::to_value(UploadedFile {
+                input: OsString::from("c.mp3"),
+                upload_url: "https://cdn.example/upload/1".to_string(),
+                bytes: 10,
+                sha256: "ab12".to_string(),
+            }),
+        ];
+        for document in documents {
+            let document = document.unwrap();
+            assert_eq!(validate(&output, &document), vec![], "{document}");
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::fmt;
+
+/// An API key whose `Debug` and `Display` show only a masked prefix; [`ApiKey::expose`] is the only way to the full value.
+#[derive(Clone, PartialEq, Eq, serde::Deserialize)]
+#[serde(transparent)]
+pub struct ApiKey(String);
+
+impl ApiKey {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    /// The full key, for the authorization header and the config file.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// The first 4 characters of keys longer than 8, followed by `…`; shorter keys are fully masked.
+    pub fn masked(&self) -> String {
+        match self.0.char_indices().nth(4) {
+            Some((end, _)) if self.0.chars().count() > 8 => format!("{}…", &self.0[..end]),
+            _ => "****".to_string(),
+        }
+    }
+}
+
+impl fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ApiKey({})", self.masked())
+    }
+}
+
+impl fmt::Display for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.masked())
+    }
+}
+
+impl std::str::FromStr for ApiKey {
+    type Err = std::convert::Infallible;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formatting_never_shows_the_full_key() {
+        let key = ApiKey::new("0123456789abcdef0123456789abcdef");
+        assert_eq!(key.to_string(), "0123…");
+        assert_eq!(format!("{key:?}"), "ApiKey(0123…)");
+        assert_eq!(key.expose(), "0123456789abcdef0123456789abcdef");
+        assert_eq!(ApiKey::new("short").to_string(), "****");
+
+        let config: crate::domain::config::ConfigFile = serde_json::from_str(r#"{"apiKey":"0123456789abcdef"}"#).unwrap();
+        assert!(!format!("{config:?}").contains("0123456789abcdef"));
+    }
+}
@@ -0,0 +1,61 @@
+//! `--identify-speakers`: the first words of each diarized speaker, shown when asking who they are.
+
+use crate::domain::subtitles::DiarizedUtterance;
+use crate::domain::transcript::format_clock_time;
+
+/// Longest stretch of a speaker's first utterance played back.
+pub const SAMPLE_MAX_MS: u64 = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpeakerSample {
+    pub speaker: String,
+    pub start_ms: u64,
+    /// At most [`SAMPLE_MAX_MS`] after `start_ms`.
+    pub end_ms: u64,
+    pub text: String,
+}
+
+/// The first utterance of each speaker, in the order the speakers first talk.
+pub fn first_utterances(utterances: &[DiarizedUtterance]) -> Vec<SpeakerSample> {
+    let mut samples: Vec<SpeakerSample> = Vec::new();
+    for utterance in utterances {
+        if samples.iter().any(|sample| sample.speaker == utterance.speaker) {
+            continue;
+        }
+        samples.push(SpeakerSample {
+            speaker: utterance.speaker.clone(),
+            start_ms: utterance.start_ms,
+            end_ms: utterance.end_ms.min(utterance.start_ms + SAMPLE_MAX_MS),
+            text: utterance.text.clone(),
+        });
+    }
+    samples
+}
+
+/// `Speaker A at 00:00:01: "Hello world."`
+pub fn format_sample(sample: &SpeakerSample) -> String {
+    format!("Speaker {} at {}: {:?}", sample.speaker, format_clock_time(sample.start_ms), sample.text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_the_first_utterance_of_each_speaker() {
+        let utterance = |start_ms, end_ms, speaker: &str, text: &str| DiarizedUtterance {
+            start_ms,
+            end_ms,
+            speaker: speaker.to_string(),
+            text: text.to_string(),
+        };
+        let samples = first_utterances(&[
+            utterance(1_000, 2_000, "B", "Hi."),
+            utterance(2_500, 30_000, "A", "Thanks for having me, it's a long story."),
+            utterance(31_000, 32_000, "B", "Go on."),
+        ]);
+        assert_eq!(samples.iter().map(|s| s.speaker.as_str()).collect::<Vec<_>>(), ["B", "A"]);
+        assert_eq!(samples[1].end_ms, 12_500);
+        assert_eq!(format_sample(&samples[0]), "Speaker B at 00:00:01: \"Hi.\"");
+    }
+}
@@ -1,3 +1,11 @@
+use std::ops::Range;
+use std::time::Duration;
+
+use crate::domain::transcript::{TimestampFormat, Word};
+use crate::domain::DomainError;
+
+const DEFAULT_SPEAKER_STYLE: &str = "Speaker {name}: ";
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DiarizedUtterance {
     pub start_ms: u64,
@@ -31,69 +39,626 @@ impl DiarizedUtterance {
     }
 }
 
-pub fn format_diarized_text(utterances: &[DiarizedUtterance]) -> String {
+/// How diarized speakers are prefixed in text and subtitles. In a template, `{name}` is the API's
+/// speaker label (`A`) and `{index}` its zero-based number padded to two digits (`00`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpeakerStyle {
+    template: Option<String>,
+}
+
+impl Default for SpeakerStyle {
+    fn default() -> Self {
+        Self {
+            template: Some(DEFAULT_SPEAKER_STYLE.to_string()),
+        }
+    }
+}
+
+impl SpeakerStyle {
+    pub fn template(value: &str) -> Result<Self, DomainError> {
+        let invalid = |message: &str| DomainError::InvalidSpeakerStyle {
+            value: value.to_string(),
+            message: message.to_string(),
+        };
+        let mut rest = value;
+        let mut placeholders = 0;
+        while let Some(open) = rest.find('{') {
+            let Some(close) = rest[open..].find('}') else {
+                return Err(invalid("unclosed '{'"));
+            };
+            match &rest[open + 1..open + close] {
+                "name" | "index" => placeholders += 1,
+                _ => return Err(invalid("placeholders are {name} and {index}")),
+            }
+            rest = &rest[open + close + 1..];
+        }
+        if placeholders == 0 {
+            return Err(invalid("must contain {name} or {index}"));
+        }
+        Ok(Self {
+            template: Some(value.to_string()),
+        })
+    }
+
+    /// Renders speakers without any prefix.
+    pub fn none() -> Self {
+        Self { template: None }
+    }
+
+    pub fn prefix(&self, speaker: &str) -> String {
+        let Some(template) = &self.template else {
+            return String::new();
+        };
+        let index = speaker_index(speaker).map_or_else(|| speaker.to_string(), |i| format!("{i:02}"));
+        template.replace("{name}", speaker).replace("{index}", &index)
+    }
+}
+
+/// `A`, `B`, ..., `AA` map to 0, 1, ..., 26; numeric labels (multichannel) are 1-based. Other
+/// labels (channel names) have no index.
+fn speaker_index(speaker: &str) -> Option<u64> {
+    if speaker.chars().all(|c| c.is_ascii_digit()) {
+        return speaker.parse::<u64>().ok().map(|n| n.saturating_sub(1));
+    }
+    if !speaker.chars().all(|c| c.is_ascii_uppercase()) {
+        return None;
+    }
+    let mut n: u64 = 0;
+    for c in speaker.chars() {
+        n = n.checked_mul(26)?.checked_add(u64::from(c.to_ascii_uppercase() as u8 - b'A') + 1)?;
+    }
+    Some(n - 1)
+}
+
+/// Timing, speaker, and text of one utterance, for [`merge_short_utterances`].
+pub struct UtteranceSpan<'a> {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub speaker: &'a str,
+    pub text: &'a str,
+}
+
+/// Consecutive utterances `range` rendered as one turn of the speaker of utterance `speaker`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergedTurn {
+    pub range: Range<usize>,
+    pub speaker: usize,
+}
+
+/// Groups utterances into turns, folding every single-word utterance shorter than `max` into the turn before it
+/// (or after it, at the start). A turn interrupted only by such interjections continues as one turn.
+pub fn merge_short_utterances(spans: &[UtteranceSpan], max: Duration) -> Vec<MergedTurn> {
+    let max_ms = u64::try_from(max.as_millis()).unwrap_or(u64::MAX);
+    let is_short =
+        |span: &UtteranceSpan| span.text.split_whitespace().count() == 1 && span.end_ms.saturating_sub(span.start_ms) < max_ms;
+
+    // Each turn's speaker is its first utterance that isn't an interjection; None while only interjections lead.
+    let mut turns: Vec<(Range<usize>, Option<usize>)> = Vec::new();
+    let mut interrupted = false;
+    for (idx, span) in spans.iter().enumerate() {
+        let short = is_short(span);
+        match turns.last_mut() {
+            Some((range, _)) if short => {
+                range.end = idx + 1;
+                interrupted = true;
+            }
+            Some((range, owner @ None)) => {
+                range.end = idx + 1;
+                *owner = Some(idx);
+            }
+            Some((range, Some(owner))) if interrupted && spans[*owner].speaker == span.speaker => {
+                range.end = idx + 1;
+                interrupted = false;
+            }
+            _ => {
+                turns.push((idx..idx + 1, (!short).then_some(idx)));
+                interrupted = false;
+            }
+        }
+    }
+    turns
+        .into_iter()
+        .map(|(range, owner)| MergedTurn {
+            speaker: owner.unwrap_or(range.start),
+            range,
+        })
+        .collect()
+}
+
+/// One line per utterance, with a blank line before each one that starts more than `paragraph_gap` after the
+/// previous one ends.
+pub fn format_diarized_text(utterances: &[DiarizedUtterance], style: &SpeakerStyle, paragraph_gap: Option<Duration>) -> String {
     let mut out = String::new();
+    let mut previous_end: Option<u64> = None;
     for utterance in utterances {
-        out.push_str("Speaker ");
-        out.push_str(&utterance.speaker);
-        out.push_str(": ");
+        if let (Some(gap), Some(end)) = (paragraph_gap, previous_end)
+            && u128::from(utterance.start_ms.saturating_sub(end)) > gap.as_millis()
+        {
+            out.push('\n');
+        }
+        previous_end = Some(utterance.end_ms);
+        out.push_str(&style.prefix(&utterance.speaker));
         out.push_str(&utterance.text);
         out.push('\n');
     }
     out
 }
 
-pub fn format_diarized_srt(utterances: &[DiarizedUtterance], chars_per_caption: u32) -> String {
-    let max_chars = chars_per_caption as usize;
+/// Words joined by spaces, in paragraphs separated by a blank line wherever the silence between two words is longer
+/// than `gap`.
+pub fn paragraph_words(words: &[Word], gap: Duration) -> String {
+    let mut out = String::new();
+    for (idx, word) in words.iter().enumerate() {
+        if let Some(previous) = idx.checked_sub(1).map(|prev| &words[prev]) {
+            let silence = word.start_ms.saturating_sub(previous.end_ms);
+            out.push_str(if u128::from(silence) > gap.as_millis() { "\n\n" } else { " " });
+        }
+        out.push_str(&word.text);
+    }
+    out
+}
+
+/// The first `count` sentences of `text`, one per line.
+pub fn first_sentences(text: &str, count: usize) -> String {
     let mut out = String::new();
-    let mut idx: u32 = 1;
+    let mut taken = 0;
+    let mut start = 0;
+    for (idx, c) in text.char_indices() {
+        let end = idx + c.len_utf8();
+        if !matches!(c, '.' | '?' | '!') || !text[end..].chars().next().is_none_or(char::is_whitespace) {
+            continue;
+        }
+        let sentence = text[start..end].trim();
+        start = end;
+        if sentence.is_empty() {
+            continue;
+        }
+        out.push_str(sentence);
+        out.push('\n');
+        taken += 1;
+        if taken == count {
+            return out;
+        }
+    }
+    let rest = text[start..].trim();
+    if !rest.is_empty() {
+        out.push_str(rest);
+        out.push('\n');
+    }
+    out
+}
 
-    for utterance in utterances {
-        let speaker_prefix = format!("Speaker {}: ", utterance.speaker);
-        let available = max_chars.saturating_sub(speaker_prefix.len()).max(1);
-        let segments = split_text_by_max_chars(&utterance.text, available);
+/// One caption per sentence, ending at words that end in `.`, `?`, or `!` (before any closing quotes).
+pub fn sentence_captions(words: &[Word]) -> Vec<Caption> {
+    let mut sentences = Vec::new();
+    let mut current: Vec<&Word> = Vec::new();
+    for word in words {
+        current.push(word);
+        let ends_sentence = word
+            .text
+            .trim_end_matches(['"', '\'', '”', '’', '»', ')'])
+            .ends_with(['.', '?', '!', '…', '。', '？', '！']);
+        if ends_sentence {
+            sentences.push(sentence_caption(&current));
+            current.clear();
+        }
+    }
+    if !current.is_empty() {
+        sentences.push(sentence_caption(&current));
+    }
+    sentences
+}
+
+fn sentence_caption(words: &[&Word]) -> Caption {
+    Caption {
+        start_ms: words[0].start_ms,
+        end_ms: words[words.len() - 1].end_ms,
+        speaker: None,
+        text: words.iter().map(|word| word.text.as_str()).collect::<Vec<_>>().join(" "),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Caption {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub speaker: Option<String>,
+    pub text: String,
+}
+
+const PAUSE_BREAK_MS: u64 = 700;
+const MIN_CAPTION_GAP_MS: u64 = 80;
+
+pub fn diarized_captions(utterances: &[DiarizedUtterance], chars_per_caption: u32, style: &SpeakerStyle) -> Vec<Caption> {
+    let turns: Vec<Caption> = utterances
+        .iter()
+        .map(|u| Caption {
+            start_ms: u.start_ms,
+            end_ms: u.end_ms,
+            speaker: Some(u.speaker.clone()),
+            text: u.text.clone(),
+        })
+        .collect();
+    split_captions(&turns, chars_per_caption, style)
+}
+
+/// Splits long captions to fit `chars_per_caption`, sharing each caption's time span across its parts.
+pub fn split_captions(captions: &[Caption], chars_per_caption: u32, style: &SpeakerStyle) -> Vec<Caption> {
+    let max_chars = chars_per_caption as usize;
+    let mut out = Vec::new();
+
+    for caption in captions {
+        let prefix_len = caption.speaker.as_deref().map_or(0, |s| style.prefix(s).len());
+        let available = max_chars.saturating_sub(prefix_len).max(1);
+        let segments = split_text_by_max_chars(&caption.text, available);
         for (seg_idx, segment) in segments.iter().enumerate() {
-            let (start, end) = segment_time(utterance.start_ms, utterance.end_ms, seg_idx, segments.len());
-            out.push_str(&idx.to_string());
-            out.push('\n');
-            out.push_str(&format_srt_time(start));
-            out.push_str(" --> ");
-            out.push_str(&format_srt_time(end));
-            out.push('\n');
-            out.push_str(&speaker_prefix);
-            out.push_str(segment);
-            out.push_str("\n\n");
-            idx = idx.saturating_add(1);
+            let (start, end) = segment_time(caption.start_ms, caption.end_ms, seg_idx, segments.len());
+            out.push(Caption {
+                start_ms: start,
+                end_ms: end,
+                speaker: caption.speaker.clone(),
+                text: segment.clone(),
+            });
         }
     }
 
     out
 }
 
-pub fn format_diarized_vtt(utterances: &[DiarizedUtterance], chars_per_caption: u32) -> String {
-    let max_chars = chars_per_caption as usize;
+/// `[hh:mm:ss] Speaker A: text` per caption.
+pub fn format_timestamped_text(captions: &[Caption], style: &SpeakerStyle, timestamps: TimestampFormat) -> String {
     let mut out = String::new();
-    out.push_str("WEBVTT\n\n");
+    for caption in captions {
+        out.push('[');
+        out.push_str(&timestamps.format(caption.start_ms));
+        out.push_str("] ");
+        push_caption_text(&mut out, caption, style);
+        out.push('\n');
+    }
+    out
+}
 
-    for utterance in utterances {
-        let speaker_prefix = format!("Speaker {}: ", utterance.speaker);
-        let available = max_chars.saturating_sub(speaker_prefix.len()).max(1);
-        let segments = split_text_by_max_chars(&utterance.text, available);
-        for (seg_idx, segment) in segments.iter().enumerate() {
-            let (start, end) = segment_time(utterance.start_ms, utterance.end_ms, seg_idx, segments.len());
-            out.push_str(&format_vtt_time(start));
-            out.push_str(" --> ");
-            out.push_str(&format_vtt_time(end));
-            out.push('\n');
-            out.push_str(&speaker_prefix);
-            out.push_str(segment);
-            out.push_str("\n\n");
+/// Groups words into captions, breaking on speaker changes, pauses, and the character budget.
+/// Speakers are kept only when `speakers` is given.
+pub fn word_captions(words: &[Word], chars_per_caption: u32, speakers: Option<&SpeakerStyle>) -> Vec<Caption> {
+    word_groups(words, chars_per_caption as usize, speakers)
+        .into_iter()
+        .map(|group| group_caption(&words[group], speakers))
+        .collect()
+}
+
+/// Like [`word_captions`], but segmented so every caption can reach `max_cps` once [`apply_max_cps`] extends it.
+///
+/// Breaks at pauses and speaker changes stay put. Where a caption between them would still read too fast, those
+/// words are split again with a smaller character budget, which moves words from a dense caption into its slower
+/// neighbours, until every caption fits; when no budget does, the words keep their usual captions.
+pub fn readable_word_captions(
+    words: &[Word],
+    chars_per_caption: u32,
+    speakers: Option<&SpeakerStyle>,
+    max_cps: f64,
+) -> Vec<Caption> {
+    let runs = word_groups(words, usize::MAX, speakers);
+    let mut captions = Vec::new();
+    for (idx, run) in runs.iter().enumerate() {
+        let limit = runs.get(idx + 1).map_or(u64::MAX, |next| words[next.start].start_ms.saturating_sub(MIN_CAPTION_GAP_MS));
+        let run = &words[run.clone()];
+        let segment = |budget: usize| -> Vec<Caption> {
+            word_groups(run, budget, speakers).into_iter().map(|group| group_caption(&run[group], speakers)).collect()
+        };
+        let usual = segment(chars_per_caption as usize);
+        let readable = std::iter::once(usual.clone())
+            .chain((1..chars_per_caption as usize).rev().map(segment).take_while(|split| split.len() <= run.len()))
+            .find(|split| reaches_max_cps(split, max_cps, limit));
+        captions.extend(readable.unwrap_or(usual));
+    }
+    captions
+}
+
+/// Whether each caption can be extended to `max_cps` before the next one, or `limit` for the last.
+fn reaches_max_cps(captions: &[Caption], max_cps: f64, limit: u64) -> bool {
+    captions.iter().enumerate().all(|(idx, caption)| {
+        let limit = captions.get(idx + 1).map_or(limit, |next| next.start_ms.saturating_sub(MIN_CAPTION_GAP_MS));
+        let required_ms = (caption.text.chars().count() as f64 * 1000.0 / max_cps).ceil() as u64;
+        caption.start_ms.saturating_add(required_ms) <= caption.end_ms.max(limit)
+    })
+}
+
+/// Ranges of `words` forming one caption each, broken on speaker changes, pauses, and `max_chars`.
+fn word_groups(words: &[Word], max_chars: usize, speakers: Option<&SpeakerStyle>) -> Vec<Range<usize>> {
+    let speaker_of = |idx: usize| speakers.and_then(|_| words[idx].speaker.as_deref());
+    let mut groups = Vec::new();
+    let (mut start, mut len, mut end_ms) = (0, 0, 0);
+
+    for (idx, word) in words.iter().enumerate() {
+        if idx > 0 {
+            let speaker = speaker_of(start);
+            let prefix_len = match (speakers, speaker) {
+                (Some(style), Some(speaker)) => style.prefix(speaker).len(),
+                _ => 0,
+            };
+            let fits = len + 1 + word.text.len() <= max_chars.saturating_sub(prefix_len);
+            let paused = word.start_ms.saturating_sub(end_ms) >= PAUSE_BREAK_MS;
+            if fits && !paused && speaker_of(idx) == speaker {
+                len += 1 + word.text.len();
+                end_ms = end_ms.max(word.end_ms);
+                continue;
+            }
+            groups.push(start..idx);
         }
+        (start, len, end_ms) = (idx, word.text.len(), word.end_ms.max(word.start_ms + 1));
+    }
+
+    if !words.is_empty() {
+        groups.push(start..words.len());
+    }
+    groups
+}
+
+/// One caption of consecutive `words`.
+fn group_caption(words: &[Word], speakers: Option<&SpeakerStyle>) -> Caption {
+    let start_ms = words[0].start_ms;
+    Caption {
+        start_ms,
+        end_ms: words.iter().map(|word| word.end_ms).max().unwrap_or(0).max(start_ms + 1),
+        speaker: speakers.and_then(|_| words[0].speaker.clone()),
+        text: words.iter().map(|word| word.text.as_str()).collect::<Vec<_>>().join(" "),
+    }
+}
+
+/// Extends display times so each caption stays at or below `max_cps`, without running into the next caption.
+pub fn apply_max_cps(captions: &mut [Caption], max_cps: f64) {
+    for idx in 0..captions.len() {
+        let next_start = captions.get(idx + 1).map(|c| c.start_ms);
+        let caption = &mut captions[idx];
+
+        let chars = caption.text.chars().count() as f64;
+        let required_ms = (chars * 1000.0 / max_cps).ceil() as u64;
+        let desired_end = caption.start_ms.saturating_add(required_ms);
+        let limit = next_start.map_or(u64::MAX, |s| s.saturating_sub(MIN_CAPTION_GAP_MS));
+
+        caption.end_ms = caption.end_ms.max(desired_end.min(limit));
+    }
+}
+
+pub fn format_srt(captions: &[Caption], style: &SpeakerStyle) -> String {
+    let mut out = String::new();
+    for (idx, caption) in captions.iter().enumerate() {
+        out.push_str(&(idx + 1).to_string());
+        out.push('\n');
+        out.push_str(&format_srt_time(caption.start_ms));
+        out.push_str(" --> ");
+        out.push_str(&format_srt_time(caption.end_ms));
+        out.push('\n');
+        push_caption_text(&mut out, caption, style);
+        out.push_str("\n\n");
+    }
+    out
+}
+
+pub fn format_vtt(captions: &[Caption], style: &SpeakerStyle) -> String {
+    let mut out = String::new();
+    out.push_str("WEBVTT\n\n");
+    for caption in captions {
+        out.push_str(&format_vtt_time(caption.start_ms));
+        out.push_str(" --> ");
+        out.push_str(&format_vtt_time(caption.end_ms));
+        out.push('\n');
+        push_caption_text(&mut out, caption, style);
+        out.push_str("\n\n");
+    }
+    out
+}
+
+fn push_caption_text(out: &mut String, caption: &Caption, style: &SpeakerStyle) {
+    if let Some(speaker) = &caption.speaker {
+        out.push_str(&style.prefix(speaker));
     }
+    out.push_str(&caption.text);
+}
 
+/// Rewrites the text lines of SRT/VTT content (lines following a timing line, up to the blank line
+/// ending the cue), leaving headers, cue numbers, and timings untouched.
+pub fn map_cue_text(content: &str, mut f: impl FnMut(&str) -> String) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut in_cue = false;
+    for line in content.split_inclusive('\n') {
+        let body = line.trim_end_matches(['\r', '\n']);
+        let ending = &line[body.len()..];
+        if body.trim().is_empty() {
+            in_cue = false;
+            out.push_str(line);
+        } else if body.contains("-->") {
+            in_cue = true;
+            out.push_str(line);
+        } else if in_cue {
+            out.push_str(&f(body));
+            out.push_str(ending);
+        } else {
+            out.push_str(line);
+        }
+    }
     out
 }
 
+/// Groups captions into parts starting at each of `boundaries` (ascending ms), with times re-based
+/// to the part start. A caption belongs to the part its start falls in; empty parts are dropped.
+pub fn split_at_boundaries(captions: &[Caption], boundaries: &[u64]) -> Vec<Vec<Caption>> {
+    let mut parts: Vec<Vec<Caption>> = Vec::new();
+    let mut current: Option<usize> = None;
+    for caption in captions {
+        let part = boundaries
+            .iter()
+            .rposition(|&b| b <= caption.start_ms)
+            .unwrap_or(0);
+        if current != Some(part) {
+            parts.push(Vec::new());
+            current = Some(part);
+        }
+        let offset = boundaries.get(part).copied().unwrap_or(0).min(caption.start_ms);
+        if let Some(out) = parts.last_mut() {
+            out.push(Caption {
+                start_ms: caption.start_ms - offset,
+                end_ms: caption.end_ms.saturating_sub(offset),
+                ..caption.clone()
+            });
+        }
+    }
+    parts
+}
+
+/// Rewrites SRT cue numbers sequentially from `start`, adding numbers to cues that lack one.
+pub fn renumber_srt(content: &str, start: u32) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut next = u64::from(start);
+    let mut block_start = 0;
+    for line in content.split_inclusive('\n') {
+        let body = line.trim_end_matches(['\r', '\n']);
+        if body.trim().is_empty() {
+            block_start = lines.len() + 1;
+        } else if body.contains("-->") {
+            let ending = &line[body.len()..];
+            let ending = if ending.is_empty() { "\n" } else { ending };
+            let numbered = lines.len() == block_start + 1
+                && lines
+                    .last()
+                    .is_some_and(|l| l.trim().chars().all(|c| c.is_ascii_digit()) && !l.trim().is_empty());
+            if numbered {
+                lines.pop();
+            }
+            lines.push(format!("{next}{ending}"));
+            next += 1;
+        }
+        lines.push(line.to_string());
+    }
+    lines.concat()
+}
+
+/// `merge`: the cues of each part, moved to start at the part's offset, as one list of captions in time order.
+pub fn merge_cues(parts: &[(u64, Vec<Cue>)]) -> Vec<Caption> {
+    let mut captions: Vec<Caption> = parts
+        .iter()
+        .flat_map(|(offset_ms, cues)| {
+            cues.iter().map(move |cue| Caption {
+                start_ms: cue.start_ms + offset_ms,
+                end_ms: cue.end_ms + offset_ms,
+                speaker: None,
+                text: cue.lines.join("\n"),
+            })
+        })
+        .collect();
+    captions.sort_by_key(|caption| caption.start_ms);
+    captions
+}
+
+/// Offsets placing each part right after the previous part's last cue, for `merge` without `--offsets`.
+pub fn consecutive_offsets(parts: &[Vec<Cue>]) -> Vec<u64> {
+    let mut offsets = Vec::with_capacity(parts.len());
+    let mut next = 0;
+    for cues in parts {
+        offsets.push(next);
+        next += cues.iter().map(|cue| cue.end_ms).max().unwrap_or(0);
+    }
+    offsets
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cue {
+    pub line: usize,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub lines: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CueParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+pub fn parse_cues(content: &str) -> Result<Vec<Cue>, CueParseError> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let lines: Vec<&str> = content.lines().map(|l| l.trim_end_matches('\r')).collect();
+
+    let mut cues = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        if line.is_empty() {
+            i += 1;
+            continue;
+        }
+
+        if (i == 0 && line.starts_with("WEBVTT")) || line.starts_with("NOTE") || line == "STYLE" || line == "REGION" {
+            while i < lines.len() && !lines[i].trim().is_empty() {
+                i += 1;
+            }
+            continue;
+        }
+
+        let timing_index = if line.contains("-->") {
+            i
+        } else if i + 1 < lines.len() && lines[i + 1].contains("-->") {
+            i + 1
+        } else {
+            return Err(CueParseError {
+                line: i + 1,
+                message: format!("expected cue timing line, found {line:?}"),
+            });
+        };
+
+        let (start_ms, end_ms) = parse_timing_line(lines[timing_index]).ok_or_else(|| CueParseError {
+            line: timing_index + 1,
+            message: format!("invalid cue timing {:?}", lines[timing_index].trim()),
+        })?;
+
+        let mut text = Vec::new();
+        i = timing_index + 1;
+        while i < lines.len() && !lines[i].trim().is_empty() {
+            text.push(lines[i].to_string());
+            i += 1;
+        }
+
+        cues.push(Cue {
+            line: timing_index + 1,
+            start_ms,
+            end_ms,
+            lines: text,
+        });
+    }
+
+    Ok(cues)
+}
+
+fn parse_timing_line(line: &str) -> Option<(u64, u64)> {
+    let (start, rest) = line.split_once("-->")?;
+    let end = rest.split_whitespace().next()?;
+    Some((parse_cue_time(start.trim())?, parse_cue_time(end)?))
+}
+
+fn parse_cue_time(value: &str) -> Option<u64> {
+    let (clock, millis) = value.split_once([',', '.'])?;
+    if millis.len() != 3 {
+        return None;
+    }
+    let millis: u64 = millis.parse().ok()?;
+
+    let parts = clock
+        .split(':')
+        .map(|p| p.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [h, m, s] => (*h, *m, *s),
+        [m, s] => (0, *m, *s),
+        _ => return None,
+    };
+    if minutes >= 60 || seconds >= 60 {
+        return None;
+    }
+
+    Some(((hours * 60 + minutes) * 60 + seconds) * 1000 + millis)
+}
+
 fn segment_time(start_ms: u64, end_ms: u64, seg_idx: usize, seg_count: usize) -> (u64, u64) {
     let duration = end_ms.saturating_sub(start_ms);
     let n = seg_count.max(1) as u64;
@@ -175,21 +740,196 @@ mod tests {
             DiarizedUtterance::new(6000, 7000, "2".to_string(), "Hi".to_string()).expect("utterance"),
         ];
 
-        let srt = format_diarized_srt(&utterances, 128);
+        let style = SpeakerStyle::default();
+        let srt = format_srt(&diarized_captions(&utterances, 128, &style), &style);
         assert!(srt.contains("00:00:02,000 --> 00:00:05,000"));
         assert!(srt.contains("Speaker 1: Hello world"));
         assert!(srt.contains("Speaker 2: Hi"));
     }
 
+    #[test]
+    fn merges_short_interjections_into_the_surrounding_turn() {
+        let span = |start_ms, end_ms, speaker, text| UtteranceSpan {
+            start_ms,
+            end_ms,
+            speaker,
+            text,
+        };
+        let spans = [
+            span(0, 300, "B", "So"),
+            span(300, 4000, "A", "We shipped the release on Friday"),
+            span(4000, 4400, "B", "Yeah."),
+            span(4400, 9000, "A", "and the rollout went fine"),
+            span(9000, 12000, "B", "Great, what about the docs?"),
+            span(12000, 14000, "A", "Pending."),
+        ];
+        let turns = merge_short_utterances(&spans, Duration::from_millis(1500));
+        assert_eq!(
+            turns,
+            vec![
+                MergedTurn { range: 0..4, speaker: 1 },
+                MergedTurn { range: 4..5, speaker: 4 },
+                MergedTurn { range: 5..6, speaker: 5 },
+            ]
+        );
+        assert_eq!(merge_short_utterances(&spans, Duration::ZERO).len(), spans.len());
+    }
+
+    #[test]
+    fn paragraphs_break_on_long_silences() {
+        let gap = Some(Duration::from_secs(2));
+        let utterances = vec![
+            DiarizedUtterance::new(0, 1000, "A".to_string(), "Hi.".to_string()).expect("utterance"),
+            DiarizedUtterance::new(2500, 3000, "B".to_string(), "Hello.".to_string()).expect("utterance"),
+            DiarizedUtterance::new(6000, 7000, "A".to_string(), "Next topic.".to_string()).expect("utterance"),
+        ];
+        let style = SpeakerStyle::default();
+        assert_eq!(
+            format_diarized_text(&utterances, &style, gap),
+            "Speaker A: Hi.\nSpeaker B: Hello.\n\nSpeaker A: Next topic.\n"
+        );
+
+        let word = |start_ms, end_ms, text: &str| Word::new(start_ms, end_ms, text.to_string(), None, None).expect("word");
+        let words = [word(0, 400, "First"), word(500, 900, "part."), word(3000, 3400, "Second"), word(3500, 3900, "part.")];
+        assert_eq!(paragraph_words(&words, Duration::from_secs(2)), "First part.\n\nSecond part.");
+        assert_eq!(paragraph_words(&words, Duration::from_secs(5)), "First part. Second part.");
+    }
+
+    #[test]
+    fn sentence_captions_end_at_terminal_punctuation() {
+        let word = |start_ms, end_ms, text: &str| Word::new(start_ms, end_ms, text.to_string(), None, None).expect("word");
+        let words = [
+            word(0, 300, "Hola,"),
+            word(300, 900, "¿qué"),
+            word(900, 1500, "tal?"),
+            word(2000, 2500, "Dijo"),
+            word(2500, 3000, "«adiós.»"),
+            word(3500, 4000, "Y"),
+        ];
+        let texts: Vec<(u64, u64, String)> = sentence_captions(&words).into_iter().map(|c| (c.start_ms, c.end_ms, c.text)).collect();
+        assert_eq!(
+            texts,
+            vec![
+                (0, 1500, "Hola, ¿qué tal?".to_string()),
+                (2000, 3000, "Dijo «adiós.»".to_string()),
+                (3500, 4000, "Y".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn formats_vtt_with_header() {
         let utterances = vec![DiarizedUtterance::new(0, 1000, "1A".to_string(), "Test".to_string()).expect("utterance")];
-        let vtt = format_diarized_vtt(&utterances, 128);
+        let style = SpeakerStyle::default();
+        let vtt = format_vtt(&diarized_captions(&utterances, 128, &style), &style);
         assert!(vtt.starts_with("WEBVTT\n\n"));
         assert!(vtt.contains("00:00:00.000 --> 00:00:01.000"));
         assert!(vtt.contains("Speaker 1A: Test"));
     }
 
+    #[test]
+    fn takes_first_sentences() {
+        let text = "Hello world. Version 1.5 is out! Is it? Trailing words";
+        assert_eq!(first_sentences(text, 2), "Hello world.\nVersion 1.5 is out!\n");
+        assert_eq!(first_sentences(text, 9), "Hello world.\nVersion 1.5 is out!\nIs it?\nTrailing words\n");
+        assert_eq!(first_sentences("", 3), "");
+    }
+
+    #[test]
+    fn parses_srt_and_vtt_cues() {
+        let srt = "1\r\n00:00:01,000 --> 00:00:02,500\r\nHello\r\nworld\r\n\r\n2\r\n00:00:03,000 --> 00:00:04,000\r\nBye\r\n";
+        let cues = parse_cues(srt).expect("srt");
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].line, 2);
+        assert_eq!((cues[0].start_ms, cues[0].end_ms), (1000, 2500));
+        assert_eq!(cues[0].lines, vec!["Hello".to_string(), "world".to_string()]);
+
+        let vtt = "WEBVTT\n\nNOTE generated\n\nintro\n00:01.000 --> 00:02.000 align:start\nHi\n";
+        let cues = parse_cues(vtt).expect("vtt");
+        assert_eq!(cues.len(), 1);
+        assert_eq!((cues[0].start_ms, cues[0].end_ms, cues[0].line), (1000, 2000, 6));
+
+        let err = parse_cues("1\n00:00:01 --> 00:00:02\nx\n").expect_err("invalid");
+        assert_eq!(err.line, 2);
+    }
+
+    #[test]
+    fn merges_parts_at_their_offsets() {
+        let first = parse_cues("1\n00:00:01,000 --> 00:00:02,000\nHello\nthere\n").expect("srt");
+        let second = parse_cues("WEBVTT\n\n00:00.500 --> 00:03.000\nBye\n").expect("vtt");
+        assert_eq!(consecutive_offsets(&[first.clone(), second.clone()]), vec![0, 2000]);
+
+        let merged = merge_cues(&[(0, first), (3_720_000, second)]);
+        assert_eq!(
+            format_srt(&merged, &SpeakerStyle::default()),
+            "1\n00:00:01,000 --> 00:00:02,000\nHello\nthere\n\n2\n01:02:00,500 --> 01:02:03,000\nBye\n\n"
+        );
+    }
+
+    #[test]
+    fn word_captions_break_on_speaker_pause_and_length() {
+        let w = |start: u64, text: &str, speaker: &str| {
+            Word::new(start, start + 300, text.to_string(), None, Some(speaker.to_string())).expect("word")
+        };
+        let words = vec![
+            w(0, "one", "A"),
+            w(400, "two", "A"),
+            w(2000, "three", "A"),
+            w(2400, "four", "B"),
+            w(2800, "fivefive", "B"),
+        ];
+
+        let captions = word_captions(&words, 20, Some(&SpeakerStyle::default()));
+        let texts: Vec<&str> = captions.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, vec!["one two", "three", "four", "fivefive"]);
+
+        let captions = word_captions(&words, 128, None);
+        let texts: Vec<&str> = captions.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, vec!["one two", "three four fivefive"]);
+    }
+
+    #[test]
+    fn max_cps_moves_words_out_of_dense_captions() {
+        let w = |start: u64, end: u64, text: &str| Word::new(start, end, text.to_string(), None, None).expect("word");
+        let words = vec![
+            w(0, 800, "aaaa"),
+            w(800, 900, "bbbb"),
+            w(900, 1000, "cccc"),
+            w(1000, 1100, "dddd"),
+            w(1100, 3000, "eeee"),
+        ];
+        let texts = |captions: &[Caption]| captions.iter().map(|c| c.text.clone()).collect::<Vec<_>>();
+
+        assert_eq!(texts(&word_captions(&words, 20, None)), ["aaaa bbbb cccc dddd", "eeee"]);
+        let captions = readable_word_captions(&words, 20, None, 15.0);
+        assert_eq!(texts(&captions), ["aaaa bbbb cccc", "dddd eeee"]);
+        assert!(reaches_max_cps(&captions, 15.0, u64::MAX));
+        // No split can slow down speech that is uniformly too fast, so it keeps its usual captions.
+        assert_eq!(texts(&readable_word_captions(&words, 20, None, 2.0)), texts(&word_captions(&words, 20, None)));
+    }
+
+    #[test]
+    fn max_cps_extends_into_gaps_but_not_past_next_caption() {
+        let mut captions = vec![
+            Caption {
+                start_ms: 0,
+                end_ms: 500,
+                speaker: None,
+                text: "a".repeat(34),
+            },
+            Caption {
+                start_ms: 1500,
+                end_ms: 1600,
+                speaker: None,
+                text: "b".repeat(17),
+            },
+        ];
+
+        apply_max_cps(&mut captions, 17.0);
+        assert_eq!(captions[0].end_ms, 1420);
+        assert_eq!(captions[1].end_ms, 2500);
+    }
+
     #[test]
     fn splits_long_text() {
         let u = DiarizedUtterance::new(
@@ -200,8 +940,58 @@ mod tests {
         )
         .expect("utterance");
 
-        let srt = format_diarized_srt(&[u], 20);
+        let style = SpeakerStyle::default();
+        let srt = format_srt(&diarized_captions(&[u], 20, &style), &style);
         let count = srt.lines().filter(|l| l.contains("-->")).count();
         assert!(count >= 2, "expected multiple segments, got {count}");
     }
+
+    #[test]
+    fn map_cue_text_rewrites_only_cue_lines() {
+        let srt = "1\r\n00:00:00,000 --> 00:00:01,000\r\nacme 1\r\n\r\n2\r\n00:00:01,000 --> 00:00:02,000\r\n2\r\n";
+        assert_eq!(
+            map_cue_text(srt, |t| t.replace("acme", "ACME").replace('2', "two")),
+            "1\r\n00:00:00,000 --> 00:00:01,000\r\nACME 1\r\n\r\n2\r\n00:00:01,000 --> 00:00:02,000\r\ntwo\r\n"
+        );
+    }
+
+    #[test]
+    fn speaker_style_templates() {
+        let style = SpeakerStyle::template("SPEAKER_{index}: ").expect("template");
+        assert_eq!(style.prefix("A"), "SPEAKER_00: ");
+        assert_eq!(style.prefix("AB"), "SPEAKER_27: ");
+        assert_eq!(style.prefix("2"), "SPEAKER_01: ");
+        assert_eq!(SpeakerStyle::template("[{name}] ").expect("template").prefix("B"), "[B] ");
+        assert_eq!(SpeakerStyle::none().prefix("A"), "");
+        assert!(SpeakerStyle::template("Speaker: ").is_err());
+        assert!(SpeakerStyle::template("{speaker}: ").is_err());
+    }
+
+    #[test]
+    fn renumber_srt_starts_at_index_and_fills_missing_numbers() {
+        let srt = "7\r\n00:00:00,000 --> 00:00:01,000\r\nHello\r\n\r\n00:00:01,000 --> 00:00:02,000\r\n42\r\n";
+        assert_eq!(
+            renumber_srt(srt, 101),
+            "101\r\n00:00:00,000 --> 00:00:01,000\r\nHello\r\n\r\n102\r\n00:00:01,000 --> 00:00:02,000\r\n42\r\n"
+        );
+    }
+
+    #[test]
+    fn split_at_boundaries_rebases_parts() {
+        let caption = |start_ms: u64, end_ms: u64| Caption {
+            start_ms,
+            end_ms,
+            speaker: None,
+            text: "x".to_string(),
+        };
+        let parts = split_at_boundaries(
+            &[caption(0, 1000), caption(59_000, 61_000), caption(125_000, 126_000)],
+            &[0, 60_000, 120_000],
+        );
+        let times: Vec<Vec<(u64, u64)>> = parts
+            .iter()
+            .map(|p| p.iter().map(|c| (c.start_ms, c.end_ms)).collect())
+            .collect();
+        assert_eq!(times, vec![vec![(0, 1000), (59_000, 61_000)], vec![(5000, 6000)]]);
+    }
 }
@@ -0,0 +1,120 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct Word {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+    pub confidence: Option<f64>,
+    pub speaker: Option<String>,
+}
+
+impl Word {
+    pub fn new(start_ms: u64, end_ms: u64, text: String, confidence: Option<f64>, speaker: Option<String>) -> Option<Self> {
+        if end_ms < start_ms {
+            return None;
+        }
+
+        let text = text.trim().to_string();
+        if text.is_empty() {
+            return None;
+        }
+
+        let speaker = speaker.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+
+        Some(Self {
+            start_ms,
+            end_ms,
+            text,
+            confidence,
+            speaker,
+        })
+    }
+}
+
+/// `text` as `[?text]` when its confidence is below `threshold`, so correctors can find it; words without a
+/// confidence are left alone.
+pub fn mark_uncertain(text: &str, confidence: Option<f64>, threshold: f64) -> Option<String> {
+    confidence
+        .is_some_and(|confidence| confidence < threshold)
+        .then(|| format!("[?{text}]"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sentiment {
+    Positive,
+    Neutral,
+    Negative,
+}
+
+impl Sentiment {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_uppercase().as_str() {
+            "POSITIVE" => Some(Self::Positive),
+            "NEUTRAL" => Some(Self::Neutral),
+            "NEGATIVE" => Some(Self::Negative),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentimentSegment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub sentiment: Sentiment,
+    pub speaker: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chapter {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub headline: String,
+    pub gist: String,
+    pub summary: String,
+}
+
+/// How timestamps are written in text, markdown, and CSV outputs; SRT/VTT keep their own formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize)]
+pub enum TimestampFormat {
+    #[default]
+    #[serde(rename = "hh:mm:ss")]
+    HhMmSs,
+    #[serde(rename = "mm:ss")]
+    MmSs,
+    #[serde(rename = "ms")]
+    Ms,
+    #[serde(rename = "seconds")]
+    Seconds,
+}
+
+impl TimestampFormat {
+    pub fn format(self, ms: u64) -> String {
+        match self {
+            Self::HhMmSs => format_clock_time(ms),
+            Self::MmSs => format!("{:02}:{:02}", ms / 60_000, (ms / 1000) % 60),
+            Self::Ms => ms.to_string(),
+            Self::Seconds => format!("{}.{:03}", ms / 1000, ms % 1000),
+        }
+    }
+}
+
+pub fn format_clock_time(ms: u64) -> String {
+    let total_seconds = ms / 1000;
+    let hours = total_seconds / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+    let seconds = total_seconds % 60;
+    format!("{hours:02}:{minutes:02}:{seconds:02}")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Highlight {
+    pub text: String,
+    pub count: u32,
+    pub rank: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entity {
+    pub entity_type: String,
+    pub text: String,
+}
@@ -0,0 +1,56 @@
+/// Captions are sent to LeMUR in batches so each response stays well inside the output limit.
+pub const TRANSLATION_BATCH_SIZE: usize = 150;
+
+pub fn build_translation_prompt(target_language: &str) -> String {
+    format!(
+        "Translate each numbered line of the input into the language with code \"{target_language}\". \
+Keep the numbering: return exactly one output line per input line, formatted as `N: translation`, \
+in the same order, with no commentary, headings, or blank lines. \
+Keep speaker names, numbers, and product names unchanged."
+    )
+}
+
+pub fn format_numbered_lines(texts: &[String]) -> String {
+    let mut out = String::new();
+    for (idx, text) in texts.iter().enumerate() {
+        out.push_str(&format!("{}: {}\n", idx + 1, text.split_whitespace().collect::<Vec<_>>().join(" ")));
+    }
+    out
+}
+
+/// Returns one entry per input line; lines the model skipped or garbled are `None`.
+pub fn parse_numbered_lines(response: &str, count: usize) -> Vec<Option<String>> {
+    let mut out = vec![None; count];
+    for line in response.lines() {
+        let line = line.trim().trim_start_matches(['-', '*']).trim();
+        let Some((number, text)) = line.split_once([':', '.', ')']) else {
+            continue;
+        };
+        let Ok(number) = number.trim().parse::<usize>() else {
+            continue;
+        };
+        let text = text.trim();
+        if number == 0 || number > count || text.is_empty() {
+            continue;
+        }
+        out[number - 1].get_or_insert_with(|| text.to_string());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbered_lines_round_trip() {
+        let input = format_numbered_lines(&["Hello  there".to_string(), "Bye".to_string()]);
+        assert_eq!(input, "1: Hello there\n2: Bye\n");
+
+        let parsed = parse_numbered_lines("Here you go:\n1: Hola: amigo\n- 3) extra\n2. Adiós\n", 2);
+        assert_eq!(parsed, vec![Some("Hola: amigo".to_string()), Some("Adiós".to_string())]);
+
+        let partial = parse_numbered_lines("2: Adiós", 2);
+        assert_eq!(partial, vec![None, Some("Adiós".to_string())]);
+    }
+}
@@ -0,0 +1,70 @@
+/// The release target this build downloads, matching the archives of the GitHub release workflow.
+pub fn release_target(os: &str, arch: &str) -> Option<&'static str> {
+    match (os, arch) {
+        ("linux", "x86_64") => Some("x86_64-unknown-linux-gnu"),
+        ("macos", "x86_64") => Some("x86_64-apple-darwin"),
+        ("macos", "aarch64") => Some("aarch64-apple-darwin"),
+        ("windows", "x86_64") => Some("x86_64-pc-windows-msvc"),
+        _ => None,
+    }
+}
+
+/// The archive name of `version` for `target`, e.g. `assemblyai-cli-0.2.0-x86_64-apple-darwin.tar.gz`.
+pub fn release_asset(version: &str, target: &str) -> String {
+    let extension = if target.contains("windows") { "zip" } else { "tar.gz" };
+    format!("assemblyai-cli-{version}-{target}.{extension}")
+}
+
+/// `MAJOR.MINOR.PATCH` of a release tag or version, with or without a leading `v`.
+///
+/// Pre-release and build suffixes are ignored.
+pub fn parse_version(value: &str) -> Option<(u64, u64, u64)> {
+    let core = value.trim().trim_start_matches('v');
+    let core = core.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let version = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(version)
+}
+
+/// Whether release `latest` is newer than `current`; unparsable versions never are.
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    match (parse_version(latest), parse_version(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+/// The lowercase hex SHA-256 from a `.sha256` file in `shasum` format (`HASH  NAME`).
+pub fn parse_checksum(contents: &str) -> Option<String> {
+    let hash = contents.split_whitespace().next()?;
+    (hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit())).then(|| hash.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_release_versions_and_reads_checksums() {
+        assert_eq!(parse_version("v0.2.10"), Some((0, 2, 10)));
+        assert_eq!(parse_version("1.0.0-rc.1"), Some((1, 0, 0)));
+        assert_eq!(parse_version("1.0"), None);
+        assert!(is_newer("v0.2.0", "0.1.4"));
+        assert!(is_newer("0.1.10", "0.1.9"));
+        assert!(!is_newer("v0.1.4", "0.1.4"));
+        assert!(!is_newer("nightly", "0.1.4"));
+
+        assert_eq!(
+            release_asset("0.2.0", release_target("macos", "aarch64").unwrap()),
+            "assemblyai-cli-0.2.0-aarch64-apple-darwin.tar.gz"
+        );
+        assert_eq!(release_target("linux", "riscv64"), None);
+
+        let hash = "AB".repeat(32);
+        assert_eq!(
+            parse_checksum(&format!("{hash}  dist/assemblyai-cli-0.2.0-x86_64-unknown-linux-gnu.tar.gz\n")),
+            Some("ab".repeat(32))
+        );
+        assert_eq!(parse_checksum("not-a-hash  file.tar.gz"), None);
+    }
+}
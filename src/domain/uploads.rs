@@ -0,0 +1,150 @@
+//! Uploads remembered by file content, so re-submitting the same file with other options skips the upload.
+//!
+//! An upload is only reused against the API and key it was made with: `upload_url`s are private to the account.
+
+use std::ffi::OsString;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// How long an `upload_url` is reused after its upload; older ones are uploaded again.
+pub const UPLOAD_URL_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Recent uploads, stored as `uploads.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadIndex {
+    pub uploads: Vec<UploadRecord>,
+}
+
+/// The API an upload was made to. Records from before scopes were kept have an empty one and are never reused.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadScope {
+    #[serde(default)]
+    pub base_url: String,
+    /// Lowercase hex SHA-256 of the API key; the key itself is never stored.
+    #[serde(default)]
+    pub api_key_sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadRecord {
+    /// Lowercase hex SHA-256 of the uploaded bytes.
+    pub sha256: String,
+    #[serde(flatten)]
+    pub scope: UploadScope,
+    pub bytes: u64,
+    pub upload_url: String,
+    /// Unix seconds of the upload.
+    pub uploaded_at: u64,
+}
+
+impl UploadIndex {
+    /// The `upload_url` of content `sha256` uploaded within `scope` less than [`UPLOAD_URL_TTL`] before `now`.
+    pub fn find(&self, sha256: &str, scope: &UploadScope, now: u64) -> Option<&str> {
+        self.uploads
+            .iter()
+            .find(|upload| upload.sha256 == sha256 && upload.scope == *scope && is_live(upload, now))
+            .map(|upload| upload.upload_url.as_str())
+    }
+
+    /// Records `upload`, replacing an earlier upload of the same content in its scope and dropping expired ones.
+    pub fn record(&mut self, upload: UploadRecord) {
+        let now = upload.uploaded_at;
+        self.uploads
+            .retain(|earlier| (earlier.sha256 != upload.sha256 || earlier.scope != upload.scope) && is_live(earlier, now));
+        self.uploads.push(upload);
+    }
+
+    /// Forgets the upload behind `upload_url`, e.g. after the API could no longer fetch it; false when unknown.
+    pub fn forget(&mut self, upload_url: &str) -> bool {
+        let before = self.uploads.len();
+        self.uploads.retain(|upload| upload.upload_url != upload_url);
+        self.uploads.len() != before
+    }
+}
+
+/// The `upload --json` document.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadedFile {
+    #[serde(with = "crate::domain::os_string")]
+    pub input: OsString,
+    pub upload_url: String,
+    pub bytes: u64,
+    /// Lowercase hex SHA-256 of the uploaded bytes.
+    pub sha256: String,
+}
+
+fn is_live(upload: &UploadRecord, now: u64) -> bool {
+    now.saturating_sub(upload.uploaded_at) < UPLOAD_URL_TTL.as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope(base_url: &str) -> UploadScope {
+        UploadScope {
+            base_url: base_url.to_string(),
+            api_key_sha256: "k1".to_string(),
+        }
+    }
+
+    fn upload(sha256: &str, url: &str, uploaded_at: u64) -> UploadRecord {
+        UploadRecord {
+            sha256: sha256.to_string(),
+            scope: scope("https://api.example"),
+            bytes: 10,
+            upload_url: url.to_string(),
+            uploaded_at,
+        }
+    }
+
+    #[test]
+    fn reuses_live_uploads_of_the_same_content() {
+        let day = UPLOAD_URL_TTL.as_secs();
+        let mut index = UploadIndex::default();
+        index.record(upload("aa", "https://cdn.example/upload/1", 1_000));
+        index.record(upload("bb", "https://cdn.example/upload/2", 2_000));
+        let api = scope("https://api.example");
+        assert_eq!(index.find("aa", &api, 1_000 + day - 1), Some("https://cdn.example/upload/1"));
+        assert_eq!(index.find("aa", &api, 1_000 + day), None);
+        assert_eq!(index.find("cc", &api, 2_000), None);
+
+        index.record(upload("aa", "https://cdn.example/upload/3", 1_500 + day));
+        let urls: Vec<&str> = index.uploads.iter().map(|u| u.upload_url.as_str()).collect();
+        assert_eq!(urls, ["https://cdn.example/upload/2", "https://cdn.example/upload/3"]);
+
+        assert!(index.forget("https://cdn.example/upload/2"));
+        assert!(!index.forget("https://cdn.example/upload/2"));
+        assert_eq!(index.find("bb", &api, 2_000), None);
+    }
+
+    #[test]
+    fn keeps_uploads_to_other_apis_and_keys_apart() {
+        let mut index = UploadIndex::default();
+        index.record(upload("aa", "https://cdn.example/upload/1", 1_000));
+        let staging = scope("https://staging.example");
+        let other_key = UploadScope {
+            api_key_sha256: "k2".to_string(),
+            ..scope("https://api.example")
+        };
+        assert_eq!(index.find("aa", &staging, 1_000), None);
+        assert_eq!(index.find("aa", &other_key, 1_000), None);
+
+        index.record(UploadRecord {
+            scope: staging.clone(),
+            ..upload("aa", "https://cdn.example/upload/2", 1_000)
+        });
+        assert_eq!(index.find("aa", &scope("https://api.example"), 1_000), Some("https://cdn.example/upload/1"));
+        assert_eq!(index.find("aa", &staging, 1_000), Some("https://cdn.example/upload/2"));
+
+        let unscoped: UploadIndex = serde_json::from_str(
+            r#"{"uploads":[{"sha256":"aa","bytes":10,"uploadUrl":"https://cdn.example/upload/0","uploadedAt":1000}]}"#,
+        )
+        .unwrap();
+        assert_eq!(unscoped.find("aa", &scope("https://api.example"), 1_000), None);
+    }
+}
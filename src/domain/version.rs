@@ -0,0 +1,28 @@
+use serde::Serialize;
+
+/// What `assemblyai-cli version` reports, for bug reports and auditing installs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionInfo {
+    pub version: String,
+    pub commit: String,
+    /// RFC 3339 UTC.
+    pub build_date: String,
+    pub target: String,
+    pub features: Vec<String>,
+    /// The API base URL requests would go to, after `ASSEMBLYAI_BASE_URL` and config `baseUrl`.
+    pub base_url: String,
+}
+
+/// `assemblyai-cli VERSION` followed by one aligned `NAME: VALUE` line per field.
+pub fn format_version_info(info: &VersionInfo) -> String {
+    let features = if info.features.is_empty() {
+        "(none)".to_string()
+    } else {
+        info.features.join(", ")
+    };
+    format!(
+        "assemblyai-cli {}\ncommit:     {}\nbuilt:      {}\ntarget:     {}\nfeatures:   {features}\napi:        {}\n",
+        info.version, info.commit, info.build_date, info.target, info.base_url
+    )
+}
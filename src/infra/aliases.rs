@@ -0,0 +1,30 @@
+use std::path::Path;
+
+use crate::domain::aliases::Aliases;
+use crate::infra::InfraError;
+
+/// Reads the alias file; a missing file has no aliases.
+pub fn load(path: &Path) -> Result<Aliases, InfraError> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Aliases::default()),
+        Err(err) => return Err(err.into()),
+    };
+    serde_json::from_str(&contents).map_err(|err| InfraError::InvalidAliasFile {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })
+}
+
+/// Replaces the alias file through a temporary file, so readers never see it half written.
+pub fn save(path: &Path, aliases: &Aliases) -> Result<(), InfraError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut contents = serde_json::to_string_pretty(aliases).map_err(std::io::Error::other)?;
+    contents.push('\n');
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, contents)?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
@@ -1,19 +1,33 @@
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
 use std::time::Duration;
 
-use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncRead, ReadBuf};
 
-use crate::domain::{CustomSpelling, Language, SpeechModel, TranscriptFormat, TranscribeOptions};
+use crate::domain::listing::{TranscriptFilter, TranscriptSummary};
+use crate::domain::polling::PollStrategy;
+use crate::domain::secret::ApiKey;
+use crate::domain::uploads::UploadScope;
+use crate::domain::{CustomSpelling, Language, TranscriptFormat, TranscribeOptions};
 
 #[derive(Debug, Clone)]
 pub struct AssemblyAiClientConfig {
-    pub api_key: String,
+    pub api_key: ApiKey,
     pub base_url: Option<String>,
+    /// Sent with every request, in order, so a later header replaces an earlier one of the same name.
+    pub extra_headers: Vec<(String, String)>,
 }
 
+pub const DEFAULT_BASE_URL: &str = "https://api.assemblyai.com";
+
 #[derive(Clone)]
 pub struct AssemblyAiClient {
     base_url: String,
+    api_key_sha256: String,
     http: reqwest::Client,
 }
 
@@ -21,38 +35,78 @@ impl AssemblyAiClient {
     pub fn new(config: AssemblyAiClientConfig) -> Result<Self, ApiError> {
         let base_url = config
             .base_url
-            .unwrap_or_else(|| "https://api.assemblyai.com".to_string());
+            .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
 
         let mut headers = HeaderMap::new();
-        headers.insert(
-            "authorization",
-            HeaderValue::from_str(&config.api_key).map_err(|_| ApiError::InvalidApiKey)?,
-        );
+        for (name, value) in &config.extra_headers {
+            let invalid = || ApiError::InvalidHeader { name: name.clone() };
+            headers.insert(
+                HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid())?,
+                HeaderValue::from_str(value).map_err(|_| invalid())?,
+            );
+        }
+        let mut authorization = HeaderValue::from_str(config.api_key.expose()).map_err(|_| ApiError::InvalidApiKey)?;
+        authorization.set_sensitive(true);
+        headers.insert("authorization", authorization);
 
+        // One pooled client serves every call of a run (clones share the pool), so polls reuse the
+        // upload's connection instead of dialing and handshaking again every few seconds. HTTPS connections
+        // negotiate HTTP/2 when the server offers it, and gzip keeps multi-megabyte transcript JSON small on the wire.
         let http = reqwest::Client::builder()
             .default_headers(headers)
+            .gzip(true)
+            .pool_idle_timeout(Duration::from_secs(90))
+            .pool_max_idle_per_host(16)
+            .tcp_keepalive(Duration::from_secs(60))
+            .tcp_nodelay(true)
             .build()
             .map_err(ApiError::HttpClientBuild)?;
 
-        Ok(Self { base_url, http })
+        let api_key_sha256 = crate::infra::uploads::hex(&Sha256::digest(config.api_key.expose().as_bytes()));
+        Ok(Self {
+            base_url,
+            api_key_sha256,
+            http,
+        })
+    }
+
+    /// The API and key uploads of this client are made to, for the upload index.
+    pub fn upload_scope(&self) -> UploadScope {
+        UploadScope {
+            base_url: self.base_url.clone(),
+            api_key_sha256: self.api_key_sha256.clone(),
+        }
     }
 
-    pub async fn upload_file(&self, path: &std::path::Path) -> Result<String, ApiError> {
+    /// Streams the file in one request, hashing the bytes as they are sent.
+    pub async fn upload_file(&self, path: &std::path::Path) -> Result<Upload, ApiError> {
         let file = tokio::fs::File::open(path).await.map_err(ApiError::Io)?;
-        let stream = tokio_util::io::ReaderStream::new(file);
+        let size = file.metadata().await.map_err(ApiError::Io)?.len();
+        let hasher = Arc::new(Mutex::new(Sha256::new()));
+        let reader = HashingReader {
+            inner: file,
+            hasher: Arc::clone(&hasher),
+        };
+        let stream = tokio_util::io::ReaderStream::new(reader);
         let body = reqwest::Body::wrap_stream(stream);
 
         let url = format!("{}/v2/upload", self.base_url.trim_end_matches('/'));
         let resp = self.http.post(url).body(body).send().await.map_err(ApiError::Http)?;
 
-        let status = resp.status();
-        let text = resp.text().await.map_err(ApiError::Http)?;
-        if !status.is_success() {
-            return Err(ApiError::HttpStatus { status, body: text });
+        if !resp.status().is_success() {
+            return Err(status_error(resp).await);
         }
+        crate::infra::metrics::METRICS.add_uploaded(size);
+        let text = resp.text().await.map_err(ApiError::Http)?;
 
         let parsed: UploadResponse = serde_json::from_str(&text).map_err(ApiError::Json)?;
-        Ok(parsed.upload_url)
+        let digest = hasher.lock().expect("upload hasher lock").clone().finalize();
+        Ok(Upload {
+            upload_url: parsed.upload_url,
+            sha256: crate::infra::uploads::hex(&digest),
+            stored_sha256: parsed.sha256,
+            stored_bytes: parsed.size,
+        })
     }
 
     pub async fn create_transcript(&self, audio_url: &str, options: &TranscribeOptions) -> Result<Transcript, ApiError> {
@@ -73,7 +127,17 @@ impl AssemblyAiClient {
     pub async fn get_transcript(&self, id: &str) -> Result<Transcript, ApiError> {
         let url = format!("{}/v2/transcript/{}", self.base_url.trim_end_matches('/'), id);
         let resp = self.http.get(url).send().await.map_err(ApiError::Http)?;
-        parse_json_response::<Transcript>(resp).await
+        parse_json_stream::<Transcript>(resp).await
+    }
+
+    /// The transcript exactly as the API returns it, for external formatters.
+    pub async fn get_transcript_json(&self, id: &str) -> Result<Vec<u8>, ApiError> {
+        let url = format!("{}/v2/transcript/{}", self.base_url.trim_end_matches('/'), id);
+        let resp = self.http.get(url).send().await.map_err(ApiError::Http)?;
+        if !resp.status().is_success() {
+            return Err(status_error(resp).await);
+        }
+        Ok(resp.bytes().await.map_err(ApiError::Http)?.to_vec())
     }
 
     pub async fn get_subtitles(
@@ -85,7 +149,7 @@ impl AssemblyAiClient {
         let suffix = match format {
             TranscriptFormat::Srt => "srt",
             TranscriptFormat::Vtt => "vtt",
-            TranscriptFormat::Text => return Err(ApiError::InvalidSubtitleFormat),
+            _ => return Err(ApiError::InvalidSubtitleFormat),
         };
 
         let url = format!(
@@ -97,23 +161,111 @@ impl AssemblyAiClient {
         );
 
         let resp = self.http.get(url).send().await.map_err(ApiError::Http)?;
-        let status = resp.status();
-        let body = resp.text().await.map_err(ApiError::Http)?;
-        if !status.is_success() {
-            return Err(ApiError::HttpStatus { status, body });
+        if !resp.status().is_success() {
+            return Err(status_error(resp).await);
         }
-        Ok(body)
+        resp.text().await.map_err(ApiError::Http)
+    }
+
+    pub async fn lemur_task(&self, request: &LemurTaskRequest) -> Result<LemurResponse, ApiError> {
+        let url = format!("{}/lemur/v3/generate/task", self.base_url.trim_end_matches('/'));
+        let resp = self
+            .http
+            .post(url)
+            .json(request)
+            .send()
+            .await
+            .map_err(ApiError::Http)?;
+
+        parse_json_response::<LemurResponse>(resp).await
+    }
+
+    /// One page of the account's transcripts, newest first; `before_id` continues after the last transcript of
+    /// the previous page.
+    pub async fn list_transcripts(
+        &self,
+        limit: u32,
+        filter: &TranscriptFilter,
+        before_id: Option<&str>,
+    ) -> Result<TranscriptPage, ApiError> {
+        let mut url = format!("{}/v2/transcript?limit={limit}", self.base_url.trim_end_matches('/'));
+        if let Some(status) = &filter.status {
+            url.push_str(&format!("&status={status}"));
+        }
+        if filter.throttled_only {
+            url.push_str("&throttled_only=true");
+        }
+        if let Some(before_id) = before_id {
+            url.push_str(&format!("&before_id={before_id}"));
+        }
+        let resp = self.http.get(url).send().await.map_err(ApiError::Http)?;
+        parse_json_response::<TranscriptPage>(resp).await
+    }
+
+    /// Deletes the transcript's text and audio from the API; the id stays listed with its metadata.
+    pub async fn delete_transcript(&self, id: &str) -> Result<(), ApiError> {
+        let url = format!("{}/v2/transcript/{}", self.base_url.trim_end_matches('/'), id);
+        let resp = self.http.delete(url).send().await.map_err(ApiError::Http)?;
+        if !resp.status().is_success() {
+            return Err(status_error(resp).await);
+        }
+        Ok(())
+    }
+
+    /// Whether the API accepts the key, checked by listing at most one transcript.
+    pub async fn check_api_key(&self) -> Result<bool, ApiError> {
+        let url = format!("{}/v2/transcript?limit=1", self.base_url.trim_end_matches('/'));
+        let resp = self
+            .http
+            .get(url)
+            .timeout(Duration::from_secs(15))
+            .send()
+            .await
+            .map_err(ApiError::Http)?;
+        match resp.status() {
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => Ok(false),
+            status if status.is_success() => Ok(true),
+            _ => Err(status_error(resp).await),
+        }
+    }
+
+    /// Short-lived token for realtime sessions, so clients never see the API key.
+    pub async fn create_realtime_token(&self, expires_in: u32) -> Result<String, ApiError> {
+        let url = format!("{}/v2/realtime/token", self.base_url.trim_end_matches('/'));
+        let resp = self
+            .http
+            .post(url)
+            .json(&RealtimeTokenRequest { expires_in })
+            .send()
+            .await
+            .map_err(ApiError::Http)?;
+
+        Ok(parse_json_response::<RealtimeTokenResponse>(resp).await?.token)
     }
 
     pub async fn poll_until_done(
         &self,
         id: &str,
+        strategy: PollStrategy,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<Transcript, ApiError> {
+        self.poll_until_done_with(id, strategy, poll_interval, timeout, |_| {}).await
+    }
+
+    /// [`Self::poll_until_done`], calling `on_poll` with the status each poll returns.
+    pub async fn poll_until_done_with(
+        &self,
+        id: &str,
+        strategy: PollStrategy,
         poll_interval: Duration,
         timeout: Duration,
+        mut on_poll: impl FnMut(&str),
     ) -> Result<Transcript, ApiError> {
         let start = tokio::time::Instant::now();
         loop {
             let t = self.get_transcript(id).await?;
+            on_poll(&t.status);
             match t.status.as_str() {
                 "completed" => return Ok(t),
                 "error" => return Ok(t),
@@ -124,7 +276,7 @@ impl AssemblyAiClient {
                 return Err(ApiError::Timeout { timeout_seconds: timeout.as_secs() });
             }
 
-            tokio::time::sleep(poll_interval).await;
+            tokio::time::sleep(strategy.next_interval(poll_interval, start.elapsed(), t.audio_duration)).await;
         }
     }
 }
@@ -134,6 +286,9 @@ pub enum ApiError {
     #[error("invalid API key header value")]
     InvalidApiKey,
 
+    #[error("invalid extra header {name:?}")]
+    InvalidHeader { name: String },
+
     #[error("failed to build HTTP client: {0}")]
     HttpClientBuild(reqwest::Error),
 
@@ -146,22 +301,110 @@ pub enum ApiError {
     #[error("json error: {0}")]
     Json(serde_json::Error),
 
-    #[error("api returned HTTP {status}: {body}")]
-    HttpStatus { status: reqwest::StatusCode, body: String },
+    #[error("api returned HTTP {status}: {body}{}", request_id_suffix(request_id))]
+    HttpStatus {
+        status: reqwest::StatusCode,
+        body: String,
+        /// The API's id for the failed call, to quote in support tickets.
+        request_id: Option<String>,
+    },
 
     #[error("transcription failed: {message}")]
     TranscriptError { message: String },
 
+    #[error("transcript {id} is {status}, not completed")]
+    TranscriptNotCompleted { id: String, status: String },
+
+    #[error("transcript {id} has no word or utterance timings")]
+    TranscriptHasNoTimings { id: String },
+
     #[error("timeout after {timeout_seconds} seconds")]
     Timeout { timeout_seconds: u64 },
 
     #[error("subtitle format is only valid for srt/vtt")]
     InvalidSubtitleFormat,
+
+    #[error("api returned unparseable subtitles at line {line}: {message}")]
+    InvalidSubtitleBody { line: usize, message: String },
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct LemurTaskRequest {
+    pub prompt: String,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub transcript_ids: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_text: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub final_model: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LemurResponse {
+    pub response: String,
+}
+
+#[derive(Debug, Serialize)]
+struct RealtimeTokenRequest {
+    expires_in: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct RealtimeTokenResponse {
+    token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TranscriptPage {
+    pub page_details: PageDetails,
+    pub transcripts: Vec<TranscriptSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PageDetails {
+    /// The page of older transcripts; absent on the last page.
+    #[serde(default)]
+    pub prev_url: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 struct UploadResponse {
     upload_url: String,
+    #[serde(default)]
+    sha256: Option<String>,
+    #[serde(default)]
+    size: Option<u64>,
+}
+
+/// An uploaded file: where the API can fetch it, and the SHA-256 of the bytes that were sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Upload {
+    pub upload_url: String,
+    pub sha256: String,
+    /// The SHA-256 the upload response reports for the stored file, when it reports one.
+    pub stored_sha256: Option<String>,
+    /// The size the upload response reports for the stored file, when it reports one.
+    pub stored_bytes: Option<u64>,
+}
+
+/// Hashes what is read through it, so the upload body is checksummed without reading the file twice.
+struct HashingReader<R> {
+    inner: R,
+    hasher: Arc<Mutex<Sha256>>,
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for HashingReader<R> {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        let filled = buf.filled().len();
+        let poll = Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = poll {
+            self.hasher.lock().expect("upload hasher lock").update(&buf.filled()[filled..]);
+        }
+        poll
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -192,6 +435,9 @@ struct CreateTranscriptRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     word_boost: Option<Vec<String>>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keyterms_prompt: Option<Vec<String>>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     custom_spelling: Option<Vec<CustomSpellingRequest>>,
 
@@ -203,14 +449,23 @@ struct CreateTranscriptRequest {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     multichannel: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sentiment_analysis: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auto_chapters: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auto_highlights: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entity_detection: Option<bool>,
 }
 
 impl CreateTranscriptRequest {
     fn from_options(audio_url: &str, options: &TranscribeOptions) -> Self {
-        let speech_model = match options.speech_model() {
-            SpeechModel::Best => Some("best".to_string()),
-            SpeechModel::Nano => Some("nano".to_string()),
-        };
+        let speech_model = Some(options.speech_model().name().to_string());
 
         let (language_detection, language_code) = match options.language() {
             Language::AutoDetect => (Some(true), None),
@@ -246,10 +501,15 @@ impl CreateTranscriptRequest {
             disfluencies: Some(options.disfluencies()),
             filter_profanity: Some(options.filter_profanity()),
             word_boost,
+            keyterms_prompt: (!options.keyterms_prompt().is_empty()).then(|| options.keyterms_prompt().to_vec()),
             custom_spelling,
             speech_threshold: options.speech_threshold(),
             speaker_labels: Some(options.speaker_labels()),
             multichannel: Some(options.multichannel()),
+            sentiment_analysis: options.sentiment_analysis().then_some(true),
+            auto_chapters: options.auto_chapters().then_some(true),
+            auto_highlights: options.auto_highlights().then_some(true),
+            entity_detection: options.entity_detection().then_some(true),
         }
     }
 }
@@ -269,7 +529,7 @@ impl CustomSpellingRequest {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct Transcript {
     pub id: String,
     pub status: String,
@@ -282,13 +542,107 @@ pub struct Transcript {
 
     #[serde(default)]
     pub utterances: Option<Vec<Utterance>>,
+
+    #[serde(default)]
+    pub words: Option<Vec<Word>>,
+
+    #[serde(default)]
+    pub sentiment_analysis_results: Option<Vec<SentimentResult>>,
+
+    #[serde(default)]
+    pub chapters: Option<Vec<ApiChapter>>,
+
+    #[serde(default)]
+    pub confidence: Option<f64>,
+
+    #[serde(default)]
+    pub language_code: Option<String>,
+
+    #[serde(default)]
+    pub language_confidence: Option<f64>,
+
+    /// Seconds; reported once the API has read the media.
+    #[serde(default)]
+    pub audio_duration: Option<f64>,
+
+    #[serde(default)]
+    pub auto_highlights_result: Option<AutoHighlightsResult>,
+
+    #[serde(default)]
+    pub entities: Option<Vec<ApiEntity>>,
 }
 
 #[derive(Debug, Deserialize)]
+pub struct ApiEntity {
+    #[serde(default)]
+    pub entity_type: Option<String>,
+
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AutoHighlightsResult {
+    #[serde(default)]
+    pub results: Vec<ApiHighlight>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApiHighlight {
+    #[serde(default)]
+    pub text: Option<String>,
+
+    #[serde(default)]
+    pub count: Option<u32>,
+
+    #[serde(default)]
+    pub rank: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApiChapter {
+    #[serde(default)]
+    pub start: Option<u64>,
+
+    #[serde(default)]
+    pub end: Option<u64>,
+
+    #[serde(default)]
+    pub headline: Option<String>,
+
+    #[serde(default)]
+    pub gist: Option<String>,
+
+    #[serde(default)]
+    pub summary: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SentimentResult {
+    #[serde(default)]
+    pub start: Option<u64>,
+
+    #[serde(default)]
+    pub end: Option<u64>,
+
+    #[serde(default)]
+    pub sentiment: Option<String>,
+
+    #[serde(default)]
+    pub speaker: Option<SpeakerLabel>,
+
+    #[serde(default)]
+    pub channel: Option<SpeakerLabel>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct Utterance {
     #[serde(default)]
     pub speaker: Option<SpeakerLabel>,
 
+    #[serde(default)]
+    pub channel: Option<SpeakerLabel>,
+
     #[serde(default)]
     pub text: Option<String>,
 
@@ -299,18 +653,146 @@ pub struct Utterance {
     pub end: Option<u64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct Word {
+    #[serde(default)]
+    pub text: Option<String>,
+
+    #[serde(default)]
+    pub start: Option<u64>,
+
+    #[serde(default)]
+    pub end: Option<u64>,
+
+    #[serde(default)]
+    pub confidence: Option<f64>,
+
+    #[serde(default)]
+    pub speaker: Option<SpeakerLabel>,
+
+    #[serde(default)]
+    pub channel: Option<SpeakerLabel>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
 #[serde(untagged)]
 pub enum SpeakerLabel {
     Number(u32),
     Label(String),
 }
 
+/// Parses a JSON body while it downloads, so a completed 10-hour transcript is never held as text next to its parsed form.
+///
+/// Only the text is streamed: the result is still built whole, word arrays included, since every output needs it.
+async fn parse_json_stream<T: serde::de::DeserializeOwned + Send + 'static>(mut resp: reqwest::Response) -> Result<T, ApiError> {
+    if !resp.status().is_success() {
+        return Err(status_error(resp).await);
+    }
+
+    let (tx, rx) = tokio::sync::mpsc::channel(8);
+    let parser = tokio::task::spawn_blocking(move || {
+        serde_json::from_reader::<_, T>(std::io::BufReader::new(ChunkReader { rx, chunk: Default::default(), pos: 0 }))
+    });
+    let download = async {
+        while let Some(chunk) = resp.chunk().await.map_err(ApiError::Http)? {
+            if tx.send(chunk).await.is_err() {
+                // The parser already stopped on invalid JSON.
+                break;
+            }
+        }
+        Ok(())
+    }
+    .await;
+    drop(tx);
+
+    let parsed = parser.await.map_err(|err| ApiError::Io(std::io::Error::other(err)))?;
+    download?;
+    parsed.map_err(ApiError::Json)
+}
+
+/// Blocking reader over body chunks sent from the download task.
+struct ChunkReader<B> {
+    rx: tokio::sync::mpsc::Receiver<B>,
+    chunk: B,
+    pos: usize,
+}
+
+impl<B: AsRef<[u8]>> std::io::Read for ChunkReader<B> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.pos == self.chunk.as_ref().len() {
+            match self.rx.blocking_recv() {
+                Some(chunk) => {
+                    self.chunk = chunk;
+                    self.pos = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+        let remaining = &self.chunk.as_ref()[self.pos..];
+        let n = remaining.len().min(buf.len());
+        buf[..n].copy_from_slice(&remaining[..n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
 async fn parse_json_response<T: for<'de> Deserialize<'de>>(resp: reqwest::Response) -> Result<T, ApiError> {
-    let status = resp.status();
-    let text = resp.text().await.map_err(ApiError::Http)?;
-    if !status.is_success() {
-        return Err(ApiError::HttpStatus { status, body: text });
+    if !resp.status().is_success() {
+        return Err(status_error(resp).await);
     }
+    let text = resp.text().await.map_err(ApiError::Http)?;
     serde_json::from_str(&text).map_err(ApiError::Json)
 }
+
+/// Response headers that carry the API's id for a call, most specific first.
+const REQUEST_ID_HEADERS: [&str; 3] = ["x-request-id", "x-amzn-requestid", "x-correlation-id"];
+
+/// [`ApiError::HttpStatus`] for a failed response, with its body and request id.
+async fn status_error(resp: reqwest::Response) -> ApiError {
+    let status = resp.status();
+    let request_id = REQUEST_ID_HEADERS.iter().find_map(|name| {
+        let value = resp.headers().get(*name)?.to_str().ok()?.trim();
+        (!value.is_empty()).then(|| value.to_string())
+    });
+    let body = resp.text().await.unwrap_or_default();
+    ApiError::HttpStatus { status, body, request_id }
+}
+
+fn request_id_suffix(request_id: &Option<String>) -> String {
+    request_id.as_ref().map(|id| format!(" (request id {id})")).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 200 response whose body arrives `chunk` bytes at a time.
+    fn chunked_response(body: &str, chunk: usize) -> reqwest::Response {
+        let chunks = tokio_util::io::ReaderStream::with_capacity(std::io::Cursor::new(body.as_bytes().to_vec()), chunk);
+        reqwest::Response::from(http::Response::new(reqwest::Body::wrap_stream(chunks)))
+    }
+
+    #[tokio::test]
+    async fn parses_a_body_split_mid_token() {
+        let body = serde_json::json!({
+            "id": "tr_1",
+            "status": "completed",
+            "text": "Привет, world.",
+            "words": [{"text": "Привет,", "start": 0, "end": 480, "confidence": 0.97}],
+        })
+        .to_string();
+        for chunk in [1, 3, 7, body.len()] {
+            let transcript = parse_json_stream::<Transcript>(chunked_response(&body, chunk)).await.unwrap();
+            assert_eq!(transcript.id, "tr_1");
+            assert_eq!(transcript.text.as_deref(), Some("Привет, world."));
+            assert_eq!(transcript.words.unwrap()[0].end, Some(480));
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_a_truncated_body() {
+        let body = r#"{"id":"tr_1","status":"completed","words":[{"text":"Hel"#;
+        let err = parse_json_stream::<Transcript>(chunked_response(body, 4)).await.unwrap_err();
+        assert!(matches!(&err, ApiError::Json(err) if err.is_eof()), "{err:?}");
+    }
+}
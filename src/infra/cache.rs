@@ -0,0 +1,144 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::domain::cache::{evictions, is_cacheable_id, CacheEntry};
+use crate::infra::lock::StateLock;
+use crate::infra::{logging, InfraError};
+
+/// Completed transcripts stored as `<dir>/<id>.json`, so reading one again skips the API.
+///
+/// Completed transcripts don't change, so entries never go stale; `maxCacheBytes` evicts the least recently
+/// used ones. A cache failure never fails a run: reads fall back to the API and failed writes are logged.
+pub struct TranscriptCache {
+    dir: PathBuf,
+    max_bytes: Option<u64>,
+}
+
+impl TranscriptCache {
+    pub fn new(dir: PathBuf, max_bytes: Option<u64>) -> Self {
+        Self { dir, max_bytes }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The cached JSON of `transcript_id`, marked as just used.
+    pub fn get(&self, transcript_id: &str) -> Option<Vec<u8>> {
+        let path = self.entry_path(transcript_id)?;
+        let json = std::fs::read(&path).ok()?;
+        if let Ok(file) = std::fs::File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(json)
+    }
+
+    /// The cached JSON of `transcript_id`, without marking it as used, e.g. when searching every entry.
+    pub fn peek(&self, transcript_id: &str) -> Option<Vec<u8>> {
+        std::fs::read(self.entry_path(transcript_id)?).ok()
+    }
+
+    /// Stores the JSON of a completed transcript, then evicts entries beyond `maxCacheBytes`.
+    pub fn put(&self, transcript_id: &str, json: &[u8]) {
+        let Some(path) = self.entry_path(transcript_id) else {
+            return;
+        };
+        if let Err(err) = self.write(&path, json) {
+            logging::warn(crate::tr!("cache-write-failed", error = err.to_string()));
+        }
+    }
+
+    fn write(&self, path: &Path, json: &[u8]) -> Result<(), InfraError> {
+        let _lock = StateLock::acquire(&self.dir)?;
+        std::fs::create_dir_all(&self.dir)?;
+        let temp = path.with_extension("json.tmp");
+        std::fs::write(&temp, json)?;
+        std::fs::rename(&temp, path)?;
+        if let Some(max_bytes) = self.max_bytes {
+            let entries = self.entries()?;
+            self.remove_unlocked(&evictions(&entries, max_bytes))?;
+        }
+        Ok(())
+    }
+
+    /// Every cached transcript; an absent cache directory is empty.
+    pub fn entries(&self) -> Result<Vec<CacheEntry>, InfraError> {
+        let read_dir = match std::fs::read_dir(&self.dir) {
+            Ok(read_dir) => read_dir,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        let mut entries = Vec::new();
+        for item in read_dir {
+            let item = item?;
+            let path = item.path();
+            let Some(transcript_id) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix(".json"))
+                .filter(|id| is_cacheable_id(id))
+            else {
+                continue;
+            };
+            let metadata = item.metadata()?;
+            let last_used = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |d| d.as_secs());
+            entries.push(CacheEntry {
+                transcript_id: transcript_id.to_string(),
+                bytes: metadata.len(),
+                last_used,
+            });
+        }
+        Ok(entries)
+    }
+
+    /// Deletes `entries` and returns how many bytes they took.
+    pub fn remove(&self, entries: &[&CacheEntry]) -> Result<u64, InfraError> {
+        let _lock = StateLock::acquire(&self.dir)?;
+        self.remove_unlocked(entries)
+    }
+
+    fn remove_unlocked(&self, entries: &[&CacheEntry]) -> Result<u64, InfraError> {
+        let mut freed = 0;
+        for entry in entries {
+            match std::fs::remove_file(self.dir.join(format!("{}.json", entry.transcript_id))) {
+                Ok(()) => freed += entry.bytes,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(freed)
+    }
+
+    fn entry_path(&self, transcript_id: &str) -> Option<PathBuf> {
+        is_cacheable_id(transcript_id).then(|| self.dir.join(format!("{transcript_id}.json")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_the_least_recently_used_transcripts() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = TranscriptCache::new(dir.path().join("cache"), Some(25));
+        cache.put("tr_1", &[b'1'; 10]);
+        cache.put("tr_2", &[b'2'; 10]);
+        let past = SystemTime::now() - std::time::Duration::from_secs(60);
+        for id in ["tr_1", "tr_2"] {
+            let file = std::fs::File::options().write(true).open(cache.dir().join(format!("{id}.json"))).unwrap();
+            file.set_modified(past).unwrap();
+        }
+        assert!(cache.get("tr_1").is_some());
+        cache.put("tr_3", &[b'3'; 10]);
+
+        let mut ids: Vec<String> = cache.entries().unwrap().into_iter().map(|e| e.transcript_id).collect();
+        ids.sort();
+        assert_eq!(ids, ["tr_1", "tr_3"]);
+        assert!(cache.get("../config").is_none());
+    }
+}
@@ -0,0 +1,42 @@
+use std::path::Path;
+
+use crate::infra::InfraError;
+
+/// Fails with [`InfraError::InsufficientSpace`] when the file system holding `dir` has less than `needed`
+/// bytes available, so a run stops before ffmpeg or a write fails halfway.
+///
+/// `dir` may not exist yet; its nearest existing ancestor is checked. File systems that can't report their
+/// free space pass.
+pub fn ensure_space(dir: &Path, needed: u64, purpose: &str) -> Result<(), InfraError> {
+    let Some(existing) = dir.ancestors().find(|ancestor| ancestor.is_dir()) else {
+        return Ok(());
+    };
+    match fs4::available_space(existing) {
+        Ok(available) if available < needed => Err(InfraError::InsufficientSpace {
+            dir: dir.to_path_buf(),
+            purpose: purpose.to_string(),
+            needed,
+            available,
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_missing_space_in_readable_sizes() {
+        let dir = tempfile::tempdir().unwrap();
+        ensure_space(&dir.path().join("not/created/yet"), 1, "extracted audio").unwrap();
+
+        let err = ensure_space(dir.path(), u64::MAX, "extracted audio").unwrap_err();
+        let message = err.to_string();
+        assert!(message.starts_with("not enough disk space for extracted audio in "), "{message}");
+        assert!(message.contains(": needs 17179869184G, "), "{message}");
+        assert_eq!(crate::domain::format_size(1536), "1.5K");
+        assert_eq!(crate::domain::format_size(10 << 20), "10M");
+        assert_eq!(crate::domain::format_size(900), "900B");
+    }
+}
@@ -3,34 +3,266 @@ use std::process::Command;
 
 use tempfile::TempPath;
 
-use crate::infra::InfraError;
+use crate::domain::transcript::Chapter;
+use crate::infra::{logging, InfraError};
+
+/// Where intermediate files go, the system temp directory by default, and whether they outlive the run.
+///
+/// Unless kept, each file is removed when dropped, whether the run succeeded or not.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TempFiles<'a> {
+    pub dir: Option<&'a Path>,
+    pub keep: bool,
+}
+
+impl TempFiles<'_> {
+    /// The directory intermediates are created in.
+    pub fn location(&self) -> PathBuf {
+        self.dir.map_or_else(std::env::temp_dir, Path::to_path_buf)
+    }
+
+    fn create(&self, suffix: &str) -> Result<TempPath, InfraError> {
+        let mut builder = tempfile::Builder::new();
+        builder.prefix("assemblyai-cli-").suffix(suffix).disable_cleanup(self.keep);
+        let file = match self.dir {
+            Some(dir) => {
+                std::fs::create_dir_all(dir)?;
+                builder.tempfile_in(dir)?
+            }
+            None => builder.tempfile()?,
+        };
+        let path = file.into_temp_path();
+        if self.keep {
+            logging::info(crate::tr!("keeping-temp-file", path = path.display().to_string()));
+        }
+        Ok(path)
+    }
+}
 
 pub struct ExtractedAudio {
     pub path: TempPath,
 }
 
-pub fn extract_audio_to_mp3(input_video: &Path) -> Result<ExtractedAudio, InfraError> {
-    let temp = tempfile::Builder::new()
-        .prefix("assemblyai-cli-")
-        .suffix(".mp3")
-        .tempfile()?
-        .into_temp_path();
+/// Extracts a mono mp3 from `input`, optionally passed through an `-af` filter chain.
+pub fn extract_audio_to_mp3(input: &Path, filter: Option<&str>, temp_files: TempFiles<'_>) -> Result<ExtractedAudio, InfraError> {
+    let temp = temp_files.create(".mp3")?;
 
     let output_path: PathBuf = temp.to_path_buf();
 
     let mut cmd = Command::new("ffmpeg");
     cmd.arg("-y")
         .arg("-i")
-        .arg(input_video)
+        .arg(input)
         .arg("-vn")
         .arg("-ac")
-        .arg("1")
-        .arg("-codec:a")
+        .arg("1");
+    if let Some(filter) = filter {
+        cmd.arg("-af").arg(filter);
+    }
+    cmd        .arg("-codec:a")
         .arg("libmp3lame")
         .arg("-q:a")
         .arg("2")
         .arg(&output_path);
 
+    run_ffmpeg(&mut cmd)?;
+    Ok(ExtractedAudio { path: temp })
+}
+
+/// Remuxes `path` in place with the transcript stored in the `lyrics` tag (the `©lyr` atom for MP4/M4A).
+pub fn embed_lyrics(path: &Path, transcript: &str, temp_files: TempFiles<'_>) -> Result<(), InfraError> {
+    let parent = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("mp4");
+
+    let existing = temp_files.create(".ffmetadata")?;
+    run_ffmpeg(Command::new("ffmpeg").arg("-y").arg("-i").arg(path).arg("-f").arg("ffmetadata").arg(&existing))?;
+
+    let contents = std::fs::read_to_string(&existing)?;
+    std::fs::write(&existing, with_global_lyrics(&contents, transcript))?;
+
+    let output = tempfile::Builder::new()
+        .prefix(".assemblyai-cli-")
+        .suffix(&format!(".{ext}"))
+        .tempfile_in(parent)?
+        .into_temp_path();
+    run_ffmpeg(
+        Command::new("ffmpeg")
+            .arg("-y")
+            .arg("-i")
+            .arg(path)
+            .arg("-f")
+            .arg("ffmetadata")
+            .arg("-i")
+            .arg(&existing)
+            .arg("-map")
+            .arg("0")
+            .arg("-map_metadata")
+            .arg("1")
+            .arg("-codec")
+            .arg("copy")
+            .arg(&output),
+    )?;
+
+    output.persist(path).map_err(|err| InfraError::Io(err.error))?;
+    Ok(())
+}
+
+/// Copies `input` to `output` with `chapters` muxed in as container chapter markers.
+pub fn write_chapters(input: &Path, output: &Path, chapters: &[Chapter], temp_files: TempFiles<'_>) -> Result<(), InfraError> {
+    let metadata = temp_files.create(".ffmetadata")?;
+    std::fs::write(&metadata, format_chapter_metadata(chapters))?;
+
+    run_ffmpeg(
+        Command::new("ffmpeg")
+            .arg("-y")
+            .arg("-i")
+            .arg(input)
+            .arg("-f")
+            .arg("ffmetadata")
+            .arg("-i")
+            .arg(&metadata)
+            .arg("-map")
+            .arg("0")
+            .arg("-map_metadata")
+            .arg("0")
+            .arg("-map_chapters")
+            .arg("1")
+            .arg("-codec")
+            .arg("copy")
+            .arg(output),
+    )
+}
+
+fn format_chapter_metadata(chapters: &[Chapter]) -> String {
+    let mut out = String::from(";FFMETADATA1\n");
+    for chapter in chapters {
+        out.push_str("[CHAPTER]\nTIMEBASE=1/1000\n");
+        out.push_str(&format!("START={}\nEND={}\n", chapter.start_ms, chapter.end_ms));
+        out.push_str(&format!("title={}\n", escape_ffmetadata(&chapter.headline)));
+    }
+    out
+}
+
+/// Replaces any `lyrics` entry in the global section of an ffmetadata file (before the first `[SECTION]`).
+fn with_global_lyrics(contents: &str, transcript: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut in_global = true;
+    let mut inserted = false;
+    for line in contents.lines() {
+        if line.starts_with('[') && in_global {
+            in_global = false;
+            lines.push(format!("lyrics={}", escape_ffmetadata(transcript)));
+            inserted = true;
+        }
+        if in_global && line.to_ascii_lowercase().starts_with("lyrics=") {
+            continue;
+        }
+        lines.push(line.to_string());
+    }
+    if !inserted {
+        lines.push(format!("lyrics={}", escape_ffmetadata(transcript)));
+    }
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+fn escape_ffmetadata(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ProbeOutput {
+    #[serde(default)]
+    streams: Vec<ProbeStream>,
+    #[serde(default)]
+    format: Option<ProbeFormat>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ProbeStream {
+    #[serde(default)]
+    codec_type: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ProbeFormat {
+    #[serde(default)]
+    duration: Option<String>,
+}
+
+/// What ffprobe found out about a local file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaProbe {
+    pub duration_seconds: Option<f64>,
+    /// Why the file can't be transcribed (no audio stream, zero duration).
+    pub problem: Option<&'static str>,
+}
+
+/// Plays `start_ms..end_ms` of `path` with ffplay, without a window; false when ffplay isn't installed or fails.
+pub fn play_clip(path: &Path, start_ms: u64, end_ms: u64) -> bool {
+    let seconds = |ms: u64| format!("{}.{:03}", ms / 1000, ms % 1000);
+    Command::new("ffplay")
+        .args(["-nodisp", "-autoexit", "-loglevel", "quiet", "-ss", &seconds(start_ms), "-t", &seconds(end_ms.saturating_sub(start_ms))])
+        .arg(path)
+        .stdin(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Probes `path` with ffprobe. Returns `Ok(None)` when ffprobe isn't installed, so checks that need
+/// it are skipped rather than required; unreadable files are `InfraError::InvalidMedia`.
+pub fn probe_media(path: &Path) -> Result<Option<MediaProbe>, InfraError> {
+    let output = match Command::new("ffprobe")
+        .args(["-v", "error", "-show_entries", "format=duration:stream=codec_type", "-of", "json"])
+        .arg(path)
+        .output()
+    {
+        Ok(output) => output,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(InfraError::Io(err)),
+    };
+
+    let invalid = |message: String| InfraError::InvalidMedia {
+        path: path.to_path_buf(),
+        message,
+    };
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(invalid(if stderr.is_empty() { "ffprobe could not read it".to_string() } else { stderr }));
+    }
+
+    let probe: ProbeOutput =
+        serde_json::from_slice(&output.stdout).map_err(|err| invalid(format!("unreadable ffprobe output: {err}")))?;
+    Ok(Some(media_probe(&probe)))
+}
+
+fn media_probe(probe: &ProbeOutput) -> MediaProbe {
+    let has = |kind: &str| probe.streams.iter().any(|s| s.codec_type.as_deref() == Some(kind));
+    let duration_seconds = probe
+        .format
+        .as_ref()
+        .and_then(|f| f.duration.as_deref())
+        .and_then(|d| d.parse::<f64>().ok());
+    let problem = if !has("audio") {
+        Some(if has("video") { "video only, no audio stream" } else { "no audio stream" })
+    } else if duration_seconds.is_some_and(|seconds| seconds <= 0.0) {
+        Some("zero duration")
+    } else {
+        None
+    };
+    MediaProbe { duration_seconds, problem }
+}
+
+fn run_ffmpeg(cmd: &mut Command) -> Result<(), InfraError> {
     let output = cmd.output().map_err(|err| {
         if err.kind() == std::io::ErrorKind::NotFound {
             InfraError::FfmpegNotFound
@@ -44,5 +276,53 @@ pub fn extract_audio_to_mp3(input_video: &Path) -> Result<ExtractedAudio, InfraE
         return Err(InfraError::FfmpegFailed { message: stderr });
     }
 
-    Ok(ExtractedAudio { path: temp })
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_unusable_media_from_probe() {
+        let probe = |json: &str| media_probe(&serde_json::from_str(json).unwrap()).problem;
+        assert_eq!(
+            probe(r#"{"streams":[{"codec_type":"audio"}],"format":{"duration":"12.5"}}"#),
+            None
+        );
+        assert_eq!(
+            probe(r#"{"streams":[{"codec_type":"video"}],"format":{"duration":"12.5"}}"#),
+            Some("video only, no audio stream")
+        );
+        assert_eq!(
+            probe(r#"{"streams":[{"codec_type":"audio"}],"format":{"duration":"0.000000"}}"#),
+            Some("zero duration")
+        );
+        assert_eq!(probe(r#"{"streams":[]}"#), Some("no audio stream"));
+    }
+
+    #[test]
+    fn formats_chapter_metadata() {
+        let chapters = vec![Chapter {
+            start_ms: 0,
+            end_ms: 61_000,
+            headline: "Intro; setup".to_string(),
+            gist: String::new(),
+            summary: String::new(),
+        }];
+        assert_eq!(
+            format_chapter_metadata(&chapters),
+            ";FFMETADATA1\n[CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=61000\ntitle=Intro\\; setup\n"
+        );
+    }
+
+    #[test]
+    fn lyrics_go_into_global_section_with_escaping() {
+        let existing = ";FFMETADATA1\ntitle=Episode 1\nlyrics=old\n[CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=1000\n";
+        let updated = with_global_lyrics(existing, "a=b; #1\nnext");
+        assert_eq!(
+            updated,
+            ";FFMETADATA1\ntitle=Episode 1\nlyrics=a\\=b\\; \\#1\\\nnext\n[CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=1000\n"
+        );
+    }
 }
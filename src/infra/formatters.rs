@@ -0,0 +1,457 @@
+use std::future::Future;
+use std::io::Write;
+use std::pin::Pin;
+use std::process::{Command, Stdio};
+
+use crate::domain::anki;
+use crate::domain::markers;
+use crate::domain::notes;
+use crate::domain::otr;
+use crate::domain::subtitles::{self, Caption};
+use crate::domain::{TranscribeOptions, TranscriptFormat};
+use crate::infra::assemblyai::{ApiError, AssemblyAiClient, LemurTaskRequest, Transcript};
+use crate::infra::InfraError;
+use crate::infra::logging;
+use crate::infra::runner::{
+    diarized_utterances, format_local_subtitles, format_text_output, input_file_name, input_title, sentence_captions,
+    subtitle_captions, transcript_chapters, transcript_entities, transcript_highlights, transcript_markers,
+    transcript_paragraphs, RunnerError, Translation,
+};
+
+/// Everything a formatter may read to render a completed transcript.
+pub struct RenderContext<'a> {
+    pub client: &'a AssemblyAiClient,
+    pub done: &'a Transcript,
+    pub options: &'a TranscribeOptions,
+    pub translation: Option<&'a Translation>,
+}
+
+pub type RenderFuture<'a> = Pin<Box<dyn Future<Output = Result<String, RunnerError>> + Send + 'a>>;
+
+/// Renders a completed transcript as one `--format`.
+pub trait Formatter: Sync {
+    /// The `--format` value, as in `TranscriptFormat::name`.
+    fn name(&self) -> &'static str;
+
+    /// One line for `--list-formats`.
+    fn description(&self) -> &'static str;
+
+    fn render<'a>(&'a self, ctx: &'a RenderContext<'a>) -> RenderFuture<'a>;
+}
+
+static FORMATTERS: &[&dyn Formatter] = &[
+    &TextFormatter,
+    &SubtitleFormatter { name: "srt", description: "SubRip subtitles" },
+    &SubtitleFormatter { name: "vtt", description: "WebVTT subtitles" },
+    &SrtDualFormatter,
+    &EdlFormatter,
+    &PremiereMarkersFormatter,
+    &AnkiTsvFormatter,
+    &OtrFormatter,
+    &MeetingNotesFormatter,
+    &ShowNotesFormatter,
+    &TableFormatter { name: "sqlite", description: "SQLite tables of transcripts, speakers, utterances, and words (needs --output)" },
+    &TableFormatter { name: "parquet", description: "Parquet tables of words and utterances next to --output" },
+    &JsonFormatter,
+];
+
+/// Every registered formatter, in `--list-formats` order.
+pub fn formatters() -> &'static [&'static dyn Formatter] {
+    FORMATTERS
+}
+
+pub fn formatter_by_name(name: &str) -> Option<&'static dyn Formatter> {
+    FORMATTERS.iter().copied().find(|formatter| formatter.name() == name)
+}
+
+/// The formatter registered for `format`.
+pub fn formatter(format: TranscriptFormat) -> &'static dyn Formatter {
+    formatter_by_name(format.name()).unwrap_or_else(|| panic!("no formatter registered for {}", format.name()))
+}
+
+/// One `NAME  DESCRIPTION` line per formatter, then `exec:COMMAND`.
+pub fn format_formatter_list() -> String {
+    let exec = ExecFormatter::new("");
+    let entries: Vec<(&str, &str)> = formatters()
+        .iter()
+        .map(|f| (f.name(), f.description()))
+        .chain([("exec:COMMAND", exec.description())])
+        .collect();
+    let width = entries.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    entries
+        .iter()
+        .map(|(name, description)| format!("{name:<width$}  {description}\n"))
+        .collect()
+}
+
+struct TextFormatter;
+
+impl Formatter for TextFormatter {
+    fn name(&self) -> &'static str {
+        "text"
+    }
+
+    fn description(&self) -> &'static str {
+        "Plain text, with speaker labels and timestamps when enabled"
+    }
+
+    fn render<'a>(&'a self, ctx: &'a RenderContext<'a>) -> RenderFuture<'a> {
+        Box::pin(async move { Ok(format_text_output(ctx.done, ctx.options)) })
+    }
+}
+
+/// SRT or VTT, built locally when possible and otherwise fetched from the API.
+struct SubtitleFormatter {
+    name: &'static str,
+    description: &'static str,
+}
+
+impl Formatter for SubtitleFormatter {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn render<'a>(&'a self, ctx: &'a RenderContext<'a>) -> RenderFuture<'a> {
+        Box::pin(async move {
+            if let Some(value) = format_local_subtitles(ctx.done, ctx.options) {
+                return Ok(value);
+            }
+            let content = ctx
+                .client
+                .get_subtitles(&ctx.done.id, ctx.options.format(), ctx.options.chars_per_caption())
+                .await?;
+            Ok(subtitles::map_cue_text(&content, |text| ctx.options.post_process().apply(text)))
+        })
+    }
+}
+
+struct SrtDualFormatter;
+
+impl Formatter for SrtDualFormatter {
+    fn name(&self) -> &'static str {
+        "srt-dual"
+    }
+
+    fn description(&self) -> &'static str {
+        "SubRip subtitles with the original and --translate-to text in each cue"
+    }
+
+    fn render<'a>(&'a self, ctx: &'a RenderContext<'a>) -> RenderFuture<'a> {
+        Box::pin(async move {
+            let Some(translation) = ctx.translation else {
+                return Ok(subtitles::format_srt(
+                    &subtitle_captions(ctx.client, ctx.done, ctx.options).await?,
+                    ctx.options.speaker_style(),
+                ));
+            };
+            let dual: Vec<Caption> = translation
+                .original
+                .iter()
+                .zip(&translation.translated)
+                .map(|(original, translated)| Caption {
+                    text: format!("{}\n{}", original.text, translated.text),
+                    ..original.clone()
+                })
+                .collect();
+            Ok(subtitles::format_srt(&dual, ctx.options.speaker_style()))
+        })
+    }
+}
+
+struct EdlFormatter;
+
+impl Formatter for EdlFormatter {
+    fn name(&self) -> &'static str {
+        "edl"
+    }
+
+    fn description(&self) -> &'static str {
+        "CMX3600 EDL of chapter, speaker, or caption markers with DaVinci Resolve comments"
+    }
+
+    fn render<'a>(&'a self, ctx: &'a RenderContext<'a>) -> RenderFuture<'a> {
+        Box::pin(async move {
+            Ok(markers::format_edl(
+                &input_title(ctx.options),
+                &transcript_markers(ctx.done, ctx.options),
+                ctx.options.timecode(),
+            ))
+        })
+    }
+}
+
+struct PremiereMarkersFormatter;
+
+impl Formatter for PremiereMarkersFormatter {
+    fn name(&self) -> &'static str {
+        "premiere-markers-csv"
+    }
+
+    fn description(&self) -> &'static str {
+        "Adobe Premiere Pro marker CSV of chapter, speaker, or caption markers"
+    }
+
+    fn render<'a>(&'a self, ctx: &'a RenderContext<'a>) -> RenderFuture<'a> {
+        Box::pin(async move {
+            Ok(markers::format_premiere_markers_csv(
+                &transcript_markers(ctx.done, ctx.options),
+                ctx.options.timecode(),
+            ))
+        })
+    }
+}
+
+/// oTranscribe document: one timestamped paragraph per speaker turn, or per sentence without speakers.
+struct OtrFormatter;
+
+impl Formatter for OtrFormatter {
+    fn name(&self) -> &'static str {
+        "otr"
+    }
+
+    fn description(&self) -> &'static str {
+        "oTranscribe document with a timestamp at each speaker turn or sentence"
+    }
+
+    fn render<'a>(&'a self, ctx: &'a RenderContext<'a>) -> RenderFuture<'a> {
+        Box::pin(async move {
+            Ok(otr::format_otr(
+                &input_file_name(ctx.options),
+                &transcript_paragraphs(ctx.client, ctx.done, ctx.options).await?,
+            ))
+        })
+    }
+}
+
+struct MeetingNotesFormatter;
+
+impl Formatter for MeetingNotesFormatter {
+    fn name(&self) -> &'static str {
+        "meeting-notes"
+    }
+
+    fn description(&self) -> &'static str {
+        "Markdown meeting minutes with attendees and action items, generated via LeMUR"
+    }
+
+    fn render<'a>(&'a self, ctx: &'a RenderContext<'a>) -> RenderFuture<'a> {
+        Box::pin(async move {
+            logging::info(crate::tr!("generating-meeting-notes"));
+            let minutes = ctx
+                .client
+                .lemur_task(&LemurTaskRequest {
+                    prompt: notes::MEETING_MINUTES_PROMPT.to_string(),
+                    transcript_ids: vec![ctx.done.id.clone()],
+                    final_model: ctx.options.lemur_model().map(str::to_string),
+                    ..Default::default()
+                })
+                .await?;
+            Ok(notes::format_meeting_notes(
+                &input_title(ctx.options),
+                &notes::attendees(&diarized_utterances(ctx.done).unwrap_or_default()),
+                &transcript_highlights(ctx.done),
+                &ctx.options.post_process().apply(&minutes.response),
+            ))
+        })
+    }
+}
+
+struct ShowNotesFormatter;
+
+impl Formatter for ShowNotesFormatter {
+    fn name(&self) -> &'static str {
+        "show-notes"
+    }
+
+    fn description(&self) -> &'static str {
+        "Markdown podcast show notes with chapters and guests, generated via LeMUR"
+    }
+
+    fn render<'a>(&'a self, ctx: &'a RenderContext<'a>) -> RenderFuture<'a> {
+        Box::pin(async move {
+            logging::info(crate::tr!("generating-show-notes"));
+            let summary = ctx
+                .client
+                .lemur_task(&LemurTaskRequest {
+                    prompt: notes::SHOW_NOTES_PROMPT.to_string(),
+                    transcript_ids: vec![ctx.done.id.clone()],
+                    final_model: ctx.options.lemur_model().map(str::to_string),
+                    ..Default::default()
+                })
+                .await?;
+            Ok(notes::format_show_notes(
+                &input_title(ctx.options),
+                &ctx.options.post_process().apply(&summary.response),
+                &transcript_chapters(ctx.done),
+                &transcript_highlights(ctx.done),
+                &notes::guest_names(&transcript_entities(ctx.done)),
+                ctx.options.timestamp_format().unwrap_or_default(),
+            ))
+        })
+    }
+}
+
+/// Table formats render nothing; the runner writes them to `--output` directly.
+struct TableFormatter {
+    name: &'static str,
+    description: &'static str,
+}
+
+impl Formatter for TableFormatter {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn render<'a>(&'a self, _ctx: &'a RenderContext<'a>) -> RenderFuture<'a> {
+        Box::pin(async { Ok(String::new()) })
+    }
+}
+
+/// `--format exec:COMMAND`: pipes the raw transcript JSON to COMMAND's stdin and renders its stdout.
+///
+/// COMMAND is split on whitespace, not run through a shell; `ASSEMBLYAI_TRANSCRIPT_ID` is set for it.
+pub struct ExecFormatter {
+    command: String,
+}
+
+impl ExecFormatter {
+    pub fn new(command: &str) -> Self {
+        Self {
+            command: command.to_string(),
+        }
+    }
+
+    fn run(&self, transcript_id: &str, json: Vec<u8>) -> Result<String, InfraError> {
+        let failed = |message: String| InfraError::FormatterFailed {
+            command: self.command.clone(),
+            message,
+        };
+        let mut parts = self.command.split_whitespace();
+        let program = parts.next().unwrap_or_default();
+        let mut child = Command::new(program)
+            .args(parts)
+            .env("ASSEMBLYAI_TRANSCRIPT_ID", transcript_id)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|err| failed(err.to_string()))?;
+
+        // Written from a thread so a formatter that streams its output can't deadlock on a full pipe.
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let writer = std::thread::spawn(move || stdin.write_all(&json));
+        let output = child.wait_with_output()?;
+        // A formatter may exit without reading all of its input; only its exit status matters.
+        let _ = writer.join();
+
+        if !output.status.success() {
+            return Err(failed(format!("exited with {}", output.status)));
+        }
+        String::from_utf8(output.stdout).map_err(|_| failed("wrote output that is not UTF-8".to_string()))
+    }
+}
+
+impl Formatter for ExecFormatter {
+    fn name(&self) -> &'static str {
+        "exec"
+    }
+
+    fn description(&self) -> &'static str {
+        "Output of an external program fed the transcript JSON on stdin"
+    }
+
+    fn render<'a>(&'a self, ctx: &'a RenderContext<'a>) -> RenderFuture<'a> {
+        Box::pin(async move {
+            let json = ctx.client.get_transcript_json(&ctx.done.id).await?;
+            Ok(self.run(&ctx.done.id, json)?)
+        })
+    }
+}
+
+/// The transcript JSON as the API returns it, less the sections `--json-include`/`--json-exclude` leave out.
+///
+/// Fetched again rather than built from `ctx.done`, which only keeps the fields this tool reads; options that rewrite
+/// the transcript are rejected with `--format json`.
+struct JsonFormatter;
+
+impl Formatter for JsonFormatter {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn description(&self) -> &'static str {
+        "Transcript JSON as returned by the API, trimmed with --json-include/--json-exclude"
+    }
+
+    fn render<'a>(&'a self, ctx: &'a RenderContext<'a>) -> RenderFuture<'a> {
+        Box::pin(async move {
+            let json = ctx.client.get_transcript_json(&ctx.done.id).await?;
+            let mut transcript: serde_json::Map<String, serde_json::Value> =
+                serde_json::from_slice(&json).map_err(ApiError::Json)?;
+            if let Some(sections) = ctx.options.json_sections() {
+                sections.apply(&mut transcript);
+            }
+            Ok(serde_json::to_string(&transcript).map_err(ApiError::Json)? + "\n")
+        })
+    }
+}
+
+/// Anki flashcards: one note per sentence, with its translation when `--translate-to` is set.
+struct AnkiTsvFormatter;
+
+impl Formatter for AnkiTsvFormatter {
+    fn name(&self) -> &'static str {
+        "anki-tsv"
+    }
+
+    fn description(&self) -> &'static str {
+        "Anki import TSV of sentences with time ranges and --translate-to translations"
+    }
+
+    fn render<'a>(&'a self, ctx: &'a RenderContext<'a>) -> RenderFuture<'a> {
+        Box::pin(async move {
+            let timestamps = ctx.options.timestamp_format().unwrap_or_default();
+            Ok(match ctx.translation {
+                Some(translation) => anki::format_anki_tsv(&translation.original, Some(&translation.translated), timestamps),
+                None => anki::format_anki_tsv(&sentence_captions(ctx.client, ctx.done, ctx.options).await?, None, timestamps),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_format_has_one_formatter() {
+        let formats = [
+            TranscriptFormat::Text,
+            TranscriptFormat::Srt,
+            TranscriptFormat::Vtt,
+            TranscriptFormat::SrtDual,
+            TranscriptFormat::Edl,
+            TranscriptFormat::PremiereMarkersCsv,
+            TranscriptFormat::AnkiTsv,
+            TranscriptFormat::Otr,
+            TranscriptFormat::MeetingNotes,
+            TranscriptFormat::ShowNotes,
+            TranscriptFormat::Sqlite,
+            TranscriptFormat::Parquet,
+            TranscriptFormat::Json,
+        ];
+        for format in formats {
+            assert_eq!(formatter(format).name(), format.name());
+        }
+        assert_eq!(formatters().len(), formats.len());
+        assert!(formatter_by_name("docx").is_none());
+    }
+}
@@ -0,0 +1,32 @@
+use std::io::Write;
+use std::path::Path;
+
+use crate::domain::history::HistoryEntry;
+use crate::infra::lock::StateLock;
+use crate::infra::InfraError;
+
+/// Reads every entry of the history file; a missing file is an empty history and unreadable lines are skipped.
+pub fn load(path: &Path) -> Result<Vec<HistoryEntry>, InfraError> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Appends `entry` with the next free id and returns that id.
+///
+/// The history is locked while the id is picked, so concurrent runs never share one.
+pub fn append(path: &Path, mut entry: HistoryEntry) -> Result<u64, InfraError> {
+    let _lock = StateLock::acquire(path)?;
+    entry.id = load(path)?.iter().map(|e| e.id).max().unwrap_or(0) + 1;
+    let mut line = serde_json::to_string(&entry).map_err(std::io::Error::other)?;
+    line.push('\n');
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())?;
+    Ok(entry.id)
+}
@@ -0,0 +1,117 @@
+use std::sync::OnceLock;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use unic_langid::LanguageIdentifier;
+
+/// Message catalogs compiled into the binary; English is the fallback for missing messages.
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../../locales/en/cli.ftl")),
+    ("ru", include_str!("../../locales/ru/cli.ftl")),
+];
+
+struct Localizer {
+    selected: FluentBundle<FluentResource>,
+    fallback: FluentBundle<FluentResource>,
+}
+
+static LOCALIZER: OnceLock<Localizer> = OnceLock::new();
+
+/// Looks up message `$id` with `name = value` arguments in the selected catalog, e.g.
+/// `tr!("wrote-transcript", path = path.display().to_string())`.
+#[macro_export]
+macro_rules! tr {
+    ($id:literal $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::infra::i18n::message($id, &[$((stringify!($name), ::fluent_bundle::FluentValue::from($value))),*])
+    };
+}
+
+/// Selects the catalog for `locale`, or else for `LC_ALL`, `LC_MESSAGES`, or `LANG`; English when none matches.
+pub fn init(locale: Option<&str>) {
+    let requested = locale
+        .map(str::to_string)
+        .or_else(|| ["LC_ALL", "LC_MESSAGES", "LANG"].iter().find_map(|name| std::env::var(name).ok().filter(|v| !v.is_empty())));
+    let language = requested.as_deref().and_then(catalog_language).unwrap_or("en");
+    let _ = LOCALIZER.set(Localizer {
+        selected: bundle(language),
+        fallback: bundle("en"),
+    });
+}
+
+/// The catalog language for a locale such as `ru`, `ru-RU`, or `ru_RU.UTF-8`.
+pub fn catalog_language(locale: &str) -> Option<&'static str> {
+    let tag = locale.split(['.', '@']).next()?.replace('_', "-");
+    let langid: LanguageIdentifier = tag.parse().ok()?;
+    CATALOGS
+        .iter()
+        .map(|(language, _)| *language)
+        .find(|language| *language == langid.language.as_str())
+}
+
+/// Names accepted by `--locale`.
+pub fn languages() -> impl Iterator<Item = &'static str> {
+    CATALOGS.iter().map(|(language, _)| *language)
+}
+
+pub fn message(id: &str, args: &[(&str, FluentValue)]) -> String {
+    let localizer = LOCALIZER.get_or_init(|| Localizer {
+        selected: bundle("en"),
+        fallback: bundle("en"),
+    });
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+    [&localizer.selected, &localizer.fallback]
+        .into_iter()
+        .find_map(|bundle| {
+            let pattern = bundle.get_message(id)?.value()?;
+            let mut errors = Vec::new();
+            Some(bundle.format_pattern(pattern, Some(&fluent_args), &mut errors).into_owned())
+        })
+        .unwrap_or_else(|| id.to_string())
+}
+
+fn bundle(language: &str) -> FluentBundle<FluentResource> {
+    let (_, source) = CATALOGS
+        .iter()
+        .find(|(name, _)| *name == language)
+        .expect("a catalog for every supported language");
+    let resource = FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, _)| resource);
+    let mut bundle = FluentBundle::new_concurrent(vec![language.parse().expect("a valid language tag")]);
+    // Messages go to terminals and log files, where bidi isolation marks would show up as stray characters.
+    bundle.set_use_isolating(false);
+    bundle.add_resource(resource).expect("catalog message ids are unique");
+    bundle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_catalogs_from_locales() {
+        assert_eq!(catalog_language("ru_RU.UTF-8"), Some("ru"));
+        assert_eq!(catalog_language("ru"), Some("ru"));
+        assert_eq!(catalog_language("en-GB"), Some("en"));
+        assert_eq!(catalog_language("C"), None);
+        assert_eq!(catalog_language("de_DE.UTF-8"), None);
+    }
+
+    #[test]
+    fn catalogs_define_the_same_messages() {
+        fn ids(source: &str) -> Vec<&str> {
+            let mut ids: Vec<&str> = source
+                .lines()
+                .filter_map(|line| line.split_once(" = ").map(|(id, _)| id))
+                .filter(|id| id.chars().all(|c| c.is_ascii_lowercase() || c == '-'))
+                .collect();
+            ids.sort();
+            ids
+        }
+        let english = ids(CATALOGS[0].1);
+        for (language, source) in &CATALOGS[1..] {
+            assert_eq!(ids(source), english, "{language}");
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use std::path::Path;
+
+use crate::domain::jobs::JobStore;
+use crate::infra::InfraError;
+
+/// Reads the job store; a missing file has no batches.
+pub fn load(path: &Path) -> Result<JobStore, InfraError> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(JobStore::default()),
+        Err(err) => return Err(err.into()),
+    };
+    serde_json::from_str(&contents).map_err(|err| InfraError::InvalidJobStore {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })
+}
+
+/// Replaces the job store through a temporary file, so a crash mid-write never leaves it truncated.
+pub fn save(path: &Path, store: &JobStore) -> Result<(), InfraError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut contents = serde_json::to_string_pretty(store).map_err(std::io::Error::other)?;
+    contents.push('\n');
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, contents)?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::fs::{File, OpenOptions};
+use std::path::Path;
+
+use crate::infra::InfraError;
+
+/// An exclusive advisory lock on a state file under `~/.assemblyai-cli`, released when dropped.
+///
+/// Hold it across a whole load-change-save, so concurrent invocations (a cron batch next to a manual
+/// run) don't overwrite each other's changes. The lock is taken on `<file>.lock` rather than on the file
+/// itself, since saves replace the file by renaming a new one over it.
+pub struct StateLock {
+    _file: File,
+}
+
+impl StateLock {
+    /// Blocks until no other process holds the lock for `path`.
+    pub fn acquire(path: &Path) -> Result<Self, InfraError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut lock_path = path.as_os_str().to_os_string();
+        lock_path.push(".lock");
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(lock_path)?;
+        file.lock()?;
+        Ok(Self { _file: file })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_concurrent_updates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join("counter");
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..20 {
+                        let _lock = StateLock::acquire(&path).unwrap();
+                        let count: u32 = std::fs::read_to_string(&path).map_or(0, |s| s.parse().unwrap());
+                        std::fs::write(&path, (count + 1).to_string()).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "160");
+    }
+}
@@ -0,0 +1,249 @@
+use std::cell::RefCell;
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::fs::File;
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use crate::domain::history::format_rfc3339_millis;
+
+/// How log events are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// Plain lines, with `warning:`/`error:` prefixes.
+    #[default]
+    Text,
+    /// One JSON object per event: level, timestamp, message, and transcript_id/file when known.
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    fn name(self) -> &'static str {
+        match self {
+            Level::Info => "info",
+            Level::Warn => "warn",
+            Level::Error => "error",
+        }
+    }
+}
+
+struct Logger {
+    format: LogFormat,
+    sink: Mutex<Box<dyn Write + Send>>,
+}
+
+/// The input and transcript the current run is working on, attached to JSON events.
+struct Context {
+    file: Option<String>,
+    transcript_id: Option<String>,
+}
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+static CONTEXT: Mutex<Context> = Mutex::new(Context {
+    file: None,
+    transcript_id: None,
+});
+
+tokio::task_local! {
+    /// The context of one batch input while inputs run side by side.
+    static TASK_CONTEXT: RefCell<Context>;
+}
+
+/// Runs `future` with a context of its own, so concurrent batch inputs don't log under each other's file.
+pub async fn scoped<F: Future>(future: F) -> F::Output {
+    let context = Context {
+        file: None,
+        transcript_id: None,
+    };
+    TASK_CONTEXT.scope(RefCell::new(context), future).await
+}
+
+fn with_context<R>(f: impl FnOnce(&mut Context) -> R) -> R {
+    let mut f = Some(f);
+    match TASK_CONTEXT.try_with(|context| f.take().expect("unused")(&mut context.borrow_mut())) {
+        Ok(result) => result,
+        Err(_) => f.take().expect("unused")(&mut CONTEXT.lock().unwrap_or_else(|poisoned| poisoned.into_inner())),
+    }
+}
+
+/// Sends every later event to `sink` in `format`; until then, events go to stderr as text.
+pub fn init(format: LogFormat, sink: Box<dyn Write + Send>) {
+    let _ = LOGGER.set(Logger {
+        format,
+        sink: Mutex::new(sink),
+    });
+}
+
+pub fn info(message: impl Display) {
+    log(Level::Info, message);
+}
+
+pub fn warn(message: impl Display) {
+    log(Level::Warn, message);
+}
+
+pub fn error(message: impl Display) {
+    log(Level::Error, message);
+}
+
+/// Starts a new run on `file`, forgetting the previous run's transcript id.
+pub fn set_file(file: &str) {
+    with_context(|context| {
+        context.file = Some(file.to_string());
+        context.transcript_id = None;
+    });
+}
+
+pub fn set_transcript_id(transcript_id: &str) {
+    with_context(|context| context.transcript_id = Some(transcript_id.to_string()));
+}
+
+pub fn log(level: Level, message: impl Display) {
+    let Some(logger) = LOGGER.get() else {
+        eprintln!("{}", text_line(level, &message));
+        return;
+    };
+    let line = match logger.format {
+        LogFormat::Text => text_line(level, &message),
+        LogFormat::Json => json_line(level, &message),
+    };
+    let mut sink = logger.sink.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    // One write per event, so a rotating file never splits a line; logging must never fail a run.
+    let _ = sink.write_all(format!("{line}\n").as_bytes()).and_then(|()| sink.flush());
+}
+
+/// An append-only log file that moves itself to `PATH.1` once it would grow past `max_size`.
+///
+/// Older files shift up to `PATH.{keep}`; the one beyond that is deleted.
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: Option<u64>,
+    keep: usize,
+}
+
+impl RotatingFile {
+    /// Opens `path` for appending; without `max_size` it is never rotated.
+    pub fn open(path: &Path, max_size: Option<u64>, keep: usize) -> std::io::Result<Self> {
+        let file = append(path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            size,
+            max_size,
+            keep,
+        })
+    }
+
+    fn rotated(&self, index: usize) -> PathBuf {
+        let mut name = OsString::from(self.path.as_os_str());
+        name.push(format!(".{index}"));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let ignore_missing = |result: std::io::Result<()>| match result {
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            other => other,
+        };
+        if self.keep == 0 {
+            ignore_missing(std::fs::remove_file(&self.path))?;
+        } else {
+            for index in (1..self.keep).rev() {
+                ignore_missing(std::fs::rename(self.rotated(index), self.rotated(index + 1)))?;
+            }
+            std::fs::rename(&self.path, self.rotated(1))?;
+        }
+        self.file = append(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.max_size.is_some_and(|max| self.size > 0 && self.size + buf.len() as u64 > max) {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn append(path: &Path) -> std::io::Result<File> {
+    std::fs::OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn text_line(level: Level, message: &dyn Display) -> String {
+    match level {
+        Level::Info => message.to_string(),
+        Level::Warn => crate::tr!("log-warning", message = message.to_string()),
+        Level::Error => crate::tr!("log-error", message = message.to_string()),
+    }
+}
+
+fn json_line(level: Level, message: &dyn Display) -> String {
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let mut event = serde_json::json!({
+        "level": level.name(),
+        "timestamp": format_rfc3339_millis(millis),
+        "message": message.to_string(),
+    });
+    with_context(|context| {
+        if let Some(transcript_id) = &context.transcript_id {
+            event["transcript_id"] = transcript_id.as_str().into();
+        }
+        if let Some(file) = &context.file {
+            event["file"] = file.as_str().into();
+        }
+    });
+    event.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotating_file_keeps_the_newest_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cli.log");
+        let mut file = RotatingFile::open(&path, Some(9), 2).unwrap();
+        for line in ["one\n", "two\n", "three\n", "four\n", "five\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+
+        let read = |path: PathBuf| std::fs::read_to_string(path).unwrap();
+        assert_eq!(read(path.clone()), "five\n");
+        assert_eq!(read(dir.path().join("cli.log.1")), "four\n");
+        assert_eq!(read(dir.path().join("cli.log.2")), "three\n");
+        assert!(!dir.path().join("cli.log.3").exists());
+
+        // Reopening appends to the current file and counts its existing size.
+        let mut file = RotatingFile::open(&path, Some(9), 2).unwrap();
+        file.write_all(b"six\n").unwrap();
+        assert_eq!(read(path.clone()), "five\nsix\n");
+        file.write_all(b"seven\n").unwrap();
+        assert_eq!(read(path), "seven\n");
+        assert_eq!(read(dir.path().join("cli.log.2")), "four\n");
+    }
+}
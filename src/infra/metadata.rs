@@ -0,0 +1,62 @@
+use std::path::Path;
+
+use id3::TagLike;
+
+use crate::infra::ffmpeg::{self, TempFiles};
+use crate::infra::InfraError;
+
+pub fn embed_transcript(path: &Path, transcript: &str, temp_files: TempFiles<'_>) -> Result<(), InfraError> {
+    let ext = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    match ext.as_str() {
+        "mp3" => embed_id3_lyrics(path, transcript),
+        _ => ffmpeg::embed_lyrics(path, transcript, temp_files),
+    }
+}
+
+fn embed_id3_lyrics(path: &Path, transcript: &str) -> Result<(), InfraError> {
+    let metadata_error = |err: id3::Error| InfraError::MetadataWrite {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    };
+
+    let mut tag = match id3::Tag::read_from_path(path) {
+        Ok(tag) => tag,
+        Err(id3::Error {
+            kind: id3::ErrorKind::NoTag,
+            ..
+        }) => id3::Tag::new(),
+        Err(err) => return Err(metadata_error(err)),
+    };
+
+    tag.remove_all_lyrics();
+    tag.add_frame(id3::frame::Lyrics {
+        lang: "XXX".to_string(),
+        description: String::new(),
+        text: transcript.to_string(),
+    });
+
+    tag.write_to_path(path, id3::Version::Id3v24).map_err(metadata_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_uslt_frame_to_mp3() {
+        let file = tempfile::Builder::new().suffix(".mp3").tempfile().expect("tempfile");
+        std::fs::write(file.path(), [0xFF, 0xFB, 0x90, 0x00]).expect("write mp3");
+
+        embed_transcript(file.path(), "hello world", TempFiles::default()).expect("embed");
+        embed_transcript(file.path(), "second pass", TempFiles::default()).expect("embed again");
+
+        let tag = id3::Tag::read_from_path(file.path()).expect("read tag");
+        let lyrics: Vec<&str> = tag.lyrics().map(|l| l.text.as_str()).collect();
+        assert_eq!(lyrics, vec!["second pass"]);
+    }
+}
@@ -0,0 +1,180 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::infra::InfraError;
+
+/// How long a scraper may take to send its request or read the response; connections are served one at a time.
+const SCRAPE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Longest request read from a scraper; the request line is all that matters.
+const MAX_REQUEST_BYTES: u64 = 8 * 1024;
+
+/// Counters of this process, served in the Prometheus text format by `--metrics-addr`.
+pub struct Metrics {
+    files_processed: AtomicU64,
+    failures: AtomicU64,
+    retries: AtomicU64,
+    bytes_uploaded: AtomicU64,
+    in_flight: AtomicU64,
+}
+
+pub static METRICS: Metrics = Metrics {
+    files_processed: AtomicU64::new(0),
+    failures: AtomicU64::new(0),
+    retries: AtomicU64::new(0),
+    bytes_uploaded: AtomicU64::new(0),
+    in_flight: AtomicU64::new(0),
+};
+
+impl Metrics {
+    /// Counts a job in flight until the returned guard is finished or dropped.
+    pub fn job_started(&'static self) -> RunningJob {
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        RunningJob { metrics: self }
+    }
+
+    pub fn add_retries(&self, count: u64) {
+        self.retries.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn add_uploaded(&self, bytes: u64) {
+        self.bytes_uploaded.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn render(&self) -> String {
+        let metrics = [
+            ("files_processed_total", "counter", "Inputs transcribed successfully.", &self.files_processed),
+            ("failures_total", "counter", "Inputs that failed.", &self.failures),
+            ("retries_total", "counter", "Inputs resubmitted from a --retry-file.", &self.retries),
+            ("uploaded_bytes_total", "counter", "Bytes uploaded to the API.", &self.bytes_uploaded),
+            ("jobs_in_flight", "gauge", "Inputs being transcribed right now.", &self.in_flight),
+        ];
+        metrics
+            .iter()
+            .map(|(name, kind, help, value)| {
+                let value = value.load(Ordering::Relaxed);
+                format!("# HELP assemblyai_{name} {help}\n# TYPE assemblyai_{name} {kind}\nassemblyai_{name} {value}\n")
+            })
+            .collect()
+    }
+}
+
+/// A job counted in flight; a cancelled job leaves the gauge when it is dropped, without counting as processed.
+pub struct RunningJob {
+    metrics: &'static Metrics,
+}
+
+impl RunningJob {
+    /// Ends the job, counting it as processed or failed.
+    pub fn finish(self, ok: bool) {
+        let counter = if ok { &self.metrics.files_processed } else { &self.metrics.failures };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Drop for RunningJob {
+    fn drop(&mut self) {
+        self.metrics.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Serves [`METRICS`] at `GET /metrics` on `addr` from a background thread, returning the bound address.
+pub fn serve(addr: SocketAddr) -> Result<SocketAddr, InfraError> {
+    let listener = TcpListener::bind(addr).map_err(|err| InfraError::MetricsBind {
+        addr,
+        message: err.to_string(),
+    })?;
+    let bound = listener.local_addr()?;
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            // A broken scrape only affects that scraper.
+            let _ = respond(stream);
+        }
+    });
+    Ok(bound)
+}
+
+fn respond(mut stream: TcpStream) -> std::io::Result<()> {
+    // A stalled or endless client would otherwise hold up every scrape after it.
+    stream.set_read_timeout(Some(SCRAPE_TIMEOUT))?;
+    stream.set_write_timeout(Some(SCRAPE_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?.take(MAX_REQUEST_BYTES));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim_end().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => ("200 OK", METRICS.render()),
+        _ => ("404 Not Found", "not found\n".to_string()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serves_counters_in_prometheus_format() {
+        let addr = serve("127.0.0.1:0".parse().unwrap()).unwrap();
+        METRICS.add_uploaded(2048);
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+        assert!(response.contains("# TYPE assemblyai_uploaded_bytes_total counter\n"));
+        let uploaded = response
+            .lines()
+            .find_map(|line| line.strip_prefix("assemblyai_uploaded_bytes_total "))
+            .and_then(|value| value.parse::<u64>().ok());
+        assert!(uploaded.is_some_and(|bytes| bytes >= 2048));
+        assert!(response.contains("\nassemblyai_jobs_in_flight "));
+    }
+
+    #[test]
+    fn cancelled_jobs_leave_the_in_flight_gauge() {
+        static JOBS: Metrics = Metrics {
+            files_processed: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+            retries: AtomicU64::new(0),
+            bytes_uploaded: AtomicU64::new(0),
+            in_flight: AtomicU64::new(0),
+        };
+        let finished = JOBS.job_started();
+        let cancelled = JOBS.job_started();
+        assert_eq!(JOBS.in_flight.load(Ordering::Relaxed), 2);
+        finished.finish(true);
+        drop(cancelled);
+        assert_eq!(JOBS.in_flight.load(Ordering::Relaxed), 0);
+        assert_eq!(JOBS.files_processed.load(Ordering::Relaxed), 1);
+        assert_eq!(JOBS.failures.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn a_stalled_scraper_does_not_block_the_next() {
+        let addr = serve("127.0.0.1:0".parse().unwrap()).unwrap();
+        let _stalled = TcpStream::connect(addr).unwrap();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.set_read_timeout(Some(SCRAPE_TIMEOUT * 5)).unwrap();
+        stream.write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+    }
+}
@@ -1,6 +1,24 @@
+pub mod aliases;
 pub mod assemblyai;
+pub mod cache;
+pub mod disk;
 pub mod ffmpeg;
+pub mod formatters;
+pub mod history;
+pub mod i18n;
+pub mod jobs;
+pub mod lock;
+pub mod logging;
+pub mod metadata;
+pub mod metrics;
+pub mod parquet;
+pub mod permissions;
 pub mod runner;
+pub mod scheduler;
+pub mod search_index;
+pub mod sqlite;
+pub mod update;
+pub mod uploads;
 
 #[derive(thiserror::Error, Debug)]
 pub enum InfraError {
@@ -13,6 +31,61 @@ pub enum InfraError {
     #[error("ffmpeg failed: {message}")]
     FfmpegFailed { message: String },
 
+    #[error("{path:?} is not usable media: {message} (use --skip-validation to upload anyway)")]
+    InvalidMedia { path: std::path::PathBuf, message: String },
+
+    #[error("failed to write metadata to {path:?}: {message}")]
+    MetadataWrite { path: std::path::PathBuf, message: String },
+
+    #[error("invalid transcript JSON {path:?}: {message}")]
+    InvalidTranscriptJson { path: std::path::PathBuf, message: String },
+
+    #[error("failed to write database {path:?}: {message}")]
+    DatabaseWrite { path: std::path::PathBuf, message: String },
+
+    #[error("failed to write parquet file {path:?}: {message}")]
+    ParquetWrite { path: std::path::PathBuf, message: String },
+
+    #[error("formatter {command:?} failed: {message}")]
+    FormatterFailed { command: String, message: String },
+
+    #[error("failed to parse alias file {path:?}: {message}")]
+    InvalidAliasFile { path: std::path::PathBuf, message: String },
+
+    #[error("failed to serve metrics on {addr}: {message}")]
+    MetricsBind { addr: std::net::SocketAddr, message: String },
+
+    #[error("failed to parse job store {path:?}: {message}")]
+    InvalidJobStore { path: std::path::PathBuf, message: String },
+
+    #[error("self-update failed: {message}")]
+    SelfUpdate { message: String },
+
+    #[error("checksum mismatch for {asset}: expected {expected}, got {actual}; the binary was not replaced")]
+    ChecksumMismatch { asset: String, expected: String, actual: String },
+
+    #[error(
+        "not enough disk space for {purpose} in {dir:?}: needs {}, {} available",
+        crate::domain::format_size(*needed),
+        crate::domain::format_size(*available)
+    )]
+    InsufficientSpace { dir: std::path::PathBuf, purpose: String, needed: u64, available: u64 },
+
+    #[error("{path:?} changed while it was uploaded: sent SHA-256 {uploaded}, now {on_disk}; not transcribed")]
+    UploadChecksumMismatch { path: std::path::PathBuf, uploaded: String, on_disk: String },
+
+    #[error("the upload of {path:?} did not verify: sent {sent}, the API stored {stored}; not transcribed")]
+    UploadStoredMismatch { path: std::path::PathBuf, sent: String, stored: String },
+
+    #[error("search index {path:?} failed: {message}")]
+    SearchIndex { path: std::path::PathBuf, message: String },
+
+    #[error("no search index at {path:?}; run `assemblyai-cli index build` first")]
+    SearchIndexMissing { path: std::path::PathBuf },
+
+    #[error("invalid search query {query:?}: {message}")]
+    InvalidSearchQuery { query: String, message: String },
+
     #[error(transparent)]
     Io(#[from] std::io::Error),
 }
@@ -20,7 +93,11 @@ pub enum InfraError {
 impl InfraError {
     pub fn exit_code(&self) -> u8 {
         match self {
-            InfraError::InputNotFound { .. } => 2,
+            InfraError::InputNotFound { .. }
+            | InfraError::InvalidTranscriptJson { .. }
+            | InfraError::InvalidMedia { .. }
+            | InfraError::InvalidSearchQuery { .. } => 2,
+            InfraError::InvalidAliasFile { .. } | InfraError::InvalidJobStore { .. } => 3,
             InfraError::FfmpegNotFound | InfraError::FfmpegFailed { .. } => 4,
             _ => 1,
         }
@@ -0,0 +1,101 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, Float64Array, Int64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+
+use crate::domain::table_output_path;
+use crate::infra::assemblyai::Transcript;
+use crate::infra::logging;
+use crate::infra::runner::speaker_to_string;
+use crate::infra::InfraError;
+
+/// Writes `out.words.parquet` and `out.utterances.parquet` next to `path` (`out.parquet`).
+pub fn write_transcript(path: &Path, done: &Transcript) -> Result<(), InfraError> {
+    let words = done.words.as_deref().unwrap_or_default();
+    let words = table(vec![
+        ("transcript_id", DataType::Utf8, false, Arc::new(StringArray::from(vec![done.id.as_str(); words.len()])) as ArrayRef),
+        ("idx", DataType::Int64, false, Arc::new(Int64Array::from_iter_values(0..words.len() as i64))),
+        (
+            "speaker",
+            DataType::Utf8,
+            true,
+            Arc::new(StringArray::from_iter(words.iter().map(|w| w.speaker.as_ref().map(speaker_to_string)))),
+        ),
+        ("start_ms", DataType::Int64, true, Arc::new(Int64Array::from_iter(words.iter().map(|w| w.start.map(|ms| ms as i64))))),
+        ("end_ms", DataType::Int64, true, Arc::new(Int64Array::from_iter(words.iter().map(|w| w.end.map(|ms| ms as i64))))),
+        ("confidence", DataType::Float64, true, Arc::new(Float64Array::from_iter(words.iter().map(|w| w.confidence)))),
+        (
+            "text",
+            DataType::Utf8,
+            false,
+            Arc::new(StringArray::from_iter_values(words.iter().map(|w| w.text.as_deref().unwrap_or_default()))),
+        ),
+    ]);
+
+    let utterances = done.utterances.as_deref().unwrap_or_default();
+    let utterances = table(vec![
+        (
+            "transcript_id",
+            DataType::Utf8,
+            false,
+            Arc::new(StringArray::from(vec![done.id.as_str(); utterances.len()])) as ArrayRef,
+        ),
+        ("idx", DataType::Int64, false, Arc::new(Int64Array::from_iter_values(0..utterances.len() as i64))),
+        (
+            "speaker",
+            DataType::Utf8,
+            true,
+            Arc::new(StringArray::from_iter(utterances.iter().map(|u| u.speaker.as_ref().map(speaker_to_string)))),
+        ),
+        ("start_ms", DataType::Int64, true, Arc::new(Int64Array::from_iter(utterances.iter().map(|u| u.start.map(|ms| ms as i64))))),
+        ("end_ms", DataType::Int64, true, Arc::new(Int64Array::from_iter(utterances.iter().map(|u| u.end.map(|ms| ms as i64))))),
+        (
+            "text",
+            DataType::Utf8,
+            false,
+            Arc::new(StringArray::from_iter_values(utterances.iter().map(|u| u.text.as_deref().unwrap_or_default()))),
+        ),
+    ]);
+
+    for (name, batch) in [("words", words), ("utterances", utterances)] {
+        let table_path = table_output_path(path, name);
+        let batch = batch.map_err(|err| parquet_error(&table_path, err))?;
+        write_batch(&table_path, &batch)?;
+        logging::info(crate::tr!(
+            "wrote-table",
+            rows = batch.num_rows(),
+            table = name,
+            path = table_path.display().to_string()
+        ));
+    }
+    Ok(())
+}
+
+fn table(columns: Vec<(&str, DataType, bool, ArrayRef)>) -> Result<RecordBatch, arrow_schema::ArrowError> {
+    let fields: Vec<Field> = columns
+        .iter()
+        .map(|(name, data_type, nullable, _)| Field::new(*name, data_type.clone(), *nullable))
+        .collect();
+    let arrays = columns.into_iter().map(|(_, _, _, array)| array).collect();
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)
+}
+
+fn write_batch(path: &Path, batch: &RecordBatch) -> Result<(), InfraError> {
+    let file = std::fs::File::create(path)?;
+    let properties = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+    let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(properties)).map_err(|err| parquet_error(path, err))?;
+    writer.write(batch).map_err(|err| parquet_error(path, err))?;
+    writer.close().map_err(|err| parquet_error(path, err))?;
+    Ok(())
+}
+
+fn parquet_error(path: &Path, err: impl std::fmt::Display) -> InfraError {
+    InfraError::ParquetWrite {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    }
+}
@@ -1,65 +1,1010 @@
+use std::collections::BTreeMap;
+use std::io::Write;
 use std::path::Path;
+use std::time::{Duration, Instant};
+
+use regex::Regex;
 
 use crate::app::TranscribePlan;
-use crate::domain::{Output, TranscriptFormat, TranscribeOptions};
-use crate::infra::assemblyai::{ApiError, AssemblyAiClient, SpeakerLabel, Transcript};
-use crate::infra::{ffmpeg, InfraError};
-
-pub async fn run_transcribe(plan: TranscribePlan, client: AssemblyAiClient, options: &TranscribeOptions) -> Result<(), RunnerError> {
-    match plan {
-        TranscribePlan::Url { url } => {
-            let transcript = transcribe_audio_url(&client, url.as_str(), options).await?;
-            write_output(transcript, options)?;
+use crate::domain::bench::BenchRun;
+use crate::domain::grep::{self, GrepMatch};
+use crate::domain::reports::{self, ChannelTurn, ReportKind};
+use crate::domain::encoding::{Compression, OutputEncoding};
+use crate::domain::listing::{TranscriptFilter, TranscriptSummary};
+use crate::domain::markers::Marker;
+use crate::domain::postprocess::PostProcessor;
+use crate::domain::speakers;
+use crate::domain::subtitles::{self, Caption, SpeakerStyle};
+use crate::domain::transcript::{Chapter, Entity, Highlight, Sentiment, SentimentSegment, Word};
+use crate::domain::{
+    channel_output_path, eval, languages, LimitAction, split_part_path, translation, Input, Language, SpeechModel, Output, SplitOutput, SubtitleSource, TeeFormat, TranscriptFormat, TranscribeOptions,
+    TranslateOptions,
+};
+use crate::infra::assemblyai::{
+    ApiChapter, ApiError, AssemblyAiClient, AutoHighlightsResult, LemurTaskRequest, SpeakerLabel, Transcript, Upload,
+};
+use crate::infra::cache::TranscriptCache;
+use crate::infra::uploads;
+use crate::infra::formatters::{self, ExecFormatter, Formatter, RenderContext};
+use crate::infra::scheduler::Scheduler;
+use crate::infra::{disk, ffmpeg, logging, metadata, parquet, sqlite, InfraError};
+
+/// Where the transcript of a run comes from.
+pub enum Submission<'a> {
+    /// Upload or link the input as planned and create a transcript, reporting its id (and the SHA-256 of the uploaded
+    /// bytes) to `on_created` before polling.
+    New {
+        plan: TranscribePlan,
+        on_created: &'a (dyn Fn(&str, Option<&str>) + Sync),
+    },
+    /// Poll a transcript an interrupted run already created.
+    Existing { transcript_id: String },
+    /// Transcribe the planned parts of one recording (`--concat`) and merge them into a single transcript.
+    Concat { plans: Vec<TranscribePlan> },
+}
+
+/// What a finished run reports back to the batch loop and history.
+#[derive(Debug, Clone)]
+pub struct Transcribed {
+    pub transcript_id: String,
+    /// Set when the language was detected rather than given.
+    pub detected_language: Option<DetectedLanguage>,
+    /// SHA-256 of the bytes this run uploaded; `None` for URLs and resumed transcripts.
+    pub content_sha256: Option<String>,
+    /// Speaker names from `--identify-speakers`, by diarization label.
+    pub speaker_names: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectedLanguage {
+    pub code: String,
+    pub confidence: Option<f64>,
+}
+
+/// Runs the plan end to end and returns the transcript id and, when detected, its language.
+pub async fn run_transcribe(
+    submission: Submission<'_>,
+    client: AssemblyAiClient,
+    options: &TranscribeOptions,
+) -> Result<Transcribed, RunnerError> {
+    let mut content_sha256 = None;
+    let mut done = match submission {
+        Submission::New { plan, on_created } => {
+            let audio_url;
+            (audio_url, content_sha256) = audio_url_for_plan(&client, plan, options).await?;
+            logging::info(crate::tr!("starting-transcription"));
+            let created = client.create_transcript(&audio_url, options).await?;
+            logging::set_transcript_id(&created.id);
+            on_created(&created.id, content_sha256.as_deref());
+            let done = await_transcript(&client, &created.id, options).await;
+            // A remembered upload the API can no longer fetch fails the transcript; upload the file again next time.
+            if let (Err(RunnerError::Api(ApiError::TranscriptError { .. })), Some(index)) = (&done, options.upload_index())
+                && let Err(err) = uploads::forget(index, &audio_url)
+            {
+                logging::warn(crate::tr!("upload-index-failed", error = err.to_string()));
+            }
+            done?
+        }
+        Submission::Existing { transcript_id } => {
+            logging::set_transcript_id(&transcript_id);
+            logging::info(crate::tr!("resuming-transcript", id = transcript_id.to_string()));
+            await_transcript(&client, &transcript_id, options).await?
+        }
+        Submission::Concat { plans } => {
+            let parts: Vec<Input> = std::iter::once(options.input().clone()).chain(options.concat().iter().cloned()).collect();
+            // Create every part's transcript before polling any, so the API processes them side by side.
+            let mut created = Vec::with_capacity(plans.len());
+            for (plan, part) in plans.into_iter().zip(&parts) {
+                let part_options = options.with_input(part.clone());
+                let (audio_url, _) = audio_url_for_plan(&client, plan, &part_options).await?;
+                logging::info(crate::tr!("starting-transcription"));
+                created.push((input_file_name(&part_options), client.create_transcript(&audio_url, options).await?.id));
+            }
+            let mut done = Vec::with_capacity(created.len());
+            for (name, transcript_id) in created {
+                done.push((name, await_transcript(&client, &transcript_id, options).await?));
+            }
+            concat_transcripts(done)
+        }
+    };
+    check_detected_language(&done, options)?;
+    check_content(&done, options)?;
+    let speaker_names = identify_speakers(&mut done, options).await?;
+
+    let translation = match options.translate_to() {
+        Some(language) => Some(translate_subtitles(&client, &done, options, language).await?),
+        None => None,
+    };
+
+    let ctx = RenderContext {
+        client: &client,
+        done: &done,
+        options,
+        translation: translation.as_ref(),
+    };
+    let exec;
+    let formatter: &dyn Formatter = match options.exec_formatter() {
+        Some(command) => {
+            exec = ExecFormatter::new(command);
+            &exec
+        }
+        None => formatters::formatter(options.format()),
+    };
+    let content = formatter.render(&ctx).await?;
+    let content = match options.srt_numbering() {
+        Some(start) if matches!(options.format(), TranscriptFormat::Srt | TranscriptFormat::SrtDual) => {
+            subtitles::renumber_srt(&content, start)
+        }
+        _ => content,
+    };
+    match options.split_output() {
+        Some(split) => write_split_output(&client, &done, options, split).await?,
+        None if options.split_by_channel() => write_channel_outputs(&done, options)?,
+        None if options.format().writes_tables() => write_table_output(&done, options)?,
+        None => write_output(&content, options.output(), options.output_encoding())?,
+    }
+    write_translated_output(translation.as_ref(), options)?;
+    embed_metadata(&done, options)?;
+    write_chapters(&done, options)?;
+    write_reports(&done, options)?;
+    print_echo(&done, options)?;
+    check_captions(&content, options)?;
+    let detected_language = match (options.language(), &done.language_code) {
+        (Language::AutoDetect, Some(code)) => Some(DetectedLanguage {
+            code: code.clone(),
+            confidence: done.language_confidence,
+        }),
+        _ => None,
+    };
+    Ok(Transcribed {
+        transcript_id: done.id,
+        detected_language,
+        content_sha256,
+        speaker_names,
+    })
+}
+
+/// Enforces `--fail-on-empty`, `--min-words`, and `--min-avg-confidence` before anything is written.
+fn check_content(done: &Transcript, options: &TranscribeOptions) -> Result<(), RunnerError> {
+    let words = match &done.words {
+        Some(words) => words.len(),
+        None => done.text.as_deref().unwrap_or_default().split_whitespace().count(),
+    };
+    let confidences: Vec<f64> = done.words.iter().flatten().filter_map(|w| w.confidence).collect();
+    let avg_confidence = if confidences.is_empty() {
+        done.confidence
+    } else {
+        Some(confidences.iter().sum::<f64>() / confidences.len() as f64)
+    };
+
+    match options.content_requirements().violation(words, avg_confidence) {
+        Some(message) => Err(RunnerError::ContentCheckFailed { message }),
+        None => Ok(()),
+    }
+}
+
+/// Reports the detected language on stderr and enforces `--expect-language` before anything is written.
+fn check_detected_language(done: &Transcript, options: &TranscribeOptions) -> Result<(), RunnerError> {
+    if !matches!(options.language(), Language::AutoDetect) {
+        return Ok(());
+    }
+    let detected = done.language_code.as_deref().unwrap_or("unknown");
+    match done.language_confidence {
+        Some(confidence) => logging::info(crate::tr!(
+            "detected-language-confidence",
+            language = detected,
+            confidence = format!("{confidence:.2}")
+        )),
+        None => logging::info(crate::tr!("detected-language", language = detected)),
+    }
+
+    let expected = options.expect_language();
+    if expected.is_empty() || languages::is_expected_language(detected, expected) {
+        return Ok(());
+    }
+    Err(RunnerError::UnexpectedLanguage {
+        detected: detected.to_string(),
+        expected: expected.join(", "),
+    })
+}
+
+fn check_captions(content: &str, options: &TranscribeOptions) -> Result<(), RunnerError> {
+    let Some(lint_options) = options.check() else {
+        return Ok(());
+    };
+
+    let source = match options.output() {
+        Output::Stdout => "<stdout>".to_string(),
+        Output::FilePath(path) => path.display().to_string(),
+    };
+
+    let cues = crate::domain::subtitles::parse_cues(content).map_err(|err| {
+        eprintln!("{source}:{}: parse: {}", err.line, err.message);
+        RunnerError::CheckFailed { count: 1 }
+    })?;
+    let violations = crate::domain::lint::lint_cues(&cues, lint_options);
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    eprint!("{}", crate::domain::lint::format_violations(&source, &violations));
+    Err(RunnerError::CheckFailed {
+        count: violations.len(),
+    })
+}
+
+fn write_output(content: &str, output: &Output, encoding: OutputEncoding) -> Result<(), InfraError> {
+    let bytes = encoding.encode(content);
+    match output {
+        Output::Stdout => {
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(&bytes)?;
+            stdout.flush()?;
             Ok(())
         }
-        TranscribePlan::LocalAudio { path } => {
-            let transcript = transcribe_local_file(&client, &path, options).await?;
-            write_output(transcript, options)?;
+        Output::FilePath(path) => {
+            write_file(path, bytes, encoding)?;
+            logging::info(crate::tr!("wrote-transcript", path = path.display().to_string()));
             Ok(())
         }
+    }
+}
+
+/// Echoes `--tee text` or `--preview N` to stdout once the file output is written.
+fn print_echo(done: &Transcript, options: &TranscribeOptions) -> Result<(), InfraError> {
+    let mut echo = if options.tee() == Some(TeeFormat::Text) {
+        format_text_output(done, options)
+    } else if let Some(count) = options.preview() {
+        match diarized_utterances(done).filter(|utterances| !utterances.is_empty()) {
+            Some(utterances) => {
+                subtitles::format_diarized_text(
+                    &utterances[..count.min(utterances.len())],
+                    options.speaker_style(),
+                    options.paragraph_gap(),
+                )
+            }
+            None => subtitles::first_sentences(done.text.as_deref().unwrap_or_default(), count),
+        }
+    } else {
+        return Ok(());
+    };
+    if !echo.ends_with('\n') {
+        echo.push('\n');
+    }
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(echo.as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Writes encoded output, compressed when `--compress` or the file extension asks for it.
+fn write_file(path: &Path, bytes: Vec<u8>, encoding: OutputEncoding) -> Result<(), InfraError> {
+    let bytes = match encoding.compression_for(path) {
+        None => bytes,
+        Some(Compression::Gzip) => {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(&bytes)?;
+            encoder.finish()?
+        }
+        Some(Compression::Zstd) => zstd::encode_all(bytes.as_slice(), 0)?,
+    };
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let name = path.file_name().unwrap_or(path.as_os_str()).to_string_lossy();
+    disk::ensure_space(dir, bytes.len() as u64, &name)?;
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Writes the transcript's rows for `--format sqlite|parquet` at `--output`.
+fn write_table_output(done: &Transcript, options: &TranscribeOptions) -> Result<(), InfraError> {
+    let Output::FilePath(path) = options.output() else {
+        return Ok(());
+    };
+    match options.format() {
+        TranscriptFormat::Parquet => parquet::write_transcript(path, done),
+        _ => sqlite::write_transcript(path, done, options),
+    }
+}
+
+/// Writes the subtitles as sequential part files next to `--output`, each re-based to start at zero.
+async fn write_split_output(
+    client: &AssemblyAiClient,
+    done: &Transcript,
+    options: &TranscribeOptions,
+    split: SplitOutput,
+) -> Result<(), RunnerError> {
+    let Output::FilePath(path) = options.output() else {
+        return Ok(());
+    };
+
+    let captions = subtitle_captions(client, done, options).await?;
+    let boundaries: Vec<u64> = match split {
+        SplitOutput::Every(every) => {
+            let every_ms = (every.as_millis() as u64).max(1);
+            let end_ms = captions.iter().map(|c| c.start_ms).max().unwrap_or(0);
+            (0..=end_ms / every_ms).map(|i| i * every_ms).collect()
+        }
+        SplitOutput::Chapter => transcript_chapters(done).iter().map(|c| c.start_ms).collect(),
+    };
+
+    let parts = subtitles::split_at_boundaries(&captions, &boundaries);
+    for (idx, part) in parts.iter().enumerate() {
+        let content = match options.format() {
+            TranscriptFormat::Vtt => subtitles::format_vtt(part, options.speaker_style()),
+            _ => subtitles::renumber_srt(
+                &subtitles::format_srt(part, options.speaker_style()),
+                options.srt_numbering().unwrap_or(1),
+            ),
+        };
+        let part_path = split_part_path(path, idx + 1, parts.len());
+        write_file(&part_path, options.output_encoding().encode(&content), options.output_encoding())?;
+        logging::info(crate::tr!("wrote-transcript", path = part_path.display().to_string()));
+    }
+    Ok(())
+}
+
+/// Writes one output per multichannel channel next to `--output` (`out.Agent.txt`, `out.2.txt`).
+fn write_channel_outputs(done: &Transcript, options: &TranscribeOptions) -> Result<(), InfraError> {
+    let Output::FilePath(path) = options.output() else {
+        return Ok(());
+    };
+
+    let mut channels: Vec<String> = Vec::new();
+    for channel in done.utterances.iter().flatten().filter_map(|u| u.channel.as_ref()) {
+        let channel = speaker_to_string(channel);
+        if !channels.contains(&channel) {
+            channels.push(channel);
+        }
+    }
+
+    for channel in &channels {
+        let in_channel = |label: &Option<SpeakerLabel>| label.as_ref().map(speaker_to_string).as_ref() == Some(channel);
+        let utterances: Vec<_> = done.utterances.iter().flatten().filter(|u| in_channel(&u.channel)).cloned().collect();
+        let channel_done = Transcript {
+            text: Some(utterances.iter().filter_map(|u| u.text.as_deref()).collect::<Vec<_>>().join(" ")),
+            words: done
+                .words
+                .as_ref()
+                .map(|words| words.iter().filter(|w| in_channel(&w.channel)).cloned().collect()),
+            utterances: Some(utterances),
+            ..Default::default()
+        };
+
+        let content = match options.format() {
+            TranscriptFormat::Text => format_text_output(&channel_done, options),
+            format => {
+                let captions = local_captions(&channel_done, options).unwrap_or_else(|| {
+                    subtitles::word_captions(
+                        &transcript_words(&channel_done),
+                        options.chars_per_caption(),
+                        caption_speakers(options),
+                    )
+                });
+                match format {
+                    TranscriptFormat::Vtt => subtitles::format_vtt(&captions, options.speaker_style()),
+                    _ => subtitles::renumber_srt(
+                        &subtitles::format_srt(&captions, options.speaker_style()),
+                        options.srt_numbering().unwrap_or(1),
+                    ),
+                }
+            }
+        };
+
+        let label = options.channel_name(channel).unwrap_or(channel);
+        write_output(&content, &Output::FilePath(channel_output_path(path, label)), options.output_encoding())?;
+    }
+    Ok(())
+}
+
+/// Uses `--channel-names` as the speaker of each channel's utterances, words, and sentiment results.
+fn label_channels(done: &mut Transcript, options: &TranscribeOptions) {
+    if !options.multichannel() || !options.has_channel_names() {
+        return;
+    }
+
+    let label = |speaker: &mut Option<SpeakerLabel>, channel: &Option<SpeakerLabel>| {
+        if let Some(channel) = channel.as_ref().map(speaker_to_string) {
+            let name = options.channel_name(&channel).map_or(channel.clone(), str::to_string);
+            *speaker = Some(SpeakerLabel::Label(name));
+        }
+    };
+    for utterance in done.utterances.iter_mut().flatten() {
+        label(&mut utterance.speaker, &utterance.channel);
+    }
+    for word in done.words.iter_mut().flatten() {
+        label(&mut word.speaker, &word.channel);
+    }
+    for result in done.sentiment_analysis_results.iter_mut().flatten() {
+        label(&mut result.speaker, &result.channel);
+    }
+}
+
+/// `--identify-speakers`: names each diarized speaker, asking on the terminal about those without a name from an
+/// earlier run, and returns every name given. Enter keeps a speaker's label; end of input cancels the run.
+async fn identify_speakers(
+    done: &mut Transcript,
+    options: &TranscribeOptions,
+) -> Result<BTreeMap<String, String>, RunnerError> {
+    if !options.identify_speakers() {
+        return Ok(BTreeMap::new());
+    }
+
+    let mut names = options.speaker_names().clone();
+    let playable = match options.input() {
+        Input::LocalPath(path) if options.audio_url().is_none() && options.concat().is_empty() => Some(path),
+        _ => None,
+    };
+    for sample in speakers::first_utterances(&diarized_utterances(done).unwrap_or_default()) {
+        if names.contains_key(&sample.speaker) {
+            continue;
+        }
+        eprintln!("{}", speakers::format_sample(&sample));
+        if let Some(path) = playable {
+            ffmpeg::play_clip(path, sample.start_ms, sample.end_ms);
+        }
+        eprint!("Name for speaker {} (Enter keeps {0}): ", sample.speaker);
+        let _ = std::io::stderr().flush();
+        let Some(answer) = read_answer().await? else {
+            eprintln!();
+            return Err(RunnerError::Cancelled);
+        };
+        let answer = answer.trim();
+        if !answer.is_empty() {
+            names.insert(sample.speaker, answer.to_string());
+        }
+    }
+
+    let rename = |speaker: &mut Option<SpeakerLabel>| {
+        if let Some(name) = speaker.as_ref().and_then(|label| names.get(&speaker_to_string(label))) {
+            *speaker = Some(SpeakerLabel::Label(name.clone()));
+        }
+    };
+    for utterance in done.utterances.iter_mut().flatten() {
+        rename(&mut utterance.speaker);
+    }
+    for word in done.words.iter_mut().flatten() {
+        rename(&mut word.speaker);
+    }
+    for result in done.sentiment_analysis_results.iter_mut().flatten() {
+        rename(&mut result.speaker);
+    }
+    Ok(names)
+}
+
+/// One line from stdin, or `None` at end of input.
+///
+/// The read runs on its own thread so Ctrl-C can cancel the prompt: a blocking read on the runtime would stall the
+/// scheduler's select, and one on `spawn_blocking` would hold up the runtime's shutdown until Enter.
+async fn read_answer() -> Result<Option<String>, InfraError> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
+        let mut answer = String::new();
+        let read = std::io::stdin().read_line(&mut answer).map(|len| (len > 0).then_some(answer));
+        let _ = tx.send(read);
+    });
+    match rx.await {
+        Ok(read) => Ok(read?),
+        Err(_) => Ok(None),
+    }
+}
+
+/// `--concat`: the parts as one transcript, each part's timings shifted by the length of the parts before it.
+///
+/// Each part becomes a chapter named after its file, so chapter-based outputs mark where the parts begin; parts with
+/// `--auto-chapters` keep their own chapters, with the part's name before each headline. Speaker labels are the
+/// API's per part, so `A` of one part needn't be `A` of the next.
+fn concat_transcripts(parts: Vec<(String, Transcript)>) -> Transcript {
+    let mut merged = Transcript {
+        status: "completed".to_string(),
+        ..Default::default()
+    };
+    let mut ids = Vec::with_capacity(parts.len());
+    let mut texts = Vec::with_capacity(parts.len());
+    let mut weighted_confidence = (0.0, 0);
+    let mut offset_ms = 0;
+    for (name, part) in parts {
+        let duration_ms = part
+            .audio_duration
+            .map(|seconds| (seconds * 1000.0).round() as u64)
+            .or_else(|| part.words.iter().flatten().filter_map(|word| word.end).max())
+            .or_else(|| part.utterances.iter().flatten().filter_map(|utterance| utterance.end).max())
+            .unwrap_or(0);
+        let shift = |ms: &mut Option<u64>| {
+            if let Some(ms) = ms {
+                *ms += offset_ms;
+            }
+        };
+
+        for mut word in part.words.into_iter().flatten() {
+            shift(&mut word.start);
+            shift(&mut word.end);
+            merged.words.get_or_insert_with(Vec::new).push(word);
+        }
+        for mut utterance in part.utterances.into_iter().flatten() {
+            shift(&mut utterance.start);
+            shift(&mut utterance.end);
+            merged.utterances.get_or_insert_with(Vec::new).push(utterance);
+        }
+        for mut result in part.sentiment_analysis_results.into_iter().flatten() {
+            shift(&mut result.start);
+            shift(&mut result.end);
+            merged.sentiment_analysis_results.get_or_insert_with(Vec::new).push(result);
+        }
+        let chapters = merged.chapters.get_or_insert_with(Vec::new);
+        match part.chapters.filter(|chapters| !chapters.is_empty()) {
+            Some(part_chapters) => {
+                for mut chapter in part_chapters {
+                    shift(&mut chapter.start);
+                    shift(&mut chapter.end);
+                    chapter.headline = Some(format!("{name}: {}", chapter.headline.unwrap_or_default()));
+                    chapters.push(chapter);
+                }
+            }
+            None => chapters.push(ApiChapter {
+                start: Some(offset_ms),
+                end: Some(offset_ms + duration_ms),
+                headline: Some(name.clone()),
+                gist: Some(name),
+                summary: None,
+            }),
+        }
+        if let Some(highlights) = part.auto_highlights_result {
+            merged
+                .auto_highlights_result
+                .get_or_insert_with(|| AutoHighlightsResult { results: Vec::new() })
+                .results
+                .extend(highlights.results);
+        }
+        merged.entities.get_or_insert_with(Vec::new).extend(part.entities.into_iter().flatten());
+        if let Some(confidence) = part.confidence {
+            weighted_confidence.0 += confidence * duration_ms as f64;
+            weighted_confidence.1 += duration_ms;
+        }
+        if merged.language_code.is_none() {
+            merged.language_code = part.language_code;
+            merged.language_confidence = part.language_confidence;
+        }
+        texts.extend(part.text);
+        ids.push(part.id);
+        offset_ms += duration_ms;
+    }
+    merged.id = ids.join("+");
+    merged.text = Some(texts.join(" "));
+    merged.confidence = (weighted_confidence.1 > 0).then(|| weighted_confidence.0 / weighted_confidence.1 as f64);
+    merged.audio_duration = Some(offset_ms as f64 / 1000.0);
+    merged
+}
+
+/// Folds short interjections into the surrounding speaker's turn (`--merge-short-utterances`), giving their words
+/// that speaker too.
+fn merge_short_utterances(done: &mut Transcript, options: &TranscribeOptions) {
+    let Some(max) = options.merge_short_utterances() else {
+        return;
+    };
+    let Some(utterances) = done.utterances.take() else {
+        return;
+    };
+
+    let speakers: Vec<String> = utterances
+        .iter()
+        .map(|u| u.speaker.as_ref().map(speaker_to_string).unwrap_or_default())
+        .collect();
+    let spans: Vec<subtitles::UtteranceSpan> = utterances
+        .iter()
+        .zip(&speakers)
+        .map(|(u, speaker)| subtitles::UtteranceSpan {
+            start_ms: u.start.unwrap_or(0),
+            end_ms: u.end.unwrap_or(0),
+            speaker,
+            text: u.text.as_deref().unwrap_or(""),
+        })
+        .collect();
+
+    let mut merged = Vec::new();
+    for turn in subtitles::merge_short_utterances(&spans, max) {
+        let owner = &utterances[turn.speaker];
+        for idx in turn.range.clone().filter(|idx| speakers[*idx] != speakers[turn.speaker]) {
+            let (start, end) = (utterances[idx].start, utterances[idx].end);
+            for word in done.words.iter_mut().flatten() {
+                if word.start >= start && word.end <= end {
+                    word.speaker = owner.speaker.clone();
+                }
+            }
+        }
+        let members = &utterances[turn.range];
+        merged.push(crate::infra::assemblyai::Utterance {
+            speaker: owner.speaker.clone(),
+            channel: owner.channel.clone(),
+            text: Some(members.iter().filter_map(|u| u.text.as_deref()).collect::<Vec<_>>().join(" ")),
+            start: members.first().and_then(|u| u.start),
+            end: members.last().and_then(|u| u.end),
+        });
+    }
+    done.utterances = Some(merged);
+}
+
+/// Marks low-confidence words (`--mark-uncertain`) and rebuilds the text and each utterance from the marked words,
+/// so every rendering shows the markers.
+fn mark_uncertain_words(done: &mut Transcript, options: &TranscribeOptions) {
+    let Some(threshold) = options.mark_uncertain() else {
+        return;
+    };
+    let Some(words) = done.words.as_mut().filter(|words| !words.is_empty()) else {
+        return;
+    };
+
+    for word in words.iter_mut() {
+        if let Some(text) = word.text.as_deref()
+            && let Some(marked) = crate::domain::transcript::mark_uncertain(text, word.confidence, threshold)
+        {
+            word.text = Some(marked);
+        }
+    }
+    done.text = Some(joined_word_text(words.iter()));
+    for utterance in done.utterances.iter_mut().flatten() {
+        let (start, end) = (utterance.start, utterance.end);
+        let text = joined_word_text(words.iter().filter(|word| word.start >= start && word.end <= end));
+        if !text.is_empty() {
+            utterance.text = Some(text);
+        }
+    }
+}
+
+fn joined_word_text<'a>(words: impl Iterator<Item = &'a crate::infra::assemblyai::Word>) -> String {
+    words.filter_map(|word| word.text.as_deref()).collect::<Vec<_>>().join(" ")
+}
+
+fn embed_metadata(done: &Transcript, options: &TranscribeOptions) -> Result<(), InfraError> {
+    if !options.embed_metadata() {
+        return Ok(());
+    }
+    let Input::LocalPath(path) = options.input() else {
+        return Ok(());
+    };
+
+    metadata::embed_transcript(path, &format_text_output(done, options), temp_files(options))?;
+    logging::info(crate::tr!("embedded-transcript", path = path.display().to_string()));
+    Ok(())
+}
+
+fn write_chapters(done: &Transcript, options: &TranscribeOptions) -> Result<(), InfraError> {
+    let (Some(output), Input::LocalPath(input)) = (options.write_chapters(), options.input()) else {
+        return Ok(());
+    };
+
+    let chapters = transcript_chapters(done);
+    if chapters.is_empty() {
+        logging::info(crate::tr!("no-chapters", path = output.display().to_string()));
+        return Ok(());
+    }
+
+    ffmpeg::write_chapters(input, output, &chapters, temp_files(options))?;
+    logging::info(crate::tr!("wrote-chapters", count = chapters.len(), path = output.display().to_string()));
+    Ok(())
+}
+
+fn write_reports(done: &Transcript, options: &TranscribeOptions) -> Result<(), InfraError> {
+    if options.reports().is_empty() {
+        return Ok(());
+    }
+
+    let words = transcript_words(done);
+    let rendered = options
+        .reports()
+        .iter()
+        .map(|report| match report {
+            ReportKind::Keywords { terms } => reports::format_keyword_report(&words, terms, options.timestamp_format().unwrap_or_default()),
+            ReportKind::SentimentTimeline {
+                bucket,
+                per_speaker,
+                format,
+            } => reports::format_sentiment_timeline(
+                &transcript_sentiments(done),
+                *bucket,
+                *per_speaker,
+                *format,
+                options.timestamp_format(),
+            ),
+            ReportKind::Silence { threshold } => reports::format_silence_report(
+                &speech_spans(done),
+                done.audio_duration.map(|seconds| (seconds * 1000.0).round() as u64),
+                *threshold,
+                options.timestamp_format().unwrap_or_default(),
+            ),
+            ReportKind::Profanity => reports::format_profanity_report(&words, options.timestamp_format().unwrap_or_default()),
+            ReportKind::Interruptions { min_overlap } => reports::format_interruption_report(
+                &channel_turns(done, options),
+                done.audio_duration.map(|seconds| (seconds * 1000.0).round() as u64),
+                *min_overlap,
+                options.timestamp_format().unwrap_or_default(),
+            ),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    match options.report_output() {
+        Some(path) => {
+            std::fs::write(path, rendered)?;
+            logging::info(crate::tr!("wrote-report", path = path.display().to_string()));
+        }
+        None => eprint!("{rendered}"),
+    }
+    Ok(())
+}
+
+/// Transcribes the same input once per speech model, concurrently through `scheduler`, and prints a comparison.
+pub async fn run_compare_models(
+    plan: TranscribePlan,
+    client: AssemblyAiClient,
+    options: &TranscribeOptions,
+    models: &[SpeechModel],
+    reference: Option<&str>,
+    scheduler: &Scheduler,
+) -> Result<(), RunnerError> {
+    let (audio_url, _) = scheduler.run(audio_url_for_plan(&client, plan, options)).await?;
+
+    let jobs = models
+        .iter()
+        .map(|model| {
+            let client = client.clone();
+            let audio_url = audio_url.clone();
+            let options = options.with_speech_model(*model);
+            async move { transcribe_audio_url(&client, &audio_url, &options).await }
+        })
+        .collect();
+    let runs: Vec<eval::ModelRun> = scheduler
+        .run_all(jobs)
+        .await?
+        .into_iter()
+        .zip(models)
+        .map(|(done, model)| eval::ModelRun {
+            model: model.name().to_string(),
+            text: done.text.unwrap_or_default(),
+            confidence: done.confidence,
+        })
+        .collect();
+    print!("{}", eval::format_model_comparison(&runs, reference));
+    Ok(())
+}
+
+/// Transcribes the input `runs` times with each model, one transcription at a time so they don't compete, and times
+/// the upload, queue, and processing of each.
+///
+/// Queue and processing times are as polls observed them, so they are accurate to the poll interval.
+pub async fn run_bench(
+    plan: TranscribePlan,
+    client: AssemblyAiClient,
+    options: &TranscribeOptions,
+    models: &[SpeechModel],
+    runs: usize,
+) -> Result<Vec<BenchRun>, RunnerError> {
+    let mut results = Vec::new();
+    for run in 1..=runs {
+        for model in models {
+            logging::info(crate::tr!("bench-run", run = run, runs = runs, model = model.name()));
+            let options = options.with_speech_model(*model);
+            let started = Instant::now();
+            let (audio_url, _) = audio_url_for_plan(&client, plan.clone(), &options).await?;
+            let uploaded = started.elapsed();
+
+            let created = client.create_transcript(&audio_url, &options).await?;
+            let submitted = started.elapsed();
+            let mut processing_since = None;
+            let done = client
+                .poll_until_done_with(&created.id, options.poll_strategy(), options.poll_interval(), options.timeout(), |status| {
+                    if status != "queued" && processing_since.is_none() {
+                        processing_since = Some(started.elapsed());
+                    }
+                })
+                .await?;
+            let total = started.elapsed();
+            if done.status == "error" {
+                return Err(RunnerError::Api(ApiError::TranscriptError {
+                    message: done.error.unwrap_or_else(|| "unknown transcription error".to_string()),
+                }));
+            }
+
+            let processing_since = processing_since.unwrap_or(total);
+            let ms = |duration: Duration| duration.as_millis() as u64;
+            results.push(BenchRun {
+                run,
+                model: model.name().to_string(),
+                transcript_id: created.id,
+                upload_ms: ms(uploaded),
+                queue_ms: ms(processing_since - submitted),
+                processing_ms: ms(total - processing_since),
+                total_ms: ms(total),
+            });
+        }
+    }
+    Ok(results)
+}
+
+/// Uploads local inputs (extracting audio from video when planned); URLs are passed through.
+///
+/// Returns the audio URL and, for uploads, the SHA-256 of the uploaded bytes.
+async fn audio_url_for_plan(
+    client: &AssemblyAiClient,
+    plan: TranscribePlan,
+    options: &TranscribeOptions,
+) -> Result<(String, Option<String>), RunnerError> {
+    let mut duration_seconds = None;
+    if let TranscribePlan::LocalAudio { path }
+    | TranscribePlan::LocalVideo { path }
+    | TranscribePlan::LocalVideoExtract { path }
+    | TranscribePlan::LocalPreprocess { path, .. } = &plan
+        && path.exists()
+    {
+        duration_seconds = check_local_media(path, options)?;
+    }
+
+    let upload = match plan {
+        TranscribePlan::Url { url } => return Ok((url.to_string(), None)),
+        TranscribePlan::LocalAudio { path } | TranscribePlan::LocalVideo { path } => {
+            upload_local_file(client, &path, options).await?
+        }
         TranscribePlan::LocalVideoExtract { path } => {
             if !path.exists() {
                 return Err(RunnerError::Infra(InfraError::InputNotFound { path }));
             }
-            let extracted = ffmpeg::extract_audio_to_mp3(&path)?;
-            let transcript = transcribe_local_file(&client, extracted.path.as_ref(), options).await?;
-            write_output(transcript, options)?;
-            Ok(())
+            ensure_extraction_space(&path, duration_seconds, options)?;
+            let extracted = ffmpeg::extract_audio_to_mp3(&path, None, temp_files(options))?;
+            upload_local_file(client, extracted.path.as_ref(), options).await?
+        }
+        TranscribePlan::LocalPreprocess { path, profile } => {
+            if !path.exists() {
+                return Err(RunnerError::Infra(InfraError::InputNotFound { path }));
+            }
+            logging::info(crate::tr!("preprocessing-audio", filters = profile.filter_chain()));
+            ensure_extraction_space(&path, duration_seconds, options)?;
+            let processed = ffmpeg::extract_audio_to_mp3(&path, Some(profile.filter_chain()), temp_files(options))?;
+            upload_local_file(client, processed.path.as_ref(), options).await?
         }
+    };
+    Ok((upload.upload_url, Some(upload.sha256)))
+}
+
+fn ensure_extraction_space(path: &Path, duration_seconds: Option<f64>, options: &TranscribeOptions) -> Result<(), InfraError> {
+    let needed = crate::domain::extracted_audio_bytes(std::fs::metadata(path)?.len(), duration_seconds);
+    disk::ensure_space(&temp_files(options).location(), needed, "extracted audio")
+}
+
+fn temp_files(options: &TranscribeOptions) -> ffmpeg::TempFiles<'_> {
+    ffmpeg::TempFiles {
+        dir: options.temp_dir(),
+        keep: options.keep_temp(),
     }
 }
 
-fn write_output(content: String, options: &TranscribeOptions) -> Result<(), InfraError> {
-    match options.output() {
-        Output::Stdout => {
-            print!("{content}");
-            Ok(())
-        }
-        Output::FilePath(path) => {
-            std::fs::write(path, content)?;
-            eprintln!("wrote transcript to {}", path.display());
+/// Probes the source file (unless validation is skipped and no duration limit needs it), rejecting
+/// unusable media and enforcing `maxDurationMinutes`; returns the duration when ffprobe reported one.
+fn check_local_media(path: &Path, options: &TranscribeOptions) -> Result<Option<f64>, RunnerError> {
+    let limits = options.upload_limits();
+    if options.skip_validation() && limits.max_duration_minutes.is_none() {
+        return Ok(None);
+    }
+
+    let probe = match ffmpeg::probe_media(path) {
+        Ok(probe) => probe,
+        Err(_) if options.skip_validation() => None,
+        Err(err) => return Err(err.into()),
+    };
+    let Some(probe) = probe else {
+        logging::info(crate::tr!("ffprobe-missing"));
+        return Ok(None);
+    };
+
+    if !options.skip_validation()
+        && let Some(problem) = probe.problem
+    {
+        return Err(RunnerError::Infra(InfraError::InvalidMedia {
+            path: path.to_path_buf(),
+            message: problem.to_string(),
+        }));
+    }
+    if let Some(message) = probe.duration_seconds.and_then(|seconds| limits.duration_violation(seconds)) {
+        limit_exceeded(message, options)?;
+    }
+    Ok(probe.duration_seconds)
+}
+
+fn limit_exceeded(message: String, options: &TranscribeOptions) -> Result<(), RunnerError> {
+    match options.upload_limits().action {
+        LimitAction::Abort => Err(RunnerError::UploadLimitExceeded { message }),
+        LimitAction::Warn => {
+            logging::warn(message);
             Ok(())
         }
     }
 }
 
-async fn transcribe_local_file(client: &AssemblyAiClient, path: &Path, options: &TranscribeOptions) -> Result<String, RunnerError> {
+async fn upload_local_file(client: &AssemblyAiClient, path: &Path, options: &TranscribeOptions) -> Result<Upload, RunnerError> {
     if !path.exists() {
         return Err(RunnerError::Infra(InfraError::InputNotFound {
             path: path.to_path_buf(),
         }));
     }
 
-    eprintln!("uploading: {path:?}");
-    let upload_url = client.upload_file(path).await?;
-    transcribe_audio_url(client, &upload_url, options).await
+    let bytes = std::fs::metadata(path).map_err(InfraError::from)?.len();
+    if let Some(message) = options.upload_limits().size_violation(bytes) {
+        limit_exceeded(message, options)?;
+    }
+
+    upload_file(client, path, bytes, options.upload_index(), options.verify_upload()).await
+}
+
+/// Uploads `path` (of `bytes` bytes), or reuses a recent upload of the same content recorded in `upload_index`.
+///
+/// With `verify`, the file is read again after the upload and must hash the same as the bytes that were sent, and
+/// any digest or size the upload response reports must match what was sent.
+pub async fn upload_file(
+    client: &AssemblyAiClient,
+    path: &Path,
+    bytes: u64,
+    upload_index: Option<&Path>,
+    verify: bool,
+) -> Result<Upload, RunnerError> {
+    let indexed = match upload_index {
+        Some(index) => {
+            let sha256 = uploads::file_sha256(path)?;
+            if let Some(upload_url) = uploads::find(index, &sha256, &client.upload_scope()) {
+                logging::info(crate::tr!("reusing-upload", path = format!("{path:?}")));
+                return Ok(Upload {
+                    upload_url,
+                    sha256,
+                    stored_sha256: None,
+                    stored_bytes: None,
+                });
+            }
+            Some(index)
+        }
+        None => None,
+    };
+    logging::info(crate::tr!("uploading", path = format!("{path:?}")));
+    let upload = client.upload_file(path).await?;
+    if verify {
+        let on_disk = uploads::file_sha256(path)?;
+        if on_disk != upload.sha256 {
+            return Err(RunnerError::Infra(InfraError::UploadChecksumMismatch {
+                path: path.to_path_buf(),
+                uploaded: upload.sha256,
+                on_disk,
+            }));
+        }
+        let stored = match (&upload.stored_sha256, upload.stored_bytes) {
+            (Some(stored), _) if !stored.eq_ignore_ascii_case(&upload.sha256) => {
+                Some((format!("SHA-256 {}", upload.sha256), format!("SHA-256 {stored}")))
+            }
+            (_, Some(stored)) if stored != bytes => Some((format!("{bytes} bytes"), format!("{stored} bytes"))),
+            _ => None,
+        };
+        if let Some((sent, stored)) = stored {
+            return Err(RunnerError::Infra(InfraError::UploadStoredMismatch {
+                path: path.to_path_buf(),
+                sent,
+                stored,
+            }));
+        }
+    }
+    if let Some(index) = indexed
+        && let Err(err) =
+            uploads::record(index, upload.sha256.clone(), client.upload_scope(), bytes, upload.upload_url.clone())
+    {
+        logging::warn(crate::tr!("upload-index-failed", error = err.to_string()));
+    }
+    Ok(upload)
 }
 
-async fn transcribe_audio_url(client: &AssemblyAiClient, audio_url: &str, options: &TranscribeOptions) -> Result<String, RunnerError> {
-    eprintln!("starting transcription");
+async fn transcribe_audio_url(client: &AssemblyAiClient, audio_url: &str, options: &TranscribeOptions) -> Result<Transcript, RunnerError> {
+    logging::info(crate::tr!("starting-transcription"));
     let created = client.create_transcript(audio_url, options).await?;
-    let done = client
-        .poll_until_done(&created.id, options.poll_interval(), options.timeout())
+    await_transcript(client, &created.id, options).await
+}
+
+/// Polls transcript `id` to completion and applies the local post-processing.
+async fn await_transcript(client: &AssemblyAiClient, id: &str, options: &TranscribeOptions) -> Result<Transcript, RunnerError> {
+    let mut done = client
+        .poll_until_done(id, options.poll_strategy(), options.poll_interval(), options.timeout())
         .await?;
 
     if done.status.as_str() == "error" {
@@ -68,59 +1013,478 @@ async fn transcribe_audio_url(client: &AssemblyAiClient, audio_url: &str, option
         }));
     }
 
-    match options.format() {
-        TranscriptFormat::Text => Ok(format_text_output(&done, options)),
-        TranscriptFormat::Srt => match format_diarized_subtitles(&done, options) {
-            Some(value) => Ok(value),
-            None => Ok(client
-                .get_subtitles(&done.id, options.format(), options.chars_per_caption())
-                .await?),
-        },
-        TranscriptFormat::Vtt => match format_diarized_subtitles(&done, options) {
-            Some(value) => Ok(value),
-            None => Ok(client
-                .get_subtitles(&done.id, options.format(), options.chars_per_caption())
-                .await?),
-        },
-    }
-}
-
-fn format_text_output(done: &Transcript, options: &TranscribeOptions) -> String {
-    if options.speaker_labels() {
-        if let Some(value) = diarized_utterances(done)
-            .as_ref()
-            .map(|u| crate::domain::subtitles::format_diarized_text(u))
+    post_process_transcript(&mut done, options.post_process());
+    label_channels(&mut done, options);
+    merge_short_utterances(&mut done, options);
+    mark_uncertain_words(&mut done, options);
+    Ok(done)
+}
+
+fn post_process_transcript(done: &mut Transcript, processor: &PostProcessor) {
+    if processor.is_empty() {
+        return;
+    }
+
+    if let Some(text) = done.text.as_mut() {
+        *text = processor.apply(text);
+    }
+    for utterance in done.utterances.iter_mut().flatten() {
+        if let Some(text) = utterance.text.as_mut() {
+            *text = processor.apply(text);
+        }
+    }
+    if let Some(words) = done.words.as_mut() {
+        let mut texts: Vec<String> = words.iter().map(|w| w.text.clone().unwrap_or_default()).collect();
+        processor.redact_word_sequence(&mut texts);
+        for (word, text) in words.iter_mut().zip(texts) {
+            if word.text.is_some() {
+                word.text = Some(processor.apply(&text));
+            }
+        }
+    }
+    for chapter in done.chapters.iter_mut().flatten() {
+        for text in [&mut chapter.headline, &mut chapter.gist, &mut chapter.summary].into_iter().flatten() {
+            *text = processor.apply(text);
+        }
+    }
+    if let Some(highlights) = done.auto_highlights_result.as_mut() {
+        for highlight in &mut highlights.results {
+            if let Some(text) = highlight.text.as_mut() {
+                *text = processor.apply(text);
+            }
+        }
+    }
+    for entity in done.entities.iter_mut().flatten() {
+        if let Some(text) = entity.text.as_mut() {
+            *text = processor.apply(text);
+        }
+    }
+}
+
+pub async fn run_translate(
+    client: AssemblyAiClient,
+    cache: Option<&TranscriptCache>,
+    options: &TranslateOptions,
+) -> Result<(), RunnerError> {
+    let done = fetch_completed_transcript(&client, cache, options.transcript_id()).await?;
+
+    let style = SpeakerStyle::default();
+    let utterances = diarized_utterances(&done).unwrap_or_default();
+    let captions = if utterances.is_empty() {
+        subtitles::word_captions(&transcript_words(&done), options.chars_per_caption(), None)
+    } else {
+        utterances
+            .iter()
+            .map(|u| Caption {
+                start_ms: u.start_ms,
+                end_ms: u.end_ms,
+                speaker: Some(u.speaker.clone()),
+                text: u.text.clone(),
+            })
+            .collect()
+    };
+    if captions.is_empty() {
+        return Err(RunnerError::Api(ApiError::TranscriptHasNoTimings { id: done.id }));
+    }
+
+    let texts: Vec<String> = captions.iter().map(|c| c.text.clone()).collect();
+    logging::info(crate::tr!("translating-segments", count = texts.len(), language = options.language().to_string()));
+    let translated = translate_texts(&client, &texts, options.language(), options.lemur_model()).await?;
+    let translated: Vec<Caption> = captions
+        .into_iter()
+        .zip(translated)
+        .map(|(caption, text)| Caption { text, ..caption })
+        .collect();
+
+    let content = match options.format() {
+        TranscriptFormat::Srt | TranscriptFormat::Vtt => {
+            let split = subtitles::split_captions(&translated, options.chars_per_caption(), &style);
+            if options.format() == TranscriptFormat::Srt {
+                subtitles::format_srt(&split, &style)
+            } else {
+                subtitles::format_vtt(&split, &style)
+            }
+        }
+        _ => subtitles::format_timestamped_text(&translated, &style, options.timestamp_format()),
+    };
+
+    write_output(&content, options.output(), options.output_encoding())?;
+    Ok(())
+}
+
+/// A completed transcript from `cache` when it has one, else from the API, caching it for next time.
+pub async fn fetch_completed_transcript(
+    client: &AssemblyAiClient,
+    cache: Option<&TranscriptCache>,
+    id: &str,
+) -> Result<Transcript, RunnerError> {
+    if let Some(json) = cache.and_then(|cache| cache.get(id))
+        && let Ok(done) = serde_json::from_slice::<Transcript>(&json)
+    {
+        return Ok(done);
+    }
+    let json = client.get_transcript_json(id).await?;
+    let done: Transcript = serde_json::from_slice(&json).map_err(ApiError::Json)?;
+    if done.status != "completed" {
+        return Err(RunnerError::Api(ApiError::TranscriptNotCompleted {
+            id: done.id,
+            status: done.status,
+        }));
+    }
+    if let Some(cache) = cache {
+        cache.put(&done.id, &json);
+    }
+    Ok(done)
+}
+
+/// Text of a completed transcript, for comparing against a reference.
+pub async fn fetch_transcript_text(
+    client: &AssemblyAiClient,
+    cache: Option<&TranscriptCache>,
+    id: &str,
+) -> Result<String, RunnerError> {
+    let done = fetch_completed_transcript(client, cache, id).await?;
+    Ok(done.text.unwrap_or_default())
+}
+
+/// Transcripts matching `filter`, newest first, paging through the account until `limit` match (all when `None`)
+/// or the transcripts get older than the filter's created range.
+pub async fn list_transcripts(
+    client: &AssemblyAiClient,
+    filter: &TranscriptFilter,
+    limit: Option<usize>,
+) -> Result<Vec<TranscriptSummary>, RunnerError> {
+    let mut matching = Vec::new();
+    let mut before_id: Option<String> = None;
+    loop {
+        let page = client.list_transcripts(LIST_PAGE_SIZE, filter, before_id.as_deref()).await?;
+        for transcript in &page.transcripts {
+            if filter.is_exhausted_by(transcript) {
+                return Ok(matching);
+            }
+            if filter.matches(transcript) {
+                matching.push(transcript.clone());
+            }
+            if let Some(limit) = limit
+                && matching.len() >= limit
+            {
+                matching.truncate(limit);
+                return Ok(matching);
+            }
+        }
+        match page.transcripts.last() {
+            Some(last) if page.page_details.prev_url.is_some() => before_id = Some(last.id.clone()),
+            _ => return Ok(matching),
+        }
+    }
+}
+
+/// Transcripts requested per listing page (the API allows up to 200).
+const LIST_PAGE_SIZE: u32 = 100;
+
+/// Reads a hypothesis from a transcript JSON file (as returned by the API) or a plain text file.
+pub fn read_hypothesis_file(path: &Path) -> Result<String, InfraError> {
+    let is_json = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    if !is_json {
+        return Ok(std::fs::read_to_string(path)?);
+    }
+
+    read_transcript_file(path)?.text.ok_or_else(|| InfraError::InvalidTranscriptJson {
+        path: path.to_path_buf(),
+        message: "missing \"text\"".to_string(),
+    })
+}
+
+/// A transcript JSON file, as returned by the API.
+pub fn read_transcript_file(path: &Path) -> Result<Transcript, InfraError> {
+    let contents = std::fs::read_to_string(path)?;
+    serde_json::from_str(&contents).map_err(|err| InfraError::InvalidTranscriptJson {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })
+}
+
+/// A completed transcript in the cache; `None` when it isn't cached or can't be parsed.
+pub fn cached_transcript(cache: &TranscriptCache, id: &str) -> Option<Transcript> {
+    serde_json::from_slice(&cache.peek(id)?).ok()
+}
+
+/// Sentences of `done` matching `pattern`, from its words, or from its text when it has no word timings.
+pub fn grep_transcript(done: &Transcript, pattern: &Regex) -> Vec<GrepMatch> {
+    let words = transcript_words(done);
+    if words.is_empty() {
+        return grep::grep_text(done.text.as_deref().unwrap_or_default(), pattern);
+    }
+    grep::grep_words(&words, pattern)
+}
+
+pub struct Translation {
+    pub original: Vec<Caption>,
+    pub translated: Vec<Caption>,
+}
+
+/// Captions built locally when possible, otherwise parsed from the API's SRT export.
+pub async fn subtitle_captions(
+    client: &AssemblyAiClient,
+    done: &Transcript,
+    options: &TranscribeOptions,
+) -> Result<Vec<Caption>, RunnerError> {
+    if let Some(captions) = local_captions(done, options) {
+        return Ok(captions);
+    }
+
+    let words = transcript_words(done);
+    if !words.is_empty() && options.subtitle_source() != Some(SubtitleSource::Api) {
+        return Ok(subtitles::word_captions(&words, options.chars_per_caption(), caption_speakers(options)));
+    }
+
+    let srt = client
+        .get_subtitles(&done.id, TranscriptFormat::Srt, options.chars_per_caption())
+        .await?;
+    let cues = subtitles::parse_cues(&srt).map_err(|err| ApiError::InvalidSubtitleBody {
+        line: err.line,
+        message: err.message,
+    })?;
+
+    Ok(cues
+        .into_iter()
+        .map(|cue| Caption {
+            start_ms: cue.start_ms,
+            end_ms: cue.end_ms,
+            speaker: None,
+            text: options.post_process().apply(&cue.lines.join(" ")),
+        })
+        .collect())
+}
+
+/// One caption per sentence from the word timings, or the subtitle captions when the transcript has no words.
+pub async fn sentence_captions(
+    client: &AssemblyAiClient,
+    done: &Transcript,
+    options: &TranscribeOptions,
+) -> Result<Vec<Caption>, RunnerError> {
+    let words = transcript_words(done);
+    if words.is_empty() {
+        return subtitle_captions(client, done, options).await;
+    }
+    Ok(subtitles::sentence_captions(&words))
+}
+
+async fn translate_subtitles(
+    client: &AssemblyAiClient,
+    done: &Transcript,
+    options: &TranscribeOptions,
+    language: &str,
+) -> Result<Translation, RunnerError> {
+    let original = if options.format() == TranscriptFormat::AnkiTsv {
+        sentence_captions(client, done, options).await?
+    } else {
+        subtitle_captions(client, done, options).await?
+    };
+    let texts: Vec<String> = original.iter().map(|c| c.text.clone()).collect();
+
+    logging::info(crate::tr!("translating-captions", count = texts.len(), language = language.to_string()));
+    let translated_texts = translate_texts(client, &texts, language, options.lemur_model()).await?;
+
+    let translated = original
+        .iter()
+        .zip(translated_texts)
+        .map(|(caption, text)| Caption {
+            text,
+            ..caption.clone()
+        })
+        .collect();
+
+    Ok(Translation { original, translated })
+}
+
+/// Translates `texts` line by line via LeMUR; lines the model drops keep their original text.
+async fn translate_texts(
+    client: &AssemblyAiClient,
+    texts: &[String],
+    language: &str,
+    lemur_model: Option<&str>,
+) -> Result<Vec<String>, RunnerError> {
+    let mut out = Vec::with_capacity(texts.len());
+    let mut missing = 0;
+
+    for batch in texts.chunks(translation::TRANSLATION_BATCH_SIZE) {
+        let request = LemurTaskRequest {
+            prompt: translation::build_translation_prompt(language),
+            input_text: Some(translation::format_numbered_lines(batch)),
+            final_model: lemur_model.map(str::to_string),
+            ..Default::default()
+        };
+        let response = client.lemur_task(&request).await?;
+
+        for (text, translated) in batch
+            .iter()
+            .zip(translation::parse_numbered_lines(&response.response, batch.len()))
         {
-            if !value.trim().is_empty() {
-                return value;
+            match translated {
+                Some(value) => out.push(value),
+                None => {
+                    missing += 1;
+                    out.push(text.clone());
+                }
             }
         }
     }
 
+    if missing > 0 {
+        logging::warn(crate::tr!("untranslated-lines", count = missing));
+    }
+
+    Ok(out)
+}
+
+fn write_translated_output(translation: Option<&Translation>, options: &TranscribeOptions) -> Result<(), InfraError> {
+    let (Some(translation), Some(path)) = (translation, options.translated_output()) else {
+        return Ok(());
+    };
+
+    let is_vtt = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("vtt"));
+    let content = if is_vtt {
+        subtitles::format_vtt(&translation.translated, options.speaker_style())
+    } else {
+        let content = subtitles::format_srt(&translation.translated, options.speaker_style());
+        match options.srt_numbering() {
+            Some(start) => subtitles::renumber_srt(&content, start),
+            None => content,
+        }
+    };
+
+    write_file(path, options.output_encoding().encode(&content), options.output_encoding())?;
+    logging::info(crate::tr!("wrote-translated-subtitles", path = path.display().to_string()));
+    Ok(())
+}
+
+pub fn input_title(options: &TranscribeOptions) -> String {
+    let title = match options.input() {
+        Input::LocalPath(path) => path.file_stem().map(|s| s.to_string_lossy().to_string()),
+        Input::Url(url) => url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back().map(str::to_string))
+            .filter(|s| !s.is_empty()),
+    };
+    title.unwrap_or_else(|| "transcript".to_string())
+}
+
+/// The input's file name, or the last segment of its URL; what oTranscribe shows as the media to reload.
+pub fn input_file_name(options: &TranscribeOptions) -> String {
+    let name = match options.input() {
+        Input::LocalPath(path) => path.file_name().map(|s| s.to_string_lossy().to_string()),
+        Input::Url(url) => url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back().map(str::to_string))
+            .filter(|s| !s.is_empty()),
+    };
+    name.unwrap_or_else(|| input_title(options))
+}
+
+/// Speaker turns, prefixed in the `--speaker-style`, when speakers are attributed; otherwise sentences.
+pub async fn transcript_paragraphs(
+    client: &AssemblyAiClient,
+    done: &Transcript,
+    options: &TranscribeOptions,
+) -> Result<Vec<Caption>, RunnerError> {
+    if options.attributes_speakers()
+        && let Some(utterances) = diarized_utterances(done)
+        && !utterances.is_empty()
+    {
+        return Ok(utterances
+            .into_iter()
+            .map(|u| Caption {
+                start_ms: u.start_ms,
+                end_ms: u.end_ms,
+                text: format!("{}{}", options.speaker_style().prefix(&u.speaker), u.text),
+                speaker: Some(u.speaker),
+            })
+            .collect());
+    }
+    sentence_captions(client, done, options).await
+}
+
+/// Chapters when available, otherwise speaker turns, otherwise word-timed captions.
+pub fn transcript_markers(done: &Transcript, options: &TranscribeOptions) -> Vec<Marker> {
+    let chapters = transcript_chapters(done);
+    if !chapters.is_empty() {
+        return chapters
+            .into_iter()
+            .map(|chapter| Marker {
+                start_ms: chapter.start_ms,
+                end_ms: chapter.end_ms,
+                name: chapter.headline,
+                description: if chapter.summary.is_empty() { chapter.gist } else { chapter.summary },
+            })
+            .collect();
+    }
+
+    if options.attributes_speakers()
+        && let Some(utterances) = diarized_utterances(done)
+        && !utterances.is_empty()
+    {
+        return utterances
+            .into_iter()
+            .map(|u| Marker {
+                start_ms: u.start_ms,
+                end_ms: u.end_ms,
+                name: format!("Speaker {}", u.speaker),
+                description: u.text,
+            })
+            .collect();
+    }
+
+    subtitles::word_captions(&transcript_words(done), options.chars_per_caption(), None)
+        .into_iter()
+        .map(|c| Marker {
+            start_ms: c.start_ms,
+            end_ms: c.end_ms,
+            name: c.text,
+            description: String::new(),
+        })
+        .collect()
+}
+
+pub fn format_text_output(done: &Transcript, options: &TranscribeOptions) -> String {
+    if options.attributes_speakers()
+        && let Some(value) = diarized_utterances(done)
+            .as_ref()
+            .map(|u| subtitles::format_diarized_text(u, options.speaker_style(), options.paragraph_gap()))
+        && !value.trim().is_empty()
+    {
+        return value;
+    }
+
+    if let Some(gap) = options.paragraph_gap() {
+        let words = transcript_words(done);
+        if !words.is_empty() {
+            return subtitles::paragraph_words(&words, gap);
+        }
+    }
     done.text.clone().unwrap_or_default()
 }
 
-fn speaker_to_string(value: &SpeakerLabel) -> String {
+pub fn speaker_to_string(value: &SpeakerLabel) -> String {
     match value {
         SpeakerLabel::Number(n) => n.to_string(),
         SpeakerLabel::Label(s) => s.clone(),
     }
 }
 
-fn format_diarized_subtitles(done: &Transcript, options: &TranscribeOptions) -> Option<String> {
-    if !options.speaker_labels() {
-        return None;
-    }
-
-    let utterances = diarized_utterances(done)?;
-    if utterances.is_empty() {
-        return None;
-    }
+pub fn format_local_subtitles(done: &Transcript, options: &TranscribeOptions) -> Option<String> {
+    let captions = local_captions(done, options)?;
 
     let result = match options.format() {
-        TranscriptFormat::Srt => crate::domain::subtitles::format_diarized_srt(&utterances, options.chars_per_caption()),
-        TranscriptFormat::Vtt => crate::domain::subtitles::format_diarized_vtt(&utterances, options.chars_per_caption()),
-        TranscriptFormat::Text => return None,
+        TranscriptFormat::Srt => subtitles::format_srt(&captions, options.speaker_style()),
+        TranscriptFormat::Vtt => subtitles::format_vtt(&captions, options.speaker_style()),
+        _ => return None,
     };
 
     if result.trim().is_empty() {
@@ -130,7 +1494,35 @@ fn format_diarized_subtitles(done: &Transcript, options: &TranscribeOptions) ->
     }
 }
 
-fn diarized_utterances(done: &Transcript) -> Option<Vec<crate::domain::subtitles::DiarizedUtterance>> {
+fn local_captions(done: &Transcript, options: &TranscribeOptions) -> Option<Vec<Caption>> {
+    if !options.local_subtitles() {
+        return None;
+    }
+    let words = transcript_words(done);
+    let mut captions = if options.attributes_speakers() && (options.max_cps().is_none() || words.is_empty()) {
+        subtitles::diarized_captions(&diarized_utterances(done)?, options.chars_per_caption(), options.speaker_style())
+    } else if let Some(max_cps) = options.max_cps() {
+        subtitles::readable_word_captions(&words, options.chars_per_caption(), caption_speakers(options), max_cps)
+    } else {
+        subtitles::word_captions(&words, options.chars_per_caption(), caption_speakers(options))
+    };
+
+    if captions.is_empty() {
+        return None;
+    }
+
+    if let Some(max_cps) = options.max_cps() {
+        subtitles::apply_max_cps(&mut captions, max_cps);
+    }
+
+    Some(captions)
+}
+
+fn caption_speakers(options: &TranscribeOptions) -> Option<&SpeakerStyle> {
+    options.attributes_speakers().then(|| options.speaker_style())
+}
+
+pub fn diarized_utterances(done: &Transcript) -> Option<Vec<crate::domain::subtitles::DiarizedUtterance>> {
     let utterances = done.utterances.as_ref()?;
     let mut out: Vec<crate::domain::subtitles::DiarizedUtterance> = Vec::new();
 
@@ -152,6 +1544,123 @@ fn diarized_utterances(done: &Transcript) -> Option<Vec<crate::domain::subtitles
     Some(out)
 }
 
+pub fn transcript_words(done: &Transcript) -> Vec<Word> {
+    let Some(words) = done.words.as_ref() else {
+        return Vec::new();
+    };
+
+    words
+        .iter()
+        .filter_map(|word| {
+            let start_ms = word.start?;
+            let end_ms = word.end?;
+            let text = word.text.clone()?;
+            let speaker = word.speaker.as_ref().map(speaker_to_string);
+            Word::new(start_ms, end_ms, text, word.confidence, speaker)
+        })
+        .collect()
+}
+
+/// Start/end ms of each word, or of each utterance when the transcript has no word timings.
+fn speech_spans(done: &Transcript) -> Vec<(u64, u64)> {
+    let words = transcript_words(done);
+    if !words.is_empty() {
+        return words.iter().map(|word| (word.start_ms, word.end_ms)).collect();
+    }
+    done.utterances
+        .iter()
+        .flatten()
+        .filter_map(|utterance| Some((utterance.start?, utterance.end?)))
+        .collect()
+}
+
+/// The utterances of each multichannel channel, named with `--channel-names` (`channel 2` otherwise).
+fn channel_turns(done: &Transcript, options: &TranscribeOptions) -> Vec<ChannelTurn> {
+    done.utterances
+        .iter()
+        .flatten()
+        .filter_map(|utterance| {
+            let channel = speaker_to_string(utterance.channel.as_ref()?);
+            Some(ChannelTurn {
+                channel: options.channel_name(&channel).map_or_else(|| format!("channel {channel}"), str::to_string),
+                start_ms: utterance.start?,
+                end_ms: utterance.end?,
+            })
+        })
+        .collect()
+}
+
+fn transcript_sentiments(done: &Transcript) -> Vec<SentimentSegment> {
+    let Some(results) = done.sentiment_analysis_results.as_ref() else {
+        return Vec::new();
+    };
+
+    results
+        .iter()
+        .filter_map(|result| {
+            Some(SentimentSegment {
+                start_ms: result.start?,
+                end_ms: result.end?,
+                sentiment: Sentiment::parse(result.sentiment.as_deref()?)?,
+                speaker: result.speaker.as_ref().map(speaker_to_string),
+            })
+        })
+        .collect()
+}
+
+pub fn transcript_highlights(done: &Transcript) -> Vec<Highlight> {
+    let Some(result) = done.auto_highlights_result.as_ref() else {
+        return Vec::new();
+    };
+
+    result
+        .results
+        .iter()
+        .filter_map(|highlight| {
+            Some(Highlight {
+                text: highlight.text.clone()?,
+                count: highlight.count.unwrap_or(1),
+                rank: highlight.rank.unwrap_or(0.0),
+            })
+        })
+        .collect()
+}
+
+pub fn transcript_entities(done: &Transcript) -> Vec<Entity> {
+    let Some(entities) = done.entities.as_ref() else {
+        return Vec::new();
+    };
+
+    entities
+        .iter()
+        .filter_map(|entity| {
+            Some(Entity {
+                entity_type: entity.entity_type.clone()?,
+                text: entity.text.clone()?,
+            })
+        })
+        .collect()
+}
+
+pub fn transcript_chapters(done: &Transcript) -> Vec<Chapter> {
+    let Some(chapters) = done.chapters.as_ref() else {
+        return Vec::new();
+    };
+
+    chapters
+        .iter()
+        .filter_map(|chapter| {
+            Some(Chapter {
+                start_ms: chapter.start?,
+                end_ms: chapter.end?,
+                headline: chapter.headline.clone().unwrap_or_default(),
+                gist: chapter.gist.clone().unwrap_or_default(),
+                summary: chapter.summary.clone().unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum RunnerError {
     #[error(transparent)]
@@ -159,4 +1668,18 @@ pub enum RunnerError {
 
     #[error(transparent)]
     Api(#[from] ApiError),
+
+    #[error("caption check failed with {count} violation(s)")]
+    CheckFailed { count: usize },
+
+    #[error("detected language {detected} is not one of the expected languages ({expected})")]
+    UnexpectedLanguage { detected: String, expected: String },
+
+    #[error("{message}; use --on-limit warn to upload anyway")]
+    UploadLimitExceeded { message: String },
+
+    #[error("content check failed: {message}")]
+    ContentCheckFailed { message: String },
+    #[error("cancelled")]
+    Cancelled,
 }
@@ -0,0 +1,188 @@
+use std::future::Future;
+use std::sync::{Arc, Once};
+
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+
+use crate::infra::logging;
+use crate::infra::runner::RunnerError;
+use crate::infra::InfraError;
+
+/// Runs transcription jobs with bounded concurrency; Ctrl-C cancels every job at its next await point.
+///
+/// One scheduler serves the whole process; clones share its permits and cancellation.
+#[derive(Clone)]
+pub struct Scheduler {
+    permits: Arc<Semaphore>,
+    cancel: CancellationToken,
+    /// Installs the Ctrl-C handler on the first job, so commands that never run one keep the default SIGINT.
+    ctrl_c: Arc<Once>,
+}
+
+impl Scheduler {
+    /// A scheduler running at most `concurrency` background jobs, cancelled by Ctrl-C once it runs a job.
+    pub fn new(concurrency: usize) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(concurrency.max(1))),
+            cancel: CancellationToken::new(),
+            ctrl_c: Arc::new(Once::new()),
+        }
+    }
+
+    /// This scheduler with its own bound of `concurrency` background jobs, cancelled along with it.
+    pub fn with_concurrency(&self, concurrency: usize) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(concurrency.max(1))),
+            ..self.clone()
+        }
+    }
+
+    #[cfg(test)]
+    fn with_cancel(concurrency: usize, cancel: CancellationToken) -> Self {
+        let ctrl_c = Once::new();
+        ctrl_c.call_once(|| {});
+        Self {
+            permits: Arc::new(Semaphore::new(concurrency.max(1))),
+            cancel,
+            ctrl_c: Arc::new(ctrl_c),
+        }
+    }
+
+    fn handle_ctrl_c(&self) {
+        self.ctrl_c.call_once(|| {
+            let cancel = self.cancel.clone();
+            tokio::spawn(async move {
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    logging::info(crate::tr!("cancelling"));
+                    cancel.cancel();
+                }
+            });
+        });
+    }
+
+    /// Runs `job` on the current task until it finishes or the scheduler is cancelled.
+    ///
+    /// Foreground jobs don't take a permit, so they may fan out through [`Scheduler::run_all`].
+    pub async fn run<T>(&self, job: impl Future<Output = Result<T, RunnerError>>) -> Result<T, RunnerError> {
+        self.handle_ctrl_c();
+        tokio::select! {
+            _ = self.cancel.cancelled() => Err(RunnerError::Cancelled),
+            result = job => result,
+        }
+    }
+
+    /// Spawns every job as a task, at most `concurrency` at a time, and returns the results in job order.
+    ///
+    /// Jobs start in order as permits free up, so with one permit they run one after another. The first failure
+    /// aborts the remaining jobs.
+    pub async fn run_all<T, F>(&self, jobs: Vec<F>) -> Result<Vec<T>, RunnerError>
+    where
+        T: Send + 'static,
+        F: Future<Output = Result<T, RunnerError>> + Send + 'static,
+    {
+        self.handle_ctrl_c();
+        let join_error = |err: tokio::task::JoinError| InfraError::Io(std::io::Error::other(err));
+        let mut tasks = tokio::task::JoinSet::new();
+        let mut results: Vec<Option<T>> = (0..jobs.len()).map(|_| None).collect();
+        for (idx, job) in jobs.into_iter().enumerate() {
+            let permit = loop {
+                tokio::select! {
+                    _ = self.cancel.cancelled() => return Err(RunnerError::Cancelled),
+                    permit = Arc::clone(&self.permits).acquire_owned() => {
+                        break permit.map_err(|err| InfraError::Io(std::io::Error::other(err)))?;
+                    }
+                    Some(joined) = tasks.join_next() => {
+                        let (idx, result) = joined.map_err(join_error)?;
+                        results[idx] = Some(result?);
+                    }
+                }
+            };
+            let cancel = self.cancel.clone();
+            tasks.spawn(async move {
+                let _permit = permit;
+                let result = tokio::select! {
+                    _ = cancel.cancelled() => Err(RunnerError::Cancelled),
+                    result = job => result,
+                };
+                (idx, result)
+            });
+        }
+
+        while let Some(joined) = tasks.join_next().await {
+            let (idx, result) = joined.map_err(join_error)?;
+            results[idx] = Some(result?);
+        }
+        Ok(results.into_iter().flatten().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn bounds_concurrency_and_keeps_job_order() {
+        let scheduler = Scheduler::with_cancel(2, CancellationToken::new());
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let jobs: Vec<_> = (0..6u64)
+            .map(|idx| {
+                let running = Arc::clone(&running);
+                let peak = Arc::clone(&peak);
+                async move {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(20 - idx * 3)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    Ok::<_, RunnerError>(idx)
+                }
+            })
+            .collect();
+
+        assert_eq!(scheduler.run_all(jobs).await.unwrap(), vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn cancellation_stops_running_jobs() {
+        let cancel = CancellationToken::new();
+        let scheduler = Scheduler::with_cancel(1, cancel.clone());
+        cancel.cancel();
+
+        let pending = scheduler.run(std::future::pending::<Result<(), RunnerError>>()).await;
+        assert!(matches!(pending, Err(RunnerError::Cancelled)));
+        let jobs = vec![std::future::pending::<Result<(), RunnerError>>()];
+        assert!(matches!(scheduler.run_all(jobs).await, Err(RunnerError::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn one_cancellation_reaches_every_copy_and_queued_job() {
+        let cancel = CancellationToken::new();
+        let scheduler = Scheduler::with_cancel(1, cancel.clone());
+        let compare = scheduler.with_concurrency(3);
+        let started = Arc::new(AtomicUsize::new(0));
+        let jobs: Vec<_> = (0..3)
+            .map(|_| {
+                let started = Arc::clone(&started);
+                async move {
+                    started.fetch_add(1, Ordering::SeqCst);
+                    std::future::pending::<Result<(), RunnerError>>().await
+                }
+            })
+            .collect();
+        let batch = tokio::spawn({
+            let scheduler = scheduler.clone();
+            async move { scheduler.run_all(jobs).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        cancel.cancel();
+
+        assert!(matches!(batch.await.unwrap(), Err(RunnerError::Cancelled)));
+        assert_eq!(started.load(Ordering::SeqCst), 1);
+        let pending = compare.run(std::future::pending::<Result<(), RunnerError>>()).await;
+        assert!(matches!(pending, Err(RunnerError::Cancelled)));
+    }
+}
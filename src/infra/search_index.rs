@@ -0,0 +1,118 @@
+//! `index build|search`: a SQLite FTS5 full-text index of the cached transcripts' sentences, ranked with BM25.
+
+use std::path::Path;
+
+use rusqlite::{params, Connection, OpenFlags};
+
+use crate::domain::grep::{self, GrepMatch};
+use crate::infra::assemblyai::Transcript;
+use crate::infra::runner::transcript_words;
+use crate::infra::InfraError;
+
+const SCHEMA: &str = "
+CREATE VIRTUAL TABLE sentences USING fts5(
+    text,
+    transcript_id UNINDEXED,
+    start_ms UNINDEXED,
+    speaker UNINDEXED,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+";
+
+/// A sentence of a search hit, with the transcript it is from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchHit {
+    pub transcript_id: String,
+    pub sentence: GrepMatch,
+}
+
+/// Replaces the index at `path` with one of `transcripts`; returns how many transcripts and sentences it holds.
+///
+/// The index is built next to `path` and renamed over it, so searches never see a half-built index.
+pub fn build(path: &Path, transcripts: impl IntoIterator<Item = Transcript>) -> Result<(usize, usize), InfraError> {
+    let index_error = |err: rusqlite::Error| InfraError::SearchIndex {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    };
+
+    let temp = path.with_extension("db.tmp");
+    match std::fs::remove_file(&temp) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    let mut conn = Connection::open(&temp).map_err(index_error)?;
+    conn.execute_batch(SCHEMA).map_err(index_error)?;
+    let tx = conn.transaction().map_err(index_error)?;
+    let mut counts = (0, 0);
+    {
+        let mut insert = tx
+            .prepare("INSERT INTO sentences (text, transcript_id, start_ms, speaker) VALUES (?1, ?2, ?3, ?4)")
+            .map_err(index_error)?;
+        for done in transcripts {
+            let words = transcript_words(&done);
+            let sentences: Vec<GrepMatch> = if words.is_empty() {
+                grep::text_sentences(done.text.as_deref().unwrap_or_default())
+                    .map(|text| GrepMatch {
+                        start_ms: None,
+                        speaker: None,
+                        text: text.to_string(),
+                    })
+                    .collect()
+            } else {
+                grep::sentences(&words)
+                    .into_iter()
+                    .map(|sentence| GrepMatch {
+                        start_ms: Some(sentence[0].start_ms),
+                        speaker: sentence[0].speaker.clone(),
+                        text: sentence.iter().map(|word| word.text.as_str()).collect::<Vec<_>>().join(" "),
+                    })
+                    .collect()
+            };
+            for sentence in &sentences {
+                insert
+                    .execute(params![sentence.text, done.id, sentence.start_ms.map(|ms| ms as i64), sentence.speaker])
+                    .map_err(index_error)?;
+            }
+            counts.0 += 1;
+            counts.1 += sentences.len();
+        }
+    }
+    tx.commit().map_err(index_error)?;
+    drop(conn);
+    std::fs::rename(&temp, path)?;
+    Ok(counts)
+}
+
+/// The `limit` sentences matching `query` (see [`grep::fts_query`]) best, best first.
+pub fn search(path: &Path, query: &str, limit: usize) -> Result<Vec<SearchHit>, InfraError> {
+    if !path.exists() {
+        return Err(InfraError::SearchIndexMissing { path: path.to_path_buf() });
+    }
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(|err| InfraError::SearchIndex {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })?;
+    // Anything failing past here is the query's syntax, which FTS5 only checks when it runs.
+    let query_error = |err: rusqlite::Error| InfraError::InvalidSearchQuery {
+        query: query.to_string(),
+        message: err.to_string(),
+    };
+    let mut select = conn
+        .prepare(
+            "SELECT transcript_id, start_ms, speaker, text FROM sentences WHERE sentences MATCH ?1 ORDER BY rank LIMIT ?2",
+        )
+        .map_err(query_error)?;
+    let rows = select
+        .query_map(params![grep::fts_query(query), limit as i64], |row| {
+            Ok(SearchHit {
+                transcript_id: row.get(0)?,
+                sentence: GrepMatch {
+                    start_ms: row.get::<_, Option<i64>>(1)?.map(|ms| ms as u64),
+                    speaker: row.get(2)?,
+                    text: row.get(3)?,
+                },
+            })
+        })
+        .map_err(query_error)?;
+    rows.collect::<Result<_, _>>().map_err(query_error)
+}
@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use rusqlite::{params, Connection};
+
+use crate::domain::batch::portable_input;
+use crate::domain::{Input, TranscribeOptions};
+use crate::infra::assemblyai::Transcript;
+use crate::infra::logging;
+use crate::infra::runner::speaker_to_string;
+use crate::infra::InfraError;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS transcripts (
+    id TEXT PRIMARY KEY,
+    input TEXT NOT NULL,
+    speech_model TEXT NOT NULL,
+    language_code TEXT,
+    language_confidence REAL,
+    confidence REAL,
+    duration_ms INTEGER,
+    text TEXT,
+    created_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS speakers (
+    transcript_id TEXT NOT NULL REFERENCES transcripts(id),
+    speaker TEXT NOT NULL,
+    utterance_count INTEGER NOT NULL,
+    talk_time_ms INTEGER NOT NULL,
+    PRIMARY KEY (transcript_id, speaker)
+);
+CREATE TABLE IF NOT EXISTS utterances (
+    transcript_id TEXT NOT NULL REFERENCES transcripts(id),
+    idx INTEGER NOT NULL,
+    speaker TEXT,
+    start_ms INTEGER,
+    end_ms INTEGER,
+    text TEXT NOT NULL,
+    PRIMARY KEY (transcript_id, idx)
+);
+CREATE TABLE IF NOT EXISTS words (
+    transcript_id TEXT NOT NULL REFERENCES transcripts(id),
+    idx INTEGER NOT NULL,
+    speaker TEXT,
+    start_ms INTEGER,
+    end_ms INTEGER,
+    confidence REAL,
+    text TEXT NOT NULL,
+    PRIMARY KEY (transcript_id, idx)
+);
+";
+
+/// Adds the transcript to the database at `path`, creating the tables on first use.
+///
+/// Rows of an earlier export of the same transcript id are replaced, so re-running a job never duplicates data.
+pub fn write_transcript(path: &Path, done: &Transcript, options: &TranscribeOptions) -> Result<(), InfraError> {
+    let database_error = |err: rusqlite::Error| InfraError::DatabaseWrite {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    };
+
+    let mut conn = Connection::open(path).map_err(database_error)?;
+    conn.execute_batch(SCHEMA).map_err(database_error)?;
+    let tx = conn.transaction().map_err(database_error)?;
+    insert_transcript(&tx, done, options).map_err(database_error)?;
+    tx.commit().map_err(database_error)?;
+
+    logging::info(crate::tr!("wrote-transcript-database", id = done.id.to_string(), path = path.display().to_string()));
+    Ok(())
+}
+
+fn insert_transcript(tx: &rusqlite::Transaction, done: &Transcript, options: &TranscribeOptions) -> rusqlite::Result<()> {
+    let id = done.id.as_str();
+    for table in ["words", "utterances", "speakers"] {
+        tx.execute(&format!("DELETE FROM {table} WHERE transcript_id = ?1"), [id])?;
+    }
+    tx.execute("DELETE FROM transcripts WHERE id = ?1", [id])?;
+
+    let input = match options.input() {
+        Input::LocalPath(path) if options.deterministic() => {
+            let cwd = std::env::current_dir().unwrap_or_default();
+            PathBuf::from(portable_input(path.as_os_str(), &cwd)).display().to_string()
+        }
+        Input::LocalPath(path) => path.display().to_string(),
+        Input::Url(url) => url.to_string(),
+    };
+    let duration_ms = done.words.iter().flatten().filter_map(|w| w.end).max();
+    // `--deterministic` rows carry the epoch rather than the time of the run.
+    let created_at = if options.deterministic() {
+        0
+    } else {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    };
+    tx.execute(
+        "INSERT INTO transcripts (id, input, speech_model, language_code, language_confidence, confidence, duration_ms, text, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            id,
+            input,
+            options.speech_model().name(),
+            done.language_code,
+            done.language_confidence,
+            done.confidence,
+            duration_ms.map(|ms| ms as i64),
+            done.text,
+            created_at as i64,
+        ],
+    )?;
+
+    let mut speakers: BTreeMap<String, (i64, i64)> = BTreeMap::new();
+    let mut insert = tx.prepare(
+        "INSERT INTO utterances (transcript_id, idx, speaker, start_ms, end_ms, text) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+    for (idx, utterance) in done.utterances.iter().flatten().enumerate() {
+        let speaker = utterance.speaker.as_ref().map(speaker_to_string);
+        if let Some(speaker) = &speaker {
+            let entry = speakers.entry(speaker.clone()).or_default();
+            entry.0 += 1;
+            entry.1 += utterance.end.unwrap_or(0).saturating_sub(utterance.start.unwrap_or(0)) as i64;
+        }
+        insert.execute(params![
+            id,
+            idx as i64,
+            speaker,
+            utterance.start.map(|ms| ms as i64),
+            utterance.end.map(|ms| ms as i64),
+            utterance.text.as_deref().unwrap_or_default(),
+        ])?;
+    }
+
+    let mut insert = tx.prepare(
+        "INSERT INTO speakers (transcript_id, speaker, utterance_count, talk_time_ms) VALUES (?1, ?2, ?3, ?4)",
+    )?;
+    for (speaker, (count, talk_time_ms)) in &speakers {
+        insert.execute(params![id, speaker, count, talk_time_ms])?;
+    }
+
+    let mut insert = tx.prepare(
+        "INSERT INTO words (transcript_id, idx, speaker, start_ms, end_ms, confidence, text) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+    )?;
+    for (idx, word) in done.words.iter().flatten().enumerate() {
+        insert.execute(params![
+            id,
+            idx as i64,
+            word.speaker.as_ref().map(speaker_to_string),
+            word.start.map(|ms| ms as i64),
+            word.end.map(|ms| ms as i64),
+            word.confidence,
+            word.text.as_deref().unwrap_or_default(),
+        ])?;
+    }
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use std::io::{Read, Write};
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::infra::InfraError;
+
+/// The GitHub API endpoint of the newest published release.
+pub const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/diskd-ai/assemblyai-cli/releases/latest";
+
+#[derive(Debug, serde::Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+impl Release {
+    pub fn asset(&self, name: &str) -> Option<&ReleaseAsset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+}
+
+/// Reads releases from `release_url` and downloads their assets; asset URLs may be relative to it.
+pub struct ReleaseClient {
+    release_url: url::Url,
+    http: reqwest::Client,
+}
+
+impl ReleaseClient {
+    pub fn new(release_url: &str) -> Result<Self, InfraError> {
+        let release_url = url::Url::parse(release_url).map_err(|err| failed(format!("invalid release URL {release_url:?}: {err}")))?;
+        // GitHub rejects API requests without a User-Agent.
+        let http = reqwest::Client::builder()
+            .user_agent(concat!("assemblyai-cli/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|err| failed(err.to_string()))?;
+        Ok(Self { release_url, http })
+    }
+
+    pub async fn latest(&self) -> Result<Release, InfraError> {
+        let body = self.get(self.release_url.clone()).await?;
+        serde_json::from_slice(&body).map_err(|err| failed(format!("unexpected release JSON from {}: {err}", self.release_url)))
+    }
+
+    pub async fn download(&self, asset: &ReleaseAsset) -> Result<Vec<u8>, InfraError> {
+        let url = self
+            .release_url
+            .join(&asset.browser_download_url)
+            .map_err(|err| failed(format!("invalid download URL for {}: {err}", asset.name)))?;
+        self.get(url).await
+    }
+
+    async fn get(&self, url: url::Url) -> Result<Vec<u8>, InfraError> {
+        let resp = self
+            .http
+            .get(url.clone())
+            .send()
+            .await
+            .map_err(|err| failed(format!("{url}: {err}")))?;
+        if !resp.status().is_success() {
+            return Err(failed(format!("{url} returned HTTP {}", resp.status())));
+        }
+        let body = resp.bytes().await.map_err(|err| failed(format!("{url}: {err}")))?;
+        Ok(body.to_vec())
+    }
+}
+
+/// Lowercase hex SHA-256 of `bytes`, as in the release `.sha256` files.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// The `binary_name` file inside a `.tar.gz` release archive.
+pub fn unpack_binary(archive: &[u8], asset: &str, binary_name: &str) -> Result<Vec<u8>, InfraError> {
+    if !asset.ends_with(".tar.gz") {
+        return Err(failed(format!("can't unpack {asset}; download it from the release page and replace the binary by hand")));
+    }
+    let unreadable = |err: std::io::Error| failed(format!("can't read {asset}: {err}"));
+    let mut entries = tar::Archive::new(flate2::read::GzDecoder::new(archive));
+    for entry in entries.entries().map_err(unreadable)? {
+        let mut entry = entry.map_err(unreadable)?;
+        if entry.path().map_err(unreadable)?.file_name().is_some_and(|name| name == binary_name) {
+            let mut binary = Vec::new();
+            entry.read_to_end(&mut binary).map_err(unreadable)?;
+            return Ok(binary);
+        }
+    }
+    Err(failed(format!("{asset} has no {binary_name}")))
+}
+
+/// Atomically replaces the executable at `exe` with `binary`.
+///
+/// The new file is written next to `exe` and renamed over it, so a failed update leaves the old binary in place.
+pub fn replace_exe(exe: &Path, binary: &[u8]) -> Result<(), InfraError> {
+    let dir = exe.parent().unwrap_or(Path::new("."));
+    let denied = |err: std::io::Error| failed(format!("can't replace {}: {err}", exe.display()));
+    let mut staged = tempfile::NamedTempFile::new_in(dir).map_err(denied)?;
+    staged.write_all(binary).map_err(denied)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::set_permissions(staged.path(), std::fs::Permissions::from_mode(0o755)).map_err(denied)?;
+    }
+    staged.persist(exe).map_err(|err| denied(err.error))?;
+    Ok(())
+}
+
+fn failed(message: String) -> InfraError {
+    InfraError::SelfUpdate { message }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unpacks_the_binary_from_a_release_archive() {
+        let mut archive = tar::Builder::new(flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default()));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(7);
+        header.set_mode(0o755);
+        header.set_cksum();
+        archive.append_data(&mut header, "assemblyai-cli", &b"new-bin"[..]).unwrap();
+        let archive = archive.into_inner().unwrap().finish().unwrap();
+
+        let asset = "assemblyai-cli-0.2.0-x86_64-unknown-linux-gnu.tar.gz";
+        assert_eq!(unpack_binary(&archive, asset, "assemblyai-cli").unwrap(), b"new-bin");
+        assert!(unpack_binary(&archive, asset, "other").is_err());
+        assert!(unpack_binary(&archive, "assemblyai-cli-0.2.0-x86_64-pc-windows-msvc.zip", "assemblyai-cli.exe").is_err());
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let exe = dir.path().join("assemblyai-cli");
+        std::fs::write(&exe, b"old-bin").unwrap();
+        replace_exe(&exe, b"new-bin").unwrap();
+        assert_eq!(std::fs::read(&exe).unwrap(), b"new-bin");
+    }
+}
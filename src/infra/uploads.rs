@@ -0,0 +1,70 @@
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sha2::{Digest, Sha256};
+
+use crate::domain::uploads::{UploadIndex, UploadRecord, UploadScope};
+use crate::infra::lock::StateLock;
+use crate::infra::InfraError;
+
+/// Lowercase hex SHA-256 of the contents of `path`, read in chunks so multi-gigabyte files aren't held in memory.
+pub fn file_sha256(path: &Path) -> Result<String, InfraError> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut std::fs::File::open(path)?, &mut hasher)?;
+    Ok(hex(&hasher.finalize()))
+}
+
+/// Lowercase hex of a digest.
+pub fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// The reusable `upload_url` of content `sha256` made within `scope`, recorded in the index at `path`.
+pub fn find(path: &Path, sha256: &str, scope: &UploadScope) -> Option<String> {
+    load(path).find(sha256, scope, now()).map(str::to_string)
+}
+
+/// Records an upload of content `sha256` made within `scope` in the index at `path`.
+pub fn record(path: &Path, sha256: String, scope: UploadScope, bytes: u64, upload_url: String) -> Result<(), InfraError> {
+    update(path, |index| {
+        index.record(UploadRecord {
+            sha256,
+            scope,
+            bytes,
+            upload_url,
+            uploaded_at: now(),
+        });
+        true
+    })
+}
+
+/// Drops `upload_url` from the index at `path`, so the next run uploads its file again.
+pub fn forget(path: &Path, upload_url: &str) -> Result<(), InfraError> {
+    update(path, |index| index.forget(upload_url))
+}
+
+fn update(path: &Path, change: impl FnOnce(&mut UploadIndex) -> bool) -> Result<(), InfraError> {
+    let _lock = StateLock::acquire(path)?;
+    let mut index = load(path);
+    if !change(&mut index) {
+        return Ok(());
+    }
+    let mut contents = serde_json::to_string_pretty(&index).map_err(std::io::Error::other)?;
+    contents.push('\n');
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, contents)?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
+
+/// The index is only a cache: a missing or unreadable one is empty and is replaced by the next upload.
+fn load(path: &Path) -> UploadIndex {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
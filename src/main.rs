@@ -2,6 +2,7 @@ mod app;
 mod domain;
 mod infra;
 
+use std::ffi::{OsStr, OsString};
 use std::path::PathBuf;
 use std::process::ExitCode;
 use std::time::Duration;
@@ -9,6 +10,7 @@ use std::time::Duration;
 use base64::Engine;
 use clap::{Args, Parser, Subcommand, ValueEnum};
 
+use crate::domain::secret::ApiKey;
 use crate::domain::{CustomSpelling, TranscriptFormat, TranscribeOptions};
 
 #[derive(Parser, Debug)]
@@ -27,6 +29,7 @@ API KEY RESOLUTION ORDER
 
 ENV VARS
   ASSEMBLYAI_BASE_URL (optional; default https://api.assemblyai.com)
+  ASSEMBLYAI_CLI_RELEASES_URL (optional; release JSON for self-update, default the latest GitHub release)
 
 EXAMPLES
   assemblyai-cli init
@@ -34,11 +37,68 @@ EXAMPLES
   assemblyai-cli transcribe ./video.mp4 --format srt --output ./video.srt
   assemblyai-cli transcribe ./file.mp3 --speaker-labels
   assemblyai-cli transcribe https://example.com/audio.wav --format vtt
+  assemblyai-cli lint ./video.srt
 "#
 )]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Fail with exit code 3 when the config file has keys assemblyai-cli doesn't recognize, instead of warning"
+    )]
+    strict_config: bool,
+
+    #[arg(
+        long = "header",
+        global = true,
+        value_name = "NAME: VALUE",
+        value_parser = parse_header,
+        help = "Extra HTTP header sent with every API request (repeatable), e.g. \"X-Org-Id: 42\"; added to config `extraHeaders`"
+    )]
+    headers: Vec<(String, String)>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value = "text",
+        help = "How progress, warnings, and errors are logged: text lines, or one JSON object per event with level, timestamp, message, transcript_id, and file"
+    )]
+    log_format: LogFormatArg,
+
+    #[arg(long, global = true, value_name = "PATH", help = "Append logs to PATH instead of stderr")]
+    log_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "SIZE",
+        requires = "log_file",
+        help = "Rotate --log-file to PATH.1 once it would grow past SIZE, e.g. 10M; when omitted, the file is never rotated"
+    )]
+    log_max_size: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        default_value_t = 5,
+        requires = "log_max_size",
+        help = "Rotated log files to keep, PATH.1 (newest) through PATH.N"
+    )]
+    log_keep: usize,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "LOCALE",
+        value_parser = parse_locale,
+        help = "Language of progress and warning messages (en, ru); when omitted, taken from LC_ALL, LC_MESSAGES, or LANG"
+    )]
+    locale: Option<String>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -47,13 +107,152 @@ enum Commands {
         about = "Transcribe a single file or URL",
         long_about = "Transcribe a single local audio/video file or HTTP(S) URL.\n\nFor local video files, ffmpeg must be available on PATH so the CLI can extract audio.\n"
     )]
-    Transcribe(TranscribeArgs),
+    Transcribe(Box<TranscribeArgs>),
 
     #[command(
         about = "Initialize ~/.assemblyai-cli config",
-        long_about = "Interactively initialize the AssemblyAI CLI configuration.\n\nThis command prompts for an API key and writes it to ~/.assemblyai-cli/config.json (preferred) or ~/.assemblyai-cli (legacy).\n"
+        long_about = "Interactively initialize the AssemblyAI CLI configuration.\n\nThis command prompts for an API key and writes it to ~/.assemblyai-cli/config.json (preferred) or ~/.assemblyai-cli (legacy).\nWith --api-key it runs without prompting, e.g. `init --api-key KEY --format srt --yes` in provisioning scripts.\n"
     )]
     Init(InitArgs),
+
+    #[command(
+        about = "Check SRT/VTT captions for quality issues",
+        long_about = "Validate SRT/VTT subtitle files for overlapping cues, reading speed, long lines, too many lines per cue, and short gaps.\n\nViolations are printed as PATH:LINE: RULE: MESSAGE. Exits with code 6 when any violation is found.\n"
+    )]
+    Lint(LintArgs),
+
+    #[command(
+        about = "Merge SRT/VTT files of separate runs into one",
+        long_about = "Join subtitle files transcribed in parts, moving each file's cues to where the part starts in the whole recording.\n\nWithout --offsets, each file starts where the previous file's last cue ends.\n"
+    )]
+    Merge(MergeArgs),
+
+    #[command(
+        about = "Fetch an existing transcript's SRT/VTT export",
+        long_about = "Download the API's SRT/VTT export of a completed transcript, split at --chars-per-caption.\n\nTrying another caption width needs no new transcription; the captions are the API's, without local options such as speaker prefixes.\n"
+    )]
+    Render(RenderArgs),
+
+    #[command(
+        about = "Translate an existing transcript via LeMUR",
+        long_about = "Fetch a completed transcript by id and translate it with LeMUR.\n\nUtterance timestamps and speaker labels are kept; transcripts without speaker labels are segmented from word timings.\n"
+    )]
+    Translate(TranslateArgs),
+
+    #[command(
+        about = "Transcribe a meeting and write markdown minutes",
+        long_about = "Preset for meeting recordings: transcribes INPUT with speaker labels and key phrase detection, then asks LeMUR for minutes.\n\nThe markdown lists attendees by speaker, key topics, a summary, decisions, and action items with owners. Equivalent to `transcribe --speaker-labels --auto-highlights --format meeting-notes`.\n"
+    )]
+    Meeting(PresetArgs),
+
+    #[command(
+        about = "Transcribe a podcast episode and write show notes",
+        long_about = "Preset for podcast episodes: transcribes INPUT with auto chapters, key phrases, and entity detection, then asks LeMUR for a summary and key quotes.\n\nThe markdown contains the episode summary, a timestamped chapter list, key quotes, and guest names. Equivalent to `transcribe --auto-chapters --auto-highlights --entity-detection --format show-notes`.\n"
+    )]
+    Shownotes(PresetArgs),
+
+    #[command(
+        about = "Score a transcript against a reference (WER/CER)",
+        long_about = "Compute word and character error rates of a hypothesis transcript against a reference text, with a word alignment.\n\nHYPOTHESIS is a transcript JSON file (as returned by the API), a plain text file, or a transcript id to fetch. Text is lowercased and stripped of surrounding punctuation before scoring.\n"
+    )]
+    Eval(EvalArgs),
+
+    #[command(
+        about = "Search transcripts with a regular expression",
+        long_about = "Print every sentence of the given transcripts that matches PATTERN, as SOURCE:[hh:mm:ss] Speaker A: sentence.\n\nA SOURCE is a transcript JSON file (as returned by the API), or a transcript id or alias, read from the local cache or fetched from the API. Without sources, searches every transcript in the cache, so no API key is needed.\nPATTERN uses Rust regex syntax and may span words within a sentence.\n"
+    )]
+    Grep(GrepArgs),
+
+    #[command(
+        about = "Build and search a full-text index of cached transcripts",
+        long_about = "Maintain ~/.assemblyai-cli/index.db, a full-text index of the sentences of the transcripts in the local cache.\n\n`index build` rebuilds it from the cache; run it again after caching more transcripts. `index search QUERY` prints the best-matching sentences, best first, as TRANSCRIPT_ID:[hh:mm:ss] Speaker A: sentence. No API key is needed.\n"
+    )]
+    Index(IndexArgs),
+
+    #[command(
+        name = "compare-models",
+        about = "Transcribe the same input with several speech models and compare",
+        long_about = "Upload INPUT once, transcribe it with each --speech-model concurrently, then print a per-model summary (confidence, word count, WER) and a word diff of each model against the first.\n\nWER is measured against --reference when given, otherwise against the first model.\n"
+    )]
+    CompareModels(CompareModelsArgs),
+
+    #[command(
+        about = "Measure end-to-end transcription latency per speech model",
+        long_about = "Transcribe INPUT --runs times with each of --models, one transcription at a time, and print how long each spent uploading, queued, and processing, followed by each model's mean.\n\nQueue and processing times are measured by polling, so they are accurate to --poll-interval-seconds. With --json, prints the runs and means as JSON (see `schema output`).\n"
+    )]
+    Bench(BenchArgs),
+
+    #[command(
+        about = "Print a temporary realtime token",
+        long_about = "Create a short-lived token for the realtime (streaming) API and print it to stdout.\n\nHand the token to browser or front-end clients instead of the API key; it stops working after --expires-in seconds.\n"
+    )]
+    Token(TokenArgs),
+
+    #[command(
+        about = "Upload a local file and print its upload URL",
+        long_about = "Upload FILE as is, without transcribing it, and print the upload URL the API returned, e.g. to pass to `transcribe --from-upload-url` or to create the transcript with another tool.\n\nLike `transcribe`, reuses an upload of the same content from the last 24 hours unless --no-upload-reuse is given. With --json, prints the input, upload URL, size, and SHA-256 as JSON (see `schema output`).\n"
+    )]
+    Upload(UploadArgs),
+
+    #[command(
+        about = "List supported language codes",
+        long_about = "Print the language codes accepted by --language, with their names, the speech models that transcribe them, and the features they support.\n\nUse --feature to list only languages supporting that feature (repeatable). No API key is needed.\n"
+    )]
+    Languages(LanguagesArgs),
+
+    #[command(
+        about = "List transcripts submitted to the account",
+        long_about = "Print the account's transcripts from the API, newest first: id, status, creation time (UTC), and audio URL, followed by the total count per status.\n\nUse --created-after/--created-before to audit a billing period and --all to page through every matching transcript instead of the first --limit.\n"
+    )]
+    List(ListArgs),
+
+    #[command(
+        about = "Delete transcripts created before a date",
+        long_about = "Page through the account's transcripts and delete those created before --before (and with --status, if given) from the API, printing progress as it goes.\n\nWithout --before, deletes the transcripts this tool created (as recorded in its history and batch state) that are older than config `retentionDays`, so a scheduled `delete --yes` keeps them within a retention policy; transcripts from other apps on the account are left alone.\nUse --dry-run to only print what would be deleted. Without --yes, asks for confirmation first. Deleted transcripts are also removed from the local cache.\n"
+    )]
+    Delete(DeleteArgs),
+
+    #[command(
+        about = "List past transcription runs and re-run them",
+        long_about = "Print recorded transcribe, meeting, and shownotes runs, newest first: id, start time (UTC), duration, options hash, transcript id, input, outputs, and tags.\n\nRuns are recorded in ~/.assemblyai-cli/history.jsonl. Use `history rerun ID` to run one again with the same arguments.\n"
+    )]
+    History(HistoryArgs),
+
+    #[command(
+        about = "Name transcript ids with local aliases",
+        long_about = "Manage human-friendly names for transcript ids, stored in ~/.assemblyai-cli/aliases.json.\n\nCommands that take a transcript id (render, translate, eval, grep) also accept an alias.\n"
+    )]
+    Alias(AliasArgs),
+
+    #[command(
+        about = "Maintain the config file",
+        long_about = "Maintenance commands for ~/.assemblyai-cli/config.json.\n\n`config migrate` upgrades an older config to the current schema (configVersion) after backing it up; a legacy ~/.assemblyai-cli file moves to ~/.assemblyai-cli/config.json.\n`config fix-permissions` makes the config (which may hold apiKey in plain text) readable by its owner only.\n"
+    )]
+    Config(ConfigArgs),
+
+    #[command(
+        about = "Manage the local transcript cache",
+        long_about = "Manage ~/.assemblyai-cli/cache, where completed transcripts read by id (translate, eval, grep) are kept so reading them again skips the API.\n\nConfig `maxCacheBytes` caps its size; the least recently used transcripts are evicted first.\n"
+    )]
+    Cache(CacheArgs),
+
+    #[command(
+        about = "Update assemblyai-cli to the latest release",
+        long_about = "Check GitHub releases for a newer assemblyai-cli, download the archive for this platform, verify its SHA-256 checksum, and replace the running binary in place.\n\nUse --check to only report whether an update is available. Installs from Homebrew or cargo should be updated with those tools instead.\n"
+    )]
+    SelfUpdate(SelfUpdateArgs),
+
+    #[command(
+        about = "Print the JSON Schema of the config file or the JSON outputs",
+        long_about = "Print a JSON Schema (draft 2020-12) to stdout.\n\n`schema config` describes ~/.assemblyai-cli/config.json, including presets and byExtension rules; point an editor at it (or set \"$schema\" in the config) for completion and validation. The config is also checked against it whenever it is loaded.\n`schema output` describes the JSON the CLI writes: `version --json`, `--failures-file`, `.meta.json` sidecars, `bench --json`, and `upload --json`.\n"
+    )]
+    Schema(SchemaArgs),
+
+    #[command(
+        about = "Print version and build information",
+        long_about = "Print the version, git commit, build date, target triple, enabled cargo features, and the API base URL in use (ASSEMBLYAI_BASE_URL, then config `baseUrl`, then the default).\n\nUse --json for a machine-readable object to attach to bug reports.\n"
+    )]
+    Version(VersionArgs),
 }
 
 #[derive(Clone, Debug, ValueEnum)]
@@ -61,6 +260,16 @@ enum FormatArg {
     Text,
     Srt,
     Vtt,
+    SrtDual,
+    Edl,
+    PremiereMarkersCsv,
+    AnkiTsv,
+    Otr,
+    MeetingNotes,
+    ShowNotes,
+    Sqlite,
+    Parquet,
+    Json,
 }
 
 impl From<FormatArg> for TranscriptFormat {
@@ -69,6 +278,257 @@ impl From<FormatArg> for TranscriptFormat {
             FormatArg::Text => TranscriptFormat::Text,
             FormatArg::Srt => TranscriptFormat::Srt,
             FormatArg::Vtt => TranscriptFormat::Vtt,
+            FormatArg::SrtDual => TranscriptFormat::SrtDual,
+            FormatArg::Edl => TranscriptFormat::Edl,
+            FormatArg::PremiereMarkersCsv => TranscriptFormat::PremiereMarkersCsv,
+            FormatArg::AnkiTsv => TranscriptFormat::AnkiTsv,
+            FormatArg::Otr => TranscriptFormat::Otr,
+            FormatArg::MeetingNotes => TranscriptFormat::MeetingNotes,
+            FormatArg::ShowNotes => TranscriptFormat::ShowNotes,
+            FormatArg::Sqlite => TranscriptFormat::Sqlite,
+            FormatArg::Parquet => TranscriptFormat::Parquet,
+            FormatArg::Json => TranscriptFormat::Json,
+        }
+    }
+}
+
+/// `--format` value: a built-in format or `exec:COMMAND`.
+#[derive(Clone, Debug)]
+enum FormatValue {
+    Builtin(FormatArg),
+    Exec(String),
+}
+
+fn parse_header(value: &str) -> Result<(String, String), String> {
+    let (name, header_value) = value
+        .split_once(':')
+        .ok_or_else(|| format!("expected \"NAME: VALUE\", got {value:?}"))?;
+    let (name, header_value) = (name.trim(), header_value.trim());
+    reqwest::header::HeaderName::from_bytes(name.as_bytes()).map_err(|_| format!("invalid header name {name:?}"))?;
+    reqwest::header::HeaderValue::from_str(header_value).map_err(|_| format!("invalid value for header {name:?}"))?;
+    Ok((name.to_string(), header_value.to_string()))
+}
+
+fn parse_locale(value: &str) -> Result<String, String> {
+    match infra::i18n::catalog_language(value) {
+        Some(language) => Ok(language.to_string()),
+        None => Err(format!(
+            "no messages for {value:?}; available: {}",
+            infra::i18n::languages().collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
+fn parse_format(value: &str) -> Result<FormatValue, String> {
+    match value.strip_prefix("exec:") {
+        Some(command) => Ok(FormatValue::Exec(command.to_string())),
+        None => <FormatArg as ValueEnum>::from_str(value, true)
+            .map(FormatValue::Builtin)
+            .map_err(|_| format!("unknown format {value:?}; see `transcribe --list-formats`")),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum MergeFormatArg {
+    Srt,
+    Vtt,
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum TranslateFormatArg {
+    #[value(alias = "txt")]
+    Text,
+    Srt,
+    Vtt,
+}
+
+impl From<TranslateFormatArg> for TranscriptFormat {
+    fn from(value: TranslateFormatArg) -> Self {
+        match value {
+            TranslateFormatArg::Text => TranscriptFormat::Text,
+            TranslateFormatArg::Srt => TranscriptFormat::Srt,
+            TranslateFormatArg::Vtt => TranscriptFormat::Vtt,
+        }
+    }
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum SplitOutputByArg {
+    Chapter,
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum LogFormatArg {
+    Text,
+    Json,
+}
+
+impl From<LogFormatArg> for infra::logging::LogFormat {
+    fn from(value: LogFormatArg) -> Self {
+        match value {
+            LogFormatArg::Text => infra::logging::LogFormat::Text,
+            LogFormatArg::Json => infra::logging::LogFormat::Json,
+        }
+    }
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum EncodingArg {
+    #[value(name = "utf-8")]
+    Utf8,
+    #[value(name = "utf-8-bom")]
+    Utf8Bom,
+    #[value(name = "utf-16le")]
+    Utf16Le,
+}
+
+impl From<EncodingArg> for domain::encoding::TextEncoding {
+    fn from(value: EncodingArg) -> Self {
+        match value {
+            EncodingArg::Utf8 => domain::encoding::TextEncoding::Utf8,
+            EncodingArg::Utf8Bom => domain::encoding::TextEncoding::Utf8Bom,
+            EncodingArg::Utf16Le => domain::encoding::TextEncoding::Utf16Le,
+        }
+    }
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum CompressArg {
+    Gzip,
+    Zstd,
+}
+
+impl From<CompressArg> for domain::encoding::Compression {
+    fn from(value: CompressArg) -> Self {
+        match value {
+            CompressArg::Gzip => domain::encoding::Compression::Gzip,
+            CompressArg::Zstd => domain::encoding::Compression::Zstd,
+        }
+    }
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum LineEndingsArg {
+    Lf,
+    Crlf,
+}
+
+impl From<LineEndingsArg> for domain::encoding::LineEndings {
+    fn from(value: LineEndingsArg) -> Self {
+        match value {
+            LineEndingsArg::Lf => domain::encoding::LineEndings::Lf,
+            LineEndingsArg::Crlf => domain::encoding::LineEndings::Crlf,
+        }
+    }
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum TimestampFormatArg {
+    #[value(name = "hh:mm:ss")]
+    HhMmSs,
+    #[value(name = "mm:ss")]
+    MmSs,
+    Ms,
+    Seconds,
+}
+
+impl From<TimestampFormatArg> for domain::transcript::TimestampFormat {
+    fn from(value: TimestampFormatArg) -> Self {
+        match value {
+            TimestampFormatArg::HhMmSs => domain::transcript::TimestampFormat::HhMmSs,
+            TimestampFormatArg::MmSs => domain::transcript::TimestampFormat::MmSs,
+            TimestampFormatArg::Ms => domain::transcript::TimestampFormat::Ms,
+            TimestampFormatArg::Seconds => domain::transcript::TimestampFormat::Seconds,
+        }
+    }
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum RedactStyleArg {
+    Block,
+    Tag,
+}
+
+impl From<RedactStyleArg> for domain::postprocess::RedactStyle {
+    fn from(value: RedactStyleArg) -> Self {
+        match value {
+            RedactStyleArg::Block => domain::postprocess::RedactStyle::Block,
+            RedactStyleArg::Tag => domain::postprocess::RedactStyle::Tag,
+        }
+    }
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum AudioProfileArg {
+    Phone,
+    Noisy,
+    Music,
+}
+
+impl From<AudioProfileArg> for domain::AudioProfile {
+    fn from(value: AudioProfileArg) -> Self {
+        match value {
+            AudioProfileArg::Phone => domain::AudioProfile::Phone,
+            AudioProfileArg::Noisy => domain::AudioProfile::Noisy,
+            AudioProfileArg::Music => domain::AudioProfile::Music,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum SubtitleSourceArg {
+    Api,
+    Local,
+}
+
+impl From<SubtitleSourceArg> for domain::SubtitleSource {
+    fn from(value: SubtitleSourceArg) -> Self {
+        match value {
+            SubtitleSourceArg::Api => domain::SubtitleSource::Api,
+            SubtitleSourceArg::Local => domain::SubtitleSource::Local,
+        }
+    }
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum TeeArg {
+    Text,
+}
+
+impl From<TeeArg> for domain::TeeFormat {
+    fn from(value: TeeArg) -> Self {
+        match value {
+            TeeArg::Text => domain::TeeFormat::Text,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum PollStrategyArg {
+    Adaptive,
+    Fixed,
+}
+
+impl From<PollStrategyArg> for domain::polling::PollStrategy {
+    fn from(value: PollStrategyArg) -> Self {
+        match value {
+            PollStrategyArg::Adaptive => domain::polling::PollStrategy::Adaptive,
+            PollStrategyArg::Fixed => domain::polling::PollStrategy::Fixed,
+        }
+    }
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum LimitActionArg {
+    Abort,
+    Warn,
+}
+
+impl From<LimitActionArg> for domain::LimitAction {
+    fn from(value: LimitActionArg) -> Self {
+        match value {
+            LimitActionArg::Abort => domain::LimitAction::Abort,
+            LimitActionArg::Warn => domain::LimitAction::Warn,
         }
     }
 }
@@ -77,6 +537,8 @@ impl From<FormatArg> for TranscriptFormat {
 enum SpeechModelArg {
     Best,
     Nano,
+    #[value(name = "slam-1")]
+    Slam1,
 }
 
 impl From<SpeechModelArg> for domain::SpeechModel {
@@ -84,46 +546,187 @@ impl From<SpeechModelArg> for domain::SpeechModel {
         match value {
             SpeechModelArg::Best => domain::SpeechModel::Best,
             SpeechModelArg::Nano => domain::SpeechModel::Nano,
+            SpeechModelArg::Slam1 => domain::SpeechModel::Slam1,
         }
     }
 }
 
-#[derive(Args, Debug)]
+#[derive(Clone, Debug, ValueEnum)]
+enum FeatureArg {
+    SpeakerLabels,
+    Punctuate,
+    FormatText,
+    Disfluencies,
+    FilterProfanity,
+    SentimentAnalysis,
+    AutoChapters,
+    AutoHighlights,
+    EntityDetection,
+}
+
+impl From<FeatureArg> for domain::languages::Feature {
+    fn from(value: FeatureArg) -> Self {
+        match value {
+            FeatureArg::SpeakerLabels => domain::languages::Feature::SpeakerLabels,
+            FeatureArg::Punctuate => domain::languages::Feature::Punctuation,
+            FeatureArg::FormatText => domain::languages::Feature::FormatText,
+            FeatureArg::Disfluencies => domain::languages::Feature::Disfluencies,
+            FeatureArg::FilterProfanity => domain::languages::Feature::FilterProfanity,
+            FeatureArg::SentimentAnalysis => domain::languages::Feature::SentimentAnalysis,
+            FeatureArg::AutoChapters => domain::languages::Feature::AutoChapters,
+            FeatureArg::AutoHighlights => domain::languages::Feature::AutoHighlights,
+            FeatureArg::EntityDetection => domain::languages::Feature::EntityDetection,
+        }
+    }
+}
+
+#[derive(Args, Clone, Debug, Default)]
 #[command(
     after_help = r#"INPUT
   INPUT can be a local file path (audio/video) or an HTTP(S) URL.
 
 LOCAL FILES
-  Audio extensions: mp3, wav, flac, m4a, ogg
-  Video extensions: mp4, avi, mov, mkv, webm (requires ffmpeg)
+  Audio extensions: mp3, wav, flac, m4a, m4b, ogg
+  Video extensions: mp4, avi, mov, mkv, webm (requires ffmpeg unless --no-extract-audio)
 
 OUTPUT
   If --output is omitted, the transcript is printed to stdout.
   If --output is provided, the transcript is written to the file and a status line is printed to stderr.
 
+BATCH
+  Several inputs (or --retry-file, or --failures-file) make a batch run: --output names a directory, and
+  each input is written to DIR/STEM.EXT. A failed input doesn't stop the batch; the run exits with code 9.
+  --failures-file PATH lists the failed inputs with their errors as JSON; --retry-file PATH re-runs them.
+  Inputs with * ? [ are expanded here too, for shells that don't (e.g. "recordings\*.m4a" on Windows);
+  --no-glob takes them literally.
+
 DIARIZATION (SPEAKER LABELS)
   --speaker-labels enables speaker diarization when the API provides utterances.
   - text: prints "Speaker X: ..." lines
   - srt/vtt: prefers diarized subtitles ("Speaker X: ...") when possible
 
+TRANSLATION (LeMUR)
+  --translate-to es --format srt-dual            bilingual cues: original on line 1, translation on line 2
+  --translate-to es --translated-output es.srt   additionally write a translated srt/vtt file
+  --translate-to en --format anki-tsv            Anki flashcards: sentence, time range, translation
+
+EDITING MARKERS
+  --format edl                   CMX3600 EDL with DaVinci Resolve marker comments
+  --format premiere-markers-csv  Premiere Pro marker CSV (name, description, in, out, duration)
+  Markers come from chapters (--auto-chapters), else speaker turns (--speaker-labels), else captions.
+  Timecodes use --frame-rate (default 30) starting at --timecode-start (default 00:00:00:00).
+
+READING SPEED
+  --max-cps 17 builds srt/vtt captions from word timings (breaking on pauses and speaker changes)
+  and extends each caption's display time, without overlapping the next one, to meet the reading speed.
+
+MEETING AND SHOW NOTES
+  --format meeting-notes  markdown minutes via LeMUR: attendees, key topics (--auto-highlights),
+                          summary, decisions, and action items with owners (see `meeting --help`)
+  --format show-notes     markdown show notes: LeMUR summary and key quotes, chapter list
+                          (--auto-chapters), and guests (--entity-detection) (see `shownotes --help`)
+
+REPORTS
+  --report keywords            top 10 terms (stopwords removed) with keyword-in-context lines
+  --report keywords=TERM,...   counts and keyword-in-context lines for the given terms/phrases
+  --report sentiment-timeline[=OPTIONS]
+                               sentiment per time bucket as CSV (requires --sentiment-analysis);
+                               OPTIONS: bucket duration (default 60s), per-speaker, csv, json
+                               e.g. --report sentiment-timeline=30s,per-speaker,json
+  --report silence[=THRESHOLD] silences of THRESHOLD (default 2s) or more, with timestamps,
+                               and the total share of silence, e.g. to trim dead air
+  --report interruptions[=MIN_OVERLAP]
+                               overlapping speech between channels of at least MIN_OVERLAP
+                               (default 500ms) and who interrupts whom (requires --multichannel)
+  --report profanity           words masked by --filter-profanity, with timestamps and speakers
+  Reports are written to stderr, or to --report-output PATH.
+
 CONFIG
   ~/.assemblyai-cli/config.json (preferred) or ~/.assemblyai-cli (legacy)
-  CLI flags override config values.
+  CLI flags override config values; `presets.NAME` (--preset) and `byExtension.EXT`
+  (matched on the input extension) override top-level keys.
 "#
 )]
 struct TranscribeArgs {
     #[arg(
         value_name = "INPUT",
+        required_unless_present_any = ["list_formats", "retry_file", "from_upload_url"],
+        default_value = "",
+        hide_default_value = true,
         help = "Local audio/video path or HTTP(S) URL"
     )]
-    input: String,
+    input: OsString,
+
+    #[arg(
+        long = "from-upload-url",
+        value_name = "URL",
+        conflicts_with_all = ["more_inputs", "retry_file", "audio_profile"],
+        help = "Transcribe an upload URL from an earlier upload (or any audio URL) without uploading; INPUT, when given, only names the outputs"
+    )]
+    from_upload_url: Option<String>,
+
+    #[arg(value_name = "INPUT", help = "More inputs, transcribed one after another into --output DIR")]
+    more_inputs: Vec<OsString>,
 
     #[arg(
         long,
-        value_enum,
-        help = "Output format (text, srt, vtt); when omitted, uses config `format` or defaults to text"
+        conflicts_with_all = ["retry_file", "from_upload_url"],
+        help = "Treat the inputs as consecutive parts of one recording: one transcript, with timestamps running on across the parts and a chapter per part"
     )]
-    format: Option<FormatArg>,
+    concat: bool,
+
+    #[arg(long, value_name = "PATH", help = "After a batch run, write the failed inputs and their errors to PATH as JSON")]
+    failures_file: Option<PathBuf>,
+
+    #[arg(long, value_name = "PATH", help = "Transcribe the inputs listed in a --failures-file of an earlier batch run")]
+    retry_file: Option<PathBuf>,
+
+    #[arg(long, help = "Start a batch over instead of resuming an interrupted run of the same command")]
+    no_resume: bool,
+
+    #[arg(long, help = "Take inputs containing *, ?, or [ literally instead of expanding them as wildcard patterns")]
+    no_glob: bool,
+
+    #[arg(
+        long,
+        value_name = "ADDR",
+        help = "During a batch run, serve Prometheus metrics at http://ADDR/metrics, e.g. 127.0.0.1:9464"
+    )]
+    metrics_addr: Option<std::net::SocketAddr>,
+
+    #[arg(long, value_name = "N", help = "Maximum number of batch inputs transcribed at once; when omitted, uses config `concurrency` or defaults to 4")]
+    concurrency: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Keep outputs byte-identical across runs for snapshot tests: no run timestamps in SQLite rows, and inputs and error messages in SQLite, --failures-file, and .meta.json sidecars relative to the working directory"
+    )]
+    deterministic: bool,
+
+    #[arg(skip)]
+    batch_item: Option<BatchItem>,
+
+    #[arg(long = "list-formats", help = "List the output formats accepted by --format and exit")]
+    list_formats: bool,
+
+    #[arg(
+        long,
+        value_parser = parse_format,
+        help = "Output format (text, srt, vtt, srt-dual, edl, premiere-markers-csv, anki-tsv, otr, meeting-notes, show-notes, sqlite, parquet, json), or exec:COMMAND to pipe the transcript JSON through COMMAND; when omitted, uses config `format` or defaults to text"
+    )]
+    format: Option<FormatValue>,
+
+    #[arg(
+        long,
+        value_name = "SECTION,...",
+        value_delimiter = ',',
+        conflicts_with = "json_exclude",
+        help = "With --format json, keep only these top-level sections of the transcript JSON (and its id), e.g. utterances,chapters"
+    )]
+    json_include: Vec<String>,
+
+    #[arg(long, value_name = "SECTION,...", value_delimiter = ',', help = "With --format json, leave out these top-level sections, e.g. words")]
+    json_exclude: Vec<String>,
 
     #[arg(
         long,
@@ -135,7 +738,7 @@ struct TranscribeArgs {
     #[arg(
         long,
         value_enum,
-        help = "Speech model (best, nano); when omitted, uses config `speechModel` or defaults to best"
+        help = "Speech model (best, nano, slam-1); when omitted, uses config `speechModel` or defaults to best"
     )]
     speech_model: Option<SpeechModelArg>,
 
@@ -157,10 +760,18 @@ struct TranscribeArgs {
     #[arg(
         long,
         value_name = "CODE",
-        help = "Language code (e.g. en, ru); only valid when language detection is disabled"
+        help = "Language code (e.g. en, ru); only valid when language detection is disabled, except `auto`, which detects the language"
     )]
     language: Option<String>,
 
+    #[arg(
+        long = "expect-language",
+        value_name = "CODES",
+        value_delimiter = ',',
+        help = "Comma-separated languages the detected language must be one of, else exit with code 7; requires language detection; when omitted, uses config `expectLanguage`"
+    )]
+    expect_language: Vec<String>,
+
     #[arg(
         long,
         action = clap::ArgAction::SetTrue,
@@ -200,8 +811,23 @@ struct TranscribeArgs {
     #[arg(long = "speaker-labels", help = "Enable speaker diarization (speaker labels); when omitted, uses config `speakerLabels` or defaults to disabled")]
     speaker_labels: bool,
 
-    #[arg(
-        long,
+    #[arg(long = "speaker-style", value_name = "TEMPLATE", conflicts_with = "no_speaker_prefix", help = "Speaker prefix template for text/SRT/VTT, with {name} (A) and {index} (00), e.g. \"[{name}] \"; when omitted, uses config `speakerStyle` or defaults to \"Speaker {name}: \"")]
+    speaker_style: Option<String>,
+
+    #[arg(long = "no-speaker-prefix", help = "Render diarized text/SRT/VTT without speaker prefixes; when omitted, uses config `speakerPrefix`")]
+    no_speaker_prefix: bool,
+
+    #[arg(long = "merge-short-utterances", value_name = "DURATION", help = "Fold single-word utterances shorter than DURATION (e.g. 1500ms) into the surrounding speaker's turn when rendering; needs --speaker-labels; when omitted, uses config `mergeShortUtterances`")]
+    merge_short_utterances: Option<String>,
+
+    #[arg(long = "paragraph-gap", value_name = "DURATION", help = "Start a new paragraph in text output where the silence between utterances (or words, without speaker labels) is longer than DURATION, e.g. 2s; when omitted, uses config `paragraphGap`")]
+    paragraph_gap: Option<String>,
+
+    #[arg(long = "mark-uncertain", value_name = "0..1", help = "Mark words with a confidence below this as [?word] in the rendered text, for human correction; when omitted, uses config `markUncertain`")]
+    mark_uncertain: Option<f64>,
+
+    #[arg(
+        long,
         action = clap::ArgAction::SetTrue,
         conflicts_with = "no_multichannel",
         help = "Enable multichannel audio; when omitted, uses config `multichannel` or defaults to enabled"
@@ -215,23 +841,186 @@ struct TranscribeArgs {
     )]
     no_multichannel: bool,
 
+    #[arg(long = "channel-names", value_name = "CHANNEL=NAME", value_delimiter = ',', help = "Comma-separated names for multichannel channels (e.g. \"1=Agent,2=Customer\"), used as the speaker of each channel's utterances; when omitted, uses config `channelNames`")]
+    channel_names: Vec<String>,
+
+    #[arg(long = "identify-speakers", help = "After diarization, show (and play, with ffplay) each speaker's first utterance and ask for their name; the names are saved next to --output and reused")]
+    identify_speakers: bool,
+
+    #[arg(long = "split-by-channel", conflicts_with_all = ["split_output_every", "split_output_by"], help = "Write one text/srt/vtt file per multichannel channel next to --output (OUT.Agent.txt, ...) instead of a combined file; when omitted, uses config `splitByChannel`")]
+    split_by_channel: bool,
+
+    #[arg(
+        long = "extract-audio",
+        action = clap::ArgAction::SetTrue,
+        conflicts_with = "no_extract_audio",
+        help = "Extract audio from local video files with ffmpeg before upload; when omitted, uses config `extractAudio` or defaults to enabled"
+    )]
+    extract_audio: bool,
+
+    #[arg(
+        long = "no-extract-audio",
+        action = clap::ArgAction::SetTrue,
+        help = "Upload local video files as-is instead of extracting audio"
+    )]
+    no_extract_audio: bool,
+
+    #[arg(long = "audio-profile", value_enum, help = "Clean up local audio with ffmpeg before upload: phone (band-pass + loudness), noisy (denoise + loudness), or music (speech band + loudness); when omitted, uses config `audioProfile`")]
+    audio_profile: Option<AudioProfileArg>,
+
+    #[arg(long = "skip-validation", help = "Upload local files without first checking them with ffprobe for an audio stream and a non-zero duration; when omitted, uses config `skipValidation`")]
+    skip_validation: bool,
+
+    #[arg(long = "temp-dir", value_name = "DIR", help = "Write extracted and preprocessed audio and other ffmpeg intermediates to DIR instead of the system temp directory; when omitted, uses config `tempDir`")]
+    temp_dir: Option<PathBuf>,
+
+    #[arg(long = "keep-temp", help = "Keep ffmpeg intermediates after the run and log their paths, for debugging")]
+    keep_temp: bool,
+
+    #[arg(long = "no-upload-reuse", help = "Upload local files even when the same content was uploaded in the last 24 hours")]
+    no_upload_reuse: bool,
+
+    #[arg(long, help = "After uploading, read the file again and fail before transcribing unless it hashes the same as the bytes sent")]
+    verify: bool,
+
+    #[arg(long = "max-upload-bytes", value_name = "BYTES", help = "Refuse to upload local files larger than BYTES; when omitted, uses config `maxUploadBytes`")]
+    max_upload_bytes: Option<u64>,
+
+    #[arg(long = "max-duration-minutes", value_name = "MINUTES", help = "Refuse to upload recordings longer than MINUTES (needs ffprobe); when omitted, uses config `maxDurationMinutes`")]
+    max_duration_minutes: Option<f64>,
+
+    #[arg(long = "on-limit", value_enum, help = "When a file exceeds --max-upload-bytes/--max-duration-minutes: abort (exit 2) or warn and upload anyway; when omitted, uses config `onLimit` or defaults to abort")]
+    on_limit: Option<LimitActionArg>,
+
+    #[arg(long = "fail-on-empty", help = "Exit with code 8 before writing output when the transcript has no words; when omitted, uses config `failOnEmpty`")]
+    fail_on_empty: bool,
+
+    #[arg(long = "min-words", value_name = "N", help = "Exit with code 8 before writing output when the transcript has fewer than N words; when omitted, uses config `minWords`")]
+    min_words: Option<usize>,
+
+    #[arg(long = "min-avg-confidence", value_name = "0..1", help = "Exit with code 8 before writing output when the average word confidence is below this; when omitted, uses config `minAvgConfidence`")]
+    min_avg_confidence: Option<f64>,
+
+    #[arg(long = "sentiment-analysis", help = "Enable sentiment analysis; when omitted, uses config `sentimentAnalysis` or defaults to disabled")]
+    sentiment_analysis: bool,
+
+    #[arg(long = "auto-chapters", help = "Enable auto chapters; when omitted, uses config `autoChapters` or defaults to disabled")]
+    auto_chapters: bool,
+
+    #[arg(long = "auto-highlights", help = "Enable key phrase detection (used by --format meeting-notes/show-notes); when omitted, uses config `autoHighlights` or defaults to disabled")]
+    auto_highlights: bool,
+
+    #[arg(long = "entity-detection", help = "Enable entity detection (guest names for --format show-notes); when omitted, uses config `entityDetection` or defaults to disabled")]
+    entity_detection: bool,
+
+    #[arg(long, value_name = "FPS", help = "Frame rate for edl/premiere-markers-csv timecodes; when omitted, uses config `frameRate` or defaults to 30")]
+    frame_rate: Option<u32>,
+
+    #[arg(long, value_name = "HH:MM:SS:FF", help = "Timecode of the first frame for edl/premiere-markers-csv (e.g. 01:00:00:00 for Resolve timelines); when omitted, uses config `timecodeStart` or defaults to 00:00:00:00")]
+    timecode_start: Option<String>,
+
+    #[arg(long = "embed-metadata", help = "Write the plain transcript into the input file's metadata (ID3 USLT for mp3, lyrics atom for m4a/mp4/mov via ffmpeg); when omitted, uses config `embedMetadata`")]
+    embed_metadata: bool,
+
+    #[arg(long = "write-chapters", help = "Write chapter markers into a copy of the input (INPUT.chapters.EXT) via ffmpeg; requires --auto-chapters and a local mp4/m4a/m4b/mov/mkv file; when omitted, uses config `writeChapters`")]
+    write_chapters: bool,
+
+    #[arg(long = "translate-to", value_name = "LANG", help = "Translate captions to LANG via LeMUR (for --format srt-dual or --translated-output); when omitted, uses config `translateTo`")]
+    translate_to: Option<String>,
+
+    #[arg(long = "translated-output", value_name = "PATH", help = "Write translated subtitles to PATH (vtt when PATH ends in .vtt, otherwise srt); requires --translate-to")]
+    translated_output: Option<PathBuf>,
+
+    #[arg(long = "lemur-model", value_name = "MODEL", help = "LeMUR final model for translation and summaries; when omitted, uses config `lemurModel` or the API default")]
+    lemur_model: Option<String>,
+
     #[arg(long, value_name = "0.0..1.0", help = "Speech threshold (0.0..=1.0); when omitted, uses config `speechThreshold`")]
     speech_threshold: Option<f64>,
 
     #[arg(long, value_name = "N", help = "Max characters per caption for srt/vtt; when omitted, uses config `charsPerCaption` or defaults to 128")]
     chars_per_caption: Option<u32>,
 
+    #[arg(long, value_name = "CPS", help = "Build srt/vtt captions locally from word timings and extend display times to stay at or below CPS characters per second; when omitted, uses config `maxCps`")]
+    max_cps: Option<f64>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Where srt/vtt captions come from: the API's export, or built locally from the words; when omitted, uses config `subtitleSource`, else builds locally only when speaker labels, --max-cps, --mark-uncertain, or --concat shape the captions"
+    )]
+    subtitle_source: Option<SubtitleSourceArg>,
+
+    #[arg(long = "srt-start-index", value_name = "N", help = "Number SRT cues from N, e.g. to append to an existing SRT; when omitted, uses config `srtStartIndex` or defaults to 1")]
+    srt_start_index: Option<u32>,
+
+    #[arg(long = "srt-renumber", help = "Rewrite SRT cue numbers sequentially, adding missing ones; when omitted, uses config `srtRenumber`")]
+    srt_renumber: bool,
+
+    #[arg(long = "split-output-every", value_name = "DURATION", conflicts_with = "split_output_by", help = "Write srt/vtt output as sequential files (OUT.part01.srt, ...) of DURATION each (e.g. 30m), with timestamps re-based per file; when omitted, uses config `splitOutputEvery`")]
+    split_output_every: Option<String>,
+
+    #[arg(long = "split-output-by", value_enum, help = "Write srt/vtt output as one file per chapter (requires --auto-chapters), with timestamps re-based per file; when omitted, uses config `splitOutputBy`")]
+    split_output_by: Option<SplitOutputByArg>,
+
+    #[arg(long, help = "Lint generated srt/vtt captions and exit with code 6 on violations (see `lint --help`); when omitted, uses config `check`")]
+    check: bool,
+
     #[arg(long = "word-boost", value_name = "PHRASE", help = "Boost recognition for PHRASE (repeatable); when omitted, uses config `wordBoost`")]
     word_boost: Vec<String>,
 
+    #[arg(long = "keyterms-prompt", value_name = "TERMS", value_delimiter = ',', help = "Comma-separated key terms to prompt the slam-1 model with (repeatable); when omitted, uses config `keytermsPrompt`")]
+    keyterms_prompt: Vec<String>,
+
     #[arg(long = "custom-spelling", value_name = "FROM=TO", help = "Custom spelling mapping (repeatable); when omitted, uses config `customSpelling`")]
     custom_spelling: Vec<String>,
 
-    #[arg(long, value_name = "SECONDS", help = "Polling interval (seconds); when omitted, uses config `pollIntervalSeconds` or defaults to 3")]
+    #[arg(long = "report", value_name = "REPORT", help = "Generate a report after transcription (repeatable): keywords[=TERM,...], sentiment-timeline[=OPTIONS], silence[=THRESHOLD], interruptions[=MIN_OVERLAP], profanity; when omitted, uses config `reports`")]
+    report: Vec<String>,
+
+    #[arg(long = "report-output", value_name = "PATH", help = "Write reports to PATH; when omitted, uses config `reportOutput` or stderr")]
+    report_output: Option<PathBuf>,
+
+    #[arg(long = "timestamp-format", value_enum, help = "Timestamp format in text, markdown, and CSV outputs (SRT/VTT keep their own); when omitted, uses config `timestampFormat` or each output's default")]
+    timestamp_format: Option<TimestampFormatArg>,
+
+    #[arg(long, value_enum, help = "Text encoding of the output; when omitted, uses config `encoding` or defaults to utf-8")]
+    encoding: Option<EncodingArg>,
+
+    #[arg(long = "line-endings", value_enum, help = "Line endings of the output; when omitted, uses config `lineEndings` or keeps the rendered endings")]
+    line_endings: Option<LineEndingsArg>,
+
+    #[arg(long, value_enum, help = "Compress the output file; when omitted, uses config `compress`, else .gz/.zst output extensions pick gzip/zstd")]
+    compress: Option<CompressArg>,
+
+    #[arg(long, value_enum, conflicts_with = "preview", help = "Also print a text rendering to stdout while writing --output; when omitted, uses config `tee`")]
+    tee: Option<TeeArg>,
+
+    #[arg(long, value_name = "N", help = "Also print the first N utterances (or sentences) to stdout while writing --output; when omitted, uses config `preview`")]
+    preview: Option<usize>,
+
+    #[arg(long = "redact-terms-file", value_name = "PATH", help = "Mask the words/phrases listed in PATH (one per line, # comments) in every output; when omitted, uses config `redactTermsFile`")]
+    redact_terms_file: Option<PathBuf>,
+
+    #[arg(long = "redact-style", value_enum, help = "Mask for redacted terms: block (\u{2588}\u{2588}\u{2588}\u{2588}) or tag ([REDACTED]); when omitted, uses config `redactStyle` or defaults to block")]
+    redact_style: Option<RedactStyleArg>,
+
+    #[arg(long = "normalize", value_name = "RULE", help = "Normalize rendered text (repeatable): quotes=straight|curly, whitespace, numbers, decimal=LOCALE; when omitted, uses config `normalize`")]
+    normalize: Vec<String>,
+
+    #[arg(long, value_name = "NAME", help = "Apply config `presets.NAME` over the top-level config keys; CLI flags still take precedence")]
+    preset: Option<String>,
+
+    #[arg(long = "poll-strategy", value_enum, help = "Polling strategy: adaptive (backs off on long jobs, tightens near the expected completion) or fixed; when omitted, uses config `pollStrategy` or defaults to adaptive")]
+    poll_strategy: Option<PollStrategyArg>,
+
+    #[arg(long, value_name = "SECONDS", help = "Polling interval (shortest adaptive interval, in seconds); when omitted, uses config `pollIntervalSeconds` or defaults to 3")]
     poll_interval_seconds: Option<u64>,
 
     #[arg(long, value_name = "SECONDS", help = "Timeout (seconds); when omitted, uses config `timeoutSeconds` or defaults to 3600")]
     timeout_seconds: Option<u64>,
+
+    #[arg(long = "tag", value_name = "TAG", help = "Tag the run in history, for `history --tag TAG` (repeatable)")]
+    tags: Vec<String>,
 }
 
 #[derive(Args, Debug)]
@@ -257,245 +1046,2541 @@ struct InitArgs {
 
     #[arg(long, help = "Overwrite existing apiKey without prompting")]
     yes: bool,
+
+    #[arg(long = "api-key", value_name = "KEY", help = "API key to store instead of prompting for one")]
+    api_key: Option<ApiKey>,
+
+    #[arg(long, value_enum, help = "Default output format to store as config `format`")]
+    format: Option<FormatArg>,
+
+    #[arg(long = "no-verify", help = "Store the API key without checking it against the API")]
+    no_verify: bool,
 }
 
-#[derive(thiserror::Error, Debug)]
-enum RunError {
-    #[error("missing AssemblyAI API key (set ASSEMBLYAI_API_KEY or put apiKey in ~/.assemblyai-cli/config.json)")]
-    MissingApiKey,
+#[derive(Args, Debug)]
+#[command(
+    after_help = r#"RULES
+  timing         cue ends before it starts
+  overlap        cue starts before the previous cue ends
+  gap            gap between cues is shorter than --min-gap-ms (but not zero)
+  reading-speed  characters per second above --max-cps
+  line-count     more lines than --max-lines
+  line-length    line longer than --max-line-length characters
 
-    #[error("unable to determine home directory (HOME/USERPROFILE is not set)")]
-    HomeNotFound,
+CONFIG
+  Thresholds default to config `lint` (maxCps, maxLineLength, maxLines, minGapMs), then 17 cps, 42 chars, 2 lines, 80ms.
 
-    #[error("failed to read config file {path:?}: {message}")]
-    ConfigRead { path: PathBuf, message: String },
+EXAMPLES
+  assemblyai-cli lint captions.srt
+  assemblyai-cli lint part1.vtt part2.vtt --max-cps 20 --max-line-length 37
+"#
+)]
+struct LintArgs {
+    #[arg(value_name = "PATH", required = true, help = "SRT/VTT files to check")]
+    paths: Vec<PathBuf>,
 
-    #[error("failed to parse config file {path:?}: {message}")]
-    ConfigParse { path: PathBuf, message: String },
+    #[command(flatten)]
+    thresholds: LintThresholdArgs,
+}
 
-    #[error("failed to write config file {path:?}: {message}")]
-    ConfigWrite { path: PathBuf, message: String },
+#[derive(Args, Debug)]
+#[command(
+    after_help = r#"OFFSETS
+  One per file, separated by commas: a clock time (1:02:00, 62:00.5) or a duration (3720s, 62m; bare numbers are seconds).
 
-    #[error("failed to read API key from stdin: {message}")]
-    InitReadStdin { message: String },
+EXAMPLES
+  assemblyai-cli merge part1.srt part2.srt --offsets 0,1:02:00 -o full.srt
+  assemblyai-cli merge part1.vtt part2.vtt part3.vtt > full.vtt
+"#
+)]
+struct MergeArgs {
+    #[arg(value_name = "PATH", required = true, help = "SRT/VTT files, in recording order")]
+    paths: Vec<PathBuf>,
 
-    #[error("API key cannot be empty")]
-    InitEmptyApiKey,
+    #[arg(long, value_name = "OFFSET,...", value_delimiter = ',', help = "Where each file starts in the merged recording")]
+    offsets: Vec<String>,
 
-    #[error(transparent)]
-    Domain(#[from] domain::DomainError),
+    #[arg(long, value_enum, help = "Output format; defaults to the --output extension, else that of the first file")]
+    format: Option<MergeFormatArg>,
 
-    #[error(transparent)]
-    Infra(#[from] infra::InfraError),
+    #[arg(short, long, value_name = "PATH", help = "Write output to PATH instead of stdout")]
+    output: Option<PathBuf>,
+}
 
-    #[error(transparent)]
-    Api(#[from] infra::assemblyai::ApiError),
+#[derive(Args, Debug)]
+#[command(
+    after_help = r#"EXAMPLES
+  assemblyai-cli render 5551722-f677-48a6-9287-39c0aafd9ac1 --chars-per-caption 32 -o narrow.srt
+  assemblyai-cli render standup --format vtt
+"#
+)]
+struct RenderArgs {
+    #[arg(value_name = "TRANSCRIPT_ID", help = "Id or alias of a completed transcript")]
+    transcript_id: String,
+
+    #[arg(long, value_enum, help = "Output format; defaults to the --output extension, else srt")]
+    format: Option<MergeFormatArg>,
+
+    #[arg(long, value_name = "N", help = "Max characters per caption; when omitted, uses config `charsPerCaption` or defaults to 128")]
+    chars_per_caption: Option<u32>,
+
+    #[arg(short, long, value_name = "PATH", help = "Write output to PATH instead of stdout")]
+    output: Option<PathBuf>,
 }
 
-impl From<infra::runner::RunnerError> for RunError {
-    fn from(value: infra::runner::RunnerError) -> Self {
-        match value {
-            infra::runner::RunnerError::Infra(err) => RunError::Infra(err),
-            infra::runner::RunnerError::Api(err) => RunError::Api(err),
+#[derive(Args, Debug)]
+#[command(
+    after_help = r#"OUTPUT
+  text  [hh:mm:ss] Speaker A: translated text (one line per utterance)
+  srt   translated captions, split to --chars-per-caption
+  vtt   same as srt, WebVTT
+
+EXAMPLES
+  assemblyai-cli translate 5551722-f677-48a6-9287-39c0aafd9ac1 --to de
+  assemblyai-cli translate 5551722-f677-48a6-9287-39c0aafd9ac1 --to fr --format srt -o meeting.fr.srt
+"#
+)]
+struct TranslateArgs {
+    #[arg(value_name = "TRANSCRIPT_ID", help = "Id or alias of a completed transcript")]
+    transcript_id: String,
+
+    #[arg(long = "to", value_name = "LANG", help = "Target language (e.g. de, fr, Spanish)")]
+    to: String,
+
+    #[arg(long, value_enum, help = "Output format; defaults to text")]
+    format: Option<TranslateFormatArg>,
+
+    #[arg(short, long, value_name = "PATH", help = "Write output to PATH instead of stdout")]
+    output: Option<PathBuf>,
+
+    #[arg(long = "lemur-model", value_name = "MODEL", help = "LeMUR final model; when omitted, uses config `lemurModel` or the API default")]
+    lemur_model: Option<String>,
+
+    #[arg(long, value_name = "N", help = "Max characters per caption for srt/vtt; when omitted, uses config `charsPerCaption` or defaults to 128")]
+    chars_per_caption: Option<u32>,
+
+    #[arg(long = "timestamp-format", value_enum, help = "Timestamp format for text output; when omitted, uses config `timestampFormat` or defaults to hh:mm:ss")]
+    timestamp_format: Option<TimestampFormatArg>,
+
+    #[arg(long, value_enum, help = "Text encoding of the output; when omitted, uses config `encoding` or defaults to utf-8")]
+    encoding: Option<EncodingArg>,
+
+    #[arg(long = "line-endings", value_enum, help = "Line endings of the output; when omitted, uses config `lineEndings` or keeps the rendered endings")]
+    line_endings: Option<LineEndingsArg>,
+
+    #[arg(long, value_enum, help = "Compress the output file; when omitted, uses config `compress`, else .gz/.zst output extensions pick gzip/zstd")]
+    compress: Option<CompressArg>,
+}
+
+#[derive(Args, Debug)]
+struct PresetArgs {
+    #[arg(value_name = "INPUT", help = "Local audio/video path or HTTP(S) URL")]
+    input: OsString,
+
+    #[arg(short, long, value_name = "PATH", help = "Write markdown to PATH instead of stdout")]
+    output: Option<PathBuf>,
+
+    #[arg(long, value_name = "CODE", help = "Language code (e.g. en, de); when omitted, the language is detected")]
+    language: Option<String>,
+
+    #[arg(long, value_enum, help = "Speech model (best, nano, slam-1); when omitted, uses config `speechModel` or defaults to best")]
+    speech_model: Option<SpeechModelArg>,
+
+    #[arg(long = "lemur-model", value_name = "MODEL", help = "LeMUR final model; when omitted, uses config `lemurModel` or the API default")]
+    lemur_model: Option<String>,
+
+    #[arg(long = "poll-strategy", value_enum, help = "Polling strategy: adaptive (backs off on long jobs, tightens near the expected completion) or fixed; when omitted, uses config `pollStrategy` or defaults to adaptive")]
+    poll_strategy: Option<PollStrategyArg>,
+
+    #[arg(long, value_name = "SECONDS", help = "Polling interval (shortest adaptive interval, in seconds); when omitted, uses config `pollIntervalSeconds` or defaults to 3")]
+    poll_interval_seconds: Option<u64>,
+
+    #[arg(long, value_name = "SECONDS", help = "Timeout (seconds); when omitted, uses config `timeoutSeconds` or defaults to 3600")]
+    timeout_seconds: Option<u64>,
+
+    #[arg(long = "tag", value_name = "TAG", help = "Tag the run in history, for `history --tag TAG` (repeatable)")]
+    tags: Vec<String>,
+}
+
+impl PresetArgs {
+    fn into_meeting_args(self) -> TranscribeArgs {
+        TranscribeArgs {
+            speaker_labels: true,
+            auto_highlights: true,
+            ..self.into_transcribe_args(FormatArg::MeetingNotes)
         }
     }
-}
 
-impl RunError {
-    fn exit_code(&self) -> u8 {
-        match self {
-            RunError::Domain(_) => 2,
-            RunError::MissingApiKey => 3,
-            RunError::HomeNotFound => 3,
-            RunError::ConfigRead { .. }
-            | RunError::ConfigParse { .. }
-            | RunError::ConfigWrite { .. }
-            | RunError::InitReadStdin { .. }
-            | RunError::InitEmptyApiKey => 3,
-            RunError::Infra(err) => err.exit_code(),
-            RunError::Api(_) => 5,
+    fn into_shownotes_args(self) -> TranscribeArgs {
+        TranscribeArgs {
+            auto_chapters: true,
+            auto_highlights: true,
+            entity_detection: true,
+            ..self.into_transcribe_args(FormatArg::ShowNotes)
+        }
+    }
+
+    fn into_transcribe_args(self, format: FormatArg) -> TranscribeArgs {
+        TranscribeArgs {
+            no_language_detection: self.language.as_deref().is_some_and(|code| !domain::is_auto_language(code)),
+            input: self.input,
+            format: Some(FormatValue::Builtin(format)),
+            output: self.output,
+            speech_model: self.speech_model,
+            language: self.language,
+            lemur_model: self.lemur_model,
+            poll_strategy: self.poll_strategy,
+            poll_interval_seconds: self.poll_interval_seconds,
+            timeout_seconds: self.timeout_seconds,
+            tags: self.tags,
+            ..Default::default()
         }
     }
 }
 
-#[tokio::main]
-async fn main() -> ExitCode {
-    let cli = Cli::parse();
+#[derive(Args, Debug)]
+#[command(
+    after_help = r#"ALIGNMENT
+  REF/HYP rows show the aligned words; errors are uppercased, gaps are ***.
+  EVAL marks S (substitution), D (deletion), and I (insertion).
+
+EXAMPLES
+  assemblyai-cli eval hypothesis.json --reference ref.txt
+  assemblyai-cli eval 5551722-f677-48a6-9287-39c0aafd9ac1 --reference ref.txt
+"#
+)]
+struct EvalArgs {
+    #[arg(value_name = "HYPOTHESIS", help = "Transcript JSON/text file, or transcript id or alias")]
+    hypothesis: String,
+
+    #[arg(long, value_name = "PATH", help = "Reference transcript (plain text)")]
+    reference: PathBuf,
+}
+
+#[derive(Args, Debug)]
+#[command(
+    after_help = r#"EXAMPLES
+  assemblyai-cli grep 'refund|cancel(led|lation)' -i
+  assemblyai-cli grep 'annual plan' calls/*.json
+  assemblyai-cli grep pricing 5551722-f677-48a6-9287-39c0aafd9ac1 weekly-sync
+"#
+)]
+struct GrepArgs {
+    #[arg(value_name = "PATTERN", help = "Regular expression to search for")]
+    pattern: String,
+
+    #[arg(value_name = "SOURCE", help = "Transcript JSON files, or transcript ids or aliases; when omitted, every cached transcript")]
+    sources: Vec<String>,
+
+    #[arg(short = 'i', long = "ignore-case", help = "Match case-insensitively")]
+    ignore_case: bool,
+}
+
+#[derive(Args, Debug)]
+#[command(
+    after_help = r#"QUERIES
+  Every word must occur in the sentence, in any order; word* matches a prefix, and OR and NOT combine words.
+  A query with double quotes is SQLite FTS5 syntax, e.g. '"contract renewal"' for the exact phrase.
+
+EXAMPLES
+  assemblyai-cli index build
+  assemblyai-cli index search "contract renewal"
+  assemblyai-cli index search 'renew* OR cancel*' --limit 50
+"#
+)]
+struct IndexArgs {
+    #[command(subcommand)]
+    command: IndexCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum IndexCommand {
+    #[command(about = "Rebuild the index from the transcripts in the cache")]
+    Build,
+
+    #[command(about = "Print the sentences best matching QUERY, with their transcript id and time")]
+    Search {
+        #[arg(value_name = "QUERY", help = "Words to find; see QUERIES")]
+        query: String,
+
+        #[arg(long, value_name = "N", default_value_t = 10, help = "Print at most N sentences")]
+        limit: usize,
+    },
+}
+
+#[derive(Args, Debug)]
+#[command(
+    after_help = r#"EXAMPLES
+  assemblyai-cli token
+  assemblyai-cli token --expires-in 600
+"#
+)]
+struct TokenArgs {
+    #[arg(long = "expires-in", value_name = "SECONDS", default_value_t = 3600, help = "Token lifetime in seconds (60..=360000)")]
+    expires_in: u32,
+}
+
+#[derive(Args, Debug)]
+#[command(
+    after_help = r#"EXAMPLES
+  assemblyai-cli upload call.mp3
+  assemblyai-cli transcribe call.mp3 --from-upload-url "$(assemblyai-cli upload call.mp3)"
+"#
+)]
+struct UploadArgs {
+    #[arg(value_name = "FILE", help = "Local file to upload")]
+    input: PathBuf,
+
+    #[arg(long = "no-upload-reuse", help = "Upload even when the same content was uploaded in the last 24 hours")]
+    no_upload_reuse: bool,
+
+    #[arg(long, help = "After uploading, read the file again and fail unless it hashes the same as the bytes sent")]
+    verify: bool,
+
+    #[arg(long, help = "Print the input, upload URL, size, and SHA-256 as JSON")]
+    json: bool,
+}
+
+#[derive(Args, Debug)]
+#[command(
+    after_help = r#"EXAMPLES
+  assemblyai-cli languages
+  assemblyai-cli languages --feature speaker-labels
+"#
+)]
+struct LanguagesArgs {
+    #[arg(long = "feature", value_enum, value_name = "FEATURE", help = "Only list languages supporting FEATURE (repeatable)")]
+    features: Vec<FeatureArg>,
+}
+
+#[derive(Args, Debug)]
+#[command(
+    after_help = r#"EXAMPLES
+  assemblyai-cli schema config > ~/.assemblyai-cli/config.schema.json
+  assemblyai-cli schema output
+"#
+)]
+struct SchemaArgs {
+    #[arg(value_enum, value_name = "DOCUMENT", help = "Which schema to print")]
+    document: SchemaDocumentArg,
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum SchemaDocumentArg {
+    Config,
+    Output,
+}
+
+#[derive(Args, Debug)]
+struct VersionArgs {
+    #[arg(long, help = "Print a JSON object with version, commit, buildDate, target, features, and baseUrl")]
+    json: bool,
+}
+
+#[derive(Args, Debug)]
+struct SelfUpdateArgs {
+    #[arg(long, help = "Only report whether a newer release is available; don't download anything")]
+    check: bool,
+}
+
+#[derive(Args, Debug)]
+#[command(
+    after_help = r#"EXAMPLES
+  assemblyai-cli list
+  assemblyai-cli list --status error --limit 50
+  assemblyai-cli list --created-after 2025-03-01 --created-before 2025-04-01 --all
+"#
+)]
+struct ListArgs {
+    #[arg(long, value_enum, help = "Only list transcripts with this status")]
+    status: Option<TranscriptStatusArg>,
+
+    #[arg(long = "created-after", value_name = "DATE", value_parser = domain::listing::parse_date_bound, help = "Only list transcripts created on or after DATE (YYYY-MM-DD or YYYY-MM-DDTHH:MM, UTC)")]
+    created_after: Option<String>,
+
+    #[arg(long = "created-before", value_name = "DATE", value_parser = domain::listing::parse_date_bound, help = "Only list transcripts created before DATE (YYYY-MM-DD or YYYY-MM-DDTHH:MM, UTC)")]
+    created_before: Option<String>,
+
+    #[arg(long = "throttled-only", help = "Only list transcripts that were throttled")]
+    throttled_only: bool,
+
+    #[arg(long, value_name = "N", default_value_t = 20, conflicts_with = "all", help = "Show at most N transcripts")]
+    limit: usize,
+
+    #[arg(long, help = "Page through every matching transcript instead of stopping at --limit")]
+    all: bool,
+}
+
+#[derive(Args, Debug)]
+#[command(
+    after_help = r#"EXAMPLES
+  assemblyai-cli delete --before 2024-01-01 --dry-run
+  assemblyai-cli delete --before 2024-01-01 --status completed --yes
+  assemblyai-cli delete --yes   # with config retentionDays, e.g. from cron
+"#
+)]
+struct DeleteArgs {
+    #[arg(long, value_name = "DATE", value_parser = domain::listing::parse_date_bound, help = "Delete the account's transcripts created before DATE (YYYY-MM-DD or YYYY-MM-DDTHH:MM, UTC); when omitted, deletes only transcripts this tool created, older than config `retentionDays`")]
+    before: Option<String>,
+
+    #[arg(long, value_enum, help = "Only delete transcripts with this status")]
+    status: Option<TranscriptStatusArg>,
+
+    #[arg(long = "dry-run", help = "Print the transcripts that would be deleted without deleting them")]
+    dry_run: bool,
+
+    #[arg(long, help = "Delete without asking for confirmation")]
+    yes: bool,
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum TranscriptStatusArg {
+    Queued,
+    Processing,
+    Completed,
+    Error,
+}
+
+impl TranscriptStatusArg {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TranscriptStatusArg::Queued => "queued",
+            TranscriptStatusArg::Processing => "processing",
+            TranscriptStatusArg::Completed => "completed",
+            TranscriptStatusArg::Error => "error",
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+#[command(
+    args_conflicts_with_subcommands = true,
+    after_help = r#"EXAMPLES
+  assemblyai-cli history
+  assemblyai-cli history --search interview --limit 5
+  assemblyai-cli history --tag client-x --tag q3
+  assemblyai-cli history rerun 12
+"#
+)]
+struct HistoryArgs {
+    #[command(subcommand)]
+    command: Option<HistoryCommand>,
+
+    #[arg(long, value_name = "TERM", help = "Only list runs whose input, transcript id, outputs, arguments, or result contain TERM")]
+    search: Option<String>,
+
+    #[arg(long = "tag", value_name = "TAG", help = "Only list runs tagged TAG (repeatable; all must match)")]
+    tags: Vec<String>,
+
+    #[arg(long, value_name = "N", default_value_t = 20, help = "Show at most N runs")]
+    limit: usize,
+}
+
+#[derive(Subcommand, Debug)]
+enum HistoryCommand {
+    #[command(about = "Run a recorded run again with the same arguments")]
+    Rerun {
+        #[arg(value_name = "ID", help = "History entry id")]
+        id: u64,
+    },
+}
+
+#[derive(Args, Debug)]
+struct ConfigArgs {
+    #[command(subcommand)]
+    command: ConfigCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    #[command(about = "Upgrade the config to the current schema, keeping a .bak copy")]
+    Migrate,
+
+    #[command(about = "Restrict the config file to its owner (0600; the directory to 0700)")]
+    FixPermissions,
+}
+
+#[derive(Args, Debug)]
+struct CacheArgs {
+    #[command(subcommand)]
+    command: CacheCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum CacheCommand {
+    #[command(about = "List cached transcripts with their size and last use, most recent first")]
+    List,
+
+    #[command(about = "Print how many transcripts are cached and the space they take")]
+    Size,
+
+    #[command(about = "Delete cached transcripts")]
+    Clear {
+        #[arg(
+            long = "older-than",
+            value_name = "DURATION",
+            value_parser = domain::parse_duration_spec,
+            help = "Only delete transcripts not used within DURATION, e.g. 30d or 12h"
+        )]
+        older_than: Option<std::time::Duration>,
+    },
+
+    #[command(about = "Print the cache directory")]
+    Path,
+}
+
+#[derive(Args, Debug)]
+#[command(
+    after_help = r#"EXAMPLES
+  assemblyai-cli alias set weekly-standup 5551722-f677-48a6-9287-39c0aafd9ac1
+  assemblyai-cli translate weekly-standup --to de
+  assemblyai-cli alias list
+"#
+)]
+struct AliasArgs {
+    #[command(subcommand)]
+    command: AliasCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum AliasCommand {
+    #[command(about = "Point NAME at a transcript id")]
+    Set {
+        #[arg(value_name = "NAME", help = "Alias (letters, digits, '-', '_', '.')")]
+        name: String,
+
+        #[arg(value_name = "TRANSCRIPT_ID", help = "Transcript id")]
+        transcript_id: String,
+    },
+
+    #[command(about = "List aliases")]
+    List,
+
+    #[command(about = "Remove an alias")]
+    Remove {
+        #[arg(value_name = "NAME", help = "Alias")]
+        name: String,
+    },
+}
+
+#[derive(Args, Debug)]
+#[command(
+    after_help = r#"EXAMPLES
+  assemblyai-cli compare-models call.mp3 --speech-model best,nano
+  assemblyai-cli compare-models call.mp3 --speech-model best --speech-model slam-1 --reference call.txt
+"#
+)]
+struct CompareModelsArgs {
+    #[arg(value_name = "INPUT", help = "Local audio/video path or HTTP(S) URL")]
+    input: OsString,
+
+    #[arg(long = "speech-model", value_enum, value_delimiter = ',', required = true, help = "Speech models to compare (best, nano, slam-1); repeat or comma-separate, at least two")]
+    speech_models: Vec<SpeechModelArg>,
+
+    #[arg(long, value_name = "PATH", help = "Reference transcript (plain text) to compute WER against")]
+    reference: Option<PathBuf>,
+
+    #[arg(long, value_name = "CODE", help = "Language code (e.g. en, de); when omitted, the language is detected")]
+    language: Option<String>,
+
+    #[arg(long = "poll-strategy", value_enum, help = "Polling strategy: adaptive (backs off on long jobs, tightens near the expected completion) or fixed; when omitted, uses config `pollStrategy` or defaults to adaptive")]
+    poll_strategy: Option<PollStrategyArg>,
+
+    #[arg(long, value_name = "SECONDS", help = "Polling interval (shortest adaptive interval, in seconds); when omitted, uses config `pollIntervalSeconds` or defaults to 3")]
+    poll_interval_seconds: Option<u64>,
+
+    #[arg(long, value_name = "SECONDS", help = "Timeout (seconds); when omitted, uses config `timeoutSeconds` or defaults to 3600")]
+    timeout_seconds: Option<u64>,
+
+    #[arg(long, value_name = "N", help = "Maximum number of transcription jobs in flight; when omitted, uses config `concurrency` or defaults to 4")]
+    concurrency: Option<usize>,
+}
+
+#[derive(Args, Debug)]
+#[command(
+    after_help = r#"EXAMPLES
+  assemblyai-cli bench call.mp3
+  assemblyai-cli bench call.mp3 --runs 5 --models best,nano --json
+"#
+)]
+struct BenchArgs {
+    #[arg(value_name = "INPUT", help = "Local audio/video path or HTTP(S) URL")]
+    input: OsString,
+
+    #[arg(long, value_name = "N", default_value_t = 3, help = "Transcriptions per model")]
+    runs: usize,
+
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "best", help = "Speech models to measure (best, nano, slam-1); repeat or comma-separate")]
+    models: Vec<SpeechModelArg>,
+
+    #[arg(long, value_name = "CODE", help = "Language code (e.g. en, de); when omitted, the language is detected")]
+    language: Option<String>,
+
+    #[arg(long, value_name = "SECONDS", help = "Polling interval (seconds); when omitted, uses config `pollIntervalSeconds` or defaults to 3")]
+    poll_interval_seconds: Option<u64>,
+
+    #[arg(long, value_name = "SECONDS", help = "Timeout of each run (seconds); when omitted, uses config `timeoutSeconds` or defaults to 3600")]
+    timeout_seconds: Option<u64>,
+
+    #[arg(long, help = "Print the runs and means as JSON")]
+    json: bool,
+}
+
+#[derive(Args, Debug, Default)]
+struct LintThresholdArgs {
+    #[arg(long, value_name = "CPS", help = "Maximum reading speed in characters per second; when omitted, uses config `lint.maxCps` or defaults to 17")]
+    max_cps: Option<f64>,
+
+    #[arg(long, value_name = "N", help = "Maximum characters per caption line; when omitted, uses config `lint.maxLineLength` or defaults to 42")]
+    max_line_length: Option<usize>,
+
+    #[arg(long, value_name = "N", help = "Maximum lines per cue; when omitted, uses config `lint.maxLines` or defaults to 2")]
+    max_lines: Option<usize>,
+
+    #[arg(long, value_name = "MS", help = "Minimum non-zero gap between cues in milliseconds; when omitted, uses config `lint.minGapMs` or defaults to 80")]
+    min_gap_ms: Option<u64>,
+}
+
+#[derive(thiserror::Error, Debug)]
+enum RunError {
+    #[error("missing AssemblyAI API key (set ASSEMBLYAI_API_KEY or put apiKey in ~/.assemblyai-cli/config.json)")]
+    MissingApiKey,
+
+    #[error("unable to determine home directory (HOME/USERPROFILE is not set)")]
+    HomeNotFound,
+
+    #[error("failed to read config file {path:?}: {message}")]
+    ConfigRead { path: PathBuf, message: String },
+
+    #[error("failed to parse config file {path:?}: {message}")]
+    ConfigParse { path: PathBuf, message: String },
+
+    #[error("preset {name:?} requested but no config file was found")]
+    PresetWithoutConfig { name: String },
+
+    #[error("failed to write config file {path:?}: {message}")]
+    ConfigWrite { path: PathBuf, message: String },
+
+    #[error("failed to read API key from stdin: {message}")]
+    InitReadStdin { message: String },
+
+    #[error("API key cannot be empty")]
+    InitEmptyApiKey,
+
+    #[error("AssemblyAI rejected the API key; check it or pass --no-verify to store it anyway")]
+    InitApiKeyRejected,
+
+    #[error("caption check failed with {count} violation(s)")]
+    CaptionCheckFailed { count: usize },
+
+    #[error("detected language {detected} is not one of the expected languages ({expected})")]
+    UnexpectedLanguage { detected: String, expected: String },
+
+    #[error("{message}; use --on-limit warn to upload anyway")]
+    UploadLimitExceeded { message: String },
+
+    #[error("content check failed: {message}")]
+    ContentCheckFailed { message: String },
+
+    #[error("cancelled")]
+    Cancelled,
+
+    #[error("history and aliases need ~/.assemblyai-cli to be a directory; move the legacy config file to ~/.assemblyai-cli/config.json")]
+    DataDirUnavailable,
+
+    #[error("no alias named {name:?} (see `assemblyai-cli alias list`)")]
+    AliasNotFound { name: String },
+
+    #[error("no history entry #{id} (see `assemblyai-cli history`)")]
+    HistoryEntryNotFound { id: u64 },
+
+    #[error("history entry #{id} cannot be re-run: {message}")]
+    HistoryEntryInvalid { id: u64, message: String },
+
+    #[error("failed to read retry file {path:?}: {message}")]
+    RetryFileRead { path: PathBuf, message: String },
+
+    #[error("failed to write failures file {path:?}: {message}")]
+    FailuresFileWrite { path: PathBuf, message: String },
+
+    #[error("failed to open log file {path:?}: {message}")]
+    LogFileOpen { path: PathBuf, message: String },
+
+    #[error("{failed} of {total} inputs failed")]
+    BatchFailed { failed: usize, total: usize },
+
+    #[error("{failed} of {total} transcripts could not be deleted")]
+    DeleteFailed { failed: usize, total: usize },
+
+    #[error(transparent)]
+    Domain(#[from] domain::DomainError),
+
+    #[error(transparent)]
+    Infra(#[from] infra::InfraError),
+
+    #[error(transparent)]
+    Api(#[from] infra::assemblyai::ApiError),
+}
+
+impl From<infra::runner::RunnerError> for RunError {
+    fn from(value: infra::runner::RunnerError) -> Self {
+        match value {
+            infra::runner::RunnerError::Infra(err) => RunError::Infra(err),
+            infra::runner::RunnerError::Api(err) => RunError::Api(err),
+            infra::runner::RunnerError::CheckFailed { count } => RunError::CaptionCheckFailed { count },
+            infra::runner::RunnerError::UploadLimitExceeded { message } => RunError::UploadLimitExceeded { message },
+            infra::runner::RunnerError::ContentCheckFailed { message } => RunError::ContentCheckFailed { message },
+            infra::runner::RunnerError::Cancelled => RunError::Cancelled,
+            infra::runner::RunnerError::UnexpectedLanguage { detected, expected } => {
+                RunError::UnexpectedLanguage { detected, expected }
+            }
+        }
+    }
+}
+
+impl RunError {
+    fn exit_code(&self) -> u8 {
+        match self {
+            RunError::Domain(_) => 2,
+            RunError::MissingApiKey => 3,
+            RunError::HomeNotFound => 3,
+            RunError::ConfigRead { .. }
+            | RunError::ConfigParse { .. }
+            | RunError::ConfigWrite { .. }
+            | RunError::PresetWithoutConfig { .. }
+            | RunError::InitReadStdin { .. }
+            | RunError::InitEmptyApiKey
+            | RunError::InitApiKeyRejected
+            | RunError::DataDirUnavailable => 3,
+            RunError::Infra(err) => err.exit_code(),
+            RunError::Api(_) => 5,
+            RunError::CaptionCheckFailed { .. } => 6,
+            RunError::UnexpectedLanguage { .. } => 7,
+            RunError::ContentCheckFailed { .. } => 8,
+            RunError::Cancelled => 130,
+            RunError::UploadLimitExceeded { .. } => 2,
+            RunError::HistoryEntryNotFound { .. } | RunError::HistoryEntryInvalid { .. } => 2,
+            RunError::AliasNotFound { .. } => 2,
+            RunError::RetryFileRead { .. } => 2,
+            RunError::FailuresFileWrite { .. } => 1,
+            RunError::LogFileOpen { .. } => 1,
+            RunError::BatchFailed { .. } | RunError::DeleteFailed { .. } => 9,
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let argv: Vec<OsString> = std::env::args_os().skip(1).collect();
+    let cli = Cli::parse();
+
+    match run(cli, &argv).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            infra::logging::error(&err);
+            ExitCode::from(err.exit_code())
+        }
+    }
+}
+
+async fn run(cli: Cli, argv: &[OsString]) -> Result<(), RunError> {
+    infra::i18n::init(cli.locale.as_deref());
+    init_logging(&cli)?;
+    if cli.strict_config {
+        check_config_keys()?;
+    }
+    let scheduler = infra::scheduler::Scheduler::new(1);
+
+    match cli.command {
+        Commands::Transcribe(args) if args.list_formats => {
+            print!("{}", infra::formatters::format_formatter_list());
+            Ok(())
+        }
+        Commands::Transcribe(args) => run_transcribe(*args, argv, &cli.headers, &scheduler).await,
+        Commands::Init(args) => run_init(args, &cli.headers).await,
+        Commands::Lint(args) => run_lint(args),
+        Commands::Merge(args) => run_merge(args),
+        Commands::Render(args) => run_render(args, &cli.headers).await,
+        Commands::Translate(args) => run_translate(args, &cli.headers).await,
+        Commands::Eval(args) => run_eval(args, &cli.headers).await,
+        Commands::Grep(args) => run_grep(args, &cli.headers).await,
+        Commands::Index(args) => run_index(args),
+        Commands::CompareModels(args) => run_compare_models(args, &cli.headers, &scheduler).await,
+        Commands::Bench(args) => run_bench(args, &cli.headers).await,
+        Commands::Token(args) => run_token(args, &cli.headers).await,
+        Commands::Upload(args) => run_upload(args, &cli.headers).await,
+        Commands::Languages(args) => run_languages(args),
+        Commands::Meeting(args) => run_transcribe(args.into_meeting_args(), argv, &cli.headers, &scheduler).await,
+        Commands::Shownotes(args) => run_transcribe(args.into_shownotes_args(), argv, &cli.headers, &scheduler).await,
+        Commands::List(args) => run_list(args, &cli.headers).await,
+        Commands::Delete(args) => run_delete(args, &cli.headers).await,
+        Commands::History(args) => run_history(args, &cli.headers, &scheduler).await,
+        Commands::Alias(args) => run_alias(args),
+        Commands::Cache(args) => run_cache(args),
+        Commands::Config(args) => run_config(args),
+        Commands::SelfUpdate(args) => run_self_update(args).await,
+        Commands::Schema(args) => run_schema(args),
+        Commands::Version(args) => run_version(args),
+    }
+}
+
+/// Routes log events to `--log-file`, rotated at `--log-max-size`, and/or formats them as `--log-format`; plain stderr text otherwise.
+fn init_logging(cli: &Cli) -> Result<(), RunError> {
+    let format = infra::logging::LogFormat::from(cli.log_format.clone());
+    let max_size = cli.log_max_size.as_deref().map(domain::parse_size_spec).transpose()?;
+    let sink: Box<dyn std::io::Write + Send> = match &cli.log_file {
+        Some(path) => Box::new(infra::logging::RotatingFile::open(path, max_size, cli.log_keep).map_err(|err| {
+            RunError::LogFileOpen {
+                path: path.clone(),
+                message: err.to_string(),
+            }
+        })?),
+        None if format == infra::logging::LogFormat::Text => return Ok(()),
+        None => Box::new(std::io::stderr()),
+    };
+    infra::logging::init(format, sink);
+    Ok(())
+}
+
+/// Runs a transcription, or a batch of them for several inputs, `--retry-file`, or `--failures-file`.
+async fn run_transcribe(
+    mut args: TranscribeArgs,
+    argv: &[OsString],
+    headers: &[(String, String)],
+    scheduler: &infra::scheduler::Scheduler,
+) -> Result<(), RunError> {
+    if args.input.is_empty()
+        && let Some(url) = &args.from_upload_url
+    {
+        args.input = OsString::from(url);
+    }
+    if !args.no_glob {
+        let mut inputs = expand_globs(std::iter::once(std::mem::take(&mut args.input)).chain(std::mem::take(&mut args.more_inputs)))?;
+        args.input = if inputs.is_empty() { OsString::new() } else { inputs.remove(0) };
+        args.more_inputs = inputs;
+    }
+    if args.concat {
+        if args.more_inputs.is_empty() {
+            return Err(domain::DomainError::OptionRequiresOption {
+                option: "--concat",
+                requires: "two or more inputs",
+            }
+            .into());
+        }
+        return transcribe_and_record(args, argv, headers, scheduler).await.map(|_| ());
+    }
+    if args.more_inputs.is_empty() && args.retry_file.is_none() && args.failures_file.is_none() {
+        return transcribe_and_record(args, argv, headers, scheduler).await.map(|_| ());
+    }
+    run_batch(args, argv, headers, scheduler).await
+}
+
+/// Expands wildcard inputs the shell left alone, as Windows shells do, into the files they match in sorted order.
+///
+/// An input naming an existing file is kept as is, so `take [1].mp3` isn't read as a pattern.
+fn expand_globs(inputs: impl Iterator<Item = OsString>) -> Result<Vec<OsString>, RunError> {
+    let options = glob::MatchOptions {
+        case_sensitive: !cfg!(windows),
+        ..Default::default()
+    };
+    let mut expanded = Vec::new();
+    for input in inputs {
+        let pattern = match input.to_str() {
+            Some(pattern) if domain::batch::is_glob_pattern(&input) && !std::path::Path::new(pattern).exists() => pattern,
+            _ => {
+                expanded.push(input);
+                continue;
+            }
+        };
+        let invalid = |message: String| domain::DomainError::InvalidGlob {
+            pattern: pattern.to_string(),
+            message,
+        };
+        let mut matches: Vec<OsString> = glob::glob_with(pattern, options)
+            .map_err(|err| invalid(err.to_string()))?
+            .filter_map(Result::ok)
+            .filter(|path| path.is_file())
+            .map(PathBuf::into_os_string)
+            .collect();
+        if matches.is_empty() {
+            return Err(domain::DomainError::NoGlobMatches {
+                pattern: pattern.to_string(),
+            }
+            .into());
+        }
+        matches.sort();
+        expanded.extend(matches);
+    }
+    Ok(expanded)
+}
+
+/// One input of a batch run, set by [`run_batch`].
+#[derive(Clone, Debug)]
+struct BatchItem {
+    /// Output path without extension; the format's extension is appended.
+    output_base: PathBuf,
+    /// `jobs.json` and the batch's id in it, when progress is persisted.
+    store: Option<(PathBuf, String)>,
+    index: usize,
+    /// Transcript created by an interrupted run of the batch, polled instead of uploading again.
+    transcript_id: Option<String>,
+    /// SHA-256 of the bytes the interrupted run uploaded for `transcript_id`.
+    content_sha256: Option<String>,
+}
+
+/// Transcribes each input into the `--output` directory, carrying on past failures.
+///
+/// Progress is kept in `~/.assemblyai-cli/jobs.json` until the batch ends, so running the same command after
+/// an interruption skips finished inputs and polls submitted ones instead of uploading them again.
+async fn run_batch(
+    mut args: TranscribeArgs,
+    argv: &[OsString],
+    headers: &[(String, String)],
+    scheduler: &infra::scheduler::Scheduler,
+) -> Result<(), RunError> {
+    let Some(dir) = args.output.take() else {
+        return Err(domain::DomainError::OptionRequiresOption {
+            option: "a batch run",
+            requires: "--output DIR",
+        }
+        .into());
+    };
+    let mut inputs: Vec<OsString> = std::iter::once(std::mem::take(&mut args.input))
+        .filter(|input| !input.is_empty())
+        .chain(std::mem::take(&mut args.more_inputs))
+        .collect();
+    if let Some(path) = &args.retry_file {
+        let read_error = |message: String| RunError::RetryFileRead {
+            path: path.clone(),
+            message,
+        };
+        let contents = std::fs::read_to_string(path).map_err(|err| read_error(err.to_string()))?;
+        let retry = domain::batch::FailureList::parse(&contents).map_err(|err| read_error(err.to_string()))?;
+        infra::metrics::METRICS.add_retries(retry.failures.len() as u64);
+        inputs.extend(retry.inputs().map(OsStr::to_os_string));
+    }
+    std::fs::create_dir_all(&dir).map_err(infra::InfraError::Io)?;
+    if let Some(addr) = args.metrics_addr {
+        let addr = infra::metrics::serve(addr)?;
+        infra::logging::info(tr!("serving-metrics", addr = addr.to_string()));
+    }
+
+    let store_path = jobs_path();
+    let cwd = std::env::current_dir().map(|dir| dir.display().to_string()).unwrap_or_default();
+    let batch_id = domain::jobs::batch_id(&cwd, argv);
+    // Held until the batch is recorded, so a run starting at the same time can't drop it from jobs.json.
+    let lock = store_path.as_deref().map(infra::lock::StateLock::acquire).transpose()?;
+    let mut store = match &store_path {
+        Some(path) => infra::jobs::load(path)?,
+        None => domain::jobs::JobStore::default(),
+    };
+    let resumed = store.batch(&batch_id).filter(|_| !args.no_resume).cloned();
+    let jobs = match resumed {
+        Some(batch) => {
+            let done = batch.jobs.iter().filter(|job| !matches!(job.state, domain::jobs::JobState::Pending)).count();
+            infra::logging::info(tr!("resuming-batch", done = done, total = batch.jobs.len()));
+            batch.jobs
+        }
+        None => {
+            let jobs: Vec<domain::jobs::Job> = inputs
+                .iter()
+                .map(|input| domain::jobs::Job {
+                    input: input.clone(),
+                    state: domain::jobs::JobState::Pending,
+                })
+                .collect();
+            store.start(domain::jobs::Batch {
+                id: batch_id.clone(),
+                args: argv.to_vec(),
+                jobs: jobs.clone(),
+            });
+            save_jobs(store_path.as_deref(), &store);
+            jobs
+        }
+    };
+    drop(lock);
+    let inputs: Vec<OsString> = jobs.iter().map(|job| job.input.clone()).collect();
+    // Speaker-name prompts can't share the terminal, so `--identify-speakers` takes the inputs one at a time.
+    let concurrency = match args.concurrency {
+        _ if args.identify_speakers => 1,
+        Some(concurrency) => concurrency,
+        None => load_config_file(None, None)?.and_then(|c| c.concurrency).unwrap_or(4),
+    };
+    if concurrency == 0 {
+        return Err(domain::DomainError::InvalidConcurrency.into());
+    }
+    let scheduler = scheduler.with_concurrency(concurrency);
+
+    // Finished inputs of a resumed batch keep their state; the rest run through the scheduler, `concurrency` at a time.
+    let mut states: Vec<(usize, OsString, domain::jobs::JobState)> = Vec::new();
+    let mut items = Vec::new();
+    for (index, (job, base)) in jobs.into_iter().zip(domain::batch::output_bases(&dir, &inputs)).enumerate() {
+        let (transcript_id, content_sha256) = match job.state {
+            state @ (domain::jobs::JobState::Done { .. } | domain::jobs::JobState::Failed { .. }) => {
+                states.push((index, job.input, state));
+                continue;
+            }
+            domain::jobs::JobState::Submitted {
+                transcript_id,
+                content_sha256,
+            } => (Some(transcript_id), content_sha256),
+            domain::jobs::JobState::Pending => (None, None),
+        };
+        let item = TranscribeArgs {
+            input: job.input.clone(),
+            batch_item: Some(BatchItem {
+                output_base: base,
+                store: store_path.clone().map(|path| (path, batch_id.clone())),
+                index,
+                transcript_id,
+                content_sha256,
+            }),
+            ..args.clone()
+        };
+        let (argv, headers, scheduler) = (argv.to_vec(), headers.to_vec(), scheduler.clone());
+        let (store_path, batch_id) = (store_path.clone(), batch_id.clone());
+        items.push(infra::logging::scoped(async move {
+            infra::logging::info(tr!("transcribing-input", input = job.input.display().to_string()));
+            let running = infra::metrics::METRICS.job_started();
+            let result = transcribe_and_record(item, &argv, &headers, &scheduler).await;
+            running.finish(result.is_ok());
+            let state = match result {
+                Ok(done) => domain::jobs::JobState::Done {
+                    transcript_id: done.transcript_id,
+                    language_code: done.detected_language.map(|language| language.code),
+                    content_sha256: done.content_sha256,
+                },
+                Err(RunError::Cancelled) => return Err(infra::runner::RunnerError::Cancelled),
+                Err(err) => {
+                    infra::logging::error(format_args!("{}: {err}", job.input.display()));
+                    domain::jobs::JobState::Failed {
+                        error: err.to_string(),
+                        exit_code: err.exit_code(),
+                    }
+                }
+            };
+            update_job(store_path.as_deref(), &batch_id, index, state.clone());
+            Ok((index, job.input, state))
+        }));
+    }
+    states.extend(scheduler.run_all(items).await?);
+    states.sort_by_key(|(index, _, _)| *index);
+
+    let mut failures = Vec::new();
+    let mut languages = Vec::new();
+    for (_, input, state) in states {
+        match state {
+            domain::jobs::JobState::Done { language_code, .. } => languages.extend(language_code),
+            domain::jobs::JobState::Failed { error, exit_code } => failures.push(domain::batch::Failure {
+                input,
+                error,
+                exit_code,
+            }),
+            domain::jobs::JobState::Submitted { .. } | domain::jobs::JobState::Pending => {}
+        }
+    }
+
+    if let Some(path) = &store_path {
+        let _lock = infra::lock::StateLock::acquire(path)?;
+        let mut store = infra::jobs::load(path)?;
+        store.finish(&batch_id);
+        save_jobs(Some(path), &store);
+    }
+    infra::logging::info(tr!(
+        "batch-summary",
+        done = inputs.len() - failures.len(),
+        total = inputs.len(),
+        dir = dir.display().to_string()
+    ));
+    if !languages.is_empty() {
+        let counts = domain::batch::language_counts(languages.iter().map(String::as_str));
+        infra::logging::info(tr!("batch-languages", languages = counts));
+    }
+    let failed = failures.len();
+    if let Some(path) = &args.failures_file {
+        if args.deterministic
+            && let Ok(cwd) = std::env::current_dir()
+        {
+            for failure in &mut failures {
+                failure.input = domain::batch::portable_input(&failure.input, &cwd);
+                failure.error = domain::batch::portable_message(&failure.error, &cwd);
+            }
+        }
+        let list = domain::batch::FailureList { failures };
+        let write_error = |message: String| RunError::FailuresFileWrite {
+            path: path.clone(),
+            message,
+        };
+        let json = serde_json::to_string_pretty(&list).map_err(|err| write_error(err.to_string()))?;
+        std::fs::write(path, format!("{json}\n")).map_err(|err| write_error(err.to_string()))?;
+    }
+    if failed > 0 {
+        return Err(RunError::BatchFailed {
+            failed,
+            total: inputs.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Sets the state of one batch input in `jobs.json`, warning when it can't be saved.
+fn update_job(path: Option<&std::path::Path>, batch_id: &str, index: usize, state: domain::jobs::JobState) {
+    let Some(path) = path else {
+        return;
+    };
+    let _lock = match infra::lock::StateLock::acquire(path) {
+        Ok(lock) => lock,
+        Err(err) => return infra::logging::warn(tr!("batch-progress-failed", error = err.to_string())),
+    };
+    match infra::jobs::load(path) {
+        Ok(mut store) => {
+            if store.update(batch_id, index, state) {
+                save_jobs(Some(path), &store);
+            }
+        }
+        Err(err) => infra::logging::warn(tr!("batch-progress-failed", error = err.to_string())),
+    }
+}
+
+fn save_jobs(path: Option<&std::path::Path>, store: &domain::jobs::JobStore) {
+    if let Some(path) = path
+        && let Err(err) = infra::jobs::save(path, store)
+    {
+        infra::logging::warn(tr!("batch-progress-save-failed", path = format!("{path:?}"), error = err.to_string()));
+    }
+}
+
+/// Runs one transcription and records it in the history file; `argv` is what `history rerun` replays.
+async fn transcribe_and_record(
+    args: TranscribeArgs,
+    argv: &[OsString],
+    headers: &[(String, String)],
+    scheduler: &infra::scheduler::Scheduler,
+) -> Result<infra::runner::Transcribed, RunError> {
+    let started_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let started = std::time::Instant::now();
+    let input = args.input.clone();
+    let tags = domain::history::parse_tags(&args.tags)?;
+    infra::logging::set_file(&input.to_string_lossy());
+
+    let mut outputs = Vec::new();
+    let result = transcribe(args, headers, scheduler, &mut outputs).await;
+
+    let entry = domain::history::HistoryEntry {
+        id: 0,
+        started_at,
+        duration_ms: started.elapsed().as_millis() as u64,
+        options_hash: domain::history::options_hash(argv, &input),
+        input,
+        args: argv.to_vec(),
+        transcript_id: result.as_ref().ok().map(|done| done.transcript_id.clone()),
+        outputs,
+        tags,
+        result: match &result {
+            Ok(_) => "ok".to_string(),
+            Err(err) => err.to_string(),
+        },
+    };
+    if let Some(path) = history_path()
+        && let Err(err) = infra::history::append(&path, entry)
+    {
+        infra::logging::warn(tr!("history-save-failed", path = format!("{path:?}"), error = err.to_string()));
+    }
+
+    result
+}
+
+async fn transcribe(
+    args: TranscribeArgs,
+    headers: &[(String, String)],
+    scheduler: &infra::scheduler::Scheduler,
+    outputs: &mut Vec<String>,
+) -> Result<infra::runner::Transcribed, RunError> {
+    let extension = domain::input_extension(&args.input);
+    let config = load_config_file(args.preset.as_deref(), extension.as_deref())?;
+    let client = api_client(&config, headers)?;
+    let batch_item = args.batch_item.clone();
+    let input = args.input.clone();
+    let mut options = resolve_transcribe_options(args, &config)?;
+    let plan = app::build_plan(&options)?;
+    let sidecar_path = match (&batch_item, options.output()) {
+        (Some(item), _) => Some(domain::batch::sidecar_path(&item.output_base)),
+        (None, domain::Output::FilePath(path)) => Some(domain::batch::sidecar_path(&path.with_extension(""))),
+        (None, domain::Output::Stdout) => None,
+    };
+    if options.identify_speakers()
+        && let Some(json) = sidecar_path.as_ref().and_then(|path| std::fs::read_to_string(path).ok())
+    {
+        options = options.with_speaker_names(domain::batch::sidecar_speakers(&json));
+    }
+
+    let output = match options.output() {
+        domain::Output::FilePath(path) => Some(path.as_path()),
+        domain::Output::Stdout => None,
+    };
+    outputs.extend(
+        [output, options.translated_output(), options.report_output()]
+            .into_iter()
+            .flatten()
+            .map(|path| path.display().to_string()),
+    );
+    let record_submitted = |transcript_id: &str, content_sha256: Option<&str>| {
+        if let Some(BatchItem { store: Some((path, batch_id)), index, .. }) = &batch_item {
+            let state = domain::jobs::JobState::Submitted {
+                transcript_id: transcript_id.to_string(),
+                content_sha256: content_sha256.map(str::to_string),
+            };
+            update_job(Some(path), batch_id, *index, state);
+        }
+    };
+    let submission = match batch_item.as_ref().and_then(|item| item.transcript_id.clone()) {
+        Some(transcript_id) => infra::runner::Submission::Existing { transcript_id },
+        None if !options.concat().is_empty() => {
+            let mut plans = vec![plan];
+            for part in options.concat() {
+                plans.push(app::build_plan(&options.with_input(part.clone()))?);
+            }
+            infra::runner::Submission::Concat { plans }
+        }
+        None => infra::runner::Submission::New {
+            plan,
+            on_created: &record_submitted,
+        },
+    };
+    let mut done = scheduler.run(infra::runner::run_transcribe(submission, client, &options)).await?;
+    if done.content_sha256.is_none() {
+        done.content_sha256 = batch_item.as_ref().and_then(|item| item.content_sha256.clone());
+    }
+    let language_for_sidecar = done.detected_language.as_ref().filter(|_| batch_item.is_some());
+    if let Some(path) = sidecar_path
+        && (language_for_sidecar.is_some() || !done.speaker_names.is_empty())
+    {
+        let sidecar = domain::batch::Sidecar {
+            input: match std::env::current_dir() {
+                Ok(cwd) if options.deterministic() => domain::batch::portable_input(&input, &cwd),
+                _ => input,
+            },
+            transcript_id: done.transcript_id.clone(),
+            language_code: done.detected_language.as_ref().map(|language| language.code.clone()),
+            language_confidence: done.detected_language.as_ref().and_then(|language| language.confidence),
+            content_sha256: done.content_sha256.clone(),
+            speakers: done.speaker_names.clone(),
+        };
+        let json = serde_json::to_string_pretty(&sidecar).map_err(|err| infra::InfraError::Io(err.into()))?;
+        std::fs::write(&path, json + "\n").map_err(infra::InfraError::Io)?;
+        outputs.push(path.display().to_string());
+    }
+    Ok(done)
+}
+
+/// Resolves transcribe flags against config values and defaults.
+fn resolve_transcribe_options(args: TranscribeArgs, config: &Option<domain::config::ConfigFile>) -> Result<TranscribeOptions, RunError> {
+    let (format, exec_formatter) = match args.format {
+        Some(FormatValue::Builtin(format)) => (format.into(), None),
+        Some(FormatValue::Exec(command)) => (TranscriptFormat::Text, Some(command)),
+        None => (config.as_ref().and_then(|c| c.format).unwrap_or(TranscriptFormat::Text), None),
+    };
+
+    let output = match args.batch_item {
+        Some(item) => {
+            let mut path = item.output_base.into_os_string();
+            path.push(format!(".{}", format.extension()));
+            Some(PathBuf::from(path))
+        }
+        None => args.output.or_else(|| config.as_ref().and_then(|c| c.output.clone())),
+    };
+
+    let speech_model = args
+        .speech_model
+        .map(Into::into)
+        .or_else(|| config.as_ref().and_then(|c| c.speech_model))
+        .unwrap_or(domain::SpeechModel::Best);
+
+    let language_detection = match (
+        cli_bool_override(args.language_detection, args.no_language_detection),
+        config.as_ref().and_then(|c| c.language_detection),
+    ) {
+        (Some(value), _) => value,
+        (None, _) if args.language.as_deref().is_some_and(domain::is_auto_language) => true,
+        (None, Some(value)) => value,
+        (None, None) => true,
+    };
+
+    let language = args.language.or_else(|| config.as_ref().and_then(|c| c.language.clone()));
+
+    let expect_language = if args.expect_language.is_empty() {
+        config
+            .as_ref()
+            .and_then(|c| c.expect_language.clone())
+            .unwrap_or_default()
+    } else {
+        args.expect_language
+    };
+
+    let punctuate = match (
+        cli_bool_override(args.punctuate, args.no_punctuate),
+        config.as_ref().and_then(|c| c.punctuate),
+    ) {
+        (Some(value), _) => value,
+        (None, Some(value)) => value,
+        (None, None) => true,
+    };
+
+    let format_text = match (
+        cli_bool_override(args.format_text, args.no_format_text),
+        config.as_ref().and_then(|c| c.format_text),
+    ) {
+        (Some(value), _) => value,
+        (None, Some(value)) => value,
+        (None, None) => true,
+    };
+
+    let multichannel = match (
+        cli_bool_override(args.multichannel, args.no_multichannel),
+        config.as_ref().and_then(|c| c.multichannel),
+    ) {
+        (Some(value), _) => value,
+        (None, Some(value)) => value,
+        (None, None) => true,
+    };
+
+    let audio_profile = args
+        .audio_profile
+        .map(Into::into)
+        .or_else(|| config.as_ref().and_then(|c| c.audio_profile));
+
+    let skip_validation = if args.skip_validation {
+        true
+    } else {
+        config.as_ref().and_then(|c| c.skip_validation).unwrap_or(false)
+    };
+
+    let temp_dir = args.temp_dir.or_else(|| config.as_ref().and_then(|c| c.temp_dir.clone()));
+
+    let upload_limits = domain::UploadLimits {
+        max_bytes: args
+            .max_upload_bytes
+            .or_else(|| config.as_ref().and_then(|c| c.max_upload_bytes)),
+        max_duration_minutes: args
+            .max_duration_minutes
+            .or_else(|| config.as_ref().and_then(|c| c.max_duration_minutes)),
+        action: args
+            .on_limit
+            .map(Into::into)
+            .or_else(|| config.as_ref().and_then(|c| c.on_limit))
+            .unwrap_or_default(),
+    };
+
+    let content_requirements = domain::ContentRequirements {
+        fail_on_empty: if args.fail_on_empty {
+            true
+        } else {
+            config.as_ref().and_then(|c| c.fail_on_empty).unwrap_or(false)
+        },
+        min_words: args.min_words.or_else(|| config.as_ref().and_then(|c| c.min_words)),
+        min_avg_confidence: args
+            .min_avg_confidence
+            .or_else(|| config.as_ref().and_then(|c| c.min_avg_confidence)),
+    };
+
+    let channel_names = if args.channel_names.is_empty() {
+        config
+            .as_ref()
+            .and_then(|c| c.channel_names.clone())
+            .unwrap_or_default()
+    } else {
+        args.channel_names
+            .iter()
+            .map(|value| domain::parse_channel_name(value))
+            .collect::<Result<_, _>>()?
+    };
+
+    let split_by_channel = if args.split_by_channel {
+        true
+    } else {
+        config.as_ref().and_then(|c| c.split_by_channel).unwrap_or(false)
+    };
+
+    let extract_audio = match (
+        cli_bool_override(args.extract_audio, args.no_extract_audio),
+        config.as_ref().and_then(|c| c.extract_audio),
+    ) {
+        (Some(value), _) => value,
+        (None, Some(value)) => value,
+        (None, None) => true,
+    };
+
+    let disfluencies = if args.disfluencies {
+        true
+    } else {
+        config.as_ref().and_then(|c| c.disfluencies).unwrap_or(false)
+    };
+
+    let filter_profanity = if args.filter_profanity {
+        true
+    } else {
+        config
+            .as_ref()
+            .and_then(|c| c.filter_profanity)
+            .unwrap_or(false)
+    };
+
+    let speaker_labels = if args.speaker_labels {
+        true
+    } else {
+        config
+            .as_ref()
+            .and_then(|c| c.speaker_labels)
+            .unwrap_or(false)
+    };
+
+    let speaker_prefix = if args.no_speaker_prefix || args.speaker_style.is_some() {
+        !args.no_speaker_prefix
+    } else {
+        config.as_ref().and_then(|c| c.speaker_prefix).unwrap_or(true)
+    };
+    let speaker_style = match args
+        .speaker_style
+        .or_else(|| config.as_ref().and_then(|c| c.speaker_style.clone()))
+    {
+        _ if !speaker_prefix => domain::subtitles::SpeakerStyle::none(),
+        Some(template) => domain::subtitles::SpeakerStyle::template(&template)?,
+        None => domain::subtitles::SpeakerStyle::default(),
+    };
+    let merge_short_utterances = args
+        .merge_short_utterances
+        .or_else(|| config.as_ref().and_then(|c| c.merge_short_utterances.clone()))
+        .map(|value| domain::parse_duration_spec(&value))
+        .transpose()?;
+    let paragraph_gap = args
+        .paragraph_gap
+        .or_else(|| config.as_ref().and_then(|c| c.paragraph_gap.clone()))
+        .map(|value| domain::parse_duration_spec(&value))
+        .transpose()?;
+
+    let sentiment_analysis = if args.sentiment_analysis {
+        true
+    } else {
+        config
+            .as_ref()
+            .and_then(|c| c.sentiment_analysis)
+            .unwrap_or(false)
+    };
+
+    let auto_chapters = if args.auto_chapters {
+        true
+    } else {
+        config.as_ref().and_then(|c| c.auto_chapters).unwrap_or(false)
+    };
+
+    let auto_highlights = if args.auto_highlights {
+        true
+    } else {
+        config.as_ref().and_then(|c| c.auto_highlights).unwrap_or(false)
+    };
+
+    let entity_detection = if args.entity_detection {
+        true
+    } else {
+        config.as_ref().and_then(|c| c.entity_detection).unwrap_or(false)
+    };
+
+    let frame_rate = args
+        .frame_rate
+        .or_else(|| config.as_ref().and_then(|c| c.frame_rate))
+        .unwrap_or(30);
+
+    let timecode_start = args
+        .timecode_start
+        .or_else(|| config.as_ref().and_then(|c| c.timecode_start.clone()));
+
+    let embed_metadata = if args.embed_metadata {
+        true
+    } else {
+        config.as_ref().and_then(|c| c.embed_metadata).unwrap_or(false)
+    };
+
+    let write_chapters = if args.write_chapters {
+        true
+    } else {
+        config.as_ref().and_then(|c| c.write_chapters).unwrap_or(false)
+    };
+
+    let translate_to = args
+        .translate_to
+        .or_else(|| config.as_ref().and_then(|c| c.translate_to.clone()));
+
+    let lemur_model = args
+        .lemur_model
+        .or_else(|| config.as_ref().and_then(|c| c.lemur_model.clone()));
+
+    let speech_threshold = args
+        .speech_threshold
+        .or_else(|| config.as_ref().and_then(|c| c.speech_threshold));
+
+    let chars_per_caption = args
+        .chars_per_caption
+        .or_else(|| config.as_ref().and_then(|c| c.chars_per_caption))
+        .unwrap_or(128);
+
+    let max_cps = args.max_cps.or_else(|| config.as_ref().and_then(|c| c.max_cps));
+    let subtitle_source = args.subtitle_source.map(Into::into).or_else(|| config.as_ref().and_then(|c| c.subtitle_source));
+
+    let srt_start_index = args
+        .srt_start_index
+        .or_else(|| config.as_ref().and_then(|c| c.srt_start_index));
+
+    let split_output = match (args.split_output_every, args.split_output_by) {
+        (Some(every), _) => Some(domain::SplitOutput::Every(domain::parse_duration_spec(&every)?)),
+        (None, Some(SplitOutputByArg::Chapter)) => Some(domain::SplitOutput::Chapter),
+        (None, None) => match (
+            config.as_ref().and_then(|c| c.split_output_every.as_deref()),
+            config.as_ref().and_then(|c| c.split_output_by),
+        ) {
+            (Some(every), _) => Some(domain::SplitOutput::Every(domain::parse_duration_spec(every)?)),
+            (None, Some(domain::config::SplitOutputBy::Chapter)) => Some(domain::SplitOutput::Chapter),
+            (None, None) => None,
+        },
+    };
+
+    let srt_renumber = if args.srt_renumber {
+        true
+    } else {
+        config
+            .as_ref()
+            .and_then(|c| c.srt_renumber)
+            .unwrap_or(false)
+    };
+
+    let check = if args.check || config.as_ref().and_then(|c| c.check).unwrap_or(false) {
+        Some(resolve_lint_options(config.as_ref(), &LintThresholdArgs::default()))
+    } else {
+        None
+    };
+
+    let word_boost = if args.word_boost.is_empty() {
+        config
+            .as_ref()
+            .and_then(|c| c.word_boost.clone())
+            .unwrap_or_default()
+    } else {
+        args.word_boost
+    };
+
+    let keyterms_prompt = if args.keyterms_prompt.is_empty() {
+        config
+            .as_ref()
+            .and_then(|c| c.keyterms_prompt.clone())
+            .unwrap_or_default()
+    } else {
+        args.keyterms_prompt
+    };
+
+    let custom_spelling_cli = args
+        .custom_spelling
+        .into_iter()
+        .map(|s| domain::parse_custom_spelling_kv(&s))
+        .collect::<Result<Vec<CustomSpelling>, domain::DomainError>>()?;
+
+    let custom_spelling = if custom_spelling_cli.is_empty() {
+        config
+            .as_ref()
+            .and_then(|c| c.custom_spelling.clone())
+            .unwrap_or_default()
+    } else {
+        custom_spelling_cli
+    };
+
+    let post_replace = config
+        .as_ref()
+        .and_then(|c| c.post_replace.clone())
+        .unwrap_or_default();
+
+    let redact_terms = match args
+        .redact_terms_file
+        .or_else(|| config.as_ref().and_then(|c| c.redact_terms_file.clone()))
+    {
+        Some(path) => domain::postprocess::parse_redact_terms(&read_input_file(&path)?),
+        None => Vec::new(),
+    };
+
+    let redact_style = args
+        .redact_style
+        .map(Into::into)
+        .or_else(|| config.as_ref().and_then(|c| c.redact_style))
+        .unwrap_or_default();
+
+    let normalize_specs = if args.normalize.is_empty() {
+        config
+            .as_ref()
+            .and_then(|c| c.normalize.clone())
+            .unwrap_or_default()
+    } else {
+        args.normalize
+    };
+    let normalize = normalize_specs
+        .iter()
+        .map(|s| domain::postprocess::parse_normalize_spec(s))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let report_specs = if args.report.is_empty() {
+        config
+            .as_ref()
+            .and_then(|c| c.reports.clone())
+            .unwrap_or_default()
+    } else {
+        args.report
+    };
+
+    let reports = report_specs
+        .iter()
+        .map(|s| domain::reports::parse_report_spec(s))
+        .collect::<Result<Vec<_>, domain::DomainError>>()?;
+
+    let report_output = args
+        .report_output
+        .or_else(|| config.as_ref().and_then(|c| c.report_output.clone()));
+
+    let timestamp_format = args
+        .timestamp_format
+        .map(Into::into)
+        .or_else(|| config.as_ref().and_then(|c| c.timestamp_format));
+
+    let output_encoding = resolve_output_encoding(args.encoding, args.line_endings, args.compress, config);
+
+    // Config `tee`/`preview` only apply when writing to a file; the flags are rejected without --output.
+    let config_echo = config.as_ref().filter(|_| output.is_some());
+    let tee = args.tee.map(Into::into).or_else(|| config_echo.and_then(|c| c.tee));
+    let preview = args.preview.or_else(|| config_echo.and_then(|c| c.preview));
+
+    let poll_strategy = args
+        .poll_strategy
+        .map(Into::into)
+        .or_else(|| config.as_ref().and_then(|c| c.poll_strategy))
+        .unwrap_or_default();
+
+    let poll_interval_seconds = args
+        .poll_interval_seconds
+        .or_else(|| config.as_ref().and_then(|c| c.poll_interval_seconds))
+        .unwrap_or(3);
+
+    let timeout_seconds = args
+        .timeout_seconds
+        .or_else(|| config.as_ref().and_then(|c| c.timeout_seconds))
+        .unwrap_or(3600);
+
+    let options = TranscribeOptions::new(domain::TranscribeOptionsParams {
+        input: args.input,
+        concat: if args.concat { args.more_inputs } else { Vec::new() },
+        audio_url: args.from_upload_url,
+        format,
+        output,
+        speech_model,
+        language_detection,
+        expect_language,
+        language,
+        punctuate,
+        format_text,
+        disfluencies,
+        filter_profanity,
+        speaker_labels,
+        speaker_style,
+        merge_short_utterances,
+        paragraph_gap,
+        mark_uncertain: args.mark_uncertain.or_else(|| config.as_ref().and_then(|c| c.mark_uncertain)),
+        multichannel,
+        channel_names,
+        identify_speakers: args.identify_speakers,
+        split_by_channel,
+        extract_audio,
+        audio_profile,
+        skip_validation,
+        temp_dir,
+        keep_temp: args.keep_temp,
+        upload_index: if args.no_upload_reuse { None } else { data_file_path("uploads.json") },
+        verify_upload: args.verify,
+        upload_limits,
+        content_requirements,
+        sentiment_analysis,
+        auto_chapters,
+        auto_highlights,
+        entity_detection,
+        speech_threshold,
+        chars_per_caption,
+        max_cps,
+        subtitle_source,
+        srt_start_index,
+        srt_renumber,
+        split_output,
+        check,
+        frame_rate,
+        timecode_start,
+        embed_metadata,
+        write_chapters,
+        translate_to,
+        translated_output: args.translated_output,
+        lemur_model,
+        word_boost,
+        keyterms_prompt,
+        custom_spelling,
+        post_replace,
+        redact_terms,
+        redact_style,
+        normalize,
+        reports,
+        report_output,
+        timestamp_format,
+        output_encoding,
+        tee,
+        preview,
+        exec_formatter,
+        json_include: args.json_include,
+        json_exclude: args.json_exclude,
+        poll_strategy,
+        poll_interval: Duration::from_secs(poll_interval_seconds),
+        timeout: Duration::from_secs(timeout_seconds),
+        deterministic: args.deterministic,
+    })?;
+
+    Ok(options)
+}
+
+fn api_client(
+    config: &Option<domain::config::ConfigFile>,
+    headers: &[(String, String)],
+) -> Result<infra::assemblyai::AssemblyAiClient, RunError> {
+    let api_key = load_api_key(config.as_ref())?;
+
+    let base_url = std::env::var("ASSEMBLYAI_BASE_URL")
+        .ok()
+        .or_else(|| config.as_ref().and_then(|c| c.base_url.clone()));
+
+    Ok(infra::assemblyai::AssemblyAiClient::new(infra::assemblyai::AssemblyAiClientConfig {
+        api_key,
+        base_url,
+        extra_headers: extra_headers(config.as_ref().and_then(|c| c.extra_headers.as_ref()), headers),
+    })?)
+}
+
+/// Config `extraHeaders` followed by `--header` values, so a flag replaces a config header of the same name.
+fn extra_headers(
+    config: Option<&std::collections::BTreeMap<String, String>>,
+    headers: &[(String, String)],
+) -> Vec<(String, String)> {
+    config
+        .into_iter()
+        .flatten()
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .chain(headers.iter().cloned())
+        .collect()
+}
+
+fn resolve_output_encoding(
+    encoding: Option<EncodingArg>,
+    line_endings: Option<LineEndingsArg>,
+    compress: Option<CompressArg>,
+    config: &Option<domain::config::ConfigFile>,
+) -> domain::encoding::OutputEncoding {
+    domain::encoding::OutputEncoding {
+        encoding: encoding
+            .map(Into::into)
+            .or_else(|| config.as_ref().and_then(|c| c.encoding))
+            .unwrap_or_default(),
+        line_endings: line_endings
+            .map(Into::into)
+            .or_else(|| config.as_ref().and_then(|c| c.line_endings)),
+        compression: compress.map(Into::into).or_else(|| config.as_ref().and_then(|c| c.compress)),
+    }
+}
+
+async fn run_translate(args: TranslateArgs, headers: &[(String, String)]) -> Result<(), RunError> {
+    let config = load_config_file(None, None)?;
+    let client = api_client(&config, headers)?;
+
+    let lemur_model = args
+        .lemur_model
+        .or_else(|| config.as_ref().and_then(|c| c.lemur_model.clone()));
+
+    let chars_per_caption = args
+        .chars_per_caption
+        .or_else(|| config.as_ref().and_then(|c| c.chars_per_caption))
+        .unwrap_or(128);
+
+    let timestamp_format = args
+        .timestamp_format
+        .map(Into::into)
+        .or_else(|| config.as_ref().and_then(|c| c.timestamp_format))
+        .unwrap_or_default();
+
+    let output_encoding = resolve_output_encoding(args.encoding, args.line_endings, args.compress, &config);
+
+    let options = domain::TranslateOptions::new(domain::TranslateOptionsParams {
+        transcript_id: resolve_transcript_id(args.transcript_id)?,
+        language: args.to,
+        format: args.format.map(TranscriptFormat::from).unwrap_or(TranscriptFormat::Text),
+        output: args.output,
+        lemur_model,
+        chars_per_caption,
+        timestamp_format,
+        output_encoding,
+    })?;
+
+    infra::runner::run_translate(client, transcript_cache(&config).as_ref(), &options).await?;
+    Ok(())
+}
+
+async fn run_compare_models(
+    args: CompareModelsArgs,
+    headers: &[(String, String)],
+    scheduler: &infra::scheduler::Scheduler,
+) -> Result<(), RunError> {
+    let mut models: Vec<domain::SpeechModel> = Vec::new();
+    let first_model = args.speech_models.first().cloned();
+    for model in args.speech_models.into_iter().map(domain::SpeechModel::from) {
+        if !models.contains(&model) {
+            models.push(model);
+        }
+    }
+    if models.len() < 2 {
+        return Err(domain::DomainError::CompareNeedsTwoModels.into());
+    }
+
+    let reference = args.reference.as_deref().map(read_input_file).transpose()?;
+
+    let extension = domain::input_extension(&args.input);
+    let config = load_config_file(None, extension.as_deref())?;
+    let client = api_client(&config, headers)?;
+    let concurrency = args
+        .concurrency
+        .or_else(|| config.as_ref().and_then(|c| c.concurrency))
+        .unwrap_or(4);
+    if concurrency == 0 {
+        return Err(domain::DomainError::InvalidConcurrency.into());
+    }
+    let options = resolve_transcribe_options(
+        TranscribeArgs {
+            no_language_detection: args.language.as_deref().is_some_and(|code| !domain::is_auto_language(code)),
+            input: args.input,
+            speech_model: first_model,
+            format: Some(FormatValue::Builtin(FormatArg::Text)),
+            language: args.language,
+            poll_strategy: args.poll_strategy,
+            poll_interval_seconds: args.poll_interval_seconds,
+            timeout_seconds: args.timeout_seconds,
+            ..Default::default()
+        },
+        &config,
+    )?;
+    for model in &models {
+        options.with_speech_model(*model).check_language_support()?;
+    }
+    let plan = app::build_plan(&options)?;
+
+    let scheduler = scheduler.with_concurrency(concurrency);
+    infra::runner::run_compare_models(plan, client, &options, &models, reference.as_deref(), &scheduler).await?;
+    Ok(())
+}
+
+async fn run_bench(args: BenchArgs, headers: &[(String, String)]) -> Result<(), RunError> {
+    if args.runs == 0 {
+        return Err(domain::DomainError::InvalidBenchRuns.into());
+    }
+    let mut models: Vec<domain::SpeechModel> = Vec::new();
+    let first_model = args.models.first().cloned();
+    for model in args.models.into_iter().map(domain::SpeechModel::from) {
+        if !models.contains(&model) {
+            models.push(model);
+        }
+    }
+
+    let extension = domain::input_extension(&args.input);
+    let config = load_config_file(None, extension.as_deref())?;
+    let client = api_client(&config, headers)?;
+    // A fixed interval, so every run's queue and processing times are observed at the same resolution, and a fresh
+    // upload each run, since that is part of what is measured.
+    let options = resolve_transcribe_options(
+        TranscribeArgs {
+            no_language_detection: args.language.as_deref().is_some_and(|code| !domain::is_auto_language(code)),
+            input: args.input,
+            speech_model: first_model,
+            format: Some(FormatValue::Builtin(FormatArg::Text)),
+            language: args.language,
+            poll_strategy: Some(PollStrategyArg::Fixed),
+            poll_interval_seconds: args.poll_interval_seconds,
+            timeout_seconds: args.timeout_seconds,
+            no_upload_reuse: true,
+            ..Default::default()
+        },
+        &config,
+    )?;
+    for model in &models {
+        options.with_speech_model(*model).check_language_support()?;
+    }
+    let plan = app::build_plan(&options)?;
+
+    let runs = infra::runner::run_bench(plan, client, &options, &models, args.runs).await?;
+    let report = domain::bench::BenchReport::new(runs);
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report).expect("bench report serializes"));
+    } else {
+        print!("{}", domain::bench::format_bench_table(&report));
+    }
+    Ok(())
+}
+
+async fn run_eval(args: EvalArgs, headers: &[(String, String)]) -> Result<(), RunError> {
+    let reference = read_input_file(&args.reference)?;
+
+    let hypothesis_path = std::path::Path::new(&args.hypothesis);
+    let hypothesis = if hypothesis_path.exists() {
+        infra::runner::read_hypothesis_file(hypothesis_path)?
+    } else {
+        let config = load_config_file(None, None)?;
+        let client = api_client(&config, headers)?;
+        let transcript_id = resolve_transcript_id(args.hypothesis)?;
+        infra::runner::fetch_transcript_text(&client, transcript_cache(&config).as_ref(), &transcript_id).await?
+    };
+
+    let evaluation = domain::eval::evaluate(&reference, &hypothesis);
+    print!("{}", domain::eval::format_evaluation(&evaluation));
+    Ok(())
+}
+
+async fn run_grep(args: GrepArgs, headers: &[(String, String)]) -> Result<(), RunError> {
+    let pattern = domain::grep::build_pattern(&args.pattern, args.ignore_case)?;
+    let config = load_config_file(None, None)?;
+    let cache = transcript_cache(&config);
+
+    if args.sources.is_empty() {
+        let mut entries = match &cache {
+            Some(cache) => cache.entries()?,
+            None => Vec::new(),
+        };
+        entries.sort_by(|a, b| a.transcript_id.cmp(&b.transcript_id));
+        for entry in entries {
+            let Some(done) = cache.as_ref().and_then(|cache| infra::runner::cached_transcript(cache, &entry.transcript_id)) else {
+                continue;
+            };
+            for found in infra::runner::grep_transcript(&done, &pattern) {
+                println!("{}", domain::grep::format_match(&entry.transcript_id, &found));
+            }
+        }
+        return Ok(());
+    }
+
+    // Only sources missing from the cache need the API, and so an API key.
+    let mut client = None;
+    for source in args.sources {
+        let path = std::path::Path::new(&source);
+        let done = if path.exists() {
+            infra::runner::read_transcript_file(path)?
+        } else {
+            let transcript_id = resolve_transcript_id(source.clone())?;
+            match cache.as_ref().and_then(|cache| infra::runner::cached_transcript(cache, &transcript_id)) {
+                Some(done) => done,
+                None => {
+                    if client.is_none() {
+                        client = Some(api_client(&config, headers)?);
+                    }
+                    let client = client.as_ref().expect("client was just created");
+                    infra::runner::fetch_completed_transcript(client, cache.as_ref(), &transcript_id).await?
+                }
+            }
+        };
+        for found in infra::runner::grep_transcript(&done, &pattern) {
+            println!("{}", domain::grep::format_match(&source, &found));
+        }
+    }
+    Ok(())
+}
+
+fn run_index(args: IndexArgs) -> Result<(), RunError> {
+    let path = match default_config_path() {
+        None => return Err(RunError::HomeNotFound),
+        Some(_) => data_file_path("index.db").ok_or(RunError::DataDirUnavailable)?,
+    };
+
+    match args.command {
+        IndexCommand::Build => {
+            let config = load_config_file(None, None)?;
+            let cache = transcript_cache(&config).ok_or(RunError::DataDirUnavailable)?;
+            let mut entries = cache.entries()?;
+            entries.sort_by(|a, b| a.transcript_id.cmp(&b.transcript_id));
+            let transcripts = entries
+                .iter()
+                .filter_map(|entry| infra::runner::cached_transcript(&cache, &entry.transcript_id));
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).map_err(infra::InfraError::from)?;
+            }
+            let (count, sentences) = infra::search_index::build(&path, transcripts)?;
+            infra::logging::info(tr!(
+                "built-search-index",
+                count = count,
+                sentences = sentences,
+                path = path.display().to_string()
+            ));
+        }
+        IndexCommand::Search { query, limit } => {
+            for hit in infra::search_index::search(&path, &query, limit)? {
+                println!("{}", domain::grep::format_match(&hit.transcript_id, &hit.sentence));
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn run_token(args: TokenArgs, headers: &[(String, String)]) -> Result<(), RunError> {
+    let expires_in = domain::validate_token_expiry(args.expires_in)?;
+    let client = api_client(&load_config_file(None, None)?, headers)?;
+    let token = client.create_realtime_token(expires_in).await.map_err(RunError::Api)?;
+    println!("{token}");
+    Ok(())
+}
+
+async fn run_upload(args: UploadArgs, headers: &[(String, String)]) -> Result<(), RunError> {
+    let client = api_client(&load_config_file(None, None)?, headers)?;
+    let bytes = match std::fs::metadata(&args.input) {
+        Ok(metadata) if metadata.is_file() => metadata.len(),
+        _ => return Err(infra::InfraError::InputNotFound { path: args.input }.into()),
+    };
+    let upload_index = if args.no_upload_reuse { None } else { data_file_path("uploads.json") };
+    let upload = infra::runner::upload_file(&client, &args.input, bytes, upload_index.as_deref(), args.verify).await?;
+    if args.json {
+        let uploaded = domain::uploads::UploadedFile {
+            input: args.input.into_os_string(),
+            upload_url: upload.upload_url,
+            bytes,
+            sha256: upload.sha256,
+        };
+        println!("{}", serde_json::to_string_pretty(&uploaded).expect("upload result serializes"));
+    } else {
+        println!("{}", upload.upload_url);
+    }
+    Ok(())
+}
+
+async fn run_list(args: ListArgs, headers: &[(String, String)]) -> Result<(), RunError> {
+    let client = api_client(&load_config_file(None, None)?, headers)?;
+    let filter = domain::listing::TranscriptFilter {
+        status: args.status.map(|status| status.as_str().to_string()),
+        created_after: args.created_after,
+        created_before: args.created_before,
+        throttled_only: args.throttled_only,
+    };
+    let limit = (!args.all).then_some(args.limit);
+    let transcripts = infra::runner::list_transcripts(&client, &filter, limit).await?;
+    print!("{}", domain::listing::format_transcript_list(&transcripts));
+    println!("{}", domain::listing::format_list_totals(&transcripts));
+    Ok(())
+}
+
+async fn run_delete(args: DeleteArgs, headers: &[(String, String)]) -> Result<(), RunError> {
+    let config = load_config_file(None, None)?;
+    let client = api_client(&config, headers)?;
+    // An explicit --before covers the whole account; retention only covers transcripts this tool created.
+    let retention = args.before.is_none();
+    let before = match (args.before, config.as_ref().and_then(|c| c.retention_days)) {
+        (Some(before), _) => before,
+        (None, Some(0)) => return Err(domain::DomainError::InvalidRetentionDays.into()),
+        (None, Some(days)) => {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs());
+            domain::listing::retention_cutoff(now, days)
+        }
+        (None, None) => return Err(domain::DomainError::DeleteNeedsCutoff.into()),
+    };
+    let filter = domain::listing::TranscriptFilter {
+        status: args.status.map(|status| status.as_str().to_string()),
+        created_before: Some(before),
+        ..domain::listing::TranscriptFilter::default()
+    };
+    let mut matching = infra::runner::list_transcripts(&client, &filter, None).await?;
+    if retention {
+        // Already deleted transcripts are no longer listed, so only live ones are deleted.
+        let recorded = recorded_transcript_ids()?;
+        matching.retain(|transcript| recorded.contains(&transcript.id));
+    }
+    if args.dry_run {
+        print!("{}", domain::listing::format_transcript_list(&matching));
+        println!("would delete {}", domain::listing::format_list_totals(&matching));
+        return Ok(());
+    }
+    if matching.is_empty() || (!args.yes && !prompt_confirm_delete(&matching)?) {
+        infra::logging::info(tr!("delete-aborted"));
+        return Ok(());
+    }
+
+    let cache = transcript_cache(&config);
+    let cached = cache.as_ref().map(|cache| cache.entries()).transpose()?.unwrap_or_default();
+    let mut deleted = Vec::new();
+    for (idx, transcript) in matching.iter().enumerate() {
+        infra::logging::info(tr!("deleting-transcript", current = idx + 1, total = matching.len(), id = transcript.id.as_str()));
+        match client.delete_transcript(&transcript.id).await {
+            Ok(()) => deleted.push(transcript.clone()),
+            Err(err) => infra::logging::warn(tr!("delete-failed", id = transcript.id.as_str(), error = err.to_string())),
+        }
+    }
+    if let Some(cache) = &cache {
+        let removed: Vec<&domain::cache::CacheEntry> = cached
+            .iter()
+            .filter(|entry| deleted.iter().any(|transcript| transcript.id == entry.transcript_id))
+            .collect();
+        cache.remove(&removed)?;
+    }
+
+    println!("deleted {}", domain::listing::format_list_totals(&deleted));
+    if deleted.len() < matching.len() {
+        return Err(RunError::DeleteFailed {
+            failed: matching.len() - deleted.len(),
+            total: matching.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Transcript ids this tool created, from `history.jsonl` and the batches still in `jobs.json`.
+fn recorded_transcript_ids() -> Result<std::collections::BTreeSet<String>, RunError> {
+    let mut ids = std::collections::BTreeSet::new();
+    if let Some(path) = history_path() {
+        ids.extend(infra::history::load(&path)?.into_iter().filter_map(|entry| entry.transcript_id));
+    }
+    if let Some(path) = jobs_path() {
+        ids.extend(infra::jobs::load(&path)?.transcript_ids().map(str::to_string));
+    }
+    Ok(ids)
+}
+
+async fn run_history(
+    args: HistoryArgs,
+    headers: &[(String, String)],
+    scheduler: &infra::scheduler::Scheduler,
+) -> Result<(), RunError> {
+    let path = match default_config_path() {
+        None => return Err(RunError::HomeNotFound),
+        Some(_) => history_path().ok_or(RunError::DataDirUnavailable)?,
+    };
+    let entries = infra::history::load(&path)?;
+
+    match args.command {
+        None => {
+            let matching: Vec<&domain::history::HistoryEntry> = entries
+                .iter()
+                .filter(|entry| args.search.as_deref().is_none_or(|term| entry.matches(term)))
+                .filter(|entry| args.tags.iter().all(|tag| entry.has_tag(tag)))
+                .collect();
+            let shown = &matching[matching.len().saturating_sub(args.limit)..];
+            print!("{}", domain::history::format_history(shown));
+            Ok(())
+        }
+        Some(HistoryCommand::Rerun { id }) => {
+            let entry = entries
+                .into_iter()
+                .find(|entry| entry.id == id)
+                .ok_or(RunError::HistoryEntryNotFound { id })?;
+            let cli = Cli::try_parse_from(std::iter::once(OsString::from("assemblyai-cli")).chain(entry.args.iter().cloned()))
+                .map_err(|err| RunError::HistoryEntryInvalid { id, message: err.to_string() })?;
+            let args = match cli.command {
+                Commands::Transcribe(args) => *args,
+                Commands::Meeting(args) => args.into_meeting_args(),
+                Commands::Shownotes(args) => args.into_shownotes_args(),
+                _ => {
+                    return Err(RunError::HistoryEntryInvalid {
+                        id,
+                        message: "not a transcription run".to_string(),
+                    });
+                }
+            };
+            infra::logging::info(tr!("rerunning", id = id, args = entry.args.join(OsStr::new(" ")).display().to_string()));
+            let headers = [cli.headers, headers.to_vec()].concat();
+            run_transcribe(args, &entry.args, &headers, scheduler).await
+        }
+    }
+}
+
+fn run_alias(args: AliasArgs) -> Result<(), RunError> {
+    let path = match default_config_path() {
+        None => return Err(RunError::HomeNotFound),
+        Some(_) => aliases_path().ok_or(RunError::DataDirUnavailable)?,
+    };
+    let _lock = infra::lock::StateLock::acquire(&path)?;
+    let mut aliases = infra::aliases::load(&path)?;
+
+    match args.command {
+        AliasCommand::Set { name, transcript_id } => {
+            aliases.set(&name, &transcript_id)?;
+            infra::aliases::save(&path, &aliases)?;
+            println!("{name} -> {transcript_id}");
+        }
+        AliasCommand::List => print!("{}", domain::aliases::format_aliases(&aliases)),
+        AliasCommand::Remove { name } => {
+            aliases.remove(&name).ok_or(RunError::AliasNotFound { name })?;
+            infra::aliases::save(&path, &aliases)?;
+        }
+    }
+    Ok(())
+}
+
+fn run_cache(args: CacheArgs) -> Result<(), RunError> {
+    let config = load_config_file(None, None)?;
+    let cache = match default_config_path() {
+        None => return Err(RunError::HomeNotFound),
+        Some(_) => transcript_cache(&config).ok_or(RunError::DataDirUnavailable)?,
+    };
+
+    match args.command {
+        CacheCommand::Path => println!("{}", cache.dir().display()),
+        CacheCommand::List => print!("{}", domain::cache::format_cache_list(&cache.entries()?)),
+        CacheCommand::Size => {
+            let entries = cache.entries()?;
+            println!("{}", domain::cache::format_cache_size(&entries.iter().collect::<Vec<_>>()));
+        }
+        CacheCommand::Clear { older_than } => {
+            let entries = cache.entries()?;
+            let removed = match older_than {
+                Some(older_than) => {
+                    let now = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map_or(0, |d| d.as_secs());
+                    domain::cache::unused_since(&entries, now, older_than)
+                }
+                None => entries.iter().collect(),
+            };
+            cache.remove(&removed)?;
+            println!("removed {}", domain::cache::format_cache_size(&removed));
+        }
+    }
+    Ok(())
+}
+
+fn run_config(args: ConfigArgs) -> Result<(), RunError> {
+    let root_path = default_config_path().ok_or(RunError::HomeNotFound)?;
+    match args.command {
+        ConfigCommand::Migrate => migrate_config(&root_path)?,
+        ConfigCommand::FixPermissions => {
+            let Some(path) = resolve_config_file_path(&root_path) else {
+                infra::logging::info(tr!("no-config-file", path = root_path.display().to_string()));
+                return Ok(());
+            };
+            let mut paths = vec![path];
+            if root_path.is_dir() {
+                paths.push(root_path);
+            }
+            for path in paths {
+                infra::permissions::restrict(&path).map_err(|err| RunError::ConfigWrite {
+                    path: path.clone(),
+                    message: err.to_string(),
+                })?;
+                infra::logging::info(tr!("restricted-config", path = path.display().to_string()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Backs up and rewrites the config at the current schema version, moving a legacy single-file config into
+/// `~/.assemblyai-cli/config.json`.
+fn migrate_config(root_path: &std::path::Path) -> Result<(), RunError> {
+    let Some(path) = resolve_config_file_path(root_path) else {
+        infra::logging::info(tr!("no-config-file", path = root_path.display().to_string()));
+        return Ok(());
+    };
+    let contents = std::fs::read_to_string(&path).map_err(|err| RunError::ConfigRead {
+        path: path.clone(),
+        message: err.to_string(),
+    })?;
+    let mut root: serde_json::Value = serde_json::from_str(&contents).map_err(|err| RunError::ConfigParse {
+        path: path.clone(),
+        message: err.to_string(),
+    })?;
+    let version = domain::config::config_version(&root);
+    let changes = domain::config::migrate(&mut root);
+    let legacy = path == root_path;
+    if version >= domain::config::CONFIG_VERSION && !legacy {
+        infra::logging::info(tr!("config-up-to-date", path = path.display().to_string(), version = domain::config::CONFIG_VERSION));
+        return Ok(());
+    }
+
+    let write_error = |path: &std::path::Path, err: std::io::Error| RunError::ConfigWrite {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    };
+    let mut backup = path.clone().into_os_string();
+    backup.push(".bak");
+    let backup = PathBuf::from(backup);
+    let target = if legacy {
+        std::fs::rename(&path, &backup).map_err(|err| write_error(&backup, err))?;
+        std::fs::create_dir_all(root_path).map_err(|err| write_error(root_path, err))?;
+        root_path.join("config.json")
+    } else {
+        std::fs::copy(&path, &backup).map_err(|err| write_error(&backup, err))?;
+        path
+    };
+    let serialized = serde_json::to_string_pretty(&root).map_err(|err| write_error(&target, err.into()))?;
+    infra::permissions::write_private(&target, &format!("{serialized}\n")).map_err(|err| write_error(&target, err))?;
+
+    if legacy {
+        infra::logging::info(tr!("moved-legacy-config", path = target.display().to_string()));
+    }
+    for change in &changes {
+        eprintln!("  {change}");
+    }
+    infra::logging::info(tr!(
+        "wrote-migrated-config",
+        path = target.display().to_string(),
+        version = domain::config::CONFIG_VERSION,
+        backup = backup.display().to_string()
+    ));
+    Ok(())
+}
 
-    let result = match cli.command {
-        Commands::Transcribe(args) => run_transcribe(args).await,
-        Commands::Init(args) => run_init(args),
+/// Resolves a transcript id argument through the alias file; ids without an alias pass through.
+fn resolve_transcript_id(name_or_id: String) -> Result<String, RunError> {
+    let Some(path) = aliases_path() else {
+        return Ok(name_or_id);
     };
+    let aliases = infra::aliases::load(&path)?;
+    Ok(aliases.resolve(&name_or_id).to_string())
+}
 
-    match result {
-        Ok(()) => ExitCode::SUCCESS,
-        Err(err) => {
-            eprintln!("error: {err}");
-            ExitCode::from(err.exit_code())
-        }
+fn run_version(args: VersionArgs) -> Result<(), RunError> {
+    // A broken config shouldn't stop a bug report; it only affects the reported base URL.
+    let config = load_config_file(None, None).ok().flatten();
+    let features = env!("ASSEMBLYAI_CLI_FEATURES");
+    let info = domain::version::VersionInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        commit: env!("ASSEMBLYAI_CLI_COMMIT").to_string(),
+        build_date: domain::history::format_rfc3339_millis(env!("ASSEMBLYAI_CLI_BUILT_AT").parse::<u64>().unwrap_or(0) * 1000),
+        target: env!("ASSEMBLYAI_CLI_TARGET").to_string(),
+        features: features.split(',').filter(|feature| !feature.is_empty()).map(str::to_string).collect(),
+        base_url: std::env::var("ASSEMBLYAI_BASE_URL")
+            .ok()
+            .or_else(|| config.and_then(|c| c.base_url))
+            .unwrap_or_else(|| infra::assemblyai::DEFAULT_BASE_URL.to_string()),
+    };
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&info).expect("version info serializes"));
+    } else {
+        print!("{}", domain::version::format_version_info(&info));
     }
+    Ok(())
 }
 
-async fn run_transcribe(args: TranscribeArgs) -> Result<(), RunError> {
-    let config = load_config_file()?;
-    let api_key = load_api_key(config.as_ref())?;
+async fn run_self_update(args: SelfUpdateArgs) -> Result<(), RunError> {
+    let current = env!("CARGO_PKG_VERSION");
+    let release_url = std::env::var("ASSEMBLYAI_CLI_RELEASES_URL").unwrap_or_else(|_| infra::update::LATEST_RELEASE_URL.to_string());
+    let client = infra::update::ReleaseClient::new(&release_url)?;
+    let release = client.latest().await?;
+    let latest = release.tag_name.trim_start_matches('v');
+    if !domain::update::is_newer(latest, current) {
+        println!("assemblyai-cli {current} is up to date");
+        return Ok(());
+    }
 
-    let base_url = std::env::var("ASSEMBLYAI_BASE_URL")
-        .ok()
-        .or_else(|| config.as_ref().and_then(|c| c.base_url.clone()));
+    let unavailable = |message: String| RunError::Infra(infra::InfraError::SelfUpdate { message });
+    let target = domain::update::release_target(std::env::consts::OS, std::env::consts::ARCH).ok_or_else(|| {
+        unavailable(format!(
+            "no prebuilt release for {}/{}",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        ))
+    })?;
+    let asset_name = domain::update::release_asset(latest, target);
+    let asset = release
+        .asset(&asset_name)
+        .ok_or_else(|| unavailable(format!("release {} has no {asset_name}", release.tag_name)))?;
+    let checksum = release
+        .asset(&format!("{asset_name}.sha256"))
+        .ok_or_else(|| unavailable(format!("release {} has no checksum for {asset_name}", release.tag_name)))?;
+    if args.check {
+        println!("update available: {current} -> {latest} ({asset_name})");
+        return Ok(());
+    }
 
-    let format = args
-        .format
-        .map(Into::into)
-        .or_else(|| config.as_ref().and_then(|c| c.format))
-        .unwrap_or(TranscriptFormat::Text);
+    infra::logging::info(tr!("downloading", asset = asset_name.as_str()));
+    let expected = domain::update::parse_checksum(&String::from_utf8_lossy(&client.download(checksum).await?))
+        .ok_or_else(|| unavailable(format!("{} is not a SHA-256 checksum", checksum.name)))?;
+    let archive = client.download(asset).await?;
+    let actual = infra::update::sha256_hex(&archive);
+    if actual != expected {
+        return Err(RunError::Infra(infra::InfraError::ChecksumMismatch {
+            asset: asset_name,
+            expected,
+            actual,
+        }));
+    }
+    let binary_name = if cfg!(windows) { "assemblyai-cli.exe" } else { "assemblyai-cli" };
+    let binary = infra::update::unpack_binary(&archive, &asset_name, binary_name)?;
+    let exe = std::env::current_exe().and_then(|exe| exe.canonicalize()).map_err(infra::InfraError::Io)?;
+    infra::update::replace_exe(&exe, &binary)?;
+    println!("updated assemblyai-cli {current} -> {latest}");
+    Ok(())
+}
 
-    let output = args.output.or_else(|| config.as_ref().and_then(|c| c.output.clone()));
+fn run_schema(args: SchemaArgs) -> Result<(), RunError> {
+    print!(
+        "{}",
+        match args.document {
+            SchemaDocumentArg::Config => domain::schema::CONFIG_SCHEMA,
+            SchemaDocumentArg::Output => domain::schema::OUTPUT_SCHEMA,
+        }
+    );
+    Ok(())
+}
 
-    let speech_model = args
-        .speech_model
-        .map(Into::into)
-        .or_else(|| config.as_ref().and_then(|c| c.speech_model))
-        .unwrap_or(domain::SpeechModel::Best);
+fn run_languages(args: LanguagesArgs) -> Result<(), RunError> {
+    let features: Vec<domain::languages::Feature> = args.features.into_iter().map(Into::into).collect();
+    let languages = domain::languages::languages_with(&features);
+    print!("{}", domain::languages::format_languages(&languages));
+    Ok(())
+}
 
-    let language_detection = match (
-        cli_bool_override(args.language_detection, args.no_language_detection),
-        config.as_ref().and_then(|c| c.language_detection),
-    ) {
-        (Some(value), _) => value,
-        (None, Some(value)) => value,
-        (None, None) => true,
-    };
+fn read_input_file(path: &std::path::Path) -> Result<String, RunError> {
+    std::fs::read_to_string(path).map_err(|err| {
+        if err.kind() == std::io::ErrorKind::NotFound {
+            RunError::Infra(infra::InfraError::InputNotFound { path: path.to_path_buf() })
+        } else {
+            RunError::Infra(infra::InfraError::Io(err))
+        }
+    })
+}
 
-    let language = args.language.or_else(|| config.as_ref().and_then(|c| c.language.clone()));
+fn run_lint(args: LintArgs) -> Result<(), RunError> {
+    let config = load_config_file(None, None)?;
+    let options = resolve_lint_options(config.as_ref(), &args.thresholds);
 
-    let punctuate = match (
-        cli_bool_override(args.punctuate, args.no_punctuate),
-        config.as_ref().and_then(|c| c.punctuate),
-    ) {
-        (Some(value), _) => value,
-        (None, Some(value)) => value,
-        (None, None) => true,
-    };
+    let mut total = 0;
+    for path in &args.paths {
+        let contents = read_input_file(path)?;
 
-    let format_text = match (
-        cli_bool_override(args.format_text, args.no_format_text),
-        config.as_ref().and_then(|c| c.format_text),
-    ) {
-        (Some(value), _) => value,
-        (None, Some(value)) => value,
-        (None, None) => true,
-    };
+        let cues = domain::subtitles::parse_cues(&contents).map_err(|err| domain::DomainError::InvalidSubtitleFile {
+            path: path.clone(),
+            line: err.line,
+            message: err.message,
+        })?;
 
-    let multichannel = match (
-        cli_bool_override(args.multichannel, args.no_multichannel),
-        config.as_ref().and_then(|c| c.multichannel),
-    ) {
-        (Some(value), _) => value,
-        (None, Some(value)) => value,
-        (None, None) => true,
-    };
+        let violations = domain::lint::lint_cues(&cues, &options);
+        print!("{}", domain::lint::format_violations(&path.display().to_string(), &violations));
+        total += violations.len();
+    }
 
-    let disfluencies = if args.disfluencies {
-        true
-    } else {
-        config.as_ref().and_then(|c| c.disfluencies).unwrap_or(false)
-    };
+    if total > 0 {
+        return Err(RunError::CaptionCheckFailed { count: total });
+    }
 
-    let filter_profanity = if args.filter_profanity {
-        true
-    } else {
-        config
-            .as_ref()
-            .and_then(|c| c.filter_profanity)
-            .unwrap_or(false)
-    };
+    Ok(())
+}
 
-    let speaker_labels = if args.speaker_labels {
-        true
+fn run_merge(args: MergeArgs) -> Result<(), RunError> {
+    let mut parts = Vec::with_capacity(args.paths.len());
+    for path in &args.paths {
+        let contents = read_input_file(path)?;
+        parts.push(domain::subtitles::parse_cues(&contents).map_err(|err| domain::DomainError::InvalidSubtitleFile {
+            path: path.clone(),
+            line: err.line,
+            message: err.message,
+        })?);
+    }
+    let offsets = if args.offsets.is_empty() {
+        domain::subtitles::consecutive_offsets(&parts)
+    } else if args.offsets.len() != parts.len() {
+        return Err(domain::DomainError::OffsetCountMismatch {
+            offsets: args.offsets.len(),
+            inputs: parts.len(),
+        }
+        .into());
     } else {
-        config
-            .as_ref()
-            .and_then(|c| c.speaker_labels)
-            .unwrap_or(false)
+        args.offsets.iter().map(|offset| domain::parse_offset_spec(offset)).collect::<Result<_, _>>()?
     };
 
-    let speech_threshold = args
-        .speech_threshold
-        .or_else(|| config.as_ref().and_then(|c| c.speech_threshold));
+    let format = subtitle_format(args.format, Some(args.output.as_ref().unwrap_or(&args.paths[0])));
+    let cue_count: usize = parts.iter().map(Vec::len).sum();
+    let captions = domain::subtitles::merge_cues(&offsets.into_iter().zip(parts).collect::<Vec<_>>());
+    let style = domain::subtitles::SpeakerStyle::default();
+    let contents = match format {
+        MergeFormatArg::Srt => domain::subtitles::format_srt(&captions, &style),
+        MergeFormatArg::Vtt => domain::subtitles::format_vtt(&captions, &style),
+    };
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, contents).map_err(infra::InfraError::Io)?;
+            infra::logging::info(tr!(
+                "merged-subtitles",
+                count = args.paths.len(),
+                cues = cue_count,
+                path = path.display().to_string()
+            ));
+        }
+        None => print!("{contents}"),
+    }
+    Ok(())
+}
 
+async fn run_render(args: RenderArgs, headers: &[(String, String)]) -> Result<(), RunError> {
+    let config = load_config_file(None, None)?;
     let chars_per_caption = args
         .chars_per_caption
         .or_else(|| config.as_ref().and_then(|c| c.chars_per_caption))
         .unwrap_or(128);
-
-    let word_boost = if args.word_boost.is_empty() {
-        config
-            .as_ref()
-            .and_then(|c| c.word_boost.clone())
-            .unwrap_or_default()
-    } else {
-        args.word_boost
-    };
-
-    let custom_spelling_cli = args
-        .custom_spelling
-        .into_iter()
-        .map(|s| domain::parse_custom_spelling_kv(&s))
-        .collect::<Result<Vec<CustomSpelling>, domain::DomainError>>()?;
-
-    let custom_spelling = if custom_spelling_cli.is_empty() {
-        config
-            .as_ref()
-            .and_then(|c| c.custom_spelling.clone())
-            .unwrap_or_default()
-    } else {
-        custom_spelling_cli
+    if chars_per_caption == 0 {
+        return Err(domain::DomainError::InvalidCharsPerCaption.into());
+    }
+    let transcript_id = resolve_transcript_id(args.transcript_id)?;
+    let format = match subtitle_format(args.format, args.output.as_deref()) {
+        MergeFormatArg::Srt => TranscriptFormat::Srt,
+        MergeFormatArg::Vtt => TranscriptFormat::Vtt,
     };
 
-    let poll_interval_seconds = args
-        .poll_interval_seconds
-        .or_else(|| config.as_ref().and_then(|c| c.poll_interval_seconds))
-        .unwrap_or(3);
-
-    let timeout_seconds = args
-        .timeout_seconds
-        .or_else(|| config.as_ref().and_then(|c| c.timeout_seconds))
-        .unwrap_or(3600);
-
-    let options = TranscribeOptions::new(domain::TranscribeOptionsParams {
-        input: args.input,
-        format,
-        output,
-        speech_model,
-        language_detection,
-        language,
-        punctuate,
-        format_text,
-        disfluencies,
-        filter_profanity,
-        speaker_labels,
-        multichannel,
-        speech_threshold,
-        chars_per_caption,
-        word_boost,
-        custom_spelling,
-        poll_interval: Duration::from_secs(poll_interval_seconds),
-        timeout: Duration::from_secs(timeout_seconds),
-    })?;
-
-    let plan = app::build_plan(&options)?;
+    let client = api_client(&config, headers)?;
+    let contents = client.get_subtitles(&transcript_id, format, chars_per_caption).await?;
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, contents).map_err(infra::InfraError::Io)?;
+            infra::logging::info(tr!("wrote-transcript", path = path.display().to_string()));
+        }
+        None => print!("{contents}"),
+    }
+    Ok(())
+}
 
-    let client = infra::assemblyai::AssemblyAiClient::new(infra::assemblyai::AssemblyAiClientConfig {
-        api_key,
-        base_url,
-    })?;
+/// `--format`, else VTT when `named` ends in `.vtt`, else SRT.
+fn subtitle_format(format: Option<MergeFormatArg>, named: Option<&std::path::Path>) -> MergeFormatArg {
+    format.unwrap_or_else(|| match named {
+        Some(path) if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("vtt")) => MergeFormatArg::Vtt,
+        _ => MergeFormatArg::Srt,
+    })
+}
 
-    infra::runner::run_transcribe(plan, client, &options).await?;
-    Ok(())
+fn resolve_lint_options(
+    config: Option<&domain::config::ConfigFile>,
+    args: &LintThresholdArgs,
+) -> domain::lint::LintOptions {
+    let defaults = domain::lint::LintOptions::default();
+    let lint = config.and_then(|c| c.lint.as_ref());
+    domain::lint::LintOptions {
+        max_cps: args
+            .max_cps
+            .or_else(|| lint.and_then(|l| l.max_cps))
+            .unwrap_or(defaults.max_cps),
+        max_line_chars: args
+            .max_line_length
+            .or_else(|| lint.and_then(|l| l.max_line_length))
+            .unwrap_or(defaults.max_line_chars),
+        max_lines: args
+            .max_lines
+            .or_else(|| lint.and_then(|l| l.max_lines))
+            .unwrap_or(defaults.max_lines),
+        min_gap_ms: args
+            .min_gap_ms
+            .or_else(|| lint.and_then(|l| l.min_gap_ms))
+            .unwrap_or(defaults.min_gap_ms),
+    }
 }
 
-fn run_init(args: InitArgs) -> Result<(), RunError> {
+async fn run_init(args: InitArgs, headers: &[(String, String)]) -> Result<(), RunError> {
     let Some(root_path) = default_config_path() else {
         return Err(RunError::HomeNotFound);
     };
@@ -554,14 +3639,48 @@ fn run_init(args: InitArgs) -> Result<(), RunError> {
     if existing_api_key.is_some() && !args.force && !args.yes {
         let overwrite = prompt_overwrite_existing_api_key(&target_path)?;
         if !overwrite {
-            eprintln!("init aborted; existing apiKey preserved (use --yes to overwrite)");
+            infra::logging::info(tr!("init-aborted"));
             return Ok(());
         }
     }
 
-    let api_key = prompt_api_key_from_stdin()?;
-    let api_key = normalize_api_key(&api_key);
-    obj.insert("apiKey".to_string(), serde_json::Value::String(api_key));
+    let base_url = std::env::var("ASSEMBLYAI_BASE_URL")
+        .ok()
+        .or_else(|| obj.get("baseUrl").and_then(|v| v.as_str()).map(str::to_string));
+    let config_headers: Option<std::collections::BTreeMap<String, String>> =
+        obj.get("extraHeaders").and_then(|v| serde_json::from_value(v.clone()).ok());
+    let headers = extra_headers(config_headers.as_ref(), headers);
+    let api_key = match &args.api_key {
+        Some(api_key) => {
+            let api_key = ApiKey::new(normalize_api_key(api_key.expose()));
+            if api_key.expose().is_empty() {
+                return Err(RunError::InitEmptyApiKey);
+            }
+            if !args.no_verify && verify_api_key(&api_key, base_url, headers).await == Some(false) {
+                return Err(RunError::InitApiKeyRejected);
+            }
+            api_key
+        }
+        None => loop {
+            let api_key = ApiKey::new(normalize_api_key(&prompt_api_key_from_stdin()?));
+            if args.no_verify || verify_api_key(&api_key, base_url.clone(), headers.clone()).await != Some(false) {
+                break api_key;
+            }
+            infra::logging::warn(tr!("api-key-rejected"));
+            if !prompt_reenter_api_key()? {
+                infra::logging::info(tr!("storing-rejected-key"));
+                break api_key;
+            }
+        },
+    };
+    if obj.is_empty() {
+        obj.insert("configVersion".to_string(), serde_json::Value::from(domain::config::CONFIG_VERSION));
+    }
+    obj.insert("apiKey".to_string(), serde_json::Value::String(api_key.expose().to_string()));
+    if let Some(format) = args.format {
+        let format = TranscriptFormat::from(format).name();
+        obj.insert("format".to_string(), serde_json::Value::String(format.to_string()));
+    }
 
     if let Some(parent) = target_path.parent() {
         std::fs::create_dir_all(parent).map_err(|err| RunError::ConfigWrite {
@@ -576,15 +3695,34 @@ fn run_init(args: InitArgs) -> Result<(), RunError> {
             message: err.to_string(),
         })?;
 
-    std::fs::write(&target_path, format!("{serialized}\n")).map_err(|err| RunError::ConfigWrite {
+    infra::permissions::write_private(&target_path, &format!("{serialized}\n")).map_err(|err| RunError::ConfigWrite {
         path: target_path.clone(),
         message: err.to_string(),
     })?;
 
-    eprintln!("wrote config to {}", target_path.display());
+    infra::logging::info(tr!("wrote-config", path = target_path.display().to_string()));
     Ok(())
 }
 
+/// Whether the API accepts `api_key`; `None` (after a warning) when it can't be reached.
+async fn verify_api_key(api_key: &ApiKey, base_url: Option<String>, extra_headers: Vec<(String, String)>) -> Option<bool> {
+    let checked = match infra::assemblyai::AssemblyAiClient::new(infra::assemblyai::AssemblyAiClientConfig {
+        api_key: api_key.clone(),
+        base_url,
+        extra_headers,
+    }) {
+        Ok(client) => client.check_api_key().await,
+        Err(err) => Err(err),
+    };
+    match checked {
+        Ok(valid) => Some(valid),
+        Err(err) => {
+            infra::logging::warn(tr!("api-key-unverified", error = err.to_string()));
+            None
+        }
+    }
+}
+
 fn init_target_config_path(root_path: &std::path::Path) -> Result<PathBuf, String> {
     if root_path.exists() {
         if root_path.is_dir() {
@@ -649,6 +3787,46 @@ fn prompt_overwrite_existing_api_key(path: &std::path::Path) -> Result<bool, Run
     Ok(answer == "y" || answer == "yes")
 }
 
+/// End of input (e.g. a script without --yes) declines.
+fn prompt_confirm_delete(transcripts: &[domain::listing::TranscriptSummary]) -> Result<bool, RunError> {
+    use std::io::Write;
+
+    eprint!("Delete {} from AssemblyAI? This cannot be undone. [y/N]: ", domain::listing::format_list_totals(transcripts));
+    let _ = std::io::stderr().flush();
+
+    let mut input = String::new();
+    let bytes = std::io::stdin().read_line(&mut input).map_err(infra::InfraError::Io)?;
+
+    if bytes == 0 {
+        return Ok(false);
+    }
+
+    let answer = input.trim().to_ascii_lowercase();
+    Ok(answer == "y" || answer == "yes")
+}
+
+/// Defaults to yes on an empty answer; end of input keeps the entered key.
+fn prompt_reenter_api_key() -> Result<bool, RunError> {
+    use std::io::Write;
+
+    eprint!("Enter a different key? [Y/n]: ");
+    let _ = std::io::stderr().flush();
+
+    let mut input = String::new();
+    let bytes = std::io::stdin()
+        .read_line(&mut input)
+        .map_err(|err| RunError::InitReadStdin {
+            message: err.to_string(),
+        })?;
+
+    if bytes == 0 {
+        return Ok(false);
+    }
+
+    let answer = input.trim().to_ascii_lowercase();
+    Ok(answer.is_empty() || answer == "y" || answer == "yes")
+}
+
 fn cli_bool_override(yes_flag: bool, no_flag: bool) -> Option<bool> {
     if yes_flag {
         Some(true)
@@ -659,13 +3837,12 @@ fn cli_bool_override(yes_flag: bool, no_flag: bool) -> Option<bool> {
     }
 }
 
-fn load_config_file() -> Result<Option<domain::config::ConfigFile>, RunError> {
-    let Some(path) = default_config_path() else {
-        return Ok(None);
-    };
-
-    let Some(path) = resolve_config_file_path(&path) else {
-        return Ok(None);
+fn load_config_file(preset: Option<&str>, extension: Option<&str>) -> Result<Option<domain::config::ConfigFile>, RunError> {
+    let Some(path) = default_config_path().and_then(|path| resolve_config_file_path(&path)) else {
+        return match preset {
+            Some(name) => Err(RunError::PresetWithoutConfig { name: name.to_string() }),
+            None => Ok(None),
+        };
     };
 
     let contents = std::fs::read_to_string(&path).map_err(|err| RunError::ConfigRead {
@@ -673,15 +3850,67 @@ fn load_config_file() -> Result<Option<domain::config::ConfigFile>, RunError> {
         message: err.to_string(),
     })?;
 
-    let config: domain::config::ConfigFile =
-        serde_json::from_str(&contents).map_err(|err| RunError::ConfigParse {
-            path,
-            message: err.to_string(),
-        })?;
+    let config = domain::config::parse_config_file(&contents, preset, extension).map_err(|err| RunError::ConfigParse {
+        path: path.clone(),
+        message: err.to_string(),
+    })?;
+
+    if let Some(version) = config.migrated_from {
+        infra::logging::warn(tr!(
+            "config-outdated",
+            path = path.display().to_string(),
+            version = version,
+            current = domain::config::CONFIG_VERSION
+        ));
+    } else if let Some(version) = config.config_version.filter(|v| *v > domain::config::CONFIG_VERSION) {
+        infra::logging::warn(tr!(
+            "config-too-new",
+            path = path.display().to_string(),
+            version = version,
+            current = domain::config::CONFIG_VERSION
+        ));
+    }
+
+    for key in &config.unknown_keys {
+        infra::logging::warn(format_args!("{}: {key}", path.display()));
+    }
+
+    if config.api_key.is_some()
+        && let Some(mode) = infra::permissions::loose_mode(&path)
+    {
+        infra::logging::warn(tr!(
+            "config-readable-by-others",
+            path = path.display().to_string(),
+            mode = format!("{mode:04o}")
+        ));
+    }
 
     Ok(Some(config))
 }
 
+/// `--strict-config`: fails when the config file has keys [`domain::config::unknown_keys`] reports.
+fn check_config_keys() -> Result<(), RunError> {
+    let Some(path) = default_config_path().and_then(|path| resolve_config_file_path(&path)) else {
+        return Ok(());
+    };
+    let contents = std::fs::read_to_string(&path).map_err(|err| RunError::ConfigRead {
+        path: path.clone(),
+        message: err.to_string(),
+    })?;
+    let root: serde_json::Value = serde_json::from_str(&contents).map_err(|err| RunError::ConfigParse {
+        path: path.clone(),
+        message: err.to_string(),
+    })?;
+    let unknown = domain::config::unknown_keys(&root);
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    Err(RunError::ConfigParse {
+        path,
+        message: unknown.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "),
+    })
+}
+
 fn resolve_config_file_path(path: &std::path::Path) -> Option<PathBuf> {
     if !path.exists() {
         return None;
@@ -701,6 +3930,35 @@ fn resolve_config_file_path(path: &std::path::Path) -> Option<PathBuf> {
     None
 }
 
+/// `~/.assemblyai-cli/history.jsonl`; None without a home directory or when `~/.assemblyai-cli` is a legacy config file.
+fn history_path() -> Option<PathBuf> {
+    data_file_path("history.jsonl")
+}
+
+/// `~/.assemblyai-cli/jobs.json`; None in the same cases as [`history_path`].
+fn jobs_path() -> Option<PathBuf> {
+    data_file_path("jobs.json")
+}
+
+/// `~/.assemblyai-cli/aliases.json`; None in the same cases as [`history_path`].
+fn aliases_path() -> Option<PathBuf> {
+    data_file_path("aliases.json")
+}
+
+/// `~/.assemblyai-cli/cache/`, limited by config `maxCacheBytes`; None in the same cases as [`history_path`].
+fn transcript_cache(config: &Option<domain::config::ConfigFile>) -> Option<infra::cache::TranscriptCache> {
+    let max_bytes = config.as_ref().and_then(|c| c.max_cache_bytes);
+    data_file_path("cache").map(|dir| infra::cache::TranscriptCache::new(dir, max_bytes))
+}
+
+fn data_file_path(name: &str) -> Option<PathBuf> {
+    let dir = default_config_path()?;
+    if dir.is_file() {
+        return None;
+    }
+    Some(dir.join(name))
+}
+
 fn default_config_path() -> Option<PathBuf> {
     let home = std::env::var("HOME")
         .ok()
@@ -708,12 +3966,13 @@ fn default_config_path() -> Option<PathBuf> {
     Some(PathBuf::from(home).join(".assemblyai-cli"))
 }
 
-fn load_api_key(config: Option<&domain::config::ConfigFile>) -> Result<String, RunError> {
+fn load_api_key(config: Option<&domain::config::ConfigFile>) -> Result<ApiKey, RunError> {
     if let Some(value) = config
-        .and_then(|c| c.api_key.as_deref())
+        .and_then(|c| c.api_key.as_ref())
+        .map(ApiKey::expose)
         .and_then(non_empty_trimmed)
     {
-        return Ok(normalize_api_key(value));
+        return Ok(ApiKey::new(normalize_api_key(value)));
     }
 
     if let Some(value) = std::env::var("ASSEMBLYAI_API_KEY")
@@ -721,13 +3980,13 @@ fn load_api_key(config: Option<&domain::config::ConfigFile>) -> Result<String, R
         .as_deref()
         .and_then(non_empty_trimmed)
     {
-        return Ok(normalize_api_key(value));
+        return Ok(ApiKey::new(normalize_api_key(value)));
     }
 
-    if let Some(value) = std::env::var("ASSEMBLY_AI_KEY").ok().as_deref().and_then(non_empty_trimmed) {
-        if let Some(decoded) = decode_base64_to_hex_key(value) {
-            return Ok(decoded);
-        }
+    if let Some(value) = std::env::var("ASSEMBLY_AI_KEY").ok().as_deref().and_then(non_empty_trimmed)
+        && let Some(decoded) = decode_base64_to_hex_key(value)
+    {
+        return Ok(ApiKey::new(decoded));
     }
 
     Err(RunError::MissingApiKey)
@@ -757,7 +4016,7 @@ fn normalize_api_key(value: &str) -> String {
 
 fn decode_base64_to_hex_key(value: &str) -> Option<String> {
     let mut padded = value.trim().to_string();
-    while padded.len() % 4 != 0 {
+    while !padded.len().is_multiple_of(4) {
         padded.push('=');
     }
 
@@ -7,6 +7,8 @@ fn set_temp_home(cmd: &mut Command) -> tempfile::TempDir {
     let dir = tempfile::tempdir().expect("tempdir");
     cmd.env("HOME", dir.path());
     cmd.env("USERPROFILE", dir.path());
+    // Messages are asserted in English whatever the developer's locale is.
+    cmd.env_remove("LC_ALL").env_remove("LC_MESSAGES").env("LANG", "C");
     dir
 }
 
@@ -22,6 +24,181 @@ fn dummy_audio_path() -> &'static str {
     "input.mp3"
 }
 
+#[derive(Debug, Clone)]
+struct RecordedRequest {
+    method: String,
+    path: String,
+    /// The query string without the leading `?`, empty when there is none.
+    query: String,
+    /// Header lines as sent, names lowercased.
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+/// Minimal HTTP/1.1 stand-in for the AssemblyAI API; routes match on method and path, or on path and query when
+/// the route has one (those take precedence).
+struct MockApi {
+    base_url: String,
+    requests: std::sync::Arc<std::sync::Mutex<Vec<RecordedRequest>>>,
+}
+
+impl MockApi {
+    fn start(routes: Vec<(&'static str, &'static str, u16, String)>) -> Self {
+        use std::io::{BufRead, BufReader, Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind mock api");
+        let base_url = format!("http://{}", listener.local_addr().expect("addr"));
+        let requests = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let mut reader = BufReader::new(stream.try_clone().expect("clone stream"));
+
+                let mut request_line = String::new();
+                if reader.read_line(&mut request_line).unwrap_or(0) == 0 {
+                    continue;
+                }
+                let mut parts = request_line.split_whitespace();
+                let method = parts.next().unwrap_or_default().to_string();
+                let target = parts.next().unwrap_or_default().to_string();
+                let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+                let (path, query) = (path.to_string(), query.to_string());
+
+                let mut content_length = 0usize;
+                let mut chunked = false;
+                let mut headers = Vec::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).expect("read header");
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    let lower = line.to_ascii_lowercase();
+                    if let Some((name, value)) = line.split_once(':') {
+                        headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+                    }
+                    if let Some(value) = lower.strip_prefix("content-length:") {
+                        content_length = value.trim().parse().unwrap_or(0);
+                    }
+                    if lower.starts_with("transfer-encoding:") && lower.contains("chunked") {
+                        chunked = true;
+                    }
+                }
+
+                let mut body = Vec::new();
+                if chunked {
+                    loop {
+                        let mut size_line = String::new();
+                        reader.read_line(&mut size_line).expect("read chunk size");
+                        let size = usize::from_str_radix(size_line.trim(), 16).unwrap_or(0);
+                        let mut chunk = vec![0u8; size + 2];
+                        reader.read_exact(&mut chunk).expect("read chunk");
+                        if size == 0 {
+                            break;
+                        }
+                        body.extend_from_slice(&chunk[..size]);
+                    }
+                } else {
+                    body.resize(content_length, 0);
+                    reader.read_exact(&mut body).expect("read body");
+                }
+
+                let request_id = {
+                    let mut recorded = recorded.lock().expect("lock");
+                    recorded.push(RecordedRequest {
+                        method: method.clone(),
+                        path: path.clone(),
+                        query,
+                        headers,
+                        body: String::from_utf8_lossy(&body).to_string(),
+                    });
+                    format!("req_{}", recorded.len())
+                };
+
+                let (status, response) = routes
+                    .iter()
+                    .find(|(m, p, _, _)| *m == method && *p == target)
+                    .or_else(|| routes.iter().find(|(m, p, _, _)| *m == method && *p == path))
+                    .map(|(_, _, status, body)| (*status, body.clone()))
+                    .unwrap_or((404, r#"{"error":"not found"}"#.to_string()));
+
+                // Like the API, compress for clients that accept gzip.
+                let accepts_gzip = recorded.lock().expect("lock").last().is_some_and(|request| {
+                    request.headers.iter().any(|(name, value)| name == "accept-encoding" && value.contains("gzip"))
+                });
+                let (encoding, response) = if accepts_gzip {
+                    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                    encoder.write_all(response.as_bytes()).expect("gzip response");
+                    ("Content-Encoding: gzip\r\n", encoder.finish().expect("gzip response"))
+                } else {
+                    ("", response.into_bytes())
+                };
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status} X\r\nContent-Type: application/json\r\n{encoding}Content-Length: {}\r\nX-Request-Id: {request_id}\r\nConnection: close\r\n\r\n",
+                    response.len()
+                );
+                let _ = stream.write_all(&response);
+            }
+        });
+
+        Self { base_url, requests }
+    }
+
+    fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().expect("lock").clone()
+    }
+}
+
+/// Routes for a local upload that completes immediately with `transcript` as the polled body.
+fn completed_transcript_routes(transcript: serde_json::Value) -> Vec<(&'static str, &'static str, u16, String)> {
+    vec![
+        ("POST", "/v2/upload", 200, r#"{"upload_url":"https://cdn.example/upload/1"}"#.to_string()),
+        ("POST", "/v2/transcript", 200, r#"{"id":"tr_1","status":"queued"}"#.to_string()),
+        ("GET", "/v2/transcript/tr_1", 200, transcript.to_string()),
+    ]
+}
+
+fn sample_transcript() -> serde_json::Value {
+    serde_json::json!({
+        "id": "tr_1",
+        "status": "completed",
+        "text": "Hello world. Good bye.",
+        "words": [
+            {"text": "Hello", "start": 0, "end": 400, "confidence": 0.98, "speaker": "A"},
+            {"text": "world.", "start": 400, "end": 900, "confidence": 0.91, "speaker": "A"},
+            {"text": "Good", "start": 2000, "end": 2300, "confidence": 0.95, "speaker": "B"},
+            {"text": "bye.", "start": 2300, "end": 2700, "confidence": 0.52, "speaker": "B"}
+        ],
+        "utterances": [
+            {"speaker": "A", "text": "Hello world.", "start": 0, "end": 900},
+            {"speaker": "B", "text": "Good bye.", "start": 2000, "end": 2700}
+        ]
+    })
+}
+
+fn temp_audio_file(dir: &tempfile::TempDir) -> PathBuf {
+    let path = dir.path().join("input.mp3");
+    std::fs::write(&path, b"fake audio").expect("write audio");
+    path
+}
+
+fn mock_transcribe_cmd(api: &MockApi) -> (Command, tempfile::TempDir) {
+    mock_cmd(api, "transcribe")
+}
+
+fn mock_cmd(api: &MockApi, subcommand: &str) -> (Command, tempfile::TempDir) {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let home = set_temp_home(&mut cmd);
+    cmd.env("ASSEMBLYAI_API_KEY", "dummy");
+    cmd.env("ASSEMBLYAI_BASE_URL", &api.base_url);
+    cmd.arg(subcommand);
+    (cmd, home)
+}
+
 #[test]
 fn init_creates_config_json() {
     let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
@@ -98,6 +275,509 @@ fn init_existing_api_key_overwrite_updates_value() {
     assert_eq!(parsed.get("apiKey").and_then(|v| v.as_str()), Some("new-key"));
 }
 
+#[test]
+fn init_non_interactive_verifies_key_and_writes_config() {
+    let api = MockApi::start(vec![("GET", "/v2/transcript", 200, r#"{"transcripts":[]}"#.to_string())]);
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let home = set_temp_home(&mut cmd);
+    cmd.env("ASSEMBLYAI_BASE_URL", &api.base_url)
+        .args(["init", "--api-key", "good-key", "--format", "srt", "--yes"]);
+    cmd.assert().success();
+
+    let parsed: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(config_json_path(&home)).expect("read config.json")).expect("parse json");
+    assert_eq!(parsed.get("apiKey").and_then(|v| v.as_str()), Some("good-key"));
+    assert_eq!(parsed.get("format").and_then(|v| v.as_str()), Some("srt"));
+    assert!(api.requests().iter().any(|r| r.method == "GET" && r.path == "/v2/transcript"));
+
+    let api = MockApi::start(vec![("GET", "/v2/transcript", 401, r#"{"error":"Authentication error"}"#.to_string())]);
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let home = set_temp_home(&mut cmd);
+    cmd.env("ASSEMBLYAI_BASE_URL", &api.base_url)
+        .args(["init", "--api-key", "typo-key"]);
+    cmd.assert()
+        .code(3)
+        .stderr(predicate::str::contains("AssemblyAI rejected the API key"));
+    assert!(!config_json_path(&home).exists());
+}
+
+#[test]
+fn init_offers_to_reenter_a_rejected_key() {
+    let api = MockApi::start(vec![("GET", "/v2/transcript", 401, r#"{"error":"Authentication error"}"#.to_string())]);
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let home = set_temp_home(&mut cmd);
+    cmd.env("ASSEMBLYAI_BASE_URL", &api.base_url)
+        .arg("init")
+        .write_stdin("typo-key\n\nsecond-key\nn\n");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("warning: AssemblyAI rejected this API key"))
+        .stderr(predicate::str::contains("storing the rejected key anyway"));
+
+    let parsed: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(config_json_path(&home)).expect("read config.json")).expect("parse json");
+    assert_eq!(parsed.get("apiKey").and_then(|v| v.as_str()), Some("second-key"));
+    assert_eq!(api.requests().len(), 2);
+}
+
+#[cfg(unix)]
+#[test]
+fn config_with_api_key_is_private_and_loose_permissions_are_flagged() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let home = set_temp_home(&mut cmd);
+    cmd.args(["init", "--api-key", "some-key", "--no-verify"]);
+    cmd.assert().success();
+    assert_eq!(mode(&config_json_path(&home)), 0o600);
+
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    std::fs::create_dir_all(config_path(&home)).unwrap();
+    std::fs::write(config_json_path(&home), r#"{"apiKey":"dummy"}"#).unwrap();
+    std::fs::set_permissions(config_json_path(&home), std::fs::Permissions::from_mode(0o644)).unwrap();
+    cmd.arg(temp_audio_file(&home));
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("contains apiKey but is accessible to other users (mode 0644)"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    cmd.env("HOME", home.path()).env("USERPROFILE", home.path());
+    cmd.args(["config", "fix-permissions"]);
+    cmd.assert().success();
+    assert_eq!(mode(&config_json_path(&home)), 0o600);
+    assert_eq!(mode(&config_path(&home)), 0o700);
+}
+
+#[test]
+fn config_migrate_upgrades_legacy_config_with_backup() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let legacy = config_path(&home);
+    std::fs::write(&legacy, r#"{"format":"txt","timeoutSeconds":60}"#).unwrap();
+    cmd.arg(temp_audio_file(&home));
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("uses config schema version 1; run `assemblyai-cli config migrate`"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    cmd.env("HOME", home.path()).env("USERPROFILE", home.path());
+    cmd.args(["config", "migrate"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("format: \"txt\" -> \"text\""));
+
+    let migrated: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(config_json_path(&home)).unwrap()).unwrap();
+    assert_eq!(migrated, serde_json::json!({"configVersion": 2, "format": "text", "timeoutSeconds": 60}));
+    let backup = home.path().join(".assemblyai-cli.bak");
+    assert_eq!(std::fs::read_to_string(backup).unwrap(), r#"{"format":"txt","timeoutSeconds":60}"#);
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    cmd.env("HOME", home.path()).env("USERPROFILE", home.path());
+    cmd.args(["config", "migrate"]);
+    cmd.assert().success().stderr(predicate::str::contains("is up to date (configVersion 2)"));
+}
+
+#[test]
+fn unknown_config_keys_warn_and_fail_with_strict_config() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    std::fs::write(config_path(&home), r#"{"configVersion":2,"speakerLables":true}"#).unwrap();
+    let audio = temp_audio_file(&home);
+    cmd.arg(&audio);
+    cmd.assert().success().stderr(predicate::str::contains(
+        "unknown config key `speakerLables` (did you mean `speakerLabels`?)",
+    ));
+
+    let requests = api.requests().len();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    cmd.env("HOME", home.path()).env("USERPROFILE", home.path());
+    cmd.env("ASSEMBLYAI_API_KEY", "dummy").env("ASSEMBLYAI_BASE_URL", &api.base_url);
+    cmd.args(["transcribe", "--strict-config"]).arg(&audio);
+    cmd.assert()
+        .code(3)
+        .stderr(predicate::str::contains("did you mean `speakerLabels`?"));
+    assert_eq!(api.requests().len(), requests);
+}
+
+#[test]
+fn extra_headers_from_config_and_flags_reach_every_request() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    std::fs::write(
+        config_path(&home),
+        r#"{"configVersion":2,"extraHeaders":{"X-Org-Id":"41","X-Team":"media"}}"#,
+    )
+    .unwrap();
+    cmd.arg(temp_audio_file(&home)).args(["--header", "X-Org-Id: 42"]);
+    cmd.assert().success();
+
+    let requests = api.requests();
+    assert!(requests.len() >= 3);
+    for request in &requests {
+        let header = |name: &str| {
+            request.headers.iter().filter(|(n, _)| n == name).map(|(_, v)| v.as_str()).collect::<Vec<_>>()
+        };
+        assert_eq!(header("x-org-id"), vec!["42"], "{} {}", request.method, request.path);
+        assert_eq!(header("x-team"), vec!["media"]);
+        assert_eq!(header("authorization"), vec!["dummy"]);
+    }
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    cmd.env("HOME", home.path()).env("USERPROFILE", home.path());
+    cmd.args(["transcribe", "input.mp3", "--header", "X-Org-Id"]);
+    cmd.assert().code(2).stderr(predicate::str::contains("expected \"NAME: VALUE\""));
+}
+
+#[test]
+fn api_errors_include_the_request_id() {
+    let api = MockApi::start(vec![
+        ("POST", "/v2/upload", 200, r#"{"upload_url":"https://cdn.example/upload/1"}"#.to_string()),
+        ("POST", "/v2/transcript", 500, r#"{"error":"Internal server error"}"#.to_string()),
+    ]);
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home));
+    cmd.assert().code(5).stderr(predicate::str::contains(
+        r#"api returned HTTP 500 Internal Server Error: {"error":"Internal server error"} (request id req_2)"#,
+    ));
+}
+
+#[test]
+fn json_logs_carry_the_file_and_transcript_id() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    let log = home.path().join("run.log");
+    cmd.arg(&input).arg("--log-format").arg("json").arg("--log-file").arg(&log);
+    cmd.assert().success().stderr(predicate::str::is_empty());
+
+    let events: Vec<serde_json::Value> = std::fs::read_to_string(&log)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert!(!events.is_empty());
+    assert!(events.iter().all(|event| event["level"] == "info" && event["file"] == input.display().to_string()));
+    assert!(events.iter().all(|event| event["timestamp"].as_str().is_some_and(|ts| ts.ends_with('Z'))));
+    let polled = events.iter().find(|event| event["transcript_id"] == "tr_1").expect("an event after the transcript was created");
+    assert!(polled["message"].is_string());
+}
+
+#[test]
+fn progress_messages_follow_the_locale() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.env("LANG", "ru_RU.UTF-8").arg(&input);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("запуск транскрибации").and(predicate::str::contains("загрузка: ")));
+
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.env("LANG", "ru_RU.UTF-8").arg(&input).args(["--locale", "en"]);
+    cmd.assert().success().stderr(predicate::str::contains("starting transcription"));
+
+    let (mut cmd, _home) = mock_transcribe_cmd(&api);
+    cmd.args(["--locale", "xx", "input.mp3"]);
+    cmd.assert().failure().code(2).stderr(predicate::str::contains("available: en, ru"));
+}
+
+#[test]
+fn version_json_reports_build_info_and_the_base_url_in_use() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let home = set_temp_home(&mut cmd);
+    std::fs::create_dir_all(config_path(&home)).unwrap();
+    std::fs::write(config_json_path(&home), r#"{"baseUrl":"https://eu.api.example"}"#).unwrap();
+    cmd.env_remove("ASSEMBLYAI_BASE_URL").args(["version", "--json"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let info: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(info["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(info["baseUrl"], "https://eu.api.example");
+    assert!(info["features"].is_array());
+    for key in ["commit", "buildDate", "target"] {
+        assert!(info[key].as_str().is_some_and(|value| !value.is_empty()), "{key}");
+    }
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    cmd.env("HOME", home.path()).env("USERPROFILE", home.path());
+    cmd.env("ASSEMBLYAI_BASE_URL", "http://127.0.0.1:9").arg("version");
+    cmd.assert().success().stdout(predicate::str::contains("api:        http://127.0.0.1:9"));
+}
+
+/// A release 9.9.9 with an archive and checksum for every target, served from `/asset` and `/asset.sha256`.
+fn release_routes(checksum: &str) -> Vec<(&'static str, &'static str, u16, String)> {
+    let targets = ["x86_64-unknown-linux-gnu", "x86_64-apple-darwin", "aarch64-apple-darwin", "x86_64-pc-windows-msvc"];
+    let assets: Vec<serde_json::Value> = targets
+        .iter()
+        .flat_map(|target| {
+            let archive = if target.contains("windows") { "zip" } else { "tar.gz" };
+            let name = format!("assemblyai-cli-9.9.9-{target}.{archive}");
+            [
+                serde_json::json!({"name": format!("{name}.sha256"), "browser_download_url": "/asset.sha256"}),
+                serde_json::json!({"name": name, "browser_download_url": "/asset"}),
+            ]
+        })
+        .collect();
+    vec![
+        ("GET", "/releases/latest", 200, serde_json::json!({"tag_name": "v9.9.9", "assets": assets}).to_string()),
+        ("GET", "/asset", 200, "not an archive".to_string()),
+        ("GET", "/asset.sha256", 200, format!("{checksum}  assemblyai-cli.tar.gz\n")),
+    ]
+}
+
+fn self_update_cmd(api: &MockApi) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    cmd.env("ASSEMBLYAI_CLI_RELEASES_URL", format!("{}/releases/latest", api.base_url));
+    cmd.arg("self-update");
+    cmd
+}
+
+#[test]
+fn self_update_check_reports_a_newer_release() {
+    let api = MockApi::start(release_routes(&"0".repeat(64)));
+    self_update_cmd(&api)
+        .arg("--check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "update available: {} -> 9.9.9 (assemblyai-cli-9.9.9-",
+            env!("CARGO_PKG_VERSION")
+        )));
+    assert_eq!(api.requests().len(), 1);
+}
+
+#[test]
+fn self_update_rejects_a_checksum_mismatch() {
+    let api = MockApi::start(release_routes(&"0".repeat(64)));
+    let exe = assert_cmd::cargo::cargo_bin!("assemblyai-cli");
+    let before = std::fs::metadata(exe).unwrap().modified().unwrap();
+    self_update_cmd(&api)
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("checksum mismatch for assemblyai-cli-9.9.9-").and(predicate::str::contains("the binary was not replaced")));
+    assert_eq!(std::fs::metadata(exe).unwrap().modified().unwrap(), before);
+}
+
+#[test]
+fn quoted_wildcard_inputs_expand_to_a_batch() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let recordings = home.path().join("recordings");
+    std::fs::create_dir_all(&recordings).unwrap();
+    for name in ["monday.m4a", "tuesday.m4a", "notes.txt"] {
+        std::fs::write(recordings.join(name), b"fake audio").unwrap();
+    }
+    let out = home.path().join("out");
+    cmd.arg(recordings.join("*.m4a")).arg("--output").arg(&out);
+    cmd.assert().success();
+    assert!(out.join("monday.txt").is_file() && out.join("tuesday.txt").is_file());
+    assert!(!out.join("notes.txt").exists());
+
+    let (mut cmd, _home) = mock_transcribe_cmd(&api);
+    cmd.arg(recordings.join("*.m4a")).arg("--no-glob");
+    cmd.assert().code(2).stderr(predicate::str::contains("input file not found"));
+
+    let (mut cmd, _home) = mock_transcribe_cmd(&api);
+    cmd.arg(recordings.join("*.wav"));
+    cmd.assert().code(2).stderr(predicate::str::contains("no files match"));
+}
+
+#[test]
+fn language_auto_records_each_detected_language_in_a_batch() {
+    let mut transcript = sample_transcript();
+    transcript["language_code"] = "es".into();
+    transcript["language_confidence"] = 0.93.into();
+    let api = MockApi::start(completed_transcript_routes(transcript));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    std::fs::create_dir_all(config_path(&home)).unwrap();
+    std::fs::write(config_json_path(&home), r#"{"languageDetection":false}"#).unwrap();
+    let inputs = ["monday.mp3", "tuesday.mp3"].map(|name| home.path().join(name));
+    for input in &inputs {
+        std::fs::write(input, b"fake audio").unwrap();
+    }
+    let out = home.path().join("out");
+    cmd.args(&inputs).args(["--language", "auto", "--output"]).arg(&out);
+    cmd.assert().success().stderr(predicate::str::contains("detected languages: es 2"));
+
+    let create = api.requests().into_iter().find(|r| r.method == "POST" && r.path == "/v2/transcript").unwrap();
+    let create: serde_json::Value = serde_json::from_str(&create.body).unwrap();
+    assert_eq!(create["language_detection"], true);
+    assert!(create.get("language_code").is_none_or(serde_json::Value::is_null));
+    let sidecar: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(out.join("tuesday.meta.json")).unwrap()).unwrap();
+    assert_eq!(
+        sidecar,
+        serde_json::json!({
+            "input": inputs[1].display().to_string(),
+            "transcriptId": "tr_1",
+            "languageCode": "es",
+            "languageConfidence": 0.93,
+            "contentSha256": "72401f193251f177a310936253acb57e91e29d2aa582093974351e65e267bb32"
+        })
+    );
+
+    let (mut cmd, _home) = mock_transcribe_cmd(&api);
+    cmd.args(["input.mp3", "--language", "auto", "--no-language-detection"]);
+    cmd.assert().code(2).stderr(predicate::str::contains("--language auto requires language detection"));
+}
+
+#[test]
+fn unicode_file_names_keep_their_names_in_outputs_and_history() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let inputs = ["Клод интервью.m4a", "podcast 🎙️ ep1.mp3"].map(|name| home.path().join(name));
+    for input in &inputs {
+        std::fs::write(input, b"fake audio").unwrap();
+    }
+    let out = home.path().join("выход");
+    cmd.args(&inputs).arg("--output").arg(&out);
+    cmd.assert().success();
+    assert!(out.join("Клод интервью.txt").is_file());
+    assert!(out.join("podcast 🎙️ ep1.txt").is_file());
+
+    let history = std::fs::read_to_string(config_path(&home).join("history.jsonl")).unwrap();
+    // Inputs run side by side, so history is in the order they finished.
+    let mut recorded: Vec<String> = history
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["input"].as_str().unwrap().to_string())
+        .collect();
+    recorded.sort();
+    let mut expected = inputs.map(|input| input.display().to_string()).to_vec();
+    expected.sort();
+    assert_eq!(recorded, expected);
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_file_names_survive_failures_and_retry_files() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let name = std::ffi::OsStr::from_bytes(b"caf\xe9.mp3");
+    let input = home.path().join(name);
+    let out = home.path().join("out");
+    let failures = home.path().join("failures.json");
+    cmd.arg(temp_audio_file(&home)).arg(&input).arg("--output").arg(&out).arg("--failures-file").arg(&failures);
+    cmd.assert().code(9);
+    let listed: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&failures).unwrap()).unwrap();
+    assert!(listed["failures"][0]["input"]["unix"].is_array(), "{listed}");
+
+    std::fs::write(&input, b"fake audio").unwrap();
+    let mut retry = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    retry.env("HOME", home.path()).env("USERPROFILE", home.path());
+    retry.env("ASSEMBLYAI_API_KEY", "dummy").env("ASSEMBLYAI_BASE_URL", &api.base_url);
+    retry.arg("transcribe").arg("--retry-file").arg(&failures).arg("--output").arg(&out);
+    retry.assert().success();
+    assert!(out.join(std::ffi::OsStr::from_bytes(b"caf\xe9.txt")).is_file());
+}
+
+#[test]
+fn batch_run_records_failures_and_retries_them() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let first = temp_audio_file(&home);
+    let missing = home.path().join("later.mp3");
+    let out = home.path().join("out");
+    let failures = home.path().join("failures.json");
+    cmd.arg(&first).arg(&missing).arg("--output").arg(&out).arg("--failures-file").arg(&failures);
+    cmd.assert()
+        .code(9)
+        .stderr(predicate::str::contains("transcribed 1 of 2 inputs").and(predicate::str::contains("1 of 2 inputs failed")));
+    assert!(out.join("input.txt").is_file());
+    let listed: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&failures).unwrap()).unwrap();
+    assert_eq!(listed["failures"][0]["input"], missing.display().to_string());
+    assert!(listed["failures"][0]["error"].as_str().unwrap().contains("later.mp3"));
+    assert_eq!(listed["failures"].as_array().unwrap().len(), 1);
+
+    std::fs::write(&missing, b"fake audio").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    cmd.env("HOME", home.path()).env("USERPROFILE", home.path());
+    cmd.env("ASSEMBLYAI_API_KEY", "dummy").env("ASSEMBLYAI_BASE_URL", &api.base_url);
+    cmd.args(["transcribe", "--retry-file"]).arg(&failures).arg("--failures-file").arg(&failures);
+    cmd.arg("--output").arg(&out);
+    cmd.assert().success().stderr(predicate::str::contains("transcribed 1 of 1 inputs"));
+    assert!(out.join("later.txt").is_file());
+    assert_eq!(std::fs::read_to_string(&failures).unwrap(), "{\n  \"failures\": []\n}\n");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    cmd.env("HOME", home.path()).env("USERPROFILE", home.path());
+    cmd.args(["transcribe"]).arg(&first).arg(&missing);
+    cmd.assert().code(2).stderr(predicate::str::contains("--output DIR"));
+}
+
+#[test]
+fn interrupted_batch_resumes_without_uploading_submitted_inputs() {
+    let pending = MockApi::start(completed_transcript_routes(serde_json::json!({"id": "tr_1", "status": "processing"})));
+    let home = tempfile::tempdir().unwrap();
+    std::fs::write(home.path().join("a.mp3"), b"fake audio").unwrap();
+    std::fs::write(home.path().join("b.mp3"), b"other fake audio").unwrap();
+    // One input at a time, so the run is interrupted with b.mp3 not yet started.
+    let args = ["transcribe", "a.mp3", "b.mp3", "--output", "out", "--poll-interval-seconds", "1", "--concurrency", "1"];
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"))
+        .current_dir(home.path())
+        .env("HOME", home.path())
+        .env("USERPROFILE", home.path())
+        .env("ASSEMBLYAI_API_KEY", "dummy")
+        .env("ASSEMBLYAI_BASE_URL", &pending.base_url)
+        .args(args)
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let jobs = home.path().join(".assemblyai-cli").join("jobs.json");
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while !std::fs::read_to_string(&jobs).unwrap_or_default().contains("\"submitted\"") {
+        assert!(std::time::Instant::now() < deadline, "batch never recorded a submitted job");
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    cmd.current_dir(home.path()).env("HOME", home.path()).env("USERPROFILE", home.path());
+    cmd.env("ASSEMBLYAI_API_KEY", "dummy").env("ASSEMBLYAI_BASE_URL", &api.base_url);
+    cmd.args(args);
+    cmd.assert().success().stderr(
+        predicate::str::contains("resuming an interrupted batch: 1 of 2 inputs were already started")
+            .and(predicate::str::contains("resuming transcript tr_1")),
+    );
+    let uploads = api.requests().iter().filter(|r| r.path == "/v2/upload").count();
+    assert_eq!(uploads, 1, "only b.mp3 is uploaded");
+    assert!(home.path().join("out/a.txt").is_file() && home.path().join("out/b.txt").is_file());
+    assert_eq!(std::fs::read_to_string(&jobs).unwrap(), "{\n  \"batches\": []\n}\n");
+}
+
+#[test]
+fn batch_inputs_run_side_by_side() {
+    let api = MockApi::start(completed_transcript_routes(serde_json::json!({"id": "tr_1", "status": "processing"})));
+    let home = tempfile::tempdir().unwrap();
+    for name in ["a.mp3", "b.mp3", "c.mp3"] {
+        std::fs::write(home.path().join(name), name).unwrap();
+    }
+    let run = |extra: &[&str]| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+        cmd.current_dir(home.path()).env("HOME", home.path()).env("USERPROFILE", home.path());
+        cmd.env("ASSEMBLYAI_API_KEY", "dummy").env("ASSEMBLYAI_BASE_URL", &api.base_url);
+        cmd.args(["transcribe", "a.mp3", "b.mp3", "c.mp3", "--output", "out", "--no-resume"]);
+        cmd.args(["--poll-interval-seconds", "1", "--timeout-seconds", "2"]).args(extra);
+        cmd.assert()
+    };
+
+    // Each input waits out its 2-second timeout; one after another, that would take at least 6 seconds.
+    let started = std::time::Instant::now();
+    run(&["--concurrency", "3"]).code(9);
+    let elapsed = started.elapsed();
+    assert!(elapsed < std::time::Duration::from_secs(5), "batch took {elapsed:?}");
+    assert_eq!(api.requests().iter().filter(|r| r.path == "/v2/upload").count(), 3);
+
+    run(&["--concurrency", "0"]).code(2);
+}
+
 #[test]
 fn help_mentions_config_and_env_vars() {
     let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
@@ -169,6 +849,32 @@ fn invalid_config_json_exits_3() {
         .stderr(predicate::str::contains("failed to parse config file"));
 }
 
+#[test]
+fn config_schema_violation_exits_3_naming_the_key() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let home = set_temp_home(&mut cmd);
+    std::fs::write(config_path(&home), r#"{"apiKey":"k","presets":{"subs":{"frameRate":"25"}}}"#).expect("write config");
+
+    cmd.arg("transcribe").arg(dummy_audio_path());
+    cmd.assert()
+        .failure()
+        .code(3)
+        .stderr(predicate::str::contains("`presets.subs.frameRate` must be an integer, not string"));
+}
+
+#[test]
+fn schema_prints_config_and_output_schemas() {
+    for (document, title) in [("config", "assemblyai-cli config"), ("output", "assemblyai-cli JSON output")] {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+        set_temp_home(&mut cmd);
+        let output = cmd.args(["schema", document]).output().expect("run schema");
+        assert!(output.status.success());
+        let schema: serde_json::Value = serde_json::from_slice(&output.stdout).expect("schema json");
+        assert_eq!(schema["title"], title);
+        assert_eq!(schema["$schema"], "https://json-schema.org/draft/2020-12/schema");
+    }
+}
+
 #[test]
 fn config_path_is_directory_exits_3() {
     let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
@@ -213,3 +919,2074 @@ fn invalid_speech_threshold_in_config_exits_2() {
         .code(2)
         .stderr(predicate::str::contains("invalid speech threshold"));
 }
+
+#[test]
+fn invalid_report_exits_2() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let _home = set_temp_home(&mut cmd);
+    cmd.env("ASSEMBLYAI_API_KEY", "dummy");
+    cmd.arg("transcribe").arg(dummy_audio_path()).arg("--report").arg("bogus");
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("invalid report"));
+}
+
+#[test]
+fn sentiment_timeline_without_sentiment_analysis_exits_2() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let _home = set_temp_home(&mut cmd);
+    cmd.env("ASSEMBLYAI_API_KEY", "dummy");
+    cmd.arg("transcribe")
+        .arg(dummy_audio_path())
+        .arg("--report")
+        .arg("sentiment-timeline");
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("requires --sentiment-analysis"));
+}
+
+#[test]
+fn silence_report_lists_gaps_between_words() {
+    let mut transcript = sample_transcript();
+    transcript["audio_duration"] = 4.0.into();
+    let api = MockApi::start(completed_transcript_routes(transcript));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home))
+        .args(["--poll-interval-seconds", "0", "--report", "silence=1s"]);
+    cmd.assert().success().stderr(predicate::str::contains(
+        "Silences of 1.0s or more\n  [00:00:00] - [00:00:02]  1.1s\n  [00:00:02] - [00:00:04]  1.3s\n\n\
+         Total silence  2.4s of 4.0s (60.0%)\n",
+    ));
+}
+
+#[test]
+fn profanity_report_lists_masked_words() {
+    let mut transcript = sample_transcript();
+    transcript["words"][3]["text"] = "b**.".into();
+    let api = MockApi::start(completed_transcript_routes(transcript));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home)).args([
+        "--poll-interval-seconds",
+        "0",
+        "--filter-profanity",
+        "--report",
+        "profanity",
+        "--timestamp-format",
+        "seconds",
+    ]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Profanity\n  [2.300] - [2.700]  Speaker B  b**.\n\nTotal  1\n"));
+
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home)).args(["--report", "profanity"]);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("report profanity requires --filter-profanity"));
+}
+
+#[test]
+fn lint_reports_violations_with_line_numbers_and_exits_6() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let path = dir.path().join("captions.srt");
+    std::fs::write(
+        &path,
+        "1\n00:00:00,000 --> 00:00:02,000\nHello\n\n2\n00:00:01,000 --> 00:00:03,000\nWorld\n",
+    )
+    .expect("write srt");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let _home = set_temp_home(&mut cmd);
+    cmd.arg("lint").arg(&path);
+    cmd.assert()
+        .failure()
+        .code(6)
+        .stdout(predicate::str::contains("captions.srt:6: overlap"));
+}
+
+#[test]
+fn lint_clean_captions_succeeds() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let path = dir.path().join("captions.vtt");
+    std::fs::write(&path, "WEBVTT\n\n00:00.000 --> 00:02.000\nHello\n").expect("write vtt");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let _home = set_temp_home(&mut cmd);
+    cmd.arg("lint").arg(&path);
+    cmd.assert().success().stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn merge_retimes_parts_to_their_offsets() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let first = dir.path().join("part1.srt");
+    let second = dir.path().join("part2.vtt");
+    std::fs::write(&first, "1\n00:00:01,000 --> 00:00:02,000\nHello\n").expect("write srt");
+    std::fs::write(&second, "WEBVTT\n\n00:00.500 --> 00:01.500\nAgain\n").expect("write vtt");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let _home = set_temp_home(&mut cmd);
+    cmd.arg("merge").arg(&first).arg(&second).args(["--offsets", "0,1:02:00"]);
+    cmd.assert()
+        .success()
+        .stdout("1\n00:00:01,000 --> 00:00:02,000\nHello\n\n2\n01:02:00,500 --> 01:02:01,500\nAgain\n\n");
+
+    let out = dir.path().join("full.vtt");
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let _home = set_temp_home(&mut cmd);
+    cmd.arg("merge").arg(&first).arg(&second).arg("-o").arg(&out);
+    cmd.assert().success();
+    assert_eq!(
+        std::fs::read_to_string(&out).unwrap(),
+        "WEBVTT\n\n00:00:01.000 --> 00:00:02.000\nHello\n\n00:00:02.500 --> 00:00:03.500\nAgain\n\n"
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let _home = set_temp_home(&mut cmd);
+    cmd.arg("merge").arg(&first).arg(&second).args(["--offsets", "0"]);
+    cmd.assert().failure().code(2).stderr(predicate::str::contains("got 1 for 2 files"));
+}
+
+#[test]
+fn render_fetches_the_api_export_at_a_caption_width() {
+    let vtt = "WEBVTT\n\n00:00.000 --> 00:00.900\nHello world.\n";
+    let api = MockApi::start(vec![("GET", "/v2/transcript/tr_1/vtt", 200, vtt.to_string())]);
+    let (mut cmd, home) = mock_cmd(&api, "render");
+    let out = home.path().join("narrow.vtt");
+    cmd.args(["tr_1", "--chars-per-caption", "32", "-o"]).arg(&out);
+    cmd.assert().success();
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), vtt);
+    let requests = api.requests();
+    assert_eq!((requests[0].path.as_str(), requests[0].query.as_str()), ("/v2/transcript/tr_1/vtt", "chars_per_caption=32"));
+}
+
+#[test]
+fn invalid_timecode_start_exits_2() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let _home = set_temp_home(&mut cmd);
+    cmd.env("ASSEMBLYAI_API_KEY", "dummy");
+    cmd.arg("transcribe")
+        .arg(dummy_audio_path())
+        .args(["--format", "edl", "--frame-rate", "25", "--timecode-start", "01:00:00:25"]);
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("invalid timecode"));
+}
+
+#[test]
+fn write_chapters_requires_auto_chapters() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let _home = set_temp_home(&mut cmd);
+    cmd.env("ASSEMBLYAI_API_KEY", "dummy");
+    cmd.arg("transcribe").arg("talk.mp4").arg("--write-chapters");
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("--write-chapters requires --auto-chapters"));
+}
+
+#[test]
+fn transcribe_local_file_prints_text() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.arg(&input).args(["--poll-interval-seconds", "0"]);
+    cmd.assert().success().stdout("Hello world. Good bye.");
+
+    let requests = api.requests();
+    assert_eq!((requests[0].method.as_str(), requests[0].path.as_str()), ("POST", "/v2/upload"));
+    assert_eq!(requests[0].body, "fake audio");
+    let create: serde_json::Value = serde_json::from_str(&requests[1].body).expect("create body");
+    assert_eq!(create["audio_url"], "https://cdn.example/upload/1");
+}
+
+#[test]
+fn transcript_downloads_are_gzip_compressed() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home)).args(["--poll-interval-seconds", "0"]);
+    cmd.assert().success().stdout("Hello world. Good bye.");
+
+    let requests = api.requests();
+    let poll = requests.iter().find(|r| r.method == "GET" && r.path == "/v2/transcript/tr_1").expect("transcript GET");
+    assert!(
+        poll.headers.iter().any(|(name, value)| name == "accept-encoding" && value.contains("gzip")),
+        "{:?}",
+        poll.headers
+    );
+}
+
+#[test]
+fn srt_dual_interleaves_lemur_translation() {
+    let mut routes = completed_transcript_routes(sample_transcript());
+    routes.push((
+        "POST",
+        "/lemur/v3/generate/task",
+        200,
+        r#"{"request_id":"lm_1","response":"1: Hola mundo.\n2: Adiós."}"#.to_string(),
+    ));
+    let api = MockApi::start(routes);
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    let translated = home.path().join("es.vtt");
+    cmd.arg(&input)
+        .args(["--poll-interval-seconds", "0", "--format", "srt-dual", "--translate-to", "es"])
+        .arg("--translated-output")
+        .arg(&translated);
+    cmd.assert().success().stdout(
+        "1\n00:00:00,000 --> 00:00:00,900\nHello world.\nHola mundo.\n\n2\n00:00:02,000 --> 00:00:02,700\nGood bye.\nAdiós.\n\n",
+    );
+
+    let vtt = std::fs::read_to_string(&translated).expect("read translated");
+    assert_eq!(vtt, "WEBVTT\n\n00:00:00.000 --> 00:00:00.900\nHola mundo.\n\n00:00:02.000 --> 00:00:02.700\nAdiós.\n\n");
+
+    let lemur = api
+        .requests()
+        .into_iter()
+        .find(|r| r.path == "/lemur/v3/generate/task")
+        .expect("lemur request");
+    let body: serde_json::Value = serde_json::from_str(&lemur.body).expect("lemur body");
+    assert_eq!(body["input_text"], "1: Hello world.\n2: Good bye.\n");
+}
+
+#[test]
+fn anki_tsv_pairs_sentences_with_their_translation() {
+    let mut routes = completed_transcript_routes(sample_transcript());
+    routes.push((
+        "POST",
+        "/lemur/v3/generate/task",
+        200,
+        r#"{"request_id":"lm_1","response":"1: Hola mundo.\n2: Adiós."}"#.to_string(),
+    ));
+    let api = MockApi::start(routes);
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.arg(&input).args([
+        "--poll-interval-seconds",
+        "0",
+        "--format",
+        "anki-tsv",
+        "--translate-to",
+        "es",
+        "--timestamp-format",
+        "seconds",
+    ]);
+    cmd.assert().success().stdout(
+        "#separator:tab\n#html:false\n#columns:Sentence\tTime\tTranslation\nHello world.\t0.000-0.900\tHola mundo.\nGood bye.\t2.000-2.700\tAdiós.\n",
+    );
+}
+
+#[test]
+fn otr_writes_an_otranscribe_document_of_speaker_turns() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    let output = home.path().join("input.otr");
+    cmd.arg(&input)
+        .args(["--poll-interval-seconds", "0", "--speaker-labels", "--format", "otr", "--output"])
+        .arg(&output);
+    cmd.assert().success();
+
+    let document: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&output).expect("read otr")).expect("otr json");
+    assert_eq!(
+        document["text"],
+        "<p><span class=\"timestamp\" contenteditable=\"false\" data-timestamp=\"0.000\">00:00</span> Speaker A: Hello world.</p>\
+         <p><span class=\"timestamp\" contenteditable=\"false\" data-timestamp=\"2.000\">00:02</span> Speaker B: Good bye.</p>"
+    );
+    assert_eq!(document["media"], "input.mp3");
+}
+
+#[test]
+fn translate_keeps_utterance_timestamps_and_speakers() {
+    let api = MockApi::start(vec![
+        ("GET", "/v2/transcript/tr_1", 200, sample_transcript().to_string()),
+        (
+            "POST",
+            "/lemur/v3/generate/task",
+            200,
+            r#"{"request_id":"lm_1","response":"1: Hallo Welt.\n2: Auf Wiedersehen."}"#.to_string(),
+        ),
+    ]);
+    let (mut cmd, _home) = mock_cmd(&api, "translate");
+    cmd.args(["tr_1", "--to", "de", "--format", "txt"]);
+    cmd.assert()
+        .success()
+        .stdout("[00:00:00] Speaker A: Hallo Welt.\n[00:00:02] Speaker B: Auf Wiedersehen.\n");
+}
+
+#[test]
+fn timestamp_format_applies_to_text_output() {
+    let api = MockApi::start(vec![
+        ("GET", "/v2/transcript/tr_1", 200, sample_transcript().to_string()),
+        (
+            "POST",
+            "/lemur/v3/generate/task",
+            200,
+            r#"{"request_id":"lm_1","response":"1: Hallo Welt.\n2: Auf Wiedersehen."}"#.to_string(),
+        ),
+    ]);
+    let cases = [("hh:mm:ss", "00:00:00", "00:00:02"), ("mm:ss", "00:00", "00:02"), ("ms", "0", "2000"), ("seconds", "0.000", "2.000")];
+    for (format, first, second) in cases {
+        let (mut cmd, _home) = mock_cmd(&api, "translate");
+        cmd.args(["tr_1", "--to", "de", "--format", "txt", "--timestamp-format", format]);
+        cmd.assert()
+            .success()
+            .stdout(format!("[{first}] Speaker A: Hallo Welt.\n[{second}] Speaker B: Auf Wiedersehen.\n"));
+    }
+
+    let (mut cmd, home) = mock_cmd(&api, "translate");
+    std::fs::create_dir_all(home.path().join(".assemblyai-cli")).expect("create config dir");
+    std::fs::write(home.path().join(".assemblyai-cli/config.json"), r#"{"timestampFormat":"ms"}"#).expect("write config");
+    cmd.args(["tr_1", "--to", "de", "--format", "txt"]);
+    cmd.assert()
+        .success()
+        .stdout("[0] Speaker A: Hallo Welt.\n[2000] Speaker B: Auf Wiedersehen.\n");
+
+    for sub in ["translate", "transcribe"] {
+        let (mut cmd, _home) = mock_cmd(&api, sub);
+        cmd.args(["tr_1", "--timestamp-format", "hh:mm"]);
+        cmd.assert()
+            .failure()
+            .code(2)
+            .stderr(predicate::str::contains("invalid value 'hh:mm' for '--timestamp-format"))
+            .stderr(predicate::str::contains("[possible values: hh:mm:ss, mm:ss, ms, seconds]"));
+    }
+    assert_eq!(api.requests().len(), 10, "rejected values send no requests");
+}
+
+#[test]
+fn translate_rejects_incomplete_transcript() {
+    let api = MockApi::start(vec![(
+        "GET",
+        "/v2/transcript/tr_1",
+        200,
+        r#"{"id":"tr_1","status":"processing"}"#.to_string(),
+    )]);
+    let (mut cmd, _home) = mock_cmd(&api, "translate");
+    cmd.args(["tr_1", "--to", "de"]);
+    cmd.assert()
+        .failure()
+        .code(5)
+        .stderr(predicate::str::contains("not completed"));
+}
+
+#[test]
+fn meeting_writes_markdown_minutes() {
+    let mut transcript = sample_transcript();
+    transcript["auto_highlights_result"] = serde_json::json!({
+        "status": "success",
+        "results": [{"text": "good bye", "count": 1, "rank": 0.08, "timestamps": []}]
+    });
+    let mut routes = completed_transcript_routes(transcript);
+    routes.push((
+        "POST",
+        "/lemur/v3/generate/task",
+        200,
+        r###"{"request_id":"lm_1","response":"## Summary\n\nGreetings.\n\n## Decisions\n\n- None recorded.\n\n## Action items\n\n- [ ] Speaker B: say goodbye"}"###
+            .to_string(),
+    ));
+    let api = MockApi::start(routes);
+    let (mut cmd, home) = mock_cmd(&api, "meeting");
+    let input = temp_audio_file(&home);
+    cmd.arg(&input).args(["--poll-interval-seconds", "0"]);
+    cmd.assert().success().stdout(
+        "# Meeting notes: input\n\n## Attendees\n\n- Speaker A (1 turn, 00:00:00 speaking)\n- Speaker B (1 turn, 00:00:00 speaking)\n\n## Key topics\n\n- good bye (1x)\n\n## Summary\n\nGreetings.\n\n## Decisions\n\n- None recorded.\n\n## Action items\n\n- [ ] Speaker B: say goodbye\n",
+    );
+
+    let requests = api.requests();
+    let create = requests
+        .iter()
+        .find(|r| r.method == "POST" && r.path == "/v2/transcript")
+        .expect("create request");
+    let body: serde_json::Value = serde_json::from_str(&create.body).expect("create body");
+    assert_eq!(body["speaker_labels"], true);
+    assert_eq!(body["auto_highlights"], true);
+    let lemur = requests
+        .iter()
+        .find(|r| r.path == "/lemur/v3/generate/task")
+        .expect("lemur request");
+    let body: serde_json::Value = serde_json::from_str(&lemur.body).expect("lemur body");
+    assert_eq!(body["transcript_ids"], serde_json::json!(["tr_1"]));
+}
+
+#[test]
+fn shownotes_enables_chapters_highlights_and_entities() {
+    let mut transcript = sample_transcript();
+    transcript["chapters"] = serde_json::json!([
+        {"start": 0, "end": 2700, "headline": "Greetings", "gist": "hello", "summary": "They greet."}
+    ]);
+    transcript["entities"] = serde_json::json!([
+        {"entity_type": "person_name", "text": "Ada Lovelace", "start": 0, "end": 900}
+    ]);
+    let mut routes = completed_transcript_routes(transcript);
+    routes.push((
+        "POST",
+        "/lemur/v3/generate/task",
+        200,
+        r###"{"request_id":"lm_1","response":"## Summary\n\nA short hello.\n\n## Key quotes\n\n- \"Hello world.\""}"###.to_string(),
+    ));
+    let api = MockApi::start(routes);
+    let (mut cmd, home) = mock_cmd(&api, "shownotes");
+    let input = temp_audio_file(&home);
+    cmd.arg(&input).args(["--poll-interval-seconds", "0"]);
+    cmd.assert().success().stdout(
+        "# input\n\n## Summary\n\nA short hello.\n\n## Chapters\n\n- 00:00:00 Greetings\n\n## Key quotes\n\n- \"Hello world.\"\n\n## Guests\n\n- Ada Lovelace\n",
+    );
+
+    let create = api
+        .requests()
+        .into_iter()
+        .find(|r| r.method == "POST" && r.path == "/v2/transcript")
+        .expect("create request");
+    let body: serde_json::Value = serde_json::from_str(&create.body).expect("create body");
+    assert_eq!(body["auto_chapters"], true);
+    assert_eq!(body["auto_highlights"], true);
+    assert_eq!(body["entity_detection"], true);
+}
+
+#[test]
+fn unknown_preset_exits_3() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let home = set_temp_home(&mut cmd);
+    let dir = home.path().join(".assemblyai-cli");
+    std::fs::create_dir_all(&dir).expect("create config dir");
+    std::fs::write(
+        dir.join("config.json"),
+        r#"{"apiKey":"dummy","presets":{"interview":{"speakerLabels":true}}}"#,
+    )
+    .expect("write config");
+    cmd.args(["transcribe", "https://example.com/a.mp3", "--preset", "podcast"]);
+    cmd.assert()
+        .failure()
+        .code(3)
+        .stderr(predicate::str::contains("unknown preset \"podcast\" (available: interview)"));
+}
+
+#[test]
+fn preset_applies_config_bundle() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let dir = home.path().join(".assemblyai-cli");
+    std::fs::create_dir_all(&dir).expect("create config dir");
+    std::fs::write(
+        dir.join("config.json"),
+        r#"{"presets":{"interview":{"speakerLabels":true,"format":"txt"}}}"#,
+    )
+    .expect("write config");
+    let input = temp_audio_file(&home);
+    cmd.arg(&input).args(["--poll-interval-seconds", "0", "--preset", "interview"]);
+    cmd.assert()
+        .success()
+        .stdout("Speaker A: Hello world.\nSpeaker B: Good bye.\n");
+}
+
+#[test]
+fn by_extension_rule_selects_format_and_skips_extraction() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let dir = home.path().join(".assemblyai-cli");
+    std::fs::create_dir_all(&dir).expect("create config dir");
+    std::fs::write(
+        dir.join("config.json"),
+        r#"{"byExtension":{"mp4":{"extractAudio":false,"format":"srt","speakerLabels":true}}}"#,
+    )
+    .expect("write config");
+    let input = home.path().join("clip.mp4");
+    std::fs::write(&input, b"fake video").expect("write video");
+    cmd.arg(&input).args(["--poll-interval-seconds", "0"]);
+    cmd.assert().success().stdout(
+        "1\n00:00:00,000 --> 00:00:00,900\nSpeaker A: Hello world.\n\n2\n00:00:02,000 --> 00:00:02,700\nSpeaker B: Good bye.\n\n",
+    );
+
+    let upload = api
+        .requests()
+        .into_iter()
+        .find(|r| r.path == "/v2/upload")
+        .expect("upload request");
+    assert_eq!(upload.body, "fake video");
+}
+
+#[test]
+fn eval_scores_transcript_json_against_reference() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let home = set_temp_home(&mut cmd);
+    let hypothesis = home.path().join("hyp.json");
+    std::fs::write(&hypothesis, sample_transcript().to_string()).expect("write hypothesis");
+    let reference = home.path().join("ref.txt");
+    std::fs::write(&reference, "Hello, world! Goodbye.\n").expect("write reference");
+
+    cmd.arg("eval").arg(&hypothesis).arg("--reference").arg(&reference);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("WER: 66.67% (S=1 D=0 I=1, N=3)\n"))
+        .stdout(predicate::str::contains("REF:  hello world ***  GOODBYE\nHYP:  hello world GOOD BYE\nEVAL:             I    S\n"));
+}
+
+#[test]
+fn grep_searches_files_ids_and_the_cache() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_cmd(&api, "grep");
+    cmd.args(["-i", "GOOD\\s+bye", "tr_1"]);
+    cmd.assert().success().stdout("tr_1:[00:00:02] Speaker B: Good bye.\n");
+    assert_eq!(api.requests().last().map(|r| r.path.as_str()), Some("/v2/transcript/tr_1"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    cmd.env("HOME", home.path())
+        .env("USERPROFILE", home.path())
+        .env_remove("ASSEMBLYAI_API_KEY")
+        .args(["grep", "world"]);
+    cmd.assert().success().stdout("tr_1:[00:00:00] Speaker A: Hello world.\n");
+
+    let file = home.path().join("call.json");
+    std::fs::write(&file, sample_transcript().to_string()).expect("write transcript");
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    cmd.env("HOME", home.path()).env("USERPROFILE", home.path()).env_remove("ASSEMBLYAI_API_KEY");
+    cmd.arg("grep").arg("bye").arg(&file);
+    cmd.assert()
+        .success()
+        .stdout(format!("{}:[00:00:02] Speaker B: Good bye.\n", file.display()));
+
+    let (mut cmd, _home) = mock_cmd(&api, "grep");
+    cmd.args(["(unclosed", "tr_1"]);
+    cmd.assert().code(2).stderr(predicate::str::contains("invalid pattern"));
+}
+
+#[test]
+fn index_searches_cached_transcripts() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_cmd(&api, "grep");
+    cmd.args(["hello", "tr_1"]);
+    cmd.assert().success();
+
+    let run = |args: &[&str]| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+        cmd.env("HOME", home.path())
+            .env("USERPROFILE", home.path())
+            .env_remove("ASSEMBLYAI_API_KEY")
+            .args(args);
+        cmd.assert()
+    };
+
+    run(&["index", "search", "bye"])
+        .failure()
+        .stderr(predicate::str::contains("run `assemblyai-cli index build` first"));
+    run(&["index", "build"])
+        .success()
+        .stderr(predicate::str::contains("indexed 1 transcripts (2 sentences)"));
+    run(&["index", "search", "bye good"]).success().stdout("tr_1:[00:00:02] Speaker B: Good bye.\n");
+    run(&["index", "search", "wor*"]).success().stdout("tr_1:[00:00:00] Speaker A: Hello world.\n");
+    run(&["index", "search", "hello NOT world"]).success().stdout("");
+    run(&["index", "search", "\"unbalanced"])
+        .code(2)
+        .stderr(predicate::str::contains("invalid search query"));
+}
+
+#[test]
+fn compare_models_submits_each_model_once() {
+    let mut transcript = sample_transcript();
+    transcript["confidence"] = serde_json::json!(0.9);
+    let api = MockApi::start(completed_transcript_routes(transcript));
+    let (mut cmd, home) = mock_cmd(&api, "compare-models");
+    let input = temp_audio_file(&home);
+    cmd.arg(&input)
+        .args(["--speech-model", "best,nano", "--poll-interval-seconds", "0"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "MODEL  CONFIDENCE   WORDS  WER (vs best)\nbest   0.900            4  0.00%\nnano   0.900            4  0.00%\n\n== best vs nano ==\n",
+        ));
+
+    let requests = api.requests();
+    assert_eq!(requests.iter().filter(|r| r.path == "/v2/upload").count(), 1);
+    let mut models: Vec<String> = requests
+        .iter()
+        .filter(|r| r.method == "POST" && r.path == "/v2/transcript")
+        .map(|r| {
+            let body: serde_json::Value = serde_json::from_str(&r.body).expect("create body");
+            body["speech_model"].as_str().unwrap_or_default().to_string()
+        })
+        .collect();
+    models.sort();
+    assert_eq!(models, vec!["best".to_string(), "nano".to_string()]);
+}
+
+#[test]
+fn compare_models_requires_two_models() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    set_temp_home(&mut cmd);
+    cmd.args(["compare-models", "https://example.com/a.mp3", "--speech-model", "best,best"]);
+    cmd.assert().failure().code(2);
+}
+
+#[test]
+fn reuploads_only_changed_content() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let home = tempfile::tempdir().unwrap();
+    let input = temp_audio_file(&home);
+    let run = |extra: &[&str]| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+        cmd.env("HOME", home.path()).env("USERPROFILE", home.path());
+        cmd.env("ASSEMBLYAI_API_KEY", "dummy").env("ASSEMBLYAI_BASE_URL", &api.base_url);
+        cmd.arg("transcribe").arg(&input).args(["--poll-interval-seconds", "0"]).args(extra);
+        cmd.assert().success()
+    };
+    let uploads = || api.requests().iter().filter(|r| r.path == "/v2/upload").count();
+
+    run(&[]);
+    run(&["--speech-model", "nano"]).stderr(predicate::str::contains("reusing the upload of"));
+    assert_eq!(uploads(), 1);
+    let audio_urls: Vec<String> = api
+        .requests()
+        .iter()
+        .filter(|r| r.method == "POST" && r.path == "/v2/transcript")
+        .map(|r| serde_json::from_str::<serde_json::Value>(&r.body).unwrap()["audio_url"].to_string())
+        .collect();
+    assert_eq!(audio_urls, ["\"https://cdn.example/upload/1\"", "\"https://cdn.example/upload/1\""]);
+
+    run(&["--no-upload-reuse"]);
+    assert_eq!(uploads(), 2);
+    std::fs::write(&input, b"edited audio").unwrap();
+    run(&[]);
+    assert_eq!(uploads(), 3);
+
+    let other = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    cmd.env("HOME", home.path()).env("USERPROFILE", home.path());
+    cmd.env("ASSEMBLYAI_API_KEY", "dummy").env("ASSEMBLYAI_BASE_URL", &other.base_url);
+    cmd.arg("transcribe").arg(&input).args(["--poll-interval-seconds", "0"]);
+    cmd.assert().success().stderr(predicate::str::contains("reusing the upload of").not());
+    assert_eq!(other.requests().iter().filter(|r| r.path == "/v2/upload").count(), 1);
+    let index = std::fs::read_to_string(home.path().join(".assemblyai-cli").join("uploads.json")).unwrap();
+    assert!(!index.contains("dummy"), "the API key itself is never stored");
+}
+
+#[test]
+fn upload_prints_the_upload_url() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_cmd(&api, "upload");
+    let input = temp_audio_file(&home);
+    cmd.arg(&input);
+    cmd.assert().success().stdout("https://cdn.example/upload/1\n");
+    let upload = &api.requests()[0];
+    assert_eq!(upload.body, "fake audio");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    cmd.env("HOME", home.path()).env("USERPROFILE", home.path());
+    cmd.env("ASSEMBLYAI_API_KEY", "dummy").env("ASSEMBLYAI_BASE_URL", &api.base_url);
+    cmd.arg("upload").arg(&input).args(["--no-upload-reuse", "--verify", "--json"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let uploaded: serde_json::Value = serde_json::from_slice(&output).expect("upload json");
+    assert_eq!(uploaded["uploadUrl"], "https://cdn.example/upload/1");
+    assert_eq!(uploaded["bytes"], 10);
+    assert_eq!(uploaded["sha256"], "72401f193251f177a310936253acb57e91e29d2aa582093974351e65e267bb32");
+    assert_eq!(uploaded["input"], input.to_str().unwrap());
+    assert_eq!(api.requests().iter().filter(|r| r.path == "/v2/upload").count(), 2);
+
+    let (mut cmd, home) = mock_cmd(&api, "upload");
+    cmd.arg(home.path().join("missing.mp3"));
+    cmd.assert().failure().code(2);
+}
+
+#[test]
+fn verify_checks_what_the_upload_response_reports() {
+    let sha256 = "72401f193251f177a310936253acb57e91e29d2aa582093974351e65e267bb32";
+    let stored = |response: &str| {
+        let mut routes = completed_transcript_routes(sample_transcript());
+        routes[0].3 = response.to_string();
+        MockApi::start(routes)
+    };
+
+    let api = stored(&format!(r#"{{"upload_url":"https://cdn.example/upload/1","sha256":"{sha256}","size":10}}"#));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home)).args(["--verify", "--poll-interval-seconds", "0"]);
+    cmd.assert().success().stdout(predicate::str::contains("Hello world."));
+    assert_eq!(api.requests().iter().filter(|r| r.method == "POST" && r.path == "/v2/transcript").count(), 1);
+
+    let cases = [
+        (
+            format!(r#"{{"upload_url":"https://cdn.example/upload/1","sha256":"{}","size":10}}"#, "0".repeat(64)),
+            format!("sent SHA-256 {sha256}, the API stored SHA-256 {}; not transcribed", "0".repeat(64)),
+        ),
+        (
+            r#"{"upload_url":"https://cdn.example/upload/1","size":9}"#.to_string(),
+            "sent 10 bytes, the API stored 9 bytes; not transcribed".to_string(),
+        ),
+    ];
+    for (response, message) in cases {
+        let api = stored(&response);
+        let (mut cmd, home) = mock_transcribe_cmd(&api);
+        cmd.arg(temp_audio_file(&home)).args(["--verify", "--poll-interval-seconds", "0"]);
+        cmd.assert().failure().code(1).stderr(predicate::str::contains(message.as_str()));
+        assert!(!api.requests().iter().any(|r| r.method == "POST" && r.path == "/v2/transcript"));
+
+        let (mut cmd, home) = mock_cmd(&api, "upload");
+        cmd.arg(temp_audio_file(&home)).arg("--verify");
+        cmd.assert().failure().code(1).stderr(predicate::str::contains(message.as_str()));
+
+        let (mut cmd, home) = mock_cmd(&api, "upload");
+        cmd.arg(temp_audio_file(&home));
+        cmd.assert().success().stdout("https://cdn.example/upload/1\n");
+    }
+}
+
+#[test]
+fn from_upload_url_transcribes_without_uploading() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, _home) = mock_transcribe_cmd(&api);
+    cmd.args(["--from-upload-url", "https://cdn.example/upload/7", "--poll-interval-seconds", "0"]);
+    cmd.assert().success().stdout(predicate::str::contains("Hello world."));
+
+    let (mut cmd, _home) = mock_transcribe_cmd(&api);
+    cmd.args(["call.mp3", "--from-upload-url", "https://cdn.example/upload/7", "--format", "otr", "--poll-interval-seconds", "0"]);
+    cmd.assert().success().stdout(predicate::str::contains(r#""media":"call.mp3""#));
+
+    let requests = api.requests();
+    assert_eq!(requests.iter().filter(|r| r.path == "/v2/upload").count(), 0);
+    for create in requests.iter().filter(|r| r.method == "POST" && r.path == "/v2/transcript") {
+        let body: serde_json::Value = serde_json::from_str(&create.body).expect("create body");
+        assert_eq!(body["audio_url"], "https://cdn.example/upload/7");
+    }
+
+    let (mut cmd, _home) = mock_transcribe_cmd(&api);
+    cmd.args(["--from-upload-url", "upload/7"]);
+    cmd.assert().failure().code(2).stderr(predicate::str::contains("invalid input URL: upload/7"));
+}
+
+#[test]
+fn bench_times_each_run_of_each_model() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_cmd(&api, "bench");
+    let input = temp_audio_file(&home);
+    cmd.arg(&input)
+        .args(["--runs", "2", "--models", "best,nano", "--poll-interval-seconds", "0", "--json"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).expect("bench json");
+    let runs: Vec<(u64, &str)> = report["runs"]
+        .as_array()
+        .expect("runs")
+        .iter()
+        .map(|run| (run["run"].as_u64().unwrap(), run["model"].as_str().unwrap()))
+        .collect();
+    assert_eq!(runs, vec![(1, "best"), (1, "nano"), (2, "best"), (2, "nano")]);
+    assert_eq!(report["summary"][1]["model"], "nano");
+    assert_eq!(report["summary"][1]["runs"], 2);
+
+    let requests = api.requests();
+    assert_eq!(requests.iter().filter(|r| r.path == "/v2/upload").count(), 4);
+    assert_eq!(requests.iter().filter(|r| r.method == "POST" && r.path == "/v2/transcript").count(), 4);
+
+    let (mut cmd, _home) = mock_cmd(&api, "bench");
+    cmd.arg(&input).args(["--runs", "1", "--poll-interval-seconds", "0"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("RUN   MODEL    UPLOAD     QUEUE  PROCESSING     TOTAL\n1     best  ").and(predicate::str::contains("\nmean  best  ")));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    set_temp_home(&mut cmd);
+    cmd.args(["bench", "https://example.com/a.mp3", "--runs", "0"]);
+    cmd.assert().failure().code(2);
+}
+
+#[test]
+fn post_replace_rules_rewrite_output() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let dir = home.path().join(".assemblyai-cli");
+    std::fs::create_dir_all(&dir).expect("create config dir");
+    std::fs::write(
+        dir.join("config.json"),
+        r#"{"postReplace":[{"from":"world","to":"World"},{"from":"^Good (\\w+)","to":"Good-$1","regex":true}]}"#,
+    )
+    .expect("write config");
+    let input = temp_audio_file(&home);
+    cmd.arg(&input)
+        .args(["--poll-interval-seconds", "0", "--speaker-labels"]);
+    cmd.assert()
+        .success()
+        .stdout("Speaker A: Hello World.\nSpeaker B: Good-bye.\n");
+}
+
+#[test]
+fn merge_short_utterances_folds_interjections_into_the_speakers_turn() {
+    let transcript = serde_json::json!({
+        "id": "tr_1",
+        "status": "completed",
+        "text": "We shipped it. Yeah. And it works.",
+        "utterances": [
+            {"speaker": "A", "text": "We shipped it.", "start": 0, "end": 2000},
+            {"speaker": "B", "text": "Yeah.", "start": 2000, "end": 2300},
+            {"speaker": "A", "text": "And it works.", "start": 2300, "end": 4000},
+            {"speaker": "B", "text": "Great.", "start": 5000, "end": 7000}
+        ]
+    });
+    let api = MockApi::start(completed_transcript_routes(transcript));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.arg(&input)
+        .args(["--poll-interval-seconds", "0", "--speaker-labels", "--merge-short-utterances", "1500ms"]);
+    cmd.assert()
+        .success()
+        .stdout("Speaker A: We shipped it. Yeah. And it works.\nSpeaker B: Great.\n");
+
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home)).args(["--merge-short-utterances", "1500ms"]);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--merge-short-utterances requires --speaker-labels"));
+}
+
+#[test]
+fn identify_speakers_names_speakers_and_remembers_them_in_the_sidecar() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    let out = home.path().join("out.txt");
+    cmd.arg(&input)
+        .args(["--poll-interval-seconds", "0", "--speaker-labels", "--identify-speakers", "--output"])
+        .arg(&out)
+        .write_stdin("Alice\n\n");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Speaker A at 00:00:00: \"Hello world.\""))
+        .stderr(predicate::str::contains("Name for speaker B"));
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), "Speaker Alice: Hello world.\nSpeaker B: Good bye.\n");
+    let sidecar: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(home.path().join("out.meta.json")).unwrap()).unwrap();
+    assert_eq!(sidecar["speakers"], serde_json::json!({"A": "Alice"}));
+
+    let (mut cmd, _other) = mock_transcribe_cmd(&api);
+    cmd.arg(&input)
+        .args(["--poll-interval-seconds", "0", "--speaker-labels", "--identify-speakers", "--output"])
+        .arg(&out)
+        .write_stdin("Bob\n");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Name for speaker A").not());
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), "Speaker Alice: Hello world.\nSpeaker Bob: Good bye.\n");
+
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home)).arg("--identify-speakers");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--identify-speakers requires --speaker-labels"));
+}
+
+#[test]
+fn identify_speakers_cancels_at_end_of_input_or_ctrl_c() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home))
+        .args(["--poll-interval-seconds", "0", "--speaker-labels", "--identify-speakers"])
+        .write_stdin("");
+    cmd.assert().code(130).stdout("");
+
+    let home = tempfile::tempdir().unwrap();
+    std::fs::write(home.path().join("a.mp3"), b"fake audio").unwrap();
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"))
+        .current_dir(home.path())
+        .env("HOME", home.path())
+        .env("USERPROFILE", home.path())
+        .env("ASSEMBLYAI_API_KEY", "dummy")
+        .env("ASSEMBLYAI_BASE_URL", &api.base_url)
+        .args(["transcribe", "a.mp3", "--poll-interval-seconds", "0", "--speaker-labels", "--identify-speakers"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stderr = child.stderr.take().unwrap();
+    let (prompted, prompt) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mut seen = Vec::new();
+        let mut buf = [0; 256];
+        while let Ok(len @ 1..) = std::io::Read::read(&mut stderr, &mut buf) {
+            seen.extend_from_slice(&buf[..len]);
+            if String::from_utf8_lossy(&seen).contains("Name for speaker A") {
+                let _ = prompted.send(());
+            }
+        }
+    });
+    prompt.recv_timeout(std::time::Duration::from_secs(10)).expect("never prompted for a name");
+    let interrupt = std::process::Command::new("kill").args(["-INT", &child.id().to_string()]).status().unwrap();
+    assert!(interrupt.success());
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+        if std::time::Instant::now() > deadline {
+            child.kill().unwrap();
+            panic!("Ctrl-C at the prompt didn't cancel the run");
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    };
+    assert_eq!(status.code(), Some(130));
+}
+
+#[test]
+fn paragraph_gap_splits_text_output_at_long_silences() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home))
+        .args(["--poll-interval-seconds", "0", "--paragraph-gap", "1s"]);
+    cmd.assert().success().stdout("Hello world.\n\nGood bye.");
+
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home))
+        .args(["--poll-interval-seconds", "0", "--speaker-labels", "--paragraph-gap", "1s"]);
+    cmd.assert().success().stdout("Speaker A: Hello world.\n\nSpeaker B: Good bye.\n");
+}
+
+#[test]
+fn mark_uncertain_flags_low_confidence_words() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home))
+        .args(["--poll-interval-seconds", "0", "--speaker-labels", "--mark-uncertain", "0.6"]);
+    cmd.assert()
+        .success()
+        .stdout("Speaker A: Hello world.\nSpeaker B: Good [?bye.]\n");
+
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home))
+        .args(["--poll-interval-seconds", "0", "--mark-uncertain", "0.95"]);
+    cmd.assert().success().stdout("Hello [?world.] Good [?bye.]");
+}
+
+#[test]
+fn redact_terms_file_masks_terms_in_output() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let terms = home.path().join("secrets.txt");
+    std::fs::write(&terms, "# codenames\nWORLD\n").expect("write terms");
+    let input = temp_audio_file(&home);
+    cmd.arg(&input)
+        .args(["--poll-interval-seconds", "0", "--redact-style", "tag", "--redact-terms-file"])
+        .arg(&terms);
+    cmd.assert().success().stdout("Hello [REDACTED]. Good bye.");
+
+    // The raw-document formats would pass the term through in clear text, so they refuse to run.
+    for format in ["json", "exec:cat"] {
+        let (mut cmd, home) = mock_transcribe_cmd(&api);
+        cmd.arg(temp_audio_file(&home))
+            .args(["--poll-interval-seconds", "0", "--format", format, "--redact-terms-file"])
+            .arg(&terms);
+        let output = cmd.assert().code(2).get_output().clone();
+        assert!(!String::from_utf8_lossy(&output.stdout).to_lowercase().contains("world"));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be combined with --redact-terms-file"));
+    }
+}
+
+#[test]
+fn speaker_style_template_changes_prefix() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.arg(&input).args([
+        "--poll-interval-seconds",
+        "0",
+        "--speaker-labels",
+        "--speaker-style",
+        "SPEAKER_{index}: ",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout("SPEAKER_00: Hello world.\nSPEAKER_01: Good bye.\n");
+}
+
+#[test]
+fn encoding_and_line_endings_apply_to_output_file() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    let output = home.path().join("out.txt");
+    cmd.arg(&input)
+        .args(["--poll-interval-seconds", "0", "--speaker-labels", "--encoding", "utf-8-bom", "--line-endings", "crlf", "--output"])
+        .arg(&output);
+    cmd.assert().success();
+    let bytes = std::fs::read(&output).expect("read output");
+    assert_eq!(bytes, b"\xEF\xBB\xBFSpeaker A: Hello world.\r\nSpeaker B: Good bye.\r\n".to_vec());
+}
+
+#[test]
+fn srt_start_index_offsets_cue_numbers() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.arg(&input).args([
+        "--poll-interval-seconds",
+        "0",
+        "--speaker-labels",
+        "--format",
+        "srt",
+        "--srt-start-index",
+        "41",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("41\n00:00:00,000 --> 00:00:00,900\n"))
+        .stdout(predicate::str::contains("\n\n42\n00:00:02,000 --> 00:00:02,700\n"));
+}
+
+#[test]
+fn split_output_every_writes_rebased_parts() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    let output = home.path().join("out.srt");
+    cmd.arg(&input)
+        .args([
+            "--poll-interval-seconds",
+            "0",
+            "--speaker-labels",
+            "--format",
+            "srt",
+            "--split-output-every",
+            "1s",
+            "--output",
+        ])
+        .arg(&output);
+    cmd.assert().success();
+
+    assert!(!output.exists());
+    let first = std::fs::read_to_string(home.path().join("out.part01.srt")).expect("read part 1");
+    assert_eq!(first, "1\n00:00:00,000 --> 00:00:00,900\nSpeaker A: Hello world.\n\n");
+    let second = std::fs::read_to_string(home.path().join("out.part02.srt")).expect("read part 2");
+    assert_eq!(second, "1\n00:00:00,000 --> 00:00:00,700\nSpeaker B: Good bye.\n\n");
+}
+
+#[test]
+fn split_output_by_chapter_requires_auto_chapters() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.arg(&input)
+        .args(["--format", "srt", "--split-output-by", "chapter", "--output"])
+        .arg(home.path().join("out.srt"));
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--auto-chapters"));
+}
+
+#[test]
+fn token_prints_temporary_realtime_token() {
+    let api = MockApi::start(vec![("POST", "/v2/realtime/token", 200, r#"{"token":"tmp_123"}"#.to_string())]);
+    let (mut cmd, _home) = mock_cmd(&api, "token");
+    cmd.args(["--expires-in", "600"]);
+    cmd.assert().success().stdout("tmp_123\n");
+
+    let requests = api.requests();
+    assert_eq!(requests.len(), 1);
+    let body: serde_json::Value = serde_json::from_str(&requests[0].body).expect("json body");
+    assert_eq!(body["expires_in"], 600);
+}
+
+#[test]
+fn token_rejects_out_of_range_lifetime() {
+    let api = MockApi::start(Vec::new());
+    let (mut cmd, _home) = mock_cmd(&api, "token");
+    cmd.args(["--expires-in", "10"]);
+    cmd.assert().code(2).stderr(predicate::str::contains("60..=360000"));
+    assert!(api.requests().is_empty());
+}
+
+#[test]
+fn keyterms_prompt_is_sent_for_slam_1() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.arg(&input).args([
+        "--poll-interval-seconds",
+        "0",
+        "--speech-model",
+        "slam-1",
+        "--keyterms-prompt",
+        "Kubernetes, Jane Doe",
+    ]);
+    cmd.assert().success();
+
+    let create = api
+        .requests()
+        .into_iter()
+        .find(|r| r.method == "POST" && r.path == "/v2/transcript")
+        .expect("create request");
+    let body: serde_json::Value = serde_json::from_str(&create.body).expect("create body");
+    assert_eq!(body["keyterms_prompt"], serde_json::json!(["Kubernetes", "Jane Doe"]));
+    assert!(body.get("word_boost").is_none());
+}
+
+#[test]
+fn keyterms_prompt_requires_slam_1() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.arg(&input).args(["--keyterms-prompt", "Kubernetes"]);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--keyterms-prompt is not supported by speech model best"));
+    assert!(api.requests().is_empty());
+}
+
+#[test]
+fn language_feature_support_is_checked_before_upload() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.arg(&input)
+        .args(["--no-language-detection", "--language", "ja", "--auto-chapters"]);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--auto-chapters is not supported for language ja"));
+    assert!(api.requests().is_empty());
+}
+
+#[test]
+fn language_model_support_is_checked_before_upload() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.arg(&input).args([
+        "--no-language-detection",
+        "--language",
+        "de",
+        "--speech-model",
+        "slam-1",
+    ]);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("language de is not supported by speech model slam-1"));
+    assert!(api.requests().is_empty());
+}
+
+#[test]
+fn languages_filters_by_feature_without_api_key() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let _home = set_temp_home(&mut cmd);
+    cmd.env_remove("ASSEMBLYAI_API_KEY").env_remove("ASSEMBLY_AI_KEY");
+    cmd.args(["languages", "--feature", "speaker-labels"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("ru     Russian"))
+        .stdout(predicate::str::contains("Swedish").not());
+}
+
+#[test]
+fn list_formats_enumerates_formatters_without_input() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    let _home = set_temp_home(&mut cmd);
+    cmd.env_remove("ASSEMBLYAI_API_KEY").env_remove("ASSEMBLY_AI_KEY");
+    cmd.args(["transcribe", "--list-formats"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("text  "))
+        .stdout(predicate::str::contains("\nsrt-dual  "))
+        .stdout(predicate::str::contains("\nparquet  "));
+}
+
+#[test]
+fn detected_language_is_reported_and_checked_against_expected() {
+    let mut transcript = sample_transcript();
+    transcript["language_code"] = serde_json::json!("es");
+    transcript["language_confidence"] = serde_json::json!(0.87);
+    let api = MockApi::start(completed_transcript_routes(transcript));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    let output = home.path().join("out.txt");
+    cmd.arg(&input)
+        .args(["--poll-interval-seconds", "0", "--expect-language", "en,de", "--output"])
+        .arg(&output);
+    cmd.assert()
+        .code(7)
+        .stderr(predicate::str::contains("detected language: es (confidence 0.87)"))
+        .stderr(predicate::str::contains("detected language es is not one of the expected languages (en, de)"));
+    assert!(!output.exists());
+}
+
+#[test]
+fn expect_language_requires_language_detection() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.arg(&input)
+        .args(["--no-language-detection", "--language", "en", "--expect-language", "en"]);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--expect-language requires language detection"));
+    assert!(api.requests().is_empty());
+}
+
+fn multichannel_transcript() -> serde_json::Value {
+    let mut transcript = sample_transcript();
+    for (idx, channel) in ["1", "1", "2", "2"].into_iter().enumerate() {
+        transcript["words"][idx]["speaker"] = serde_json::json!(channel);
+        transcript["words"][idx]["channel"] = serde_json::json!(channel);
+    }
+    for (idx, channel) in ["1", "2"].into_iter().enumerate() {
+        transcript["utterances"][idx]["speaker"] = serde_json::json!(channel);
+        transcript["utterances"][idx]["channel"] = serde_json::json!(channel);
+    }
+    transcript
+}
+
+#[test]
+fn channel_names_label_multichannel_utterances() {
+    let api = MockApi::start(completed_transcript_routes(multichannel_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.arg(&input).args([
+        "--poll-interval-seconds",
+        "0",
+        "--channel-names",
+        "1=Agent,2=Customer",
+        "--speaker-style",
+        "{name}: ",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout("Agent: Hello world.\nCustomer: Good bye.\n");
+}
+
+#[test]
+fn split_by_channel_writes_one_file_per_channel() {
+    let api = MockApi::start(completed_transcript_routes(multichannel_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    let output = home.path().join("call.srt");
+    cmd.arg(&input)
+        .args([
+            "--poll-interval-seconds",
+            "0",
+            "--format",
+            "srt",
+            "--channel-names",
+            "1=Agent",
+            "--no-speaker-prefix",
+            "--split-by-channel",
+            "--output",
+        ])
+        .arg(&output);
+    cmd.assert().success();
+
+    assert!(!output.exists());
+    let agent = std::fs::read_to_string(home.path().join("call.Agent.srt")).expect("agent file");
+    assert_eq!(agent, "1\n00:00:00,000 --> 00:00:00,900\nHello world.\n\n");
+    let other = std::fs::read_to_string(home.path().join("call.2.srt")).expect("channel 2 file");
+    assert_eq!(other, "1\n00:00:02,000 --> 00:00:02,700\nGood bye.\n\n");
+}
+
+#[test]
+fn interruptions_report_counts_overlaps_between_channels() {
+    let mut transcript = multichannel_transcript();
+    transcript["utterances"][1]["start"] = 300.into();
+    let api = MockApi::start(completed_transcript_routes(transcript));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home)).args([
+        "--poll-interval-seconds",
+        "0",
+        "--channel-names",
+        "1=Agent,2=Customer",
+        "--report",
+        "interruptions=200ms",
+    ]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Customer interrupts Agent  0.6s"))
+        .stderr(predicate::str::contains("Interruptions\n  Customer -> Agent  1\n"));
+
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home)).args(["--no-multichannel", "--report", "interruptions"]);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("report interruptions requires --multichannel"));
+}
+
+#[test]
+fn split_by_channel_requires_multichannel() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.arg(&input)
+        .args(["--no-multichannel", "--split-by-channel", "--output"])
+        .arg(home.path().join("out.txt"));
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--split-by-channel requires --multichannel"));
+    assert!(api.requests().is_empty());
+}
+
+/// Installs a shell script as `name` in a `bin` dir under `home` and returns a PATH that finds it first.
+#[cfg(unix)]
+fn fake_tool_path(home: &tempfile::TempDir, name: &str, script: &str) -> String {
+    use std::os::unix::fs::PermissionsExt;
+
+    let bin = home.path().join("bin");
+    std::fs::create_dir_all(&bin).expect("bin dir");
+    let tool = bin.join(name);
+    std::fs::write(&tool, format!("#!/bin/sh\n{script}")).expect("write fake tool");
+    std::fs::set_permissions(&tool, std::fs::Permissions::from_mode(0o755)).expect("chmod");
+    format!("{}:{}", bin.display(), std::env::var("PATH").unwrap_or_default())
+}
+
+#[cfg(unix)]
+#[test]
+fn exec_format_pipes_transcript_json_through_external_formatter() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    let output = home.path().join("out.custom");
+
+    let path = fake_tool_path(&home, "my-formatter", "echo \"$ASSEMBLYAI_TRANSCRIPT_ID $1\"\ngrep -c '\"Good bye.\"'\n");
+    cmd.env("PATH", &path)
+        .arg(&input)
+        .args(["--format", "exec:my-formatter --flag", "--output"])
+        .arg(&output);
+    cmd.assert().success();
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), "tr_1 --flag\n1\n");
+
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.env("PATH", fake_tool_path(&home, "broken-formatter", "exit 3\n"))
+        .arg(&input)
+        .args(["--format", "exec:broken-formatter"]);
+    cmd.assert()
+        .code(1)
+        .stderr(predicate::str::contains("formatter \"broken-formatter\" failed: exited with exit status: 3"));
+}
+
+#[cfg(unix)]
+#[test]
+fn audio_profile_filters_audio_with_ffmpeg_before_upload() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+
+    let args_log = home.path().join("ffmpeg-args");
+    let script = format!(
+        "echo \"$@\" > '{}'\nfor last; do :; done\nprintf 'filtered' > \"$last\"\n",
+        args_log.display()
+    );
+
+    cmd.env("PATH", fake_tool_path(&home, "ffmpeg", &script))
+        .arg(&input)
+        .args(["--poll-interval-seconds", "0", "--audio-profile", "phone"]);
+    cmd.assert().success();
+
+    let args = std::fs::read_to_string(&args_log).expect("ffmpeg was run");
+    assert!(args.contains("-af highpass=f=200,lowpass=f=3400,loudnorm"));
+    let upload = api
+        .requests()
+        .into_iter()
+        .find(|r| r.path == "/v2/upload")
+        .expect("upload request");
+    assert_eq!(upload.body, "filtered");
+}
+
+#[cfg(unix)]
+#[test]
+fn temp_dir_holds_intermediates_and_is_cleaned_unless_kept() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    let scratch = home.path().join("scratch");
+    let args_log = home.path().join("ffmpeg-args");
+    let script = format!(
+        "echo \"$@\" > '{}'\nfor last; do :; done\nprintf 'filtered' > \"$last\"\n",
+        args_log.display()
+    );
+    let path = fake_tool_path(&home, "ffmpeg", &script);
+
+    cmd.env("PATH", &path).arg(&input).args(["--audio-profile", "phone", "--temp-dir"]).arg(&scratch);
+    cmd.assert().success();
+    let args = std::fs::read_to_string(&args_log).expect("ffmpeg was run");
+    assert!(args.trim_end().ends_with(".mp3") && args.contains(&scratch.display().to_string()), "{args}");
+    assert_eq!(std::fs::read_dir(&scratch).unwrap().count(), 0);
+
+    let (mut cmd, _home) = mock_transcribe_cmd(&api);
+    cmd.env("PATH", &path).arg(&input).args(["--audio-profile", "phone", "--keep-temp", "--temp-dir"]).arg(&scratch);
+    cmd.assert().success().stderr(predicate::str::contains("keeping temporary file"));
+    let kept: Vec<_> = std::fs::read_dir(&scratch).unwrap().map(|entry| entry.unwrap().path()).collect();
+    assert_eq!(kept.len(), 1);
+    assert_eq!(std::fs::read(&kept[0]).unwrap(), b"filtered");
+}
+
+#[test]
+fn audio_profile_requires_local_input() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, _home) = mock_transcribe_cmd(&api);
+    cmd.args(["https://example.com/call.mp3", "--audio-profile", "noisy"]);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--audio-profile requires a local input file"));
+    assert!(api.requests().is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn video_only_media_is_rejected_before_upload() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    let script = r#"echo '{"streams":[{"codec_type":"video"}],"format":{"duration":"3.0"}}'"#;
+    cmd.env("PATH", fake_tool_path(&home, "ffprobe", script)).arg(&input);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("is not usable media: video only, no audio stream"));
+    assert!(api.requests().is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn skip_validation_uploads_without_probing() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.env("PATH", fake_tool_path(&home, "ffprobe", "exit 1"))
+        .arg(&input)
+        .args(["--poll-interval-seconds", "0", "--skip-validation"]);
+    cmd.assert().success();
+}
+
+#[test]
+fn upload_size_limit_aborts_or_warns() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.arg(&input).args(["--max-upload-bytes", "5"]);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("file is 10 bytes, over the 5-byte upload limit"));
+    assert!(api.requests().is_empty());
+
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.arg(&input)
+        .args(["--poll-interval-seconds", "0", "--max-upload-bytes", "5", "--on-limit", "warn"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("warning: file is 10 bytes"));
+}
+
+#[cfg(unix)]
+#[test]
+fn duration_limit_is_checked_with_ffprobe() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    let script = r#"echo '{"streams":[{"codec_type":"audio"}],"format":{"duration":"7200.0"}}'"#;
+    cmd.env("PATH", fake_tool_path(&home, "ffprobe", script))
+        .arg(&input)
+        .args(["--max-duration-minutes", "90"]);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("recording is 120.0 minutes, over the 90-minute limit"));
+    assert!(api.requests().is_empty());
+}
+
+#[test]
+fn history_records_runs_and_reruns_them() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    let output = home.path().join("out.txt");
+    cmd.arg(&input)
+        .args(["--poll-interval-seconds", "0", "--tag", "client-x", "--output"])
+        .arg(&output);
+    cmd.assert().success();
+
+    let history = |args: &[&str]| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+        cmd.env("HOME", home.path())
+            .env("USERPROFILE", home.path())
+            .env("ASSEMBLYAI_API_KEY", "dummy")
+            .env("ASSEMBLYAI_BASE_URL", &api.base_url)
+            .arg("history")
+            .args(args);
+        cmd.assert()
+    };
+
+    history(&[])
+        .success()
+        .stdout(predicate::str::contains("   1  "))
+        .stdout(predicate::str::contains("tr_1"))
+        .stdout(predicate::str::contains("input.mp3 -> "))
+        .stdout(predicate::str::contains("out.txt"));
+    history(&["--search", "interview"]).success().stdout("");
+    history(&["--tag", "CLIENT-X"])
+        .success()
+        .stdout(predicate::str::contains("#client-x"));
+    history(&["--tag", "client-x", "--tag", "q3"]).success().stdout("");
+
+    std::fs::remove_file(&output).expect("remove output");
+    history(&["rerun", "1"])
+        .success()
+        .stderr(predicate::str::contains("re-running #1"));
+    assert!(output.exists());
+    history(&["--search", "out.txt"])
+        .success()
+        .stdout(predicate::str::contains("   2  "));
+    history(&["rerun", "9"]).code(2).stderr(predicate::str::contains("no history entry #9"));
+}
+
+#[test]
+fn transcripts_read_by_id_are_cached_and_managed_with_cache_commands() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_cmd(&api, "eval");
+    let reference = home.path().join("ref.txt");
+    std::fs::write(&reference, "Hello world. Good bye.\n").unwrap();
+    cmd.arg("tr_1").arg("--reference").arg(&reference);
+    cmd.assert().success().stdout(predicate::str::starts_with("WER: 0.00%"));
+    assert_eq!(api.requests().len(), 1);
+
+    let run = |args: &[&str]| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+        cmd.env("HOME", home.path())
+            .env("USERPROFILE", home.path())
+            .env("ASSEMBLYAI_API_KEY", "dummy")
+            .env("ASSEMBLYAI_BASE_URL", &api.base_url)
+            .args(args);
+        cmd.assert()
+    };
+    run(&["eval", "tr_1", "--reference", reference.to_str().unwrap()])
+        .success()
+        .stdout(predicate::str::starts_with("WER: 0.00%"));
+    assert_eq!(api.requests().len(), 1, "the second read comes from the cache");
+
+    let cache_dir = config_path(&home).join("cache");
+    run(&["cache", "path"]).success().stdout(format!("{}\n", cache_dir.display()));
+    run(&["cache", "list"]).success().stdout(predicate::str::starts_with("tr_1  "));
+    run(&["cache", "size"]).success().stdout(predicate::str::starts_with("1 transcript, "));
+    run(&["cache", "clear", "--older-than", "30d"]).success().stdout("removed 0 transcripts, 0B\n");
+    run(&["cache", "clear"]).success().stdout(predicate::str::starts_with("removed 1 transcript, "));
+    assert!(!cache_dir.join("tr_1.json").exists());
+}
+
+#[test]
+fn list_pages_through_a_created_range_and_prints_totals() {
+    let page = |transcripts: serde_json::Value, prev_url: Option<&str>| {
+        serde_json::json!({"page_details": {"limit": 100, "prev_url": prev_url}, "transcripts": transcripts}).to_string()
+    };
+    let transcript = |id: &str, status: &str, created: &str| {
+        serde_json::json!({"id": id, "status": status, "created": created, "audio_url": format!("https://cdn.example/{id}.mp3")})
+    };
+    let api = MockApi::start(vec![
+        (
+            "GET",
+            "/v2/transcript?limit=100&throttled_only=true",
+            200,
+            page(
+                serde_json::json!([
+                    transcript("tr_a", "completed", "2025-04-02T08:00:00.1"),
+                    transcript("tr_b", "completed", "2025-03-20T10:15:30.5"),
+                ]),
+                Some("https://api.assemblyai.com/v2/transcript?limit=100&before_id=tr_b"),
+            ),
+        ),
+        (
+            "GET",
+            "/v2/transcript?limit=100&throttled_only=true&before_id=tr_b",
+            200,
+            page(
+                serde_json::json!([
+                    transcript("tr_c", "error", "2025-03-01T00:00:00.0"),
+                    transcript("tr_d", "completed", "2025-02-28T23:59:59.9"),
+                ]),
+                Some("https://api.assemblyai.com/v2/transcript?limit=100&before_id=tr_d"),
+            ),
+        ),
+    ]);
+    let (mut cmd, _home) = mock_cmd(&api, "list");
+    cmd.args(["--created-after", "2025-03-01", "--created-before", "2025-04-01", "--throttled-only", "--all"]);
+    cmd.assert().success().stdout(
+        "tr_b  completed   2025-03-20 10:15:30  https://cdn.example/tr_b.mp3\n\
+         tr_c  error       2025-03-01 00:00:00  https://cdn.example/tr_c.mp3\n\
+         2 transcripts (1 completed, 1 error)\n",
+    );
+
+    let queries: Vec<String> = api.requests().into_iter().map(|request| request.query).collect();
+    assert_eq!(
+        queries,
+        ["limit=100&throttled_only=true", "limit=100&throttled_only=true&before_id=tr_b"],
+        "stops once transcripts predate the range"
+    );
+}
+
+#[test]
+fn delete_removes_transcripts_created_before_a_date() {
+    let listing = serde_json::json!({
+        "page_details": {"limit": 100, "prev_url": null},
+        "transcripts": [
+            {"id": "tr_new", "status": "completed", "created": "2024-02-01T09:00:00.0", "audio_url": "https://cdn.example/new.mp3"},
+            {"id": "tr_old", "status": "completed", "created": "2023-12-01T09:00:00.0", "audio_url": "https://cdn.example/old.mp3"},
+            {"id": "tr_err", "status": "error", "created": "2023-11-01T09:00:00.0", "audio_url": "https://cdn.example/err.mp3"}
+        ]
+    });
+    let api = MockApi::start(vec![
+        ("GET", "/v2/transcript", 200, listing.to_string()),
+        ("DELETE", "/v2/transcript/tr_old", 200, r#"{"id":"tr_old","status":"completed"}"#.to_string()),
+    ]);
+    let home = tempfile::tempdir().unwrap();
+    let cache_dir = config_path(&home).join("cache");
+    std::fs::create_dir_all(&cache_dir).unwrap();
+    std::fs::write(cache_dir.join("tr_old.json"), r#"{"id":"tr_old","status":"completed"}"#).unwrap();
+    let run = |args: &[&str], stdin: &str| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+        cmd.env("HOME", home.path())
+            .env("USERPROFILE", home.path())
+            .env("LANG", "C")
+            .env("ASSEMBLYAI_API_KEY", "dummy")
+            .env("ASSEMBLYAI_BASE_URL", &api.base_url)
+            .arg("delete")
+            .args(args)
+            .write_stdin(stdin);
+        cmd.assert()
+    };
+    let deletes = || api.requests().iter().filter(|request| request.method == "DELETE").count();
+
+    run(&["--before", "2024-01-01", "--dry-run"], "").success().stdout(
+        "tr_old  completed   2023-12-01 09:00:00  https://cdn.example/old.mp3\n\
+         tr_err  error       2023-11-01 09:00:00  https://cdn.example/err.mp3\n\
+         would delete 2 transcripts (1 completed, 1 error)\n",
+    );
+    run(&["--before", "2024-01-01"], "n\n")
+        .success()
+        .stdout("")
+        .stderr(predicate::str::contains("nothing deleted"));
+    assert_eq!(deletes(), 0);
+
+    run(&["--before", "2024-01-01", "--status", "completed", "--yes"], "")
+        .success()
+        .stdout("deleted 1 transcript (1 completed)\n")
+        .stderr(predicate::str::contains("deleting 1/1: tr_old"));
+    assert!(api.requests().iter().any(|request| request.method == "GET" && request.query == "limit=100&status=completed"));
+    assert!(!cache_dir.join("tr_old.json").exists());
+
+    run(&["--before", "2024-01-01", "--yes"], "")
+        .code(9)
+        .stderr(predicate::str::contains("could not delete tr_err"))
+        .stderr(predicate::str::contains("1 of 2 transcripts could not be deleted"));
+    assert_eq!(deletes(), 3);
+}
+
+#[test]
+fn delete_without_before_uses_retention_days() {
+    let listing = serde_json::json!({
+        "page_details": {"limit": 100, "prev_url": null},
+        "transcripts": [
+            {"id": "tr_recent", "status": "completed", "created": "2999-01-01T00:00:00.0", "audio_url": "https://cdn.example/recent.mp3"},
+            {"id": "tr_expired", "status": "completed", "created": "2020-01-01T00:00:00.0", "audio_url": "https://cdn.example/expired.mp3"},
+            {"id": "tr_other_app", "status": "completed", "created": "2019-01-01T00:00:00.0", "audio_url": "https://cdn.example/other.mp3"}
+        ]
+    });
+    let api = MockApi::start(vec![
+        ("GET", "/v2/transcript", 200, listing.to_string()),
+        ("DELETE", "/v2/transcript/tr_expired", 200, r#"{"id":"tr_expired","status":"completed"}"#.to_string()),
+    ]);
+    let (mut cmd, home) = mock_cmd(&api, "delete");
+    cmd.arg("--dry-run");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("delete needs --before DATE or config `retentionDays`"));
+
+    std::fs::create_dir_all(config_path(&home)).unwrap();
+    std::fs::write(config_json_path(&home), r#"{"retentionDays": 30}"#).unwrap();
+    // Only transcripts this tool recorded are subject to retention; tr_other_app came from elsewhere.
+    let recorded = ["tr_recent", "tr_expired", "tr_deleted_earlier"].map(|id| {
+        format!(
+            r#"{{"id":1,"startedAt":0,"durationMs":0,"input":"a.mp3","args":["transcribe","a.mp3"],"optionsHash":"x","transcriptId":"{id}","result":"ok"}}"#
+        )
+    });
+    std::fs::write(config_path(&home).join("history.jsonl"), recorded.join("\n") + "\n").unwrap();
+    let run = |args: &[&str]| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+        cmd.env("HOME", home.path())
+            .env("USERPROFILE", home.path())
+            .env("ASSEMBLYAI_API_KEY", "dummy")
+            .env("ASSEMBLYAI_BASE_URL", &api.base_url)
+            .arg("delete")
+            .args(args);
+        cmd.assert()
+    };
+    run(&["--dry-run"]).success().stdout(
+        "tr_expired  completed   2020-01-01 00:00:00  https://cdn.example/expired.mp3\n\
+         would delete 1 transcript (1 completed)\n",
+    );
+    run(&["--yes"]).success().stdout("deleted 1 transcript (1 completed)\n");
+    let deleted: Vec<String> = api
+        .requests()
+        .into_iter()
+        .filter(|request| request.method == "DELETE")
+        .map(|request| request.path)
+        .collect();
+    assert_eq!(deleted, ["/v2/transcript/tr_expired"]);
+
+    run(&["--before", "2019-06-01", "--dry-run"])
+        .success()
+        .stdout(predicate::str::contains("tr_other_app"));
+}
+
+#[test]
+fn concurrent_runs_keep_every_state_change() {
+    let home = tempfile::tempdir().unwrap();
+    let children: Vec<_> = (0..8)
+        .map(|n| {
+            std::process::Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"))
+                .env("HOME", home.path())
+                .env("USERPROFILE", home.path())
+                .args(["alias", "set", &format!("call-{n}"), &format!("tr_{n}")])
+                .stdout(std::process::Stdio::null())
+                .spawn()
+                .unwrap()
+        })
+        .collect();
+    for mut child in children {
+        assert!(child.wait().unwrap().success());
+    }
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+    cmd.env("HOME", home.path()).env("USERPROFILE", home.path()).args(["alias", "list"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    assert_eq!(String::from_utf8(output).unwrap().lines().count(), 8);
+}
+
+#[test]
+fn alias_resolves_to_transcript_id() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_cmd(&api, "alias");
+    cmd.args(["set", "weekly-standup", "tr_1"]);
+    cmd.assert().success().stdout("weekly-standup -> tr_1\n");
+
+    let run = |args: &[&str]| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+        cmd.env("HOME", home.path())
+            .env("USERPROFILE", home.path())
+            .env("ASSEMBLYAI_API_KEY", "dummy")
+            .env("ASSEMBLYAI_BASE_URL", &api.base_url)
+            .args(args);
+        cmd.assert()
+    };
+
+    run(&["alias", "list"]).success().stdout("weekly-standup  tr_1\n");
+
+    let reference = home.path().join("ref.txt");
+    std::fs::write(&reference, "Hello world. Good bye.\n").expect("write reference");
+    run(&["eval", "weekly-standup", "--reference", reference.to_str().unwrap()])
+        .success()
+        .stdout(predicate::str::starts_with("WER: 0.00%"));
+    assert_eq!(api.requests().last().map(|r| r.path.as_str()), Some("/v2/transcript/tr_1"));
+
+    run(&["alias", "remove", "weekly-standup"]).success();
+    run(&["alias", "remove", "weekly-standup"])
+        .code(2)
+        .stderr(predicate::str::contains("no alias named \"weekly-standup\""));
+    run(&["alias", "set", "two words", "tr_1"]).code(2);
+}
+
+#[test]
+fn sqlite_format_appends_normalized_tables() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    let db = home.path().join("transcripts.db");
+    for _ in 0..2 {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("assemblyai-cli"));
+        cmd.env("HOME", home.path())
+            .env("ASSEMBLYAI_API_KEY", "dummy")
+            .env("ASSEMBLYAI_BASE_URL", &api.base_url)
+            .arg("transcribe")
+            .arg(&input)
+            .args(["--poll-interval-seconds", "0", "--format", "sqlite", "--output"])
+            .arg(&db);
+        cmd.assert().success().stdout("");
+    }
+
+    let conn = rusqlite::Connection::open(&db).expect("open db");
+    let count = |sql: &str| conn.query_row(sql, [], |row| row.get::<_, i64>(0)).expect("query");
+    assert_eq!(count("SELECT COUNT(*) FROM transcripts WHERE id = 'tr_1'"), 1);
+    assert_eq!(count("SELECT COUNT(*) FROM utterances"), 2);
+    assert_eq!(count("SELECT COUNT(*) FROM words WHERE speaker = 'B'"), 2);
+    assert_eq!(count("SELECT talk_time_ms FROM speakers WHERE speaker = 'A'"), 900);
+    assert_eq!(count("SELECT duration_ms FROM transcripts"), 2700);
+
+    cmd.arg(&input).args(["--format", "sqlite"]);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--format sqlite requires --output"));
+}
+
+#[test]
+fn deterministic_outputs_drop_run_times_and_absolute_paths() {
+    let mut transcript = sample_transcript();
+    transcript["language_code"] = "es".into();
+    let api = MockApi::start(completed_transcript_routes(transcript));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    std::fs::write(home.path().join("monday.mp3"), b"fake audio").unwrap();
+    let inputs = ["monday.mp3", "missing.mp3"].map(|name| home.path().join(name));
+    cmd.current_dir(home.path())
+        .args(&inputs)
+        .args(["--poll-interval-seconds", "0", "--language", "auto", "--deterministic", "--output", "out"])
+        .args(["--failures-file", "failures.json"]);
+    cmd.assert().code(9);
+
+    let read = |name: &str| std::fs::read_to_string(home.path().join(name)).unwrap();
+    let sidecar: serde_json::Value = serde_json::from_str(&read("out/monday.meta.json")).unwrap();
+    assert_eq!(sidecar["input"], "monday.mp3");
+    let failures: serde_json::Value = serde_json::from_str(&read("failures.json")).unwrap();
+    assert_eq!(failures["failures"][0]["input"], "missing.mp3");
+    let error = failures["failures"][0]["error"].as_str().unwrap();
+    assert!(error.contains("missing.mp3") && !error.contains(&home.path().display().to_string()), "{error}");
+
+    let (mut cmd, _home) = mock_transcribe_cmd(&api);
+    let db = home.path().join("transcripts.db");
+    cmd.current_dir(home.path())
+        .arg(&inputs[0])
+        .args(["--poll-interval-seconds", "0", "--format", "sqlite", "--deterministic", "--output"])
+        .arg(&db);
+    cmd.assert().success();
+    let conn = rusqlite::Connection::open(&db).expect("open db");
+    let row: (String, i64) = conn
+        .query_row("SELECT input, created_at FROM transcripts", [], |row| Ok((row.get(0)?, row.get(1)?)))
+        .expect("query");
+    assert_eq!(row, ("monday.mp3".to_string(), 0));
+}
+
+#[test]
+fn parquet_format_writes_word_and_utterance_tables() {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.arg(&input)
+        .args(["--poll-interval-seconds", "0", "--format", "parquet", "--output"])
+        .arg(home.path().join("call.parquet"));
+    cmd.assert()
+        .success()
+        .stdout("")
+        .stderr(predicate::str::contains("wrote 4 words to"));
+
+    for (table, rows, columns) in [("words", 4, 7), ("utterances", 2, 6)] {
+        let file = std::fs::File::open(home.path().join(format!("call.{table}.parquet"))).expect("open parquet");
+        let reader = SerializedFileReader::new(file).expect("read parquet");
+        let metadata = reader.metadata().file_metadata();
+        assert_eq!(metadata.num_rows(), rows, "{table}");
+        assert_eq!(metadata.schema_descr().num_columns(), columns, "{table}");
+    }
+}
+
+#[test]
+fn compresses_output_by_extension_or_flag() {
+    use std::io::Read;
+
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    let gz = home.path().join("out.txt.gz");
+    cmd.arg(&input).args(["--poll-interval-seconds", "0", "--output"]).arg(&gz);
+    cmd.assert().success();
+    let mut text = String::new();
+    flate2::read::GzDecoder::new(std::fs::File::open(&gz).expect("open gz"))
+        .read_to_string(&mut text)
+        .expect("gunzip");
+    assert_eq!(text, "Hello world. Good bye.");
+
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    let zst = home.path().join("out.txt");
+    cmd.arg(&input)
+        .args(["--poll-interval-seconds", "0", "--compress", "zstd", "--output"])
+        .arg(&zst);
+    cmd.assert().success();
+    let bytes = zstd::decode_all(std::fs::File::open(&zst).expect("open zst")).expect("unzstd");
+    assert_eq!(bytes, b"Hello world. Good bye.");
+
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home)).args(["--compress", "gzip"]);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--compress requires --output"));
+}
+
+#[test]
+fn tee_and_preview_echo_to_stdout_while_writing_output() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    let edl = home.path().join("out.edl");
+    cmd.arg(&input)
+        .args(["--poll-interval-seconds", "0", "--format", "edl", "--tee", "text", "--output"])
+        .arg(&edl);
+    cmd.assert().success().stdout("Hello world. Good bye.\n");
+    assert!(std::fs::read_to_string(&edl).expect("read edl").starts_with("TITLE:"));
+
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let input = temp_audio_file(&home);
+    cmd.arg(&input)
+        .args(["--poll-interval-seconds", "0", "--speaker-labels", "--preview", "1", "--output"])
+        .arg(home.path().join("out.txt"));
+    cmd.assert().success().stdout("Speaker A: Hello world.\n");
+
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home)).args(["--preview", "3"]);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--preview requires --output"));
+}
+
+#[test]
+fn content_checks_exit_8_before_writing_output() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    for (args, message) in [
+        (["--min-words", "5"], "transcript has 4 word(s), fewer than --min-words 5"),
+        (["--min-avg-confidence", "0.9"], "average word confidence 0.84 is below --min-avg-confidence 0.9"),
+    ] {
+        let (mut cmd, home) = mock_transcribe_cmd(&api);
+        let output = home.path().join("out.txt");
+        cmd.arg(temp_audio_file(&home))
+            .args(["--poll-interval-seconds", "0"])
+            .args(args)
+            .arg("--output")
+            .arg(&output);
+        cmd.assert().code(8).stderr(predicate::str::contains(message));
+        assert!(!output.exists());
+    }
+
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home))
+        .args(["--poll-interval-seconds", "0", "--min-words", "4", "--min-avg-confidence", "0.8"]);
+    cmd.assert().success();
+
+    let api = MockApi::start(completed_transcript_routes(serde_json::json!({
+        "id": "tr_1", "status": "completed", "text": "", "words": []
+    })));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home))
+        .args(["--poll-interval-seconds", "0", "--fail-on-empty"]);
+    cmd.assert()
+        .code(8)
+        .stdout("")
+        .stderr(predicate::str::contains("content check failed: transcript is empty"));
+}
+
+#[test]
+fn concat_transcribes_the_parts_as_one_recording() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    let first = temp_audio_file(&home);
+    let second = home.path().join("part2.mp3");
+    std::fs::write(&second, b"more fake audio").unwrap();
+    cmd.arg(&first)
+        .arg(&second)
+        .args(["--concat", "--poll-interval-seconds", "0", "--speaker-labels", "--format", "srt"]);
+    cmd.assert().success().stdout(
+        "1\n00:00:00,000 --> 00:00:00,900\nSpeaker A: Hello world.\n\n\
+         2\n00:00:02,000 --> 00:00:02,700\nSpeaker B: Good bye.\n\n\
+         3\n00:00:02,700 --> 00:00:03,600\nSpeaker A: Hello world.\n\n\
+         4\n00:00:04,700 --> 00:00:05,400\nSpeaker B: Good bye.\n\n",
+    );
+
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home)).args(["--concat"]);
+    cmd.assert().failure().code(2).stderr(predicate::str::contains("two or more inputs"));
+}
+
+#[test]
+fn subtitle_source_local_builds_captions_from_words() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home))
+        .args(["--poll-interval-seconds", "0", "--format", "srt", "--subtitle-source", "local"]);
+    cmd.assert()
+        .success()
+        .stdout("1\n00:00:00,000 --> 00:00:00,900\nHello world.\n\n2\n00:00:02,000 --> 00:00:02,700\nGood bye.\n\n");
+    assert!(!api.requests().iter().any(|r| r.path.ends_with("/srt")));
+
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home))
+        .args(["--format", "srt", "--subtitle-source", "api", "--max-cps", "17"]);
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("--subtitle-source api cannot be combined with --max-cps"));
+
+    for (args, other) in [
+        (&["--speaker-labels", "--identify-speakers"][..], "--identify-speakers"),
+        (&["--multichannel", "--channel-names", "1=Agent,2=Customer"][..], "--channel-names"),
+    ] {
+        let (mut cmd, home) = mock_transcribe_cmd(&api);
+        cmd.arg(temp_audio_file(&home))
+            .args(["--format", "srt", "--subtitle-source", "api"])
+            .args(args);
+        cmd.assert()
+            .failure()
+            .code(2)
+            .stderr(predicate::str::contains(format!("--subtitle-source api cannot be combined with {other}")));
+    }
+}
+
+#[test]
+fn max_cps_splits_dense_captions_and_extends_sparse_ones() {
+    let transcript = serde_json::json!({
+        "id": "tr_1",
+        "status": "completed",
+        "text": "Well then here goes home. Okay.",
+        "words": [
+            {"text": "Well", "start": 0, "end": 800, "confidence": 0.9},
+            {"text": "then", "start": 800, "end": 900, "confidence": 0.9},
+            {"text": "here", "start": 900, "end": 1000, "confidence": 0.9},
+            {"text": "goes", "start": 1000, "end": 1100, "confidence": 0.9},
+            {"text": "home.", "start": 1100, "end": 3000, "confidence": 0.9},
+            {"text": "Okay.", "start": 5000, "end": 5100, "confidence": 0.9}
+        ]
+    });
+    let api = MockApi::start(completed_transcript_routes(transcript));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home))
+        .args(["--poll-interval-seconds", "0", "--format", "srt", "--chars-per-caption", "20", "--max-cps", "15"]);
+    cmd.assert().success().stdout(
+        "1\n00:00:00,000 --> 00:00:01,000\nWell then here\n\n\
+         2\n00:00:01,000 --> 00:00:03,000\ngoes home.\n\n\
+         3\n00:00:05,000 --> 00:00:05,334\nOkay.\n\n",
+    );
+}
+
+#[test]
+fn json_format_keeps_the_chosen_sections() {
+    let api = MockApi::start(completed_transcript_routes(sample_transcript()));
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home))
+        .args(["--poll-interval-seconds", "0", "--format", "json", "--json-exclude", "words"]);
+    let stdout = cmd.assert().success().get_output().stdout.clone();
+    let json: serde_json::Value = serde_json::from_slice(&stdout).unwrap();
+    assert_eq!(json["text"], "Hello world. Good bye.");
+    assert!(json.get("words").is_none());
+
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home))
+        .args(["--poll-interval-seconds", "0", "--format", "json", "--json-include", "utterances"]);
+    let stdout = cmd.assert().success().get_output().stdout.clone();
+    let json: serde_json::Value = serde_json::from_slice(&stdout).unwrap();
+    let mut keys: Vec<&String> = json.as_object().unwrap().keys().collect();
+    keys.sort();
+    assert_eq!(keys, ["id", "utterances"]);
+
+    let (mut cmd, home) = mock_transcribe_cmd(&api);
+    cmd.arg(temp_audio_file(&home)).args(["--json-exclude", "words"]);
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("--json-exclude requires --format json"));
+
+    for rewrite in [&["--identify-speakers"][..], &["--mark-uncertain", "0.5"], &["--merge-short-utterances", "1s"]] {
+        let (mut cmd, home) = mock_transcribe_cmd(&api);
+        cmd.arg(temp_audio_file(&home))
+            .args(["--speaker-labels", "--format", "json"])
+            .args(rewrite);
+        cmd.assert()
+            .code(2)
+            .stderr(predicate::str::contains(format!("--format json cannot be combined with {}", rewrite[0])));
+    }
+}